@@ -43,7 +43,7 @@ const _: () = {
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::helper::QuasiObserver for FooObserver<'ob, S, N>
     where
-        S: ::morphix::helper::AsDeref<N>,
+        S: ::morphix::helper::AsDeref<N, Target = Foo>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = S;
@@ -54,6 +54,26 @@ const _: () = {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.b);
             ::morphix::helper::QuasiObserver::invalidate(&mut this.c);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            let __value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+            let mut mutations = ::morphix::Mutations::new();
+            mutations
+                .insert(
+                    "A",
+                    ::morphix::helper::QuasiObserver::inverse(&this.r#a, &__value.r#a)?,
+                );
+            mutations
+                .insert(
+                    "bar",
+                    ::morphix::helper::QuasiObserver::inverse(&this.b, &__value.b)?,
+                );
+            mutations
+                .extend(::morphix::helper::QuasiObserver::inverse(&this.c, &__value.c)?);
+            ::std::option::Option::Some(mutations)
+        }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::observe::Observer for FooObserver<'ob, S, N>
@@ -101,12 +121,8 @@ const _: () = {
             let mutations_c = unsafe {
                 ::morphix::observe::SerializeObserver::flat_flush(&mut this.c)
             };
-            if mutations_a.is_replace() && mutations_b.is_replace()
-                && mutations_c.is_replace()
-            {
-                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
-                return ::morphix::Mutations::replace(value);
-            }
+            let is_replace = mutations_a.is_replace() && mutations_b.is_replace()
+                && mutations_c.is_replace() || mutations_c.is_root_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(
                     !mutations_a.is_empty() as usize + !mutations_b.is_empty() as usize
@@ -115,6 +131,10 @@ const _: () = {
             mutations.insert("A", mutations_a);
             mutations.insert("bar", mutations_b);
             mutations.extend(mutations_c);
+            if is_replace {
+                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
@@ -127,6 +147,7 @@ const _: () = {
             let mutations_c = unsafe {
                 ::morphix::observe::SerializeObserver::flat_flush(&mut this.c)
             };
+            let is_root_replace = mutations_c.is_root_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(
                     !mutations_a.is_empty() as usize + !mutations_b.is_empty() as usize
@@ -139,6 +160,10 @@ const _: () = {
             mutations.insert("A", mutations_a);
             mutations.insert("bar", mutations_b);
             mutations.extend(mutations_c);
+            if is_root_replace {
+                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
     }
@@ -209,7 +234,7 @@ impl<'ob, S: ?Sized, N> ::std::ops::DerefMut for BarObserver<'ob, S, N> {
 #[automatically_derived]
 impl<'ob, S: ?Sized, N> ::morphix::helper::QuasiObserver for BarObserver<'ob, S, N>
 where
-    S: ::morphix::helper::AsDeref<N>,
+    S: ::morphix::helper::AsDeref<N, Target = Bar>,
     N: ::morphix::helper::Unsigned,
 {
     type Head = S;
@@ -218,6 +243,13 @@ where
     fn invalidate(this: &mut Self) {
         ::morphix::helper::QuasiObserver::invalidate(&mut this.0);
     }
+    fn inverse(
+        this: &Self,
+        head: &Self::Head,
+    ) -> ::std::option::Option<::morphix::Mutations> {
+        let __value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+        ::morphix::helper::QuasiObserver::inverse(&this.0, &__value.0)
+    }
 }
 #[rustfmt::skip]
 #[automatically_derived]
@@ -298,7 +330,7 @@ const _: () = {
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::helper::QuasiObserver for BazObserver<'ob, S, N>
     where
-        S: ::morphix::helper::AsDeref<N>,
+        S: ::morphix::helper::AsDeref<N, Target = Baz>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = S;
@@ -308,6 +340,24 @@ const _: () = {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.0);
             ::morphix::helper::QuasiObserver::invalidate(&mut this.1);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            let __value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+            let mut mutations = ::morphix::Mutations::new();
+            mutations
+                .insert(
+                    0usize,
+                    ::morphix::helper::QuasiObserver::inverse(&this.0, &__value.0)?,
+                );
+            mutations
+                .insert(
+                    1usize,
+                    ::morphix::helper::QuasiObserver::inverse(&this.1, &__value.1)?,
+                );
+            ::std::option::Option::Some(mutations)
+        }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::observe::Observer for BazObserver<'ob, S, N>
@@ -349,16 +399,17 @@ const _: () = {
             let mutations_1 = unsafe {
                 ::morphix::observe::SerializeObserver::flush(&mut this.1)
             };
-            if mutations_0.is_replace() && mutations_1.is_replace() {
-                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
-                return ::morphix::Mutations::replace(value);
-            }
+            let is_replace = mutations_0.is_replace() && mutations_1.is_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(
                     !mutations_0.is_empty() as usize + !mutations_1.is_empty() as usize,
                 );
             mutations.insert(0usize, mutations_0);
             mutations.insert(1usize, mutations_1);
+            if is_replace {
+                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
@@ -30,10 +30,10 @@ const _: () = {
         }
     }
     #[automatically_derived]
-    impl<'ob, O, N> ::morphix::helper::QuasiObserver for FooObserver<'ob, O>
+    impl<'ob, T, O, N> ::morphix::helper::QuasiObserver for FooObserver<'ob, O>
     where
         O: ::morphix::helper::QuasiObserver<InnerDepth = ::morphix::helper::Succ<N>>,
-        O::Head: ::morphix::helper::AsDeref<N>,
+        O::Head: ::morphix::helper::AsDeref<N, Target = Foo<T>>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = O::Head;
@@ -43,6 +43,20 @@ const _: () = {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.b);
             ::morphix::helper::QuasiObserver::invalidate(&mut this.a);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            let __value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+            let mut mutations = ::morphix::Mutations::new();
+            mutations.extend(::morphix::helper::QuasiObserver::inverse(&this.a, head)?);
+            mutations
+                .insert(
+                    "b",
+                    ::morphix::helper::QuasiObserver::inverse(&this.b, &__value.b)?,
+                );
+            ::std::option::Option::Some(mutations)
+        }
     }
     #[automatically_derived]
     impl<'ob, T, O, N> ::morphix::observe::Observer for FooObserver<'ob, O>
@@ -86,15 +100,17 @@ const _: () = {
             let mutations_b = unsafe {
                 ::morphix::observe::SerializeObserver::flush(&mut this.b)
             };
-            if mutations_a.is_replace() && mutations_b.is_replace() {
-                let head = &**(*this).as_deref_coinductive();
-                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
-                return ::morphix::Mutations::replace(value);
-            }
+            let is_replace = mutations_a.is_replace() && mutations_b.is_replace()
+                || mutations_a.is_root_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(mutations_a.len() + !mutations_b.is_empty() as usize);
             mutations.extend(mutations_a);
             mutations.insert("b", mutations_b);
+            if is_replace {
+                let head = &**(*this).as_deref_coinductive();
+                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
@@ -104,11 +120,17 @@ const _: () = {
             let mutations_b = unsafe {
                 ::morphix::observe::SerializeObserver::flush(&mut this.b)
             };
+            let is_root_replace = mutations_a.is_root_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(mutations_a.len() + !mutations_b.is_empty() as usize)
                 .with_replace(mutations_a.is_replace() && mutations_b.is_replace());
             mutations.extend(mutations_a);
             mutations.insert("b", mutations_b);
+            if is_root_replace {
+                let head = &**(*this).as_deref_coinductive();
+                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
     }
@@ -169,7 +191,7 @@ const _: () = {
     impl<'ob, O, N> ::morphix::helper::QuasiObserver for BarObserver<'ob, O>
     where
         O: ::morphix::helper::QuasiObserver<InnerDepth = ::morphix::helper::Succ<N>>,
-        O::Head: ::morphix::helper::AsDeref<N>,
+        O::Head: ::morphix::helper::AsDeref<N, Target = Bar>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = O::Head;
@@ -179,6 +201,24 @@ const _: () = {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.1);
             ::morphix::helper::QuasiObserver::invalidate(&mut this.0);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            let __value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+            let mut mutations = ::morphix::Mutations::new();
+            mutations
+                .insert(
+                    0usize,
+                    ::morphix::helper::QuasiObserver::inverse(&this.0, head)?,
+                );
+            mutations
+                .insert(
+                    1usize,
+                    ::morphix::helper::QuasiObserver::inverse(&this.1, &__value.1)?,
+                );
+            ::std::option::Option::Some(mutations)
+        }
     }
     #[automatically_derived]
     impl<'ob, O, N> ::morphix::observe::Observer for BarObserver<'ob, O>
@@ -219,17 +259,18 @@ const _: () = {
             let mutations_1 = unsafe {
                 ::morphix::observe::SerializeObserver::flush(&mut this.1)
             };
-            if mutations_0.is_replace() && mutations_1.is_replace() {
-                let head = &**(*this).as_deref_coinductive();
-                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
-                return ::morphix::Mutations::replace(value);
-            }
+            let is_replace = mutations_0.is_replace() && mutations_1.is_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(
                     !mutations_0.is_empty() as usize + !mutations_1.is_empty() as usize,
                 );
             mutations.insert(0usize, mutations_0);
             mutations.insert(1usize, mutations_1);
+            if is_replace {
+                let head = &**(*this).as_deref_coinductive();
+                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
@@ -300,7 +341,7 @@ const _: () = {
     impl<O, N> ::morphix::helper::QuasiObserver for QuxObserver<O>
     where
         O: ::morphix::helper::QuasiObserver<InnerDepth = ::morphix::helper::Succ<N>>,
-        O::Head: ::morphix::helper::AsDeref<N>,
+        O::Head: ::morphix::helper::AsDeref<N, Target = Qux>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = O::Head;
@@ -309,6 +350,12 @@ const _: () = {
         fn invalidate(this: &mut Self) {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.0);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            ::morphix::helper::QuasiObserver::inverse(&this.0, head)
+        }
     }
     #[automatically_derived]
     impl<O, N> ::morphix::observe::Observer for QuxObserver<O>
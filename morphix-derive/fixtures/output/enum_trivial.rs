@@ -13,6 +13,7 @@ const _: () = {
     #[::std::prelude::v1::derive()]
     pub struct FooObserver<'ob, S: ?Sized, N = ::morphix::helper::Zero> {
         ptr: ::morphix::helper::Pointer<S>,
+        captor: ::morphix::helper::Captor,
         initial: FooObserverInitial,
         phantom: ::std::marker::PhantomData<&'ob mut N>,
     }
@@ -40,21 +41,34 @@ const _: () = {
         }
     }
     #[automatically_derived]
-    impl<'ob, S: ?Sized, N> ::std::ops::DerefMut for FooObserver<'ob, S, N> {
+    impl<'ob, S: ?Sized, N> ::std::ops::DerefMut for FooObserver<'ob, S, N>
+    where
+        S: ::morphix::helper::AsDeref<N, Target = Foo>,
+        N: ::morphix::helper::Unsigned,
+    {
         fn deref_mut(&mut self) -> &mut Self::Target {
+            ::morphix::helper::QuasiObserver::invalidate(self);
             &mut self.ptr
         }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::helper::QuasiObserver for FooObserver<'ob, S, N>
     where
-        S: ::morphix::helper::AsDeref<N>,
+        S: ::morphix::helper::AsDeref<N, Target = Foo>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = S;
         type OuterDepth = ::morphix::helper::Succ<::morphix::helper::Zero>;
         type InnerDepth = N;
-        fn invalidate(this: &mut Self) {}
+        fn invalidate(this: &mut Self) {
+            this.captor.capture((*this.ptr).as_deref());
+        }
+        fn inverse(
+            this: &Self,
+            _: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            this.captor.inverse()
+        }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::observe::Observer for FooObserver<'ob, S, N>
@@ -65,6 +79,7 @@ const _: () = {
         fn observe(head: &mut S) -> Self {
             let __value = head.as_deref_mut();
             Self {
+                captor: ::std::default::Default::default(),
                 initial: FooObserverInitial::new(__value),
                 ptr: ::morphix::helper::Pointer::new(head),
                 phantom: ::std::marker::PhantomData,
@@ -85,23 +100,25 @@ const _: () = {
             let value = this.ptr.as_deref();
             let initial = this.initial;
             this.initial = FooObserverInitial::new(value);
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::A, Foo::A)
                 | (FooObserverInitial::B, Foo::B())
                 | (FooObserverInitial::C, Foo::C {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
             let value = this.ptr.as_deref();
             let initial = this.initial;
             this.initial = FooObserverInitial::new(value);
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::A, Foo::A)
                 | (FooObserverInitial::B, Foo::B())
                 | (FooObserverInitial::C, Foo::C {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
     }
     #[automatically_derived]
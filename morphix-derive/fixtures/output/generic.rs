@@ -73,7 +73,7 @@ const _: () = {
         _N,
     > ::morphix::helper::QuasiObserver for FooObserver<'ob, 'a, S, T, U, N, _S, _N>
     where
-        _S: ::morphix::helper::AsDeref<_N>,
+        _S: ::morphix::helper::AsDeref<_N, Target = Foo<'a, S, T, U, N>>,
         &'a mut [S; N]: ::morphix::Observe,
         Option<U>: ::morphix::Observe,
         _N: ::morphix::helper::Unsigned,
@@ -85,6 +85,24 @@ const _: () = {
             ::morphix::helper::QuasiObserver::invalidate(&mut this.a);
             ::morphix::helper::QuasiObserver::invalidate(&mut this.c);
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            let __value = ::morphix::helper::AsDeref::<_N>::as_deref(head);
+            let mut mutations = ::morphix::Mutations::new();
+            mutations
+                .insert(
+                    "a",
+                    ::morphix::helper::QuasiObserver::inverse(&this.a, &__value.a)?,
+                );
+            mutations
+                .insert(
+                    "c",
+                    ::morphix::helper::QuasiObserver::inverse(&this.c, &__value.c)?,
+                );
+            ::std::option::Option::Some(mutations)
+        }
     }
     #[automatically_derived]
     impl<'ob, 'a, S, T, U, const N: usize, _S: ?Sized, _N> ::morphix::observe::Observer
@@ -153,10 +171,7 @@ const _: () = {
             let mutations_c = unsafe {
                 ::morphix::observe::SerializeObserver::flush(&mut this.c)
             };
-            if mutations_a.is_replace() && mutations_c.is_replace() {
-                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
-                return ::morphix::Mutations::replace(value);
-            }
+            let is_replace = mutations_a.is_replace() && mutations_c.is_replace();
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(
                     !mutations_a.is_empty() as usize + !mutations_c.is_empty() as usize,
@@ -168,6 +183,10 @@ const _: () = {
             } else {
                 mutations.insert("c", mutations_c);
             }
+            if is_replace {
+                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
@@ -25,6 +25,7 @@ const _: () = {
         U: ::morphix::Observe + 'ob,
     {
         ptr: ::morphix::helper::Pointer<_S>,
+        captor: ::morphix::helper::Captor,
         mutated: bool,
         initial: FooObserverInitial,
         variant: FooObserverVariant<'ob, S, T, U>,
@@ -126,9 +127,8 @@ const _: () = {
                     let mutations_1 = unsafe {
                         ::morphix::observe::SerializeObserver::flush(u1)
                     };
-                    if mutations_0.is_replace() && mutations_1.is_replace() {
-                        return ::morphix::Mutations::replace(unsafe { &*__value });
-                    }
+                    let is_replace = mutations_0.is_replace()
+                        && mutations_1.is_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(
                             !mutations_0.is_empty() as usize
@@ -136,18 +136,26 @@ const _: () = {
                         );
                     mutations.insert(0usize, mutations_0);
                     mutations.insert(1usize, mutations_1);
+                    if is_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations.with_prefix("b"));
+                    }
                     mutations.with_prefix("b")
                 }
                 Self::C { qux, .. } => {
                     let mutations_qux = unsafe {
                         ::morphix::observe::SerializeObserver::flush(qux)
                     };
-                    if mutations_qux.is_replace() {
-                        return ::morphix::Mutations::replace(unsafe { &*__value });
-                    }
+                    let is_replace = mutations_qux.is_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(!mutations_qux.is_empty() as usize);
                     mutations.insert("QwQ", mutations_qux);
+                    if is_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations.with_prefix("OwO"));
+                    }
                     mutations.with_prefix("OwO")
                 }
                 Self::__Unknown => ::morphix::Mutations::new(),
@@ -176,6 +184,43 @@ const _: () = {
                 _ => panic!("flat_flush can only be called on structs and maps"),
             }
         }
+        fn inverse(
+            &self,
+            value: &Foo<S, T, U>,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            match (self, value) {
+                (Self::A { .. }, Foo::A { .. }) => {
+                    ::std::option::Option::Some(::morphix::Mutations::new())
+                }
+                (Self::B { 0: u0, 1: u1, .. }, Foo::B { 0: v0, 1: v1, .. }) => {
+                    let mut mutations = ::morphix::Mutations::new();
+                    mutations
+                        .insert(
+                            0usize,
+                            ::morphix::helper::QuasiObserver::inverse(u0, v0)?,
+                        );
+                    mutations
+                        .insert(
+                            1usize,
+                            ::morphix::helper::QuasiObserver::inverse(u1, v1)?,
+                        );
+                    ::std::option::Option::Some(mutations.with_prefix("b"))
+                }
+                (Self::C { qux: u1, .. }, Foo::C { qux: v1, .. }) => {
+                    let mut mutations = ::morphix::Mutations::new();
+                    mutations
+                        .insert(
+                            "QwQ",
+                            ::morphix::helper::QuasiObserver::inverse(u1, v1)?,
+                        );
+                    ::std::option::Option::Some(mutations.with_prefix("OwO"))
+                }
+                (Self::__Unknown, _) => {
+                    ::std::option::Option::Some(::morphix::Mutations::new())
+                }
+                _ => panic!("inconsistent state for FooObserver"),
+            }
+        }
     }
     #[automatically_derived]
     impl<'ob, S, T, U, _S: ?Sized, N> ::std::ops::Deref
@@ -193,12 +238,14 @@ const _: () = {
     impl<'ob, S, T, U, _S: ?Sized, N> ::std::ops::DerefMut
     for FooObserver<'ob, S, T, U, _S, N>
     where
+        Foo<S, T, U>: ::morphix::helper::Capture,
         T: Clone,
         U: ::morphix::Observe,
+        _S: ::morphix::helper::AsDeref<N, Target = Foo<S, T, U>>,
+        N: ::morphix::helper::Unsigned,
     {
         fn deref_mut(&mut self) -> &mut Self::Target {
-            self.mutated = true;
-            self.variant = FooObserverVariant::__Unknown;
+            ::morphix::helper::QuasiObserver::invalidate(self);
             &mut self.ptr
         }
     }
@@ -206,23 +253,38 @@ const _: () = {
     impl<'ob, S, T, U, _S: ?Sized, N> ::morphix::helper::QuasiObserver
     for FooObserver<'ob, S, T, U, _S, N>
     where
+        Foo<S, T, U>: ::morphix::helper::Capture,
         T: Clone,
         U: ::morphix::Observe,
-        _S: ::morphix::helper::AsDeref<N>,
+        _S: ::morphix::helper::AsDeref<N, Target = Foo<S, T, U>>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = _S;
         type OuterDepth = ::morphix::helper::Succ<::morphix::helper::Zero>;
         type InnerDepth = N;
         fn invalidate(this: &mut Self) {
+            if !this.mutated {
+                let value = (*this.ptr).as_deref();
+                this.captor.capture_with(value, || this.variant.inverse(value));
+            }
             this.mutated = true;
             this.variant = FooObserverVariant::__Unknown;
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            if this.mutated {
+                return this.captor.inverse();
+            }
+            this.variant.inverse(head.as_deref())
+        }
     }
     #[automatically_derived]
     impl<'ob, S, T, U, _S: ?Sized, N> ::morphix::observe::Observer
     for FooObserver<'ob, S, T, U, _S, N>
     where
+        Foo<S, T, U>: ::morphix::helper::Capture,
         T: Clone,
         S: 'ob,
         Option<T>: 'ob,
@@ -233,6 +295,7 @@ const _: () = {
         fn observe(head: &mut _S) -> Self {
             let __value = head.as_deref_mut();
             Self {
+                captor: ::std::default::Default::default(),
                 mutated: false,
                 initial: FooObserverInitial::new(__value),
                 variant: FooObserverVariant::observe(__value),
@@ -271,12 +334,13 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::D, Foo::D)
                 | (FooObserverInitial::E, Foo::E())
                 | (FooObserverInitial::F, Foo::F {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
             let value = this.ptr.as_deref();
@@ -287,12 +351,13 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::D, Foo::D)
                 | (FooObserverInitial::E, Foo::E())
                 | (FooObserverInitial::F, Foo::F {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
     }
     #[automatically_derived]
@@ -23,6 +23,7 @@ const _: () = {
         _N = ::morphix::helper::Zero,
     > {
         ptr: ::morphix::helper::Pointer<S>,
+        captor: ::morphix::helper::Captor,
         mutated: bool,
         variant: FooObserverVariant<'ob, N>,
         phantom: ::std::marker::PhantomData<&'ob mut _N>,
@@ -77,9 +78,8 @@ const _: () = {
                     let mutations_qux = unsafe {
                         ::morphix::observe::SerializeObserver::flat_flush(qux)
                     };
-                    if mutations_bar.is_replace() && mutations_qux.is_replace() {
-                        return ::morphix::Mutations::replace(unsafe { &*__value });
-                    }
+                    let is_replace = mutations_bar.is_replace()
+                        && mutations_qux.is_replace() || mutations_qux.is_root_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(
                             !mutations_bar.is_empty() as usize + mutations_qux.len(),
@@ -94,6 +94,11 @@ const _: () = {
                         mutations.insert("bar", mutations_bar);
                     }
                     mutations.extend(mutations_qux);
+                    if is_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations);
+                    }
                     mutations
                 }
                 Self::__Unknown => ::morphix::Mutations::new(),
@@ -114,6 +119,7 @@ const _: () = {
                     let mutations_qux = unsafe {
                         ::morphix::observe::SerializeObserver::flat_flush(qux)
                     };
+                    let is_root_replace = mutations_qux.is_root_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(
                             !mutations_bar.is_empty() as usize + mutations_qux.len(),
@@ -131,11 +137,41 @@ const _: () = {
                         mutations.insert("bar", mutations_bar);
                     }
                     mutations.extend(mutations_qux);
+                    if is_root_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations);
+                    }
                     mutations
                 }
                 _ => panic!("flat_flush can only be called on structs and maps"),
             }
         }
+        fn inverse(
+            &self,
+            value: &Foo<N>,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            match (self, value) {
+                (Self::A { 0: u0, .. }, Foo::A { 0: v0, .. }) => {
+                    let mutations = ::morphix::helper::QuasiObserver::inverse(u0, v0)?;
+                    ::std::option::Option::Some(mutations)
+                }
+                (Self::C { bar: u0, qux: u1, .. }, Foo::C { bar: v0, qux: v1, .. }) => {
+                    let mut mutations = ::morphix::Mutations::new();
+                    mutations
+                        .insert(
+                            "bar",
+                            ::morphix::helper::QuasiObserver::inverse(u0, v0)?,
+                        );
+                    mutations.extend(::morphix::helper::QuasiObserver::inverse(u1, v1)?);
+                    ::std::option::Option::Some(mutations)
+                }
+                (Self::__Unknown, _) => {
+                    ::std::option::Option::Some(::morphix::Mutations::new())
+                }
+                _ => panic!("inconsistent state for FooObserver"),
+            }
+        }
     }
     #[automatically_derived]
     impl<'ob, const N: usize, S: ?Sized, _N> ::std::ops::Deref
@@ -147,10 +183,14 @@ const _: () = {
     }
     #[automatically_derived]
     impl<'ob, const N: usize, S: ?Sized, _N> ::std::ops::DerefMut
-    for FooObserver<'ob, N, S, _N> {
+    for FooObserver<'ob, N, S, _N>
+    where
+        Foo<N>: ::morphix::helper::Capture,
+        S: ::morphix::helper::AsDeref<_N, Target = Foo<N>>,
+        _N: ::morphix::helper::Unsigned,
+    {
         fn deref_mut(&mut self) -> &mut Self::Target {
-            self.mutated = true;
-            self.variant = FooObserverVariant::__Unknown;
+            ::morphix::helper::QuasiObserver::invalidate(self);
             &mut self.ptr
         }
     }
@@ -158,27 +198,43 @@ const _: () = {
     impl<'ob, const N: usize, S: ?Sized, _N> ::morphix::helper::QuasiObserver
     for FooObserver<'ob, N, S, _N>
     where
-        S: ::morphix::helper::AsDeref<_N>,
+        Foo<N>: ::morphix::helper::Capture,
+        S: ::morphix::helper::AsDeref<_N, Target = Foo<N>>,
         _N: ::morphix::helper::Unsigned,
     {
         type Head = S;
         type OuterDepth = ::morphix::helper::Succ<::morphix::helper::Zero>;
         type InnerDepth = _N;
         fn invalidate(this: &mut Self) {
+            if !this.mutated {
+                let value = (*this.ptr).as_deref();
+                this.captor.capture_with(value, || this.variant.inverse(value));
+            }
             this.mutated = true;
             this.variant = FooObserverVariant::__Unknown;
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            if this.mutated {
+                return this.captor.inverse();
+            }
+            this.variant.inverse(head.as_deref())
+        }
     }
     #[automatically_derived]
     impl<'ob, const N: usize, S: ?Sized, _N> ::morphix::observe::Observer
     for FooObserver<'ob, N, S, _N>
     where
+        Foo<N>: ::morphix::helper::Capture,
         S: ::morphix::helper::AsDerefMut<_N, Target = Foo<N>>,
         _N: ::morphix::helper::Unsigned,
     {
         fn observe(head: &mut S) -> Self {
             let __value = head.as_deref_mut();
             Self {
+                captor: ::std::default::Default::default(),
                 mutated: false,
                 variant: FooObserverVariant::observe(__value),
                 ptr: ::morphix::helper::Pointer::new(head),
@@ -206,7 +262,8 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            ::morphix::Mutations::replace(this.as_deref())
+            let mutations = ::morphix::Mutations::replace(value);
+            this.captor.attach(mutations)
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
             let value = this.ptr.as_deref();
@@ -215,7 +272,8 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            ::morphix::Mutations::replace(this.as_deref())
+            let mutations = ::morphix::Mutations::replace(value);
+            this.captor.attach(mutations)
         }
     }
     #[automatically_derived]
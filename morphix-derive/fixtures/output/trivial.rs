@@ -8,7 +8,10 @@ pub struct Foo<T> {
 }
 #[rustfmt::skip]
 #[automatically_derived]
-impl<T> ::morphix::Observe for Foo<T> {
+impl<T> ::morphix::Observe for Foo<T>
+where
+    Self: ::morphix::helper::Capture,
+{
     type Observer<'ob, S, N> = ::morphix::general::ShallowObserver<'ob, S, N>
     where
         Self: 'ob,
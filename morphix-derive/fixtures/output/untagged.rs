@@ -18,6 +18,7 @@ const _: () = {
     #[::std::prelude::v1::derive()]
     pub struct FooObserver<'ob, S: ?Sized, N = ::morphix::helper::Zero> {
         ptr: ::morphix::helper::Pointer<S>,
+        captor: ::morphix::helper::Captor,
         mutated: bool,
         initial: FooObserverInitial,
         variant: FooObserverVariant<'ob>,
@@ -98,9 +99,8 @@ const _: () = {
                     let mutations_1 = unsafe {
                         ::morphix::observe::SerializeObserver::flush(u1)
                     };
-                    if mutations_0.is_replace() && mutations_1.is_replace() {
-                        return ::morphix::Mutations::replace(unsafe { &*__value });
-                    }
+                    let is_replace = mutations_0.is_replace()
+                        && mutations_1.is_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(
                             !mutations_0.is_empty() as usize
@@ -108,18 +108,26 @@ const _: () = {
                         );
                     mutations.insert(0usize, mutations_0);
                     mutations.insert(1usize, mutations_1);
+                    if is_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations);
+                    }
                     mutations
                 }
                 Self::C { bar } => {
                     let mutations_bar = unsafe {
                         ::morphix::observe::SerializeObserver::flush(bar)
                     };
-                    if mutations_bar.is_replace() {
-                        return ::morphix::Mutations::replace(unsafe { &*__value });
-                    }
+                    let is_replace = mutations_bar.is_replace();
                     let mut mutations = ::morphix::Mutations::new()
                         .with_capacity(!mutations_bar.is_empty() as usize);
                     mutations.insert("bar", mutations_bar);
+                    if is_replace {
+                        let value = unsafe { &*__value };
+                        return ::morphix::Mutations::replace(value)
+                            .with_inverses_of(mutations);
+                    }
                     mutations
                 }
                 Self::__Unknown => ::morphix::Mutations::new(),
@@ -143,6 +151,41 @@ const _: () = {
                 _ => panic!("flat_flush can only be called on structs and maps"),
             }
         }
+        fn inverse(&self, value: &Foo) -> ::std::option::Option<::morphix::Mutations> {
+            match (self, value) {
+                (Self::A { 0: u0, .. }, Foo::A { 0: v0, .. }) => {
+                    let mutations = ::morphix::helper::QuasiObserver::inverse(u0, v0)?;
+                    ::std::option::Option::Some(mutations)
+                }
+                (Self::B { 0: u0, 1: u1, .. }, Foo::B { 0: v0, 1: v1, .. }) => {
+                    let mut mutations = ::morphix::Mutations::new();
+                    mutations
+                        .insert(
+                            0usize,
+                            ::morphix::helper::QuasiObserver::inverse(u0, v0)?,
+                        );
+                    mutations
+                        .insert(
+                            1usize,
+                            ::morphix::helper::QuasiObserver::inverse(u1, v1)?,
+                        );
+                    ::std::option::Option::Some(mutations)
+                }
+                (Self::C { bar: u0, .. }, Foo::C { bar: v0, .. }) => {
+                    let mut mutations = ::morphix::Mutations::new();
+                    mutations
+                        .insert(
+                            "bar",
+                            ::morphix::helper::QuasiObserver::inverse(u0, v0)?,
+                        );
+                    ::std::option::Option::Some(mutations)
+                }
+                (Self::__Unknown, _) => {
+                    ::std::option::Option::Some(::morphix::Mutations::new())
+                }
+                _ => panic!("inconsistent state for FooObserver"),
+            }
+        }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::std::ops::Deref for FooObserver<'ob, S, N> {
@@ -152,26 +195,42 @@ const _: () = {
         }
     }
     #[automatically_derived]
-    impl<'ob, S: ?Sized, N> ::std::ops::DerefMut for FooObserver<'ob, S, N> {
+    impl<'ob, S: ?Sized, N> ::std::ops::DerefMut for FooObserver<'ob, S, N>
+    where
+        S: ::morphix::helper::AsDeref<N, Target = Foo>,
+        N: ::morphix::helper::Unsigned,
+    {
         fn deref_mut(&mut self) -> &mut Self::Target {
-            self.mutated = true;
-            self.variant = FooObserverVariant::__Unknown;
+            ::morphix::helper::QuasiObserver::invalidate(self);
             &mut self.ptr
         }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::helper::QuasiObserver for FooObserver<'ob, S, N>
     where
-        S: ::morphix::helper::AsDeref<N>,
+        S: ::morphix::helper::AsDeref<N, Target = Foo>,
         N: ::morphix::helper::Unsigned,
     {
         type Head = S;
         type OuterDepth = ::morphix::helper::Succ<::morphix::helper::Zero>;
         type InnerDepth = N;
         fn invalidate(this: &mut Self) {
+            if !this.mutated {
+                let value = (*this.ptr).as_deref();
+                this.captor.capture_with(value, || this.variant.inverse(value));
+            }
             this.mutated = true;
             this.variant = FooObserverVariant::__Unknown;
         }
+        fn inverse(
+            this: &Self,
+            head: &Self::Head,
+        ) -> ::std::option::Option<::morphix::Mutations> {
+            if this.mutated {
+                return this.captor.inverse();
+            }
+            this.variant.inverse(head.as_deref())
+        }
     }
     #[automatically_derived]
     impl<'ob, S: ?Sized, N> ::morphix::observe::Observer for FooObserver<'ob, S, N>
@@ -182,6 +241,7 @@ const _: () = {
         fn observe(head: &mut S) -> Self {
             let __value = head.as_deref_mut();
             Self {
+                captor: ::std::default::Default::default(),
                 mutated: false,
                 initial: FooObserverInitial::new(__value),
                 variant: FooObserverVariant::observe(__value),
@@ -211,12 +271,13 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::D, Foo::D)
                 | (FooObserverInitial::E, Foo::E())
                 | (FooObserverInitial::F, Foo::F {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
         unsafe fn flat_flush(this: &mut Self) -> ::morphix::Mutations {
             let value = this.ptr.as_deref();
@@ -227,12 +288,13 @@ const _: () = {
             }
             this.mutated = false;
             this.variant = FooObserverVariant::__Unknown;
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 (FooObserverInitial::D, Foo::D)
                 | (FooObserverInitial::E, Foo::E())
                 | (FooObserverInitial::F, Foo::F {}) => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
     }
     #[automatically_derived]
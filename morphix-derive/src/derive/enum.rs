@@ -1,7 +1,7 @@
 use std::mem::take;
 
 use proc_macro2::TokenStream;
-use quote::{format_ident, quote, quote_spanned};
+use quote::{ToTokens, format_ident, quote, quote_spanned};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
@@ -36,6 +36,7 @@ pub fn derive_observe_for_enum(
     let mut variant_relocate_arms = quote! {};
     let mut variant_flush_arms = quote! {};
     let mut variant_flat_flush_arms = quote! {};
+    let mut variant_inverse_arms = quote! {};
 
     let mut errors = quote! {};
     let mut field_tys = vec![];
@@ -91,9 +92,14 @@ pub fn derive_observe_for_enum(
         let mut observe_fields = quote! {};
         let mut relocate_stmts = quote! {};
         let mut mutation_idents = vec![];
+        let mut flat_mutation_idents = vec![];
         let mut flush_field_stmts = quote! {};
         let mut flush_mutation_stmts = quote! {};
         let mut flush_capacity = vec![];
+        let mut inverse_members = vec![];
+        let mut inverse_ob_idents = vec![];
+        let mut inverse_value_idents = vec![];
+        let mut inverse_stmts = quote! {};
         let mut has_skipped = false;
 
         let field_count = variant.fields.len();
@@ -145,6 +151,15 @@ pub fn derive_observe_for_enum(
             }
 
             flush_idents.push(quote! { #flush_ident });
+            inverse_members.push(match field_ident {
+                Some(field_ident) => quote! { #field_ident },
+                None => syn::Index::from(index).to_token_stream(),
+            });
+            inverse_ob_idents.push(quote! { #ob_ident });
+            inverse_value_idents.push(quote! { #value_ident });
+            let field_inverse = quote_spanned! { field_span =>
+                ::morphix::helper::QuasiObserver::inverse(#ob_ident, #value_ident)?
+            };
             let ob_field_ty: syn::Type = match &field_meta.general_impl {
                 None => parse_quote_spanned! { field_span =>
                     ::morphix::observe::DefaultObserver<#ob_lt, #field_ty>
@@ -186,6 +201,7 @@ pub fn derive_observe_for_enum(
                 flush_field_stmts.extend(quote_spanned! { field_span =>
                     let #mutation_ident = unsafe { ::morphix::observe::SerializeObserver::flat_flush(#flush_ident) };
                 });
+                flat_mutation_idents.push(mutation_ident.clone());
                 flush_capacity.push(quote_spanned! { field_span =>
                     #mutation_ident.len()
                 });
@@ -204,6 +220,9 @@ pub fn derive_observe_for_enum(
                         mutations.extend(#mutation_ident);
                     });
                 }
+                inverse_stmts.extend(quote_spanned! { field_span =>
+                    mutations.extend(#field_inverse);
+                });
             } else {
                 flush_field_stmts.extend(quote_spanned! { field_span =>
                     let #mutation_ident = unsafe { ::morphix::observe::SerializeObserver::flush(#flush_ident) };
@@ -231,6 +250,9 @@ pub fn derive_observe_for_enum(
                         mutations.insert(#segment, #mutation_ident);
                     });
                 }
+                inverse_stmts.extend(quote_spanned! { field_span =>
+                    mutations.insert(#segment, #field_inverse);
+                });
             }
             mutation_idents.push(mutation_ident);
         }
@@ -250,26 +272,68 @@ pub fn derive_observe_for_enum(
         } else {
             quote! {{
                 #flush_field_stmts
-                if #(#mutation_idents.is_replace())&&* {
-                    return ::morphix::Mutations::replace(unsafe { &*__value });
-                }
+                let is_replace = #(#mutation_idents.is_replace())&&* #(|| #flat_mutation_idents.is_root_replace())*;
                 let mut mutations = ::morphix::Mutations::new().with_capacity(#(#flush_capacity)+*);
                 #flush_mutation_stmts
+                if is_replace {
+                    let value = unsafe { &*__value };
+                    return ::morphix::Mutations::replace(value).with_inverses_of(mutations #mutations_chain);
+                }
                 mutations #mutations_chain
             }}
         };
 
+        let variant_inverse_expr = if inverse_members.is_empty() {
+            quote! { ::std::option::Option::Some(::morphix::Mutations::new()) }
+        } else if matches!(&variant.fields, syn::Fields::Unnamed(_)) && field_count == 1 {
+            let ob_ident = &inverse_ob_idents[0];
+            let value_ident = &inverse_value_idents[0];
+            quote! {{
+                let mutations = ::morphix::helper::QuasiObserver::inverse(#ob_ident, #value_ident)?;
+                ::std::option::Option::Some(mutations #mutations_chain)
+            }}
+        } else {
+            quote! {{
+                let mut mutations = ::morphix::Mutations::new();
+                #inverse_stmts
+                ::std::option::Option::Some(mutations #mutations_chain)
+            }}
+        };
+        variant_inverse_arms.extend(quote! {
+            (
+                Self::#variant_ident { #(#inverse_members: #inverse_ob_idents,)* .. },
+                #input_ident::#variant_ident { #(#inverse_members: #inverse_value_idents,)* .. },
+            ) => #variant_inverse_expr,
+        });
+
         let variant_flat_flush_expr = match &variant.fields {
             syn::Fields::Named(_) => {
                 if flush_capacity.is_empty() {
                     Some(quote! { ::morphix::Mutations::new() })
                 } else {
+                    let mut flush_root_replace_stmts = quote! {};
+                    let mut flush_root_replace_return = quote! {};
+                    if !flat_mutation_idents.is_empty() {
+                        // A flattened field which cannot be broken apart is replaced along with
+                        // this enum.
+                        flush_root_replace_stmts = quote! {
+                            let is_root_replace = #(#flat_mutation_idents.is_root_replace())||*;
+                        };
+                        flush_root_replace_return = quote! {
+                            if is_root_replace {
+                                let value = unsafe { &*__value };
+                                return ::morphix::Mutations::replace(value).with_inverses_of(mutations #mutations_chain);
+                            }
+                        };
+                    }
                     Some(quote! {{
                         #flush_field_stmts
+                        #flush_root_replace_stmts
                         let mut mutations = ::morphix::Mutations::new()
                             .with_capacity(#(#flush_capacity)+*)
                             .with_replace(#(#mutation_idents.is_replace())&&*);
                         #flush_mutation_stmts
+                        #flush_root_replace_return
                         mutations #mutations_chain
                     }})
                 }
@@ -366,6 +430,9 @@ pub fn derive_observe_for_enum(
     variant_flat_flush_arms.extend(quote! {
         _ => panic!("flat_flush can only be called on structs and maps"),
     });
+    variant_inverse_arms.extend(quote! {
+        (Self::__Unknown, _) => ::std::option::Option::Some(::morphix::Mutations::new()),
+    });
 
     let ob_flush_prefix_stmt = if has_initial {
         quote! {
@@ -377,14 +444,16 @@ pub fn derive_observe_for_enum(
     };
     let ob_flush_suffix_stmt = if has_initial {
         quote! {
-            match (initial, value) {
+            let mutations = match (initial, value) {
                 #initial_flush_pats => ::morphix::Mutations::new(),
                 _ => ::morphix::Mutations::replace(value),
-            }
+            };
+            this.captor.attach(mutations)
         }
     } else {
         quote! {
-            ::morphix::Mutations::replace(this.as_deref())
+            let mutations = ::morphix::Mutations::replace(value);
+            this.captor.attach(mutations)
         }
     };
 
@@ -418,6 +487,57 @@ pub fn derive_observe_for_enum(
     let (ob_impl_generics, ob_type_generics, _) = ob_generics.split_for_impl();
     let (ob_variant_impl_generics, ob_variant_type_generics, _) = ob_variant_generics.split_for_impl();
 
+    let ob_invalidate_stmt = if has_variant {
+        let variant_inverse = match cfg!(feature = "inverse") {
+            true => quote! { this.variant.inverse(value) },
+            false => quote! { ::std::option::Option::None },
+        };
+        quote! {
+            if !this.mutated {
+                let value = (*this.ptr).as_deref();
+                this.captor.capture_with(value, || #variant_inverse);
+            }
+            this.mutated = true;
+            this.variant = #ob_variant_ident::__Unknown;
+        }
+    } else {
+        quote! {
+            this.captor.capture((*this.ptr).as_deref());
+        }
+    };
+
+    let ob_inverse_impl = if !cfg!(feature = "inverse") {
+        quote! {}
+    } else if has_variant {
+        quote! {
+            fn inverse(this: &Self, head: &Self::Head) -> ::std::option::Option<::morphix::Mutations> {
+                if this.mutated {
+                    return this.captor.inverse();
+                }
+                this.variant.inverse(head.as_deref())
+            }
+        }
+    } else {
+        quote! {
+            fn inverse(this: &Self, _: &Self::Head) -> ::std::option::Option<::morphix::Mutations> {
+                this.captor.inverse()
+            }
+        }
+    };
+
+    let variant_inverse_impl = if cfg!(feature = "inverse") {
+        quote! {
+            fn inverse(&self, value: &#input_ident #input_type_generics) -> ::std::option::Option<::morphix::Mutations> {
+                match (self, value) {
+                    #variant_inverse_arms
+                    _ => panic!(#inconsistent_state),
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let input_trivial = input.generics.params.is_empty();
     let input_serialize_predicates = if input_trivial {
         quote! {}
@@ -426,6 +546,13 @@ pub fn derive_observe_for_enum(
             #input_ident #input_type_generics: ::morphix::helper::serde::Serialize + 'static,
         }
     };
+    let input_capture_predicates = if input_trivial {
+        quote! {}
+    } else {
+        quote! {
+            #input_ident #input_type_generics: ::morphix::helper::Capture,
+        }
+    };
     let self_serialize_predicates = if input_trivial {
         quote! {}
     } else {
@@ -507,6 +634,8 @@ pub fn derive_observe_for_enum(
                     #variant_flat_flush_arms
                 }
             }
+
+            #variant_inverse_impl
         }
     };
 
@@ -518,6 +647,7 @@ pub fn derive_observe_for_enum(
             #(#field_tys: ::morphix::Observe + #ob_lt),*
         {
             ptr: ::morphix::helper::Pointer<#head>,
+            captor: ::morphix::helper::Captor,
             #(#if_has_variant mutated: bool,)*
             #(#if_has_initial initial: #ob_initial_ident,)*
             #(#if_has_variant variant: #ob_variant_ident #ob_variant_type_generics,)*
@@ -545,14 +675,14 @@ pub fn derive_observe_for_enum(
         impl #ob_impl_generics ::std::ops::DerefMut
         for #ob_ident #ob_type_generics
         where
+            #input_capture_predicates
             #(#input_predicates,)*
             #(#field_tys: ::morphix::Observe,)*
+            #head: ::morphix::helper::AsDeref<#depth, Target = #input_ident #input_type_generics>,
+            #depth: ::morphix::helper::Unsigned,
         {
             fn deref_mut(&mut self) -> &mut Self::Target {
-                #(#if_has_variant
-                    self.mutated = true;
-                    self.variant = #ob_variant_ident::__Unknown;
-                )*
+                ::morphix::helper::QuasiObserver::invalidate(self);
                 &mut self.ptr
             }
         }
@@ -561,9 +691,10 @@ pub fn derive_observe_for_enum(
         impl #ob_impl_generics ::morphix::helper::QuasiObserver
         for #ob_ident #ob_type_generics
         where
+            #input_capture_predicates
             #(#input_predicates,)*
             #(#field_tys: ::morphix::Observe,)*
-            #head: ::morphix::helper::AsDeref<#depth>,
+            #head: ::morphix::helper::AsDeref<#depth, Target = #input_ident #input_type_generics>,
             #depth: ::morphix::helper::Unsigned,
         {
             type Head = #head;
@@ -571,17 +702,17 @@ pub fn derive_observe_for_enum(
             type InnerDepth = #depth;
 
             fn invalidate(this: &mut Self) {
-                #(#if_has_variant
-                    this.mutated = true;
-                    this.variant = #ob_variant_ident::__Unknown;
-                )*
+                #ob_invalidate_stmt
             }
+
+            #ob_inverse_impl
         }
 
         #[automatically_derived]
         impl #ob_impl_generics ::morphix::observe::Observer
         for #ob_ident #ob_type_generics
         where
+            #input_capture_predicates
             #(#input_predicates,)*
             #(#skipped_tys: #ob_lt,)*
             #(#field_tys: ::morphix::Observe,)*
@@ -591,6 +722,7 @@ pub fn derive_observe_for_enum(
             fn observe(head: &mut #head) -> Self {
                 let __value = head.as_deref_mut();
                 Self {
+                    captor: ::std::default::Default::default(),
                     #(#if_has_variant mutated: false,)*
                     #(#if_has_initial initial: #ob_initial_ident::new(__value),)*
                     #(#if_has_variant variant: #ob_variant_ident::observe(__value),)*
@@ -146,7 +146,7 @@ impl ObserveMeta {
                 self.general_impl = Some(GeneralImpl {
                     ob_ident: syn::Ident::new("ShallowObserver", arg.ident.span()),
                     spec_ident: syn::Ident::new("DefaultSpec", arg.ident.span()),
-                    bounds: Default::default(),
+                    bounds: parse_quote! { ::morphix::helper::Capture },
                     extra_derive: derive_default,
                 });
             }
@@ -158,6 +158,20 @@ impl ObserveMeta {
                     extra_derive: derive_snapshot,
                 });
             }
+            "counter" => {
+                if attribute_kind != AttributeKind::Field {
+                    errors.extend(
+                        syn::Error::new(arg.ident.span(), "the 'counter' argument is only allowed on fields")
+                            .to_compile_error(),
+                    );
+                }
+                self.general_impl = Some(GeneralImpl {
+                    ob_ident: syn::Ident::new("CounterObserver", arg.ident.span()),
+                    spec_ident: syn::Ident::new("SnapshotSpec", arg.ident.span()),
+                    bounds: parse_quote! { ::morphix::general::Counter },
+                    extra_derive: derive_snapshot,
+                });
+            }
             "deref" => {
                 if attribute_kind != AttributeKind::Field || derive_kind != DeriveKind::Struct {
                     errors.extend(
@@ -255,7 +269,7 @@ impl ObserveMeta {
             _ => errors.extend(
                 syn::Error::new(
                     arg.ident.span(),
-                    "unknown argument, expected 'deref', 'shallow', 'skip' or 'snapshot'",
+                    "unknown argument, expected 'counter', 'deref', 'shallow', 'skip' or 'snapshot'",
                 )
                 .to_compile_error(),
             ),
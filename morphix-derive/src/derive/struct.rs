@@ -39,10 +39,14 @@ pub fn derive_observe_for_struct(
     let mut observe_fields = quote! {};
     let mut relocate_stmts = quote! {};
     let mut mutation_idents = vec![];
+    let mut flat_mutation_idents = vec![];
     let mut has_flush_delete = false;
     let mut flush_field_stmts = quote! {};
     let mut flush_mutation_stmts = quote! {};
     let mut flush_capacity = vec![];
+    let mut inverse_stmts = quote! {};
+    let mut inverse_uses_value = false;
+    let mut newtype_inverse = None;
     let mut debug_chain = quote! {};
 
     let mut field_tys = vec![];
@@ -92,6 +96,14 @@ pub fn derive_observe_for_struct(
 
         let field_ty = &field.ty;
         let field_trivial = !GenericsDetector::detect(field_ty, &input.generics);
+        let field_value = match field_meta.deref {
+            Some(_) => quote! { head },
+            None => quote! { &__value.#field_member },
+        };
+        let field_inverse = quote_spanned! { field_span =>
+            ::morphix::helper::QuasiObserver::inverse(&this.#field_member, #field_value)
+        };
+        newtype_inverse = Some(field_inverse.clone());
         if field_meta.skip || field_meta.serde.skip || field_meta.serde.skip_serializing {
             if !field_trivial {
                 skipped_tys.push(quote! { #field_ty });
@@ -108,6 +120,7 @@ pub fn derive_observe_for_struct(
             continue;
         }
 
+        inverse_uses_value |= field_meta.deref.is_none();
         if let Some(deref_ident) = field_meta.deref {
             let ob_field_ty = match &field_meta.general_impl {
                 None => quote_spanned! { field_span =>
@@ -154,6 +167,7 @@ pub fn derive_observe_for_struct(
             flush_field_stmts.extend(quote_spanned! { field_span =>
                 let #mutation_ident = unsafe { ::morphix::observe::SerializeObserver::flat_flush(&mut this.#field_member) };
             });
+            flat_mutation_idents.push(mutation_ident.clone());
             flush_capacity.push(quote_spanned! { field_span =>
                 #mutation_ident.len()
             });
@@ -173,6 +187,9 @@ pub fn derive_observe_for_struct(
                     mutations.extend(#mutation_ident);
                 });
             }
+            inverse_stmts.extend(quote_spanned! { field_span =>
+                mutations.extend(#field_inverse?);
+            });
         } else {
             flush_field_stmts.extend(quote_spanned! { field_span =>
                 let #mutation_ident = unsafe { ::morphix::observe::SerializeObserver::flush(&mut this.#field_member) };
@@ -201,6 +218,9 @@ pub fn derive_observe_for_struct(
                     mutations.insert(#segment, #mutation_ident);
                 });
             }
+            inverse_stmts.extend(quote_spanned! { field_span =>
+                mutations.insert(#segment, #field_inverse?);
+            });
         }
         mutation_idents.push(mutation_ident);
     }
@@ -223,7 +243,7 @@ pub fn derive_observe_for_struct(
     let (input_impl_generics, input_type_generics, _) = input_generics.split_for_impl();
 
     let mut ob_generics = input_generics.clone();
-    let mut ob_quasi_generics;
+    let ob_quasi_generics;
     let mut ob_observer_generics = input_generics.clone();
 
     let deref_ident;
@@ -247,7 +267,7 @@ pub fn derive_observe_for_struct(
             .push(parse_quote! { #depth = ::morphix::helper::Zero });
         ob_quasi_generics = ob_generics.clone();
         ob_quasi_predicates = quote! {
-            #head: ::morphix::helper::AsDeref<#depth>,
+            #head: ::morphix::helper::AsDeref<#depth, Target = #input_ident #input_type_generics>,
         };
         ob_observer_generics.params.insert(0, parse_quote! { #ob_lt });
         ob_observer_generics.params.push(parse_quote! { #head: ?Sized });
@@ -318,10 +338,7 @@ pub fn derive_observe_for_struct(
         };
 
         flush_replace = quote! {
-            if #(#mutation_idents.is_replace())&&* {
-                let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
-                return ::morphix::Mutations::replace(value);
-            }
+            let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
         };
 
         input_observe_predicates = quote! {};
@@ -363,14 +380,13 @@ pub fn derive_observe_for_struct(
             ob_observer_generics.params.insert(0, parse_quote! { #ob_lt });
         }
         ob_generics.params.push(parse_quote! { #inner });
-        ob_quasi_generics = ob_generics.clone();
-        ob_quasi_generics.params.push(parse_quote! { #depth });
         ob_quasi_predicates = quote! {
             #inner: ::morphix::helper::QuasiObserver<InnerDepth = ::morphix::helper::Succ<#depth>>,
-            #inner::Head: ::morphix::helper::AsDeref<#depth>,
+            #inner::Head: ::morphix::helper::AsDeref<#depth, Target = #input_ident #input_type_generics>,
         };
         ob_observer_generics.params.push(parse_quote! { #inner });
         ob_observer_generics.params.push(parse_quote! { #depth });
+        ob_quasi_generics = ob_observer_generics.clone();
         ob_observer_predicates = quote! {
             #inner: ::morphix::observe::Observer<InnerDepth = ::morphix::helper::Succ<#depth>>,
             #inner::Head: ::morphix::helper::AsDerefMut<#depth, Target = #input_ident #input_type_generics>,
@@ -432,12 +448,9 @@ pub fn derive_observe_for_struct(
         };
 
         flush_replace = quote! {
-            if #(#mutation_idents.is_replace())&&* {
-                // let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
-                let head = &**(*this).as_deref_coinductive();
-                let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
-                return ::morphix::Mutations::replace(value);
-            }
+            // let value = ::morphix::helper::QuasiObserver::untracked_ref(&*this);
+            let head = &**(*this).as_deref_coinductive();
+            let value = ::morphix::helper::AsDeref::<N>::as_deref(head);
         };
 
         input_observe_predicates = quote! { #field_ty: ::morphix::Observe, };
@@ -494,24 +507,69 @@ pub fn derive_observe_for_struct(
     } else {
         quote! {
             #flush_field_stmts
-            #flush_replace
+            let is_replace = #(#mutation_idents.is_replace())&&* #(|| #flat_mutation_idents.is_root_replace())*;
             let mut mutations = ::morphix::Mutations::new().with_capacity(#(#flush_capacity)+*);
             #flush_mutation_stmts
+            if is_replace {
+                #flush_replace
+                return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+            }
             mutations
         }
     };
 
+    let inverse_impl = if cfg!(feature = "inverse") {
+        let prepare_value = match inverse_uses_value {
+            true => quote! {
+                let __value = ::morphix::helper::AsDeref::<#depth>::as_deref(head);
+            },
+            false => quote! {},
+        };
+        let inverse_body = match newtype_inverse {
+            Some(field_inverse) if !is_named && field_count == 1 => field_inverse,
+            _ => quote! {
+                let mut mutations = ::morphix::Mutations::new();
+                #inverse_stmts
+                ::std::option::Option::Some(mutations)
+            },
+        };
+        quote! {
+            fn inverse(this: &Self, head: &Self::Head) -> ::std::option::Option<::morphix::Mutations> {
+                #prepare_value
+                #inverse_body
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let flat_flush_impl = if !is_named && field_count == 1 {
         quote! {
             unsafe { ::morphix::observe::SerializeObserver::flat_flush(&mut this.0) }
         }
     } else {
+        let mut flush_root_replace_stmts = quote! {};
+        let mut flush_root_replace_return = quote! {};
+        if !flat_mutation_idents.is_empty() {
+            // A flattened field which cannot be broken apart is replaced along with this struct.
+            flush_root_replace_stmts = quote! {
+                let is_root_replace = #(#flat_mutation_idents.is_root_replace())||*;
+            };
+            flush_root_replace_return = quote! {
+                if is_root_replace {
+                    #flush_replace
+                    return ::morphix::Mutations::replace(value).with_inverses_of(mutations);
+                }
+            };
+        }
         quote! {
             #flush_field_stmts
+            #flush_root_replace_stmts
             let mut mutations = ::morphix::Mutations::new()
                 .with_capacity(#(#flush_capacity)+*)
                 .with_replace(#(#mutation_idents.is_replace())&&*);
             #flush_mutation_stmts
+            #flush_root_replace_return
             mutations
         }
     };
@@ -560,6 +618,8 @@ pub fn derive_observe_for_struct(
             fn invalidate(this: &mut Self) {
                 #invalidate_impl
             }
+
+            #inverse_impl
         }
 
         #[automatically_derived]
@@ -28,6 +28,8 @@ mod observe;
 ///   field
 /// - `#[morphix(snapshot)]` — use [`SnapshotObserver`](morphix::observe::SnapshotObserver) for this
 ///   field
+/// - `#[morphix(counter)]` — use [`CounterObserver`](morphix::general::CounterObserver) for this
+///   field, reporting `+=` and `-=` as increments
 ///
 /// These attributes allow you to override the default [`Observer`](morphix::observe::Observer) type
 /// that would otherwise come from the field's [`Observe`](morphix::Observe) implementation.
@@ -275,3 +275,32 @@ fn tuple_variant_multi_field() {
         Some(batch!(Pair, replace!(0, json!(42)), append!(1, json!("!"))))
     );
 }
+
+#[test]
+fn flush_inverse() {
+    use morphix::Adapter;
+
+    for (before, after) in [
+        (Shape::Circle { radius: 3.0 }, Shape::Point),
+        (Shape::Point, Shape::Origin),
+        (
+            Shape::Origin,
+            Shape::Rectangle {
+                width: 1.0,
+                height: 2.0,
+            },
+        ),
+    ] {
+        let mut s = before;
+        let mut replica = serde_json::to_value(&s).unwrap();
+        let mut ob = s.__observe();
+        **ob = after;
+        let (Json(forward), inverse) = ob.flush_inverse::<Json>().unwrap();
+        let Some(Json(inverse)) = inverse else { unreachable!() };
+        let original = replica.clone();
+        Json::mutate(&mut replica, forward.unwrap(), &mut Default::default()).unwrap();
+        assert_eq!(replica, serde_json::to_value(&s).unwrap());
+        Json::mutate(&mut replica, inverse.unwrap(), &mut Default::default()).unwrap();
+        assert_eq!(replica, original);
+    }
+}
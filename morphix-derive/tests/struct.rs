@@ -1,5 +1,8 @@
-use morphix::adapter::Json;
-use morphix::{Observe, observe};
+use std::collections::HashMap;
+
+use morphix::adapter::{Json, MessagePack};
+use morphix::observe::{ObserveExt, SerializeObserverExt};
+use morphix::{Adapter, MutationKind, Observe, observe};
 use morphix_test_utils::*;
 use serde::Serialize;
 use serde_json::json;
@@ -281,6 +284,29 @@ fn serde_flatten_with_normal_field() {
     );
 }
 
+#[derive(Serialize, Observe)]
+struct WithFlattenMap {
+    a: i32,
+    #[serde(flatten)]
+    map: HashMap<(i32, i32), String>,
+}
+
+#[test]
+fn serde_flatten_composite_keys() {
+    let mut w = WithFlattenMap {
+        a: 1,
+        map: HashMap::from([((1, 2), "x".into())]),
+    };
+    let MessagePack(mutation) = observe!(w => {
+        w.map.get_mut(&(1, 2)).unwrap().push('!');
+    })
+    .unwrap();
+    // Tuple keys cannot be flattened into path segments, so the whole struct is replaced
+    let mutation = mutation.unwrap();
+    assert!(mutation.path.is_empty());
+    assert!(matches!(mutation.kind, MutationKind::Replace(_)));
+}
+
 #[derive(Serialize, Observe)]
 struct WithVec {
     items: Vec<i32>,
@@ -295,3 +321,79 @@ fn vec_field_append() {
     .unwrap();
     assert_eq!(mutation, Some(append!(items, json!([3]))));
 }
+
+#[derive(Serialize, Observe)]
+struct WithCounter {
+    #[morphix(counter)]
+    views: u64,
+    #[morphix(counter)]
+    score: f64,
+    likes: u64,
+}
+
+#[test]
+fn counter_field_increment() {
+    let mut w = WithCounter {
+        views: 10,
+        score: 0.5,
+        likes: 3,
+    };
+    let Json(mutation) = observe!(w => {
+        w.views += 2;
+        w.score -= 1.0;
+        w.likes += 1;
+    })
+    .unwrap();
+    assert_eq!(
+        mutation,
+        Some(batch!(
+            _,
+            increment!(views, json!(2)),
+            increment!(score, json!(-1.0)),
+            replace!(likes, json!(4)),
+        ))
+    );
+
+    let Json(mutation) = observe!(w => {
+        w.views += 1;
+        w.views = 20;
+    })
+    .unwrap();
+    assert_eq!(mutation, Some(replace!(views, json!(20))));
+}
+
+#[test]
+fn flush_inverse() {
+    let mut w = WithVec { items: vec![1, 2, 3] };
+    let mut replica = serde_json::to_value(&w).unwrap();
+    let mut ob = w.__observe();
+    ob.items.truncate(1);
+    ob.items.push(4);
+    let (Json(forward), inverse) = ob.flush_inverse::<Json>().unwrap();
+    let Some(Json(inverse)) = inverse else { unreachable!() };
+    assert_eq!(forward, Some(batch!(items, truncate!(_, 2), append!(_, json!([4])))));
+    assert_eq!(inverse, Some(batch!(items, truncate!(_, 1), append!(_, json!([2, 3])))));
+    Json::mutate(&mut replica, forward.unwrap(), &mut Default::default()).unwrap();
+    assert_eq!(replica, json!({"items": [1, 4]}));
+
+    let (Json(forward), empty) = ob.flush_inverse::<Json>().unwrap();
+    assert_eq!((forward, empty.map(|Json(empty)| empty)), (None, Some(None)));
+
+    Json::mutate(&mut replica, inverse.unwrap(), &mut Default::default()).unwrap();
+    assert_eq!(replica, json!({"items": [1, 2, 3]}));
+}
+
+#[test]
+fn flush_inverse_of_replace() {
+    let mut w = WithVec { items: vec![1, 2, 3] };
+    let mut replica = serde_json::to_value(&w).unwrap();
+    let mut ob = w.__observe();
+    ob.items.push(4);
+    **ob = WithVec { items: vec![5] };
+    let (Json(forward), inverse) = ob.flush_inverse::<Json>().unwrap();
+    let Some(Json(inverse)) = inverse else { unreachable!() };
+    assert_eq!(forward, Some(replace!(_, json!({"items": [5]}))));
+    Json::mutate(&mut replica, forward.unwrap(), &mut Default::default()).unwrap();
+    Json::mutate(&mut replica, inverse.unwrap(), &mut Default::default()).unwrap();
+    assert_eq!(replica, json!({"items": [1, 2, 3]}));
+}
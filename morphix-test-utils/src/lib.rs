@@ -15,6 +15,11 @@
 //! - [`replace!(path, value)`](replace!)
 //! - [`append!(path, value)`](append!) (feature `append`)
 //! - [`truncate!(path, len)`](truncate!) (feature `truncate`)
+//! - [`splice!(path, index, delete_len, value)`](splice!) (feature `splice`)
+//! - [`mv!(path, from, to)`](mv!) (feature `move`)
+//! - [`increment!(path, delta)`](increment!) (feature `increment`)
+//! - [`add!(path, members)`](add!) (feature `set`)
+//! - [`remove!(path, members)`](remove!) (feature `set`)
 //! - [`delete!(path)`](delete!) (feature `delete`)
 //! - [`batch!(path, items...)`](batch!)
 
@@ -91,6 +96,88 @@ macro_rules! truncate {
     ($($all:tt)*) => { $crate::truncate!(@parse [] $($all)*) };
 }
 
+#[cfg(feature = "splice")]
+#[macro_export]
+macro_rules! splice {
+    (@parse [$($path:tt)*], $index:expr, $delete_len:expr, $value:expr) => {
+        ::morphix::Mutation {
+            path: $crate::__mutation_path!($($path)*).into(),
+            kind: ::morphix::MutationKind::Splice {
+                index: $index,
+                delete_len: $delete_len,
+                insert: $value,
+            },
+        }
+    };
+    (@parse [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::splice!(@parse [$($path)* $next] $($rest)*)
+    };
+    ($($all:tt)*) => { $crate::splice!(@parse [] $($all)*) };
+}
+
+#[cfg(feature = "move")]
+#[macro_export]
+macro_rules! mv {
+    (@parse [$($path:tt)*], $from:expr, $to:expr) => {
+        ::morphix::Mutation {
+            path: $crate::__mutation_path!($($path)*).into(),
+            kind: ::morphix::MutationKind::Move {
+                from: $from,
+                to: $to,
+            },
+        }
+    };
+    (@parse [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::mv!(@parse [$($path)* $next] $($rest)*)
+    };
+    ($($all:tt)*) => { $crate::mv!(@parse [] $($all)*) };
+}
+
+#[cfg(feature = "increment")]
+#[macro_export]
+macro_rules! increment {
+    (@parse [$($path:tt)*], $value:expr) => {
+        ::morphix::Mutation {
+            path: $crate::__mutation_path!($($path)*).into(),
+            kind: ::morphix::MutationKind::Increment($value),
+        }
+    };
+    (@parse [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::increment!(@parse [$($path)* $next] $($rest)*)
+    };
+    ($($all:tt)*) => { $crate::increment!(@parse [] $($all)*) };
+}
+
+#[cfg(feature = "set")]
+#[macro_export]
+macro_rules! add {
+    (@parse [$($path:tt)*], $value:expr) => {
+        ::morphix::Mutation {
+            path: $crate::__mutation_path!($($path)*).into(),
+            kind: ::morphix::MutationKind::Add($value),
+        }
+    };
+    (@parse [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::add!(@parse [$($path)* $next] $($rest)*)
+    };
+    ($($all:tt)*) => { $crate::add!(@parse [] $($all)*) };
+}
+
+#[cfg(feature = "set")]
+#[macro_export]
+macro_rules! remove {
+    (@parse [$($path:tt)*], $value:expr) => {
+        ::morphix::Mutation {
+            path: $crate::__mutation_path!($($path)*).into(),
+            kind: ::morphix::MutationKind::Remove($value),
+        }
+    };
+    (@parse [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::remove!(@parse [$($path)* $next] $($rest)*)
+    };
+    ($($all:tt)*) => { $crate::remove!(@parse [] $($all)*) };
+}
+
 #[cfg(feature = "delete")]
 #[macro_export]
 macro_rules! delete {
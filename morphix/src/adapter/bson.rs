@@ -0,0 +1,358 @@
+use bson::ser::{Error, Serializer};
+
+#[cfg(feature = "set")]
+use crate::adapter::members::{add_members_by, hash_member, remove_members_by};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
+use crate::{Adapter, Mutation, Mutations, PathSegment};
+
+type Value = bson::Bson;
+
+/// BSON adapter for morphix mutation serialization.
+///
+/// [`Bson`] implements the [`Adapter`] trait using [`bson::Bson`]. Its mutations can be converted
+/// into MongoDB update documents with [`MongoUpdate`](super::MongoUpdate).
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::Bson;
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     value: i32,
+/// }
+///
+/// let mut data = Data { value: 42 };
+/// let Bson(mutation) = observe!(data => {
+///     data.value += 1;
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bson(pub Option<Mutation<Value>>);
+
+impl From<Option<Mutation<Value>>> for Bson {
+    fn from(mutation: Option<Mutation<Value>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<Bson> for Option<Mutation<Value>> {
+    fn from(Bson(mutation): Bson) -> Self {
+        mutation
+    }
+}
+
+impl Adapter for Bson {
+    type Value = Value;
+    type Error = Error;
+
+    fn from_mutations(mutation: Mutations) -> Result<Self, Self::Error> {
+        Ok(Self(
+            mutation
+                .into_inner()
+                .map(|mutation| mutation.try_map(|value| erased_serde::serialize(&*value, Serializer::new())))
+                .transpose()?,
+        ))
+    }
+
+    fn get_mut<'a>(
+        value: &'a mut Self::Value,
+        segment: &PathSegment,
+        allow_create: bool,
+    ) -> Option<&'a mut Self::Value> {
+        match (value, segment) {
+            (Value::Array(vec), PathSegment::Positive(index)) => vec.get_mut(*index),
+            (Value::Array(vec), PathSegment::Negative(index)) => {
+                vec.len().checked_sub(*index).and_then(|i| vec.get_mut(i))
+            }
+            (Value::Document(document), segment) => {
+                let key = segment.as_key()?;
+                if allow_create {
+                    Some(document.entry(key.into_owned()).or_insert(Value::Null))
+                } else {
+                    document.get_mut(&*key)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "delete")]
+    fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
+        match (value, segment) {
+            (Value::Document(document), segment) => document.remove(&*segment.as_key()?),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "append")]
+    fn append(value: &mut Self::Value, append_value: Self::Value) -> Option<usize> {
+        match (value, append_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let len = rhs.chars().count();
+                *lhs += &rhs;
+                Some(len)
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let len = rhs.len();
+                lhs.extend(rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "append", feature = "splice"))]
+    fn len(value: &Self::Value) -> Option<usize> {
+        match value {
+            Value::String(str) => Some(str.chars().count()),
+            Value::Array(vec) => Some(vec.len()),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "truncate")]
+    fn truncate(value: &mut Self::Value, mut truncate_len: usize) -> Option<usize> {
+        match value {
+            Value::String(str) => {
+                let mut chars = str.char_indices();
+                let mut new_len = str.len();
+                while truncate_len > 0
+                    && let Some((index, _)) = chars.next_back()
+                {
+                    truncate_len -= 1;
+                    new_len = index;
+                }
+                str.truncate(new_len);
+                Some(truncate_len)
+            }
+            Value::Array(vec) => {
+                let actual_len = vec.len();
+                let new_len = actual_len.saturating_sub(truncate_len);
+                vec.truncate(new_len);
+                Some(truncate_len.saturating_sub(actual_len))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (value, insert_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let start = char_index(lhs, index)?;
+                let end = start + char_index(&lhs[start..], delete_len)?;
+                let len = rhs.chars().count();
+                lhs.replace_range(start..end, &rhs);
+                Some(len)
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let len = rhs.len();
+                lhs.splice(index..end, rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match value {
+            Value::Array(vec) => {
+                if from >= vec.len() || to >= vec.len() {
+                    return None;
+                }
+                let element = vec.remove(from);
+                vec.insert(to, element);
+                Some(())
+            }
+            Value::Document(document) => {
+                if from >= document.len() || to >= document.len() {
+                    return None;
+                }
+                let mut entries = std::mem::take(document).into_iter().collect::<Vec<_>>();
+                let entry = entries.remove(from);
+                entries.insert(to, entry);
+                document.extend(entries);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(value, delta, |value, delta| {
+            let sum = add_numbers(value, &delta)?;
+            Value::set_number(value, sum);
+            Some(())
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let added = add_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(added))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let removed = remove_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(removed))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Hashes a set member for [`add_members_by`] and [`remove_members_by`], since [`Value`] does not
+/// implement [`Hash`](std::hash::Hash). Compound values are only hashed by their length.
+#[cfg(feature = "set")]
+fn member_hash(value: &Value) -> u64 {
+    let discriminant = std::mem::discriminant(value);
+    match value {
+        Value::String(str) | Value::Symbol(str) => hash_member(&(discriminant, str)),
+        Value::Boolean(bool) => hash_member(&(discriminant, bool)),
+        Value::Int32(int) => hash_member(&(discriminant, int)),
+        Value::Int64(int) => hash_member(&(discriminant, int)),
+        Value::ObjectId(id) => hash_member(&(discriminant, id.bytes())),
+        Value::Array(vec) => hash_member(&(discriminant, vec.len())),
+        Value::Document(document) => hash_member(&(discriminant, document.len())),
+        _ => hash_member(&discriminant),
+    }
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        match self {
+            Value::Int32(value) => Some((*value).into()),
+            Value::Int64(value) => Some((*value).into()),
+            _ => None,
+        }
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        i64::try_from(value).ok().map(Value::Int64)
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Int32(value) => Some((*value).into()),
+            Value::Int64(value) => Some(*value as f64),
+            Value::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(Value::Double(value))
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Value {
+    type Number = Self;
+
+    fn duration_delta(&self) -> Option<[&Self; 2]> {
+        match self {
+            Value::Document(document) if document.len() == 1 => match document.get(DURATION_DELTA_KEY)? {
+                Value::Array(vec) => Some(<&[_; 2]>::try_from(&vec[..]).ok()?.each_ref()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Self; 2]> {
+        match self {
+            Value::Array(vec) => Some(<&mut [_; 2]>::try_from(&mut vec[..]).ok()?.each_mut()),
+            Value::Document(document) => duration_fields(document.iter_mut(), |key| Some(key.as_str())),
+            _ => None,
+        }
+    }
+
+    /// 32-bit integers are kept if the new value fits in them.
+    fn set_number(number: &mut Self, value: Self) {
+        *number = match number {
+            Value::Int32(_) => narrow(value),
+            _ => value,
+        };
+    }
+}
+
+/// Converts a 64-bit integer into a 32-bit one if it fits.
+#[cfg(feature = "increment")]
+fn narrow(value: Value) -> Value {
+    match value {
+        Value::Int64(value) => i32::try_from(value).map_or(Value::Int64(value), Value::Int32),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bson::bson;
+    use morphix_test_utils::*;
+
+    use super::*;
+    use crate::MutationError;
+
+    #[test]
+    fn apply_set_delete() {
+        let mut value = bson!({"a": 1, "b": [1, 2]});
+        Bson::mutate(&mut value, replace!(b.-1, bson!(3)), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, replace!(c, bson!({})), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, delete!(a), &mut Default::default()).unwrap();
+        assert_eq!(value, bson!({"b": [1, 3], "c": {}}));
+
+        let error = Bson::mutate(&mut value, delete!(a), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_append_truncate() {
+        let mut value = bson!({"s": "ab", "v": [1]});
+        Bson::mutate(&mut value, append!(s, bson!("cdé")), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, append!(v, bson!([2, 3])), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, truncate!(s, 2), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, truncate!(v, 1), &mut Default::default()).unwrap();
+        assert_eq!(value, bson!({"s": "abc", "v": [1, 2]}));
+    }
+
+    #[test]
+    fn apply_increment() {
+        let mut value = bson!({"a": i32::MAX, "b": 1_i64, "c": 0.5, "d": {"secs": 1_i64, "nanos": 900_000_000}});
+        Bson::mutate(&mut value, increment!(a, bson!(1)), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, increment!(b, bson!(-3)), &mut Default::default()).unwrap();
+        Bson::mutate(&mut value, increment!(c, bson!(1)), &mut Default::default()).unwrap();
+        Bson::mutate(
+            &mut value,
+            increment!(d, bson!({"$duration": [0_i64, 200_000_000]})),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            bson!({"a": i32::MAX as i64 + 1, "b": -2_i64, "c": 1.5, "d": {"secs": 2_i64, "nanos": 100_000_000}}),
+        );
+    }
+}
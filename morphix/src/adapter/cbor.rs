@@ -0,0 +1,679 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use ciborium::Value;
+use ciborium::value::Integer;
+
+#[cfg(feature = "set")]
+use crate::adapter::members::{add_members_by, hash_member, remove_members_by};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
+use crate::wire::Versioned;
+use crate::{Adapter, Mutation, Mutations, PathSegment};
+
+/// CBOR adapter for morphix mutation serialization.
+///
+/// [`Cbor`] implements the [`Adapter`] trait using [`ciborium::Value`]. Besides text strings and
+/// arrays, [`Append`](crate::MutationKind::Append), [`Truncate`](crate::MutationKind::Truncate)
+/// and [`Splice`](crate::MutationKind::Splice) also operate on byte strings, indexed by bytes.
+/// Tagged values are transparent to mutations: any mutation below a tag applies to the tagged
+/// value, and the tag is preserved.
+///
+/// The whole mutation can be encoded into a single CBOR item with [`to_vec`](Cbor::to_vec), and
+/// decoded with [`from_slice`](Cbor::from_slice).
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::Cbor;
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     value: i32,
+/// }
+///
+/// let mut data = Data { value: 42 };
+/// let patch: Cbor = observe!(data => {
+///     data.value += 1;
+/// }).unwrap();
+///
+/// let bytes = patch.to_vec().unwrap();
+/// assert_eq!(Cbor::from_slice(&bytes).unwrap(), patch);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cbor(pub Option<Mutation<Value>>);
+
+impl From<Option<Mutation<Value>>> for Cbor {
+    fn from(mutation: Option<Mutation<Value>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<Cbor> for Option<Mutation<Value>> {
+    fn from(Cbor(mutation): Cbor) -> Self {
+        mutation
+    }
+}
+
+/// Error type for decoding a [`Cbor`] item.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CborError {
+    /// The input is not valid CBOR, or does not encode a mutation in the current version of the
+    /// [wire format](crate::wire).
+    Decode(ciborium::de::Error<std::io::Error>),
+    /// The input has bytes after the encoded mutation.
+    TrailingBytes,
+}
+
+impl Display for CborError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Decode(error) => error.fmt(f),
+            Self::TrailingBytes => write!(f, "trailing bytes after mutation"),
+        }
+    }
+}
+
+impl Error for CborError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ciborium::de::Error<std::io::Error>> for CborError {
+    fn from(error: ciborium::de::Error<std::io::Error>) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl Cbor {
+    /// Encodes the mutation into a single CBOR item.
+    ///
+    /// The item holds the mutation in the compact form of the [wire format](crate::wire), wrapped
+    /// in [`Versioned`], where an empty mutation is `null`. For example, a
+    /// [`Delete`](crate::MutationKind::Delete) at `.a` is encoded as `[1, [["a"], 5]]`.
+    ///
+    /// Returns an error if a [`Negative`](PathSegment::Negative) index does not fit in an `i64`.
+    pub fn to_vec(&self) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+        let mut bytes = vec![];
+        ciborium::into_writer(&Versioned(&self.0), &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a mutation from a CBOR item produced by [`to_vec`](Cbor::to_vec).
+    pub fn from_slice(mut bytes: &[u8]) -> Result<Self, CborError> {
+        let Versioned(mutation) = ciborium::from_reader(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(CborError::TrailingBytes);
+        }
+        Ok(Self(mutation))
+    }
+}
+
+impl Adapter for Cbor {
+    type Value = Value;
+    type Error = ciborium::value::Error;
+
+    fn from_mutations(mutation: Mutations) -> Result<Self, Self::Error> {
+        Ok(Self(
+            mutation
+                .into_inner()
+                .map(|mutation| mutation.try_map(|value| Value::serialized(&*value)))
+                .transpose()?,
+        ))
+    }
+
+    fn get_mut<'a>(
+        value: &'a mut Self::Value,
+        segment: &PathSegment,
+        allow_create: bool,
+    ) -> Option<&'a mut Self::Value> {
+        match (untag_mut(value), segment) {
+            (Value::Array(vec), PathSegment::Positive(index)) => vec.get_mut(*index),
+            (Value::Array(vec), PathSegment::Negative(index)) => {
+                vec.len().checked_sub(*index).and_then(|i| vec.get_mut(i))
+            }
+            (Value::Map(map), segment) => {
+                let key = segment.as_key()?;
+                let index = match key_position(map, &key) {
+                    Some(index) => index,
+                    None if allow_create => {
+                        map.push((new_key(map, &key), Value::Null));
+                        map.len() - 1
+                    }
+                    None => return None,
+                };
+                Some(&mut map[index].1)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "delete")]
+    fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
+        match (untag_mut(value), segment) {
+            (Value::Map(map), segment) => {
+                let key = segment.as_key()?;
+                let index = key_position(map, &key)?;
+                Some(map.remove(index).1)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "append")]
+    fn append(value: &mut Self::Value, append_value: Self::Value) -> Option<usize> {
+        match (untag_mut(value), untag(append_value)) {
+            (Value::Text(lhs), Value::Text(rhs)) => {
+                let len = rhs.chars().count();
+                *lhs += &rhs;
+                Some(len)
+            }
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => {
+                let len = rhs.len();
+                lhs.extend(rhs);
+                Some(len)
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let len = rhs.len();
+                lhs.extend(rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "append", feature = "splice"))]
+    fn len(mut value: &Self::Value) -> Option<usize> {
+        while let Value::Tag(_, inner) = value {
+            value = inner;
+        }
+        match value {
+            Value::Text(str) => Some(str.chars().count()),
+            Value::Bytes(vec) => Some(vec.len()),
+            Value::Array(vec) => Some(vec.len()),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "truncate")]
+    fn truncate(value: &mut Self::Value, mut truncate_len: usize) -> Option<usize> {
+        match untag_mut(value) {
+            Value::Text(str) => {
+                let mut chars = str.char_indices();
+                let mut new_len = str.len();
+                while truncate_len > 0
+                    && let Some((index, _)) = chars.next_back()
+                {
+                    truncate_len -= 1;
+                    new_len = index;
+                }
+                str.truncate(new_len);
+                Some(truncate_len)
+            }
+            Value::Bytes(vec) => Some(truncate_vec(vec, truncate_len)),
+            Value::Array(vec) => Some(truncate_vec(vec, truncate_len)),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (untag_mut(value), untag(insert_value)) {
+            (Value::Text(lhs), Value::Text(rhs)) => {
+                let start = char_index(lhs, index)?;
+                let end = start + char_index(&lhs[start..], delete_len)?;
+                let len = rhs.chars().count();
+                lhs.replace_range(start..end, &rhs);
+                Some(len)
+            }
+            (Value::Bytes(lhs), Value::Bytes(rhs)) => splice_vec(lhs, index, delete_len, rhs),
+            (Value::Array(lhs), Value::Array(rhs)) => splice_vec(lhs, index, delete_len, rhs),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match untag_mut(value) {
+            Value::Array(vec) => move_element(vec, from, to),
+            Value::Map(map) => move_element(map, from, to),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(untag_mut(value), untag(delta), |value, delta| match (value, delta) {
+            (lhs @ (Value::Integer(_) | Value::Float(_)), rhs) => {
+                *lhs = add_numbers(lhs, &rhs)?;
+                Some(())
+            }
+            _ => None,
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (untag_mut(value), untag(members)) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let added = add_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(added))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (untag_mut(value), members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let removed = remove_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(removed))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the innermost value of a tagged value.
+fn untag_mut(mut value: &mut Value) -> &mut Value {
+    while let Value::Tag(_, inner) = value {
+        value = inner;
+    }
+    value
+}
+
+/// Strips the tags of an operand, as the tags of the mutated value are preserved.
+#[cfg(any(feature = "append", feature = "splice", feature = "increment", feature = "set"))]
+fn untag(mut value: Value) -> Value {
+    while let Value::Tag(_, inner) = value {
+        value = *inner;
+    }
+    value
+}
+
+/// Returns the index of the entry whose key has the string form `key`, which is how
+/// [`PathSegment::from_key`] represents integer and boolean keys.
+fn key_position(map: &[(Value, Value)], key: &str) -> Option<usize> {
+    map.iter().position(|(k, _)| match k {
+        Value::Text(k) => k == key,
+        Value::Integer(k) => i128::from(*k).to_string() == key,
+        Value::Bool(k) => k.to_string() == key,
+        _ => false,
+    })
+}
+
+/// Creates the key of a new entry from its string form, as an integer if the map already has
+/// integer keys.
+fn new_key(map: &[(Value, Value)], key: &str) -> Value {
+    if map.iter().any(|(k, _)| k.is_integer())
+        && let Some(key) = key.parse::<i128>().ok().and_then(|key| Integer::try_from(key).ok())
+    {
+        return Value::Integer(key);
+    }
+    key.into()
+}
+
+/// Hashes a set member for [`add_members_by`] and [`remove_members_by`], since [`Value`] does not
+/// implement [`Hash`](std::hash::Hash). Compound values are only hashed by their length.
+#[cfg(feature = "set")]
+fn member_hash(value: &Value) -> u64 {
+    let discriminant = std::mem::discriminant(value);
+    match value {
+        Value::Bool(bool) => hash_member(&(discriminant, bool)),
+        Value::Integer(integer) => hash_member(&(discriminant, integer)),
+        Value::Text(str) => hash_member(&(discriminant, str)),
+        Value::Bytes(bytes) => hash_member(&(discriminant, bytes)),
+        Value::Tag(tag, _) => hash_member(&(discriminant, tag)),
+        Value::Array(vec) => hash_member(&(discriminant, vec.len())),
+        Value::Map(map) => hash_member(&(discriminant, map.len())),
+        _ => hash_member(&discriminant),
+    }
+}
+
+#[cfg(feature = "truncate")]
+fn truncate_vec<T>(vec: &mut Vec<T>, truncate_len: usize) -> usize {
+    let actual_len = vec.len();
+    vec.truncate(actual_len.saturating_sub(truncate_len));
+    truncate_len.saturating_sub(actual_len)
+}
+
+#[cfg(feature = "splice")]
+fn splice_vec<T>(vec: &mut Vec<T>, index: usize, delete_len: usize, insert: Vec<T>) -> Option<usize> {
+    let end = index.checked_add(delete_len).filter(|end| *end <= vec.len())?;
+    let len = insert.len();
+    vec.splice(index..end, insert);
+    Some(len)
+}
+
+#[cfg(feature = "move")]
+fn move_element<T>(vec: &mut Vec<T>, from: usize, to: usize) -> Option<()> {
+    if from >= vec.len() || to >= vec.len() {
+        return None;
+    }
+    let element = vec.remove(from);
+    vec.insert(to, element);
+    Some(())
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        match self {
+            Value::Integer(integer) => Some((*integer).into()),
+            _ => None,
+        }
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        Integer::try_from(value).ok().map(Value::Integer)
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Integer(integer) => Some(i128::from(*integer) as f64),
+            Value::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(Value::Float(value))
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Value {
+    type Number = Self;
+
+    fn duration_delta(&self) -> Option<[&Self; 2]> {
+        match self {
+            Value::Map(map) => match &map[..] {
+                [(key, Value::Array(vec))] if key.as_text() == Some(DURATION_DELTA_KEY) => {
+                    Some(<&[_; 2]>::try_from(&vec[..]).ok()?.each_ref())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Self; 2]> {
+        match self {
+            Value::Array(vec) => Some(<&mut [_; 2]>::try_from(&mut vec[..]).ok()?.each_mut()),
+            Value::Map(map) => duration_fields(map.iter_mut().map(|(key, value)| (&*key, value)), |key| key.as_text()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::MutationError;
+
+    fn map<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Map(entries.into_iter().map(|(key, value)| (key.into(), value)).collect())
+    }
+
+    fn array<const N: usize>(elements: [Value; N]) -> Value {
+        Value::Array(elements.into())
+    }
+
+    fn bytes(bytes: &[u8]) -> Value {
+        Value::Bytes(bytes.into())
+    }
+
+    fn from_json(json: serde_json::Value) -> Value {
+        Value::serialized(&json).unwrap()
+    }
+
+    #[test]
+    fn from_mutations() {
+        use crate::observe::{ObserveExt, SerializeObserverExt};
+
+        let mut ranges = vec![0..1, 1..2];
+        let mut ob = ranges.__observe();
+        ob.push(2..3);
+        let Cbor(mutation) = ob.flush::<Cbor>().unwrap();
+        assert_eq!(
+            mutation,
+            Some(append!(_, array([map([("start", 2.into()), ("end", 3.into())])]))),
+        );
+    }
+
+    #[test]
+    fn apply_set() {
+        let mut value = from_json(json!({"a": 1}));
+        Cbor::mutate(&mut value, replace!(_, from_json(json!({}))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({})));
+
+        let mut value = from_json(json!({}));
+        Cbor::mutate(&mut value, replace!(a, from_json(json!(1))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": 1})));
+
+        let mut value = from_json(json!({"a": 1}));
+        Cbor::mutate(&mut value, replace!(a, from_json(json!(2))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": 2})));
+
+        let error = Cbor::mutate(
+            &mut from_json(json!({})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into()].into()
+            }
+        );
+
+        let error = Cbor::mutate(
+            &mut from_json(json!({"a": 1})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into(), "b".into()].into(),
+            }
+        );
+
+        let error = Cbor::mutate(
+            &mut from_json(json!({"a": []})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into(), "b".into()].into(),
+            }
+        );
+
+        let mut value = from_json(json!({"a": {}}));
+        Cbor::mutate(&mut value, replace!(a.b, from_json(json!(3))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": {"b": 3}})));
+
+        let mut value = from_json(json!({"0": 1, "1": 2}));
+        Cbor::mutate(&mut value, replace!(0, from_json(json!(3))), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, delete!(1), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"0": 3})));
+
+        let mut value = Value::Map(vec![(42.into(), 1.into())]);
+        Cbor::mutate(&mut value, replace!(42, Value::from(2)), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, replace!(7, Value::from(3)), &mut Default::default()).unwrap();
+        assert_eq!(value, Value::Map(vec![(42.into(), 2.into()), (7.into(), 3.into())]));
+    }
+
+    #[test]
+    fn apply_get_mut_delete() {
+        let mut value = map([("a", 1.into()), ("b", array([1.into(), 2.into()]))]);
+        Cbor::mutate(&mut value, replace!(c.d, Value::from(true)), &mut Default::default()).unwrap_err();
+        Cbor::mutate(&mut value, replace!(c, map([])), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, replace!(b.-1, Value::from(3)), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, delete!(a), &mut Default::default()).unwrap();
+        assert_eq!(value, map([("b", array([1.into(), 3.into()])), ("c", map([]))]));
+
+        let error = Cbor::mutate(&mut value, delete!(a), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_append_truncate() {
+        let mut value = map([("s", "ab".into()), ("b", bytes(b"ab")), ("v", array([1.into()]))]);
+        Cbor::mutate(&mut value, append!(s, Value::from("cdé")), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, append!(b, bytes(b"\xff\x00")), &mut Default::default()).unwrap();
+        Cbor::mutate(
+            &mut value,
+            append!(v, array([2.into(), 3.into()])),
+            &mut Default::default(),
+        )
+        .unwrap();
+        Cbor::mutate(&mut value, truncate!(s, 2), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, truncate!(b, 1), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, truncate!(v, 1), &mut Default::default()).unwrap();
+        assert_eq!(
+            value,
+            map([
+                ("s", "abc".into()),
+                ("b", bytes(b"ab\xff")),
+                ("v", array([1.into(), 2.into()]))
+            ]),
+        );
+
+        let error = Cbor::mutate(&mut value, truncate!(b, 4), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::TruncateError {
+                path: vec!["b".into()].into(),
+                actual_len: 3,
+                truncate_len: 4,
+            },
+        );
+
+        let error = Cbor::mutate(&mut value, append!(b, Value::from("x")), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["b".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_tagged() {
+        let mut value = Value::Tag(24, Box::new(map([("b", Value::Tag(64, Box::new(bytes(b"ab"))))])));
+        Cbor::mutate(&mut value, append!(b, bytes(b"c")), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, splice!(b, 0, 1, bytes(b"xy")), &mut Default::default()).unwrap();
+        assert_eq!(
+            value,
+            Value::Tag(24, Box::new(map([("b", Value::Tag(64, Box::new(bytes(b"xybc"))))]))),
+        );
+    }
+
+    #[test]
+    fn apply_increment() {
+        let mut value = map([
+            ("n", 1.into()),
+            ("f", 0.5.into()),
+            ("d", array([1.into(), 900_000_000.into()])),
+        ]);
+        Cbor::mutate(&mut value, increment!(n, Value::from(-3)), &mut Default::default()).unwrap();
+        Cbor::mutate(&mut value, increment!(f, Value::from(1)), &mut Default::default()).unwrap();
+        Cbor::mutate(
+            &mut value,
+            increment!(d, from_json(json!({"$duration": [0, 200_000_000]}))),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            map([
+                ("n", (-2).into()),
+                ("f", 1.5.into()),
+                ("d", array([2.into(), 100_000_000.into()]))
+            ]),
+        );
+
+        let error = Cbor::mutate(
+            &mut value,
+            increment!(d, array([0.into(), 200_000_000.into()])),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["d".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn wire_encoding() {
+        let patch = Cbor(Some(batch!(
+            a,
+            replace!(b.0, Value::from("x")),
+            append!(c.-1, bytes(b"\x01")),
+            truncate!(_, 2),
+            splice!(d, 1, 2, Value::from("y")),
+            mv!(e, 0, 3),
+            delete!(f),
+            increment!(g, Value::from(1)),
+            add!(h, array([2.into()])),
+            remove!(h, array([3.into()])),
+        )));
+        let bytes = patch.to_vec().unwrap();
+        assert_eq!(Cbor::from_slice(&bytes).unwrap(), patch);
+
+        assert_eq!(Cbor(None).to_vec().unwrap(), [0x82, 0x01, 0xf6]);
+        assert_eq!(
+            Cbor(Some(delete!(a))).to_vec().unwrap(),
+            [0x82, 0x01, 0x82, 0x81, 0x61, b'a', 0x05],
+        );
+        assert_eq!(
+            Cbor(Some(truncate!(-1, 1))).to_vec().unwrap(),
+            [0x82, 0x01, 0x83, 0x81, 0x20, 0x02, 0x01],
+        );
+
+        assert!(matches!(
+            Cbor::from_slice(&[0x82, 0x01, 0xf6, 0xf6]),
+            Err(CborError::TrailingBytes)
+        ));
+        assert!(matches!(
+            Cbor::from_slice(&[0x82, 0x02, 0xf6]),
+            Err(CborError::Decode(_))
+        ));
+        assert!(matches!(
+            Cbor::from_slice(&[0x82, 0x01, 0x82, 0x80, 0x0a]),
+            Err(CborError::Decode(_))
+        ));
+        assert!(matches!(
+            Cbor::from_slice(&[0x82, 0x01, 0x83, 0x80, 0x05, 0xf6]),
+            Err(CborError::Decode(_))
+        ));
+        assert!(matches!(Cbor::from_slice(&[0x82]), Err(CborError::Decode(_))));
+    }
+}
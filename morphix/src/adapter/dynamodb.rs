@@ -0,0 +1,451 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt::Display;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value, json};
+
+use super::update::{Update, export_root, get, register, resolve};
+use crate::adapter::Json;
+use crate::{Adapter, Mutation, MutationError, MutationKind, PathSegment};
+
+/// The parameters of a DynamoDB `UpdateItem` request.
+///
+/// [`DynamoDbUpdate`] converts morphix mutations into an `UpdateExpression`, together with the
+/// `ExpressionAttributeNames` and `ExpressionAttributeValues` it refers to. Every key is addressed
+/// through a name placeholder derived from the key itself, so that reserved words need no special
+/// care, and values are encoded as DynamoDB attribute values. Serializing a [`DynamoDbUpdate`]
+/// produces the corresponding members of the request.
+///
+/// DynamoDB addresses list elements with their positions, so
+/// [`Negative`](PathSegment::Negative) indices are resolved against the item the mutations apply
+/// to, which is updated along the way.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::{DynamoDbUpdate, Json};
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+/// use serde_json::json;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Profile {
+///     name: String,
+///     items: Vec<String>,
+/// }
+///
+/// let mut profile = Profile { name: "a".into(), items: vec!["x".into()] };
+/// let mut replica = serde_json::to_value(&profile).unwrap();
+/// let Json(mutation) = observe!(profile => {
+///     profile.name = "b".into();
+///     profile.items.push("y".into());
+/// }).unwrap();
+///
+/// let update = DynamoDbUpdate::from_mutation(&mut replica, mutation.unwrap()).unwrap();
+/// assert_eq!(
+///     serde_json::to_value(&update).unwrap(),
+///     json!({
+///         "UpdateExpression": "SET #name = :name, #items = list_append(#items, :items)",
+///         "ExpressionAttributeNames": {"#items": "items", "#name": "name"},
+///         "ExpressionAttributeValues": {
+///             ":name": {"S": "b"},
+///             ":items": {"L": [{"S": "y"}]},
+///         },
+///     }),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynamoDbUpdate {
+    /// The update expression, empty if the mutation has no effect.
+    pub update_expression: String,
+    /// The name placeholders of the update expression, mapped to the keys they stand for.
+    pub expression_attribute_names: BTreeMap<String, String>,
+    /// The value placeholders of the update expression, mapped to DynamoDB attribute values.
+    pub expression_attribute_values: Map<String, Value>,
+}
+
+/// Error type for converting mutations into a [`DynamoDbUpdate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynamoDbUpdateError {
+    /// The item is not a map.
+    InvalidRoot,
+    /// A mutation could not be applied to the item.
+    Mutation(MutationError),
+}
+
+impl Display for DynamoDbUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRoot => write!(f, "the item is not a map"),
+            Self::Mutation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for DynamoDbUpdateError {}
+
+impl From<MutationError> for DynamoDbUpdateError {
+    fn from(error: MutationError) -> Self {
+        Self::Mutation(error)
+    }
+}
+
+/// An update action on a single attribute.
+enum Action {
+    /// `SET` to the final value of the attribute, or `REMOVE` if it no longer exists.
+    Set,
+    /// `SET` to `list_append` of the attribute and the given elements.
+    #[cfg(feature = "append")]
+    Append(Vec<Value>),
+    /// `REMOVE` of the list elements from the given index to the end of the list.
+    #[cfg(feature = "truncate")]
+    Truncate(std::ops::Range<usize>),
+    /// `SET` to the sum of the attribute and the given number.
+    #[cfg(feature = "increment")]
+    Increment(Value),
+}
+
+impl DynamoDbUpdate {
+    /// Converts a mutation into an update.
+    ///
+    /// `value` is the item before the mutation, and is updated to the item after it.
+    ///
+    /// | Mutation | Action |
+    /// | --- | --- |
+    /// | [`Replace`](MutationKind::Replace) | `SET` |
+    /// | [`Delete`](MutationKind::Delete) | `REMOVE` |
+    /// | [`Append`](MutationKind::Append) to a list | `SET` with `list_append` |
+    /// | [`Truncate`](MutationKind::Truncate) of a list | `REMOVE` of each trailing index |
+    /// | [`Increment`](MutationKind::Increment) of a number | `SET` with `+` |
+    ///
+    /// Other mutations, such as appending to a string, are converted into a `SET` of the new
+    /// value. As DynamoDB rejects update expressions with overlapping paths, mutations at
+    /// overlapping paths are merged into a `SET` of the outermost attribute, except for
+    /// consecutive appends to the same list. A mutation of the root is converted into a `SET` or
+    /// `REMOVE` of each changed top-level attribute.
+    pub fn from_mutation(value: &mut Value, mutation: Mutation<Value>) -> Result<Self, DynamoDbUpdateError> {
+        let mut updates = vec![];
+        export(value, mutation, &mut updates)?;
+        let mut builder = Builder::default();
+        let mut set_actions = vec![];
+        let mut remove_actions = vec![];
+        for (path, action) in updates {
+            let name = builder.path(&path);
+            match action {
+                Action::Set => match get(value, &path) {
+                    Some(new_value) => {
+                        let new_value = builder.value(&path, new_value);
+                        set_actions.push(format!("{name} = {new_value}"));
+                    }
+                    None => remove_actions.push(name),
+                },
+                #[cfg(feature = "append")]
+                Action::Append(vec) => {
+                    let vec = builder.value(&path, &Value::Array(vec));
+                    set_actions.push(format!("{name} = list_append({name}, {vec})"));
+                }
+                #[cfg(feature = "truncate")]
+                Action::Truncate(range) => {
+                    remove_actions.extend(range.map(|index| format!("{name}[{index}]")));
+                }
+                #[cfg(feature = "increment")]
+                Action::Increment(delta) => {
+                    let delta = builder.value(&path, &delta);
+                    set_actions.push(format!("{name} = {name} + {delta}"));
+                }
+            }
+        }
+        let mut clauses = vec![];
+        if !set_actions.is_empty() {
+            clauses.push(format!("SET {}", set_actions.join(", ")));
+        }
+        if !remove_actions.is_empty() {
+            clauses.push(format!("REMOVE {}", remove_actions.join(", ")));
+        }
+        Ok(Self {
+            update_expression: clauses.join(" "),
+            expression_attribute_names: builder.names,
+            expression_attribute_values: builder.values,
+        })
+    }
+}
+
+/// Allocates the placeholders of an update expression.
+#[derive(Default)]
+struct Builder {
+    names: BTreeMap<String, String>,
+    name_placeholders: BTreeMap<String, String>,
+    values: Map<String, Value>,
+    used: HashSet<String>,
+}
+
+impl Builder {
+    /// Returns an unused placeholder made of `prefix` and `key`, with characters other than ASCII
+    /// alphanumerics replaced by `_`.
+    fn placeholder(&mut self, prefix: char, key: &str) -> String {
+        let mut base = String::from(prefix);
+        base.extend(
+            key.chars()
+                .map(|char| if char.is_ascii_alphanumeric() { char } else { '_' }),
+        );
+        if base.len() == 1 {
+            base.push('_');
+        }
+        let mut placeholder = base.clone();
+        let mut index = 0;
+        while !self.used.insert(placeholder.clone()) {
+            index += 1;
+            placeholder = format!("{base}_{index}");
+        }
+        placeholder
+    }
+
+    /// Builds a document path, with a name placeholder for each key.
+    fn path(&mut self, path: &[PathSegment]) -> String {
+        let mut output = String::new();
+        for segment in path {
+            match segment {
+                PathSegment::String(key) => {
+                    if !output.is_empty() {
+                        output.push('.');
+                    }
+                    let placeholder = match self.name_placeholders.get(&**key) {
+                        Some(placeholder) => placeholder.clone(),
+                        None => {
+                            let placeholder = self.placeholder('#', key);
+                            self.names.insert(placeholder.clone(), key.to_string());
+                            self.name_placeholders.insert(key.to_string(), placeholder.clone());
+                            placeholder
+                        }
+                    };
+                    output += &placeholder;
+                }
+                PathSegment::Positive(index) => output += &format!("[{index}]"),
+                PathSegment::Negative(_) => unreachable!("negative indices are resolved before building paths"),
+            }
+        }
+        output
+    }
+
+    /// Adds a value placeholder, named after the last key of `path`.
+    fn value(&mut self, path: &[PathSegment], value: &Value) -> String {
+        let key = path
+            .iter()
+            .rev()
+            .find_map(|segment| match segment {
+                PathSegment::String(key) => Some(&**key),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let placeholder = self.placeholder(':', key);
+        self.values.insert(placeholder.clone(), to_attribute_value(value));
+        placeholder
+    }
+}
+
+/// Encodes a JSON value as a DynamoDB attribute value.
+fn to_attribute_value(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({"NULL": true}),
+        Value::Bool(bool) => json!({"BOOL": bool}),
+        Value::Number(number) => json!({"N": number.to_string()}),
+        Value::String(str) => json!({"S": str}),
+        Value::Array(vec) => json!({"L": vec.iter().map(to_attribute_value).collect::<Vec<_>>()}),
+        Value::Object(map) => {
+            let map = map
+                .iter()
+                .map(|(key, value)| (key.clone(), to_attribute_value(value)))
+                .collect::<Map<_, _>>();
+            json!({"M": map})
+        }
+    }
+}
+
+impl Update for Action {
+    const SET: Self = Self::Set;
+
+    fn merge(&mut self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "append")]
+            (Self::Append(vec), Self::Append(other)) => {
+                vec.extend(other.iter().cloned());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn export(
+    value: &mut Value,
+    mutation: Mutation<Value>,
+    updates: &mut Vec<(Vec<PathSegment>, Action)>,
+) -> Result<(), DynamoDbUpdateError> {
+    for (path, kind) in mutation.flatten() {
+        if path.is_empty() {
+            let keys = export_root::<Json>(value, kind)?.ok_or(DynamoDbUpdateError::InvalidRoot)?;
+            for key in keys {
+                register(updates, vec![key.into()], Action::Set);
+            }
+        } else {
+            let path = resolve(value, &path)?;
+            export_kind(value, path, kind, updates)?;
+        }
+    }
+    Ok(())
+}
+
+fn export_kind(
+    value: &mut Value,
+    path: Vec<PathSegment>,
+    kind: MutationKind<Value>,
+    updates: &mut Vec<(Vec<PathSegment>, Action)>,
+) -> Result<(), DynamoDbUpdateError> {
+    let action = match (&kind, get(value, &path)) {
+        #[cfg(feature = "append")]
+        (MutationKind::Append(Value::Array(vec)), Some(Value::Array(_))) => Action::Append(vec.clone()),
+        #[cfg(feature = "truncate")]
+        (MutationKind::Truncate(len), Some(Value::Array(vec))) => {
+            Action::Truncate(vec.len().saturating_sub(*len)..vec.len())
+        }
+        #[cfg(feature = "increment")]
+        (MutationKind::Increment(delta @ Value::Number(_)), Some(Value::Number(_))) => Action::Increment(delta.clone()),
+        _ => Action::Set,
+    };
+    Json::mutate(
+        value,
+        Mutation {
+            path: path.clone().into(),
+            kind,
+        },
+        &mut Default::default(),
+    )?;
+    register(updates, path, action);
+    Ok(())
+}
+
+impl Serialize for DynamoDbUpdate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DynamoDbUpdate", 3)?;
+        state.serialize_field("UpdateExpression", &self.update_expression)?;
+        state.serialize_field("ExpressionAttributeNames", &self.expression_attribute_names)?;
+        state.serialize_field("ExpressionAttributeValues", &self.expression_attribute_values)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+
+    use super::*;
+
+    fn export(value: &mut Value, mutation: Mutation<Value>) -> Value {
+        let update = DynamoDbUpdate::from_mutation(value, mutation).unwrap();
+        serde_json::to_value(update).unwrap()
+    }
+
+    #[test]
+    fn export_actions() {
+        let mut value = json!({"name": "a", "stats": {"level": 1, "hp": 10}, "items": [1, 2, 3], "tag": "x"});
+        let update = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(name, json!("b")),
+                increment!(stats.level, json!(1)),
+                delete!(stats.hp),
+                truncate!(items, 2),
+                append!(tag, json!("y")),
+            ),
+        );
+        assert_eq!(
+            update,
+            json!({
+                "UpdateExpression": "SET #name = :name, #stats.#level = #stats.#level + :level, #tag = :tag \
+                                     REMOVE #stats.#hp, #items[1], #items[2]",
+                "ExpressionAttributeNames": {
+                    "#hp": "hp",
+                    "#items": "items",
+                    "#level": "level",
+                    "#name": "name",
+                    "#stats": "stats",
+                    "#tag": "tag",
+                },
+                "ExpressionAttributeValues": {
+                    ":name": {"S": "b"},
+                    ":level": {"N": "1"},
+                    ":tag": {"S": "xy"},
+                },
+            }),
+        );
+        assert_eq!(
+            value,
+            json!({"name": "b", "stats": {"level": 2}, "items": [1], "tag": "xy"})
+        );
+    }
+
+    #[test]
+    fn export_placeholders() {
+        let mut value = json!({"a-b": {"a_b": [{"": null}]}});
+        let mutation = Mutation {
+            path: vec!["a-b".into(), "a_b".into(), PathSegment::Negative(1), "".into()].into(),
+            kind: MutationKind::Replace(json!(1.5)),
+        };
+        let update = export(&mut value, batch!(_, mutation, replace!(c, json!({"d": [null]}))));
+        assert_eq!(
+            update,
+            json!({
+                "UpdateExpression": "SET #a_b.#a_b_1[0].#_ = :_, #c = :c",
+                "ExpressionAttributeNames": {"#_": "", "#a_b": "a-b", "#a_b_1": "a_b", "#c": "c"},
+                "ExpressionAttributeValues": {
+                    ":_": {"N": "1.5"},
+                    ":c": {"M": {"d": {"L": [{"NULL": true}]}}},
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn export_overlaps() {
+        let mut value = json!({"a": {"b": 1}, "c": [1, 2]});
+        let update = export(
+            &mut value,
+            batch!(
+                _,
+                append!(c, json!([3])),
+                append!(c, json!([4])),
+                replace!(a.b, json!(2)),
+                delete!(a),
+            ),
+        );
+        assert_eq!(
+            update["UpdateExpression"],
+            json!("SET #c = list_append(#c, :c) REMOVE #a")
+        );
+        assert_eq!(
+            update["ExpressionAttributeValues"][":c"],
+            json!({"L": [{"N": "3"}, {"N": "4"}]})
+        );
+
+        let update = export(&mut value, batch!(_, truncate!(c, 1), replace!(c.0, json!(0))));
+        assert_eq!(update["UpdateExpression"], json!("SET #c = :c"));
+        assert_eq!(
+            update["ExpressionAttributeValues"][":c"],
+            json!({"L": [{"N": "0"}, {"N": "2"}, {"N": "3"}]})
+        );
+    }
+
+    #[test]
+    fn export_root() {
+        let mut value = json!({"id": 1, "a": 1, "b": 2});
+        let update = export(&mut value, replace!(_, json!({"id": 1, "a": 3})));
+        assert_eq!(update["UpdateExpression"], json!("SET #a = :a REMOVE #b"));
+
+        let error = DynamoDbUpdate::from_mutation(&mut value, replace!(_, json!([]))).unwrap_err();
+        assert_eq!(error, DynamoDbUpdateError::InvalidRoot);
+    }
+}
@@ -1,6 +1,12 @@
 use serde_json::value::Serializer;
 use serde_json::{Error, Value};
 
+#[cfg(feature = "set")]
+use crate::adapter::members::{add_members_by, hash_member, remove_members_by};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
 use crate::{Adapter, Mutation, Mutations, PathSegment};
 
 /// JSON adapter for morphix mutation serialization.
@@ -27,6 +33,18 @@ use crate::{Adapter, Mutation, Mutations, PathSegment};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Json(pub Option<Mutation<Value>>);
 
+impl From<Option<Mutation<Value>>> for Json {
+    fn from(mutation: Option<Mutation<Value>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<Json> for Option<Mutation<Value>> {
+    fn from(Json(mutation): Json) -> Self {
+        mutation
+    }
+}
+
 impl Adapter for Json {
     type Value = Value;
     type Error = Error;
@@ -35,7 +53,7 @@ impl Adapter for Json {
         Ok(Self(
             mutation
                 .into_inner()
-                .map(|mutation| mutation.try_map(&mut |value| erased_serde::serialize(&*value, Serializer)))
+                .map(|mutation| mutation.try_map(|value| erased_serde::serialize(&*value, Serializer)))
                 .transpose()?,
         ))
     }
@@ -50,11 +68,12 @@ impl Adapter for Json {
             (Value::Array(vec), PathSegment::Negative(index)) => {
                 vec.len().checked_sub(*index).and_then(|i| vec.get_mut(i))
             }
-            (Value::Object(map), PathSegment::String(key)) => {
+            (Value::Object(map), segment) => {
+                let key = segment.as_key()?;
                 if allow_create {
-                    Some(map.entry(&**key).or_insert(Value::Null))
+                    Some(map.entry(key).or_insert(Value::Null))
                 } else {
-                    map.get_mut(&**key)
+                    map.get_mut(&*key)
                 }
             }
             _ => None,
@@ -64,7 +83,7 @@ impl Adapter for Json {
     #[cfg(feature = "delete")]
     fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
         match (value, segment) {
-            (Value::Object(map), PathSegment::String(key)) => map.remove(&**key),
+            (Value::Object(map), segment) => map.remove(&*segment.as_key()?),
             _ => None,
         }
     }
@@ -86,7 +105,7 @@ impl Adapter for Json {
         }
     }
 
-    #[cfg(feature = "append")]
+    #[cfg(any(feature = "append", feature = "splice"))]
     fn len(value: &Self::Value) -> Option<usize> {
         match value {
             Value::String(str) => Some(str.chars().count()),
@@ -119,6 +138,131 @@ impl Adapter for Json {
             _ => None,
         }
     }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (value, insert_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let start = char_index(lhs, index)?;
+                let end = start + char_index(&lhs[start..], delete_len)?;
+                let len = rhs.chars().count();
+                lhs.replace_range(start..end, &rhs);
+                Some(len)
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let len = rhs.len();
+                lhs.splice(index..end, rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match value {
+            Value::Array(vec) => {
+                if from >= vec.len() || to >= vec.len() {
+                    return None;
+                }
+                let element = vec.remove(from);
+                vec.insert(to, element);
+                Some(())
+            }
+            // Without `preserve_order`, objects are sorted by key, so their entries cannot be moved.
+            #[cfg(feature = "json-preserve-order")]
+            Value::Object(map) => {
+                if from >= map.len() || to >= map.len() {
+                    return None;
+                }
+                let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+                let entry = entries.remove(from);
+                entries.insert(to, entry);
+                map.extend(entries);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(value, delta, |value, delta| match (value, delta) {
+            (lhs @ Value::Number(_), rhs @ Value::Number(_)) => {
+                *lhs = add_numbers(lhs, &rhs)?;
+                Some(())
+            }
+            _ => None,
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let added = add_members_by(lhs, rhs, hash_member, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(added))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let removed = remove_members_by(lhs, rhs, hash_member, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(removed))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        self.as_i64().map(i128::from).or_else(|| self.as_u64().map(i128::from))
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        match u64::try_from(value) {
+            Ok(value) => Some(value.into()),
+            Err(_) => i64::try_from(value).ok().map(Value::from),
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        self.as_f64()
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        serde_json::Number::from_f64(value).map(Value::Number)
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Value {
+    type Number = Self;
+
+    fn duration_delta(&self) -> Option<[&Self; 2]> {
+        match self {
+            Value::Object(map) if map.len() == 1 => match map.get(DURATION_DELTA_KEY)? {
+                Value::Array(vec) => Some(<&[_; 2]>::try_from(&vec[..]).ok()?.each_ref()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Self; 2]> {
+        match self {
+            Value::Array(vec) => Some(<&mut [_; 2]>::try_from(&mut vec[..]).ok()?.each_mut()),
+            Value::Object(map) => duration_fields(map.iter_mut(), |key| Some(key.as_str())),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -170,6 +314,11 @@ mod test {
         let mut value = json!({"a": {}});
         Json::mutate(&mut value, replace!(a.b, json!(3)), &mut Default::default()).unwrap();
         assert_eq!(value, json!({"a": {"b": 3}}));
+
+        let mut value = json!({"0": 1, "1": 2});
+        Json::mutate(&mut value, replace!(0, json!(3)), &mut Default::default()).unwrap();
+        Json::mutate(&mut value, delete!(1), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"0": 3}));
     }
 
     #[test]
@@ -221,6 +370,160 @@ mod test {
         );
     }
 
+    #[test]
+    fn apply_splice() {
+        let mut value = json!([1, 2, 3, 4]);
+        Json::mutate(
+            &mut value,
+            splice!(_, 1, 2, json!(["a", "b", "c"])),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(value, json!([1, "a", "b", "c", 4]));
+
+        let mut value = json!([1, 2]);
+        Json::mutate(&mut value, splice!(_, 2, 0, json!([3])), &mut Default::default()).unwrap();
+        assert_eq!(value, json!([1, 2, 3]));
+
+        let error = Json::mutate(&mut json!([1, 2]), splice!(_, 1, 2, json!([])), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let mut value = json!("12");
+        Json::mutate(&mut value, splice!(_, 0, 1, json!("3")), &mut Default::default()).unwrap();
+        assert_eq!(value, json!("32"));
+
+        let mut value = json!("你好，世界！");
+        Json::mutate(&mut value, splice!(_, 2, 1, json!(", ")), &mut Default::default()).unwrap();
+        assert_eq!(value, json!("你好, 世界！"));
+
+        let error = Json::mutate(&mut json!("12"), splice!(_, 1, 2, json!("")), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(&mut json!("12"), splice!(_, 0, 1, json!([3])), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+    }
+
+    #[test]
+    fn apply_move() {
+        let mut value = json!([1, 2, 3, 4]);
+        Json::mutate(&mut value, mv!(_, 0, 2), &mut Default::default()).unwrap();
+        assert_eq!(value, json!([2, 3, 1, 4]));
+
+        let mut value = json!({"a": [1, 2, 3]});
+        Json::mutate(&mut value, mv!(a, 2, 0), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"a": [3, 1, 2]}));
+
+        let error = Json::mutate(&mut json!([1, 2]), mv!(_, 0, 2), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(&mut json!("12"), mv!(_, 0, 1), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+    }
+
+    #[cfg(feature = "json-preserve-order")]
+    #[test]
+    fn apply_move_entries() {
+        let mut value = json!({"a": 1, "b": 2, "c": 3});
+        Json::mutate(&mut value, mv!(_, 0, 2), &mut Default::default()).unwrap();
+        assert_eq!(value.as_object().unwrap().keys().collect::<Vec<_>>(), ["b", "c", "a"]);
+        assert_eq!(value, json!({"a": 1, "b": 2, "c": 3}));
+
+        let error = Json::mutate(&mut json!({"a": 1}), mv!(_, 0, 1), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+    }
+
+    #[test]
+    fn apply_increment() {
+        let mut value = json!({"a": 1});
+        Json::mutate(&mut value, increment!(a, json!(2)), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"a": 3}));
+
+        let mut value = json!(1);
+        Json::mutate(&mut value, increment!(_, json!(-3)), &mut Default::default()).unwrap();
+        assert_eq!(value, json!(-2));
+
+        let mut value = json!(1.5);
+        Json::mutate(&mut value, increment!(_, json!(1)), &mut Default::default()).unwrap();
+        assert_eq!(value, json!(2.5));
+
+        let mut value = json!({"secs": 1, "nanos": 800_000_000});
+        Json::mutate(
+            &mut value,
+            increment!(_, json!({"$duration": [0, 300_000_000]})),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(value, json!({"secs": 2, "nanos": 100_000_000}));
+
+        let mut value = json!([1, 200_000_000]);
+        Json::mutate(
+            &mut value,
+            increment!(_, json!({"$duration": [-1, 500_000_000]})),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(value, json!([0, 700_000_000]));
+
+        let mut value = json!([0, 200_000_000]);
+        Json::mutate(
+            &mut value,
+            increment!(_, json!({"$duration": [-1, 500_000_000]})),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(value, json!([-1, 700_000_000]));
+
+        let error = Json::mutate(
+            &mut json!([1, 0]),
+            increment!(_, json!({"$duration": [-1, -500_000_000]})),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        // Only marked deltas are added as durations.
+        let error = Json::mutate(
+            &mut json!([1, 2]),
+            increment!(_, json!([3, 4])),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(
+            &mut json!({"secs": 1, "nanos": 0}),
+            increment!(_, json!({"secs": 1, "nanos": 0})),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(&mut json!(u64::MAX), increment!(_, json!(1)), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(&mut json!("1"), increment!(_, json!(1)), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+    }
+
+    #[test]
+    fn apply_add_remove() {
+        let mut value = json!({"a": ["x", "y"]});
+        Json::mutate(&mut value, add!(a, json!(["y", "z"])), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"a": ["x", "y", "z"]}));
+
+        Json::mutate(&mut value, remove!(a, json!(["x", "w"])), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"a": ["y", "z"]}));
+
+        Json::mutate(&mut value, add!(a, json!(["w", "z", "w"])), &mut Default::default()).unwrap();
+        assert_eq!(value, json!({"a": ["y", "z", "w"]}));
+
+        let error = Json::mutate(&mut json!({}), add!(_, json!(["x"])), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+
+        let error = Json::mutate(&mut json!([]), remove!(_, json!("x")), &mut Default::default()).unwrap_err();
+        assert_eq!(error, MutationError::OperationError { path: vec![].into() });
+    }
+
     #[test]
     fn apply_batch() {
         let mut value = json!({"a": {"b": {"c": {}}}});
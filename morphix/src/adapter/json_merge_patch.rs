@@ -0,0 +1,340 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::adapter::Json;
+use crate::{Adapter, Mutation, MutationError, MutationKind, Path, PathSegment};
+
+/// A JSON Merge Patch document as defined in
+/// [RFC 7386](https://datatracker.ietf.org/doc/html/rfc7386).
+///
+/// A merge patch is an object whose members describe the new values of the corresponding members
+/// of the target document, with `null` meaning deletion. As merge patches cannot address array
+/// elements, any mutation inside an array is converted into a replacement of the whole array.
+/// Likewise, a `null` value cannot be set through a merge patch.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::{Json, JsonMergePatch};
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+/// use serde_json::json;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     title: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut data = Data { title: "a".into(), tags: vec!["x".into()] };
+/// let mut replica = serde_json::to_value(&data).unwrap();
+/// let Json(mutation) = observe!(data => {
+///     data.tags.push("y".into());
+/// }).unwrap();
+///
+/// let JsonMergePatch(patch) = JsonMergePatch::from_mutation(&mut replica, mutation.unwrap()).unwrap();
+/// assert_eq!(patch, json!({"tags": ["x", "y"]}));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonMergePatch(pub Value);
+
+impl Default for JsonMergePatch {
+    fn default() -> Self {
+        Self(Value::Object(Map::new()))
+    }
+}
+
+impl JsonMergePatch {
+    /// Folds a mutation into a single merge patch.
+    ///
+    /// `value` is the document before the mutation, and is updated to the document after it.
+    /// Replaced objects are diffed against their previous value, so that removed members become
+    /// `null`. Mutations below an array index, as well as [`Append`](MutationKind::Append),
+    /// [`Truncate`](MutationKind::Truncate) and other mutations without a merge patch counterpart,
+    /// are converted into a replacement of the nearest value addressable by string keys.
+    pub fn from_mutation(value: &mut Value, mutation: Mutation<Value>) -> Result<Self, MutationError> {
+        let mut snapshots = vec![];
+        export(value, mutation, &mut vec![], &mut snapshots)?;
+        let mut patch = Value::Object(Map::new());
+        for (path, old_value) in snapshots {
+            let Some(diff) = diff(old_value.as_ref(), get(value, &path)) else {
+                continue;
+            };
+            let Some((last, parent)) = path.split_last() else {
+                patch = diff;
+                continue;
+            };
+            let mut target = &mut patch;
+            for key in parent {
+                target = target
+                    .as_object_mut()
+                    .expect("patch ancestors are objects")
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+            }
+            target
+                .as_object_mut()
+                .expect("patch ancestors are objects")
+                .insert(last.clone(), diff);
+        }
+        Ok(Self(patch))
+    }
+
+    /// Converts the merge patch into a mutation.
+    ///
+    /// `value` is the document the patch applies to. `null` members become
+    /// [`Delete`](MutationKind::Delete) mutations, objects merged into existing objects are
+    /// converted member by member, and any other member becomes a
+    /// [`Replace`](MutationKind::Replace) mutation.
+    ///
+    /// Returns [`None`] if the patch has no effect.
+    pub fn into_mutation(self, value: &Value) -> Option<Mutation<Value>> {
+        let mut mutations = vec![];
+        import(value, self.0, &mut vec![], &mut mutations);
+        match mutations.len() {
+            0 => None,
+            1 => mutations.pop(),
+            _ => Some(Mutation {
+                path: Path::new(),
+                kind: MutationKind::Batch(mutations),
+            }),
+        }
+    }
+}
+
+fn get<'a>(mut value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    for key in path {
+        value = value.as_object()?.get(key)?;
+    }
+    Some(value)
+}
+
+/// Records the previous value at `path`, unless it is already covered by a snapshot of an
+/// ancestor. Snapshots of descendants are folded into the new snapshot.
+fn record(value: &Value, path: Vec<String>, snapshots: &mut Vec<(Vec<String>, Option<Value>)>) {
+    if snapshots.iter().any(|(recorded, _)| path.starts_with(recorded)) {
+        return;
+    }
+    let mut snapshot = get(value, &path).cloned();
+    snapshots.retain_mut(|(recorded, old_value)| {
+        let Some(relative) = recorded.strip_prefix(&path[..]) else {
+            return true;
+        };
+        let (last, parent) = relative.split_last().expect("descendants are strictly longer");
+        if let Some(Value::Object(map)) = snapshot.as_mut().and_then(|snapshot| {
+            parent
+                .iter()
+                .try_fold(snapshot, |value, key| value.as_object_mut()?.get_mut(key))
+        }) {
+            match old_value.take() {
+                Some(old_value) => map.insert(last.clone(), old_value),
+                None => map.remove(last),
+            };
+        }
+        false
+    });
+    snapshots.push((path, snapshot));
+}
+
+fn export(
+    value: &mut Value,
+    mutation: Mutation<Value>,
+    prefix: &mut Vec<PathSegment>,
+    snapshots: &mut Vec<(Vec<String>, Option<Value>)>,
+) -> Result<(), MutationError> {
+    let len = prefix.len();
+    prefix.extend(mutation.path.iter().rev().cloned());
+    let result = match mutation.kind {
+        MutationKind::Batch(mutations) => mutations
+            .into_iter()
+            .try_for_each(|mutation| export(value, mutation, prefix, snapshots)),
+        kind => {
+            let path = prefix
+                .iter()
+                .map_while(|segment| match segment {
+                    PathSegment::String(key) => Some(key.to_string()),
+                    _ => None,
+                })
+                .collect();
+            record(value, path, snapshots);
+            Json::mutate(
+                value,
+                Mutation {
+                    path: prefix.clone().into(),
+                    kind,
+                },
+                &mut Default::default(),
+            )
+        }
+    };
+    prefix.truncate(len);
+    result
+}
+
+/// Computes the merge patch turning `old_value` into `new_value`, or [`None`] if they are equal.
+fn diff(old_value: Option<&Value>, new_value: Option<&Value>) -> Option<Value> {
+    match (old_value, new_value) {
+        (None, None) => None,
+        (Some(_), None) => Some(Value::Null),
+        (Some(Value::Object(old_map)), Some(Value::Object(new_map))) => {
+            let mut patch = Map::new();
+            for key in old_map.keys() {
+                if !new_map.contains_key(key) {
+                    patch.insert(key.clone(), Value::Null);
+                }
+            }
+            for (key, value) in new_map {
+                if let Some(diff) = diff(old_map.get(key), Some(value)) {
+                    patch.insert(key.clone(), diff);
+                }
+            }
+            (!patch.is_empty()).then_some(Value::Object(patch))
+        }
+        (old_value, Some(new_value)) => (old_value != Some(new_value)).then(|| new_value.clone()),
+    }
+}
+
+/// Removes `null` members from objects, as they would be deleted by a merge.
+fn strip_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, strip_nulls(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn import(value: &Value, patch: Value, path: &mut Vec<PathSegment>, mutations: &mut Vec<Mutation<Value>>) {
+    let (map, patch) = match (value, patch) {
+        (Value::Object(map), Value::Object(patch)) => (map, patch),
+        (_, patch) => {
+            mutations.push(Mutation {
+                path: path.clone().into(),
+                kind: MutationKind::Replace(strip_nulls(patch)),
+            });
+            return;
+        }
+    };
+    for (key, patch) in patch {
+        let value = map.get(&key);
+        path.push(key.into());
+        match (value, patch) {
+            (None, Value::Null) => {}
+            (Some(_), Value::Null) => mutations.push(Mutation {
+                path: path.clone().into(),
+                kind: MutationKind::Delete,
+            }),
+            (Some(value), patch) => import(value, patch, path, mutations),
+            (None, patch) => mutations.push(Mutation {
+                path: path.clone().into(),
+                kind: MutationKind::Replace(strip_nulls(patch)),
+            }),
+        }
+        path.pop();
+    }
+}
+
+impl Serialize for JsonMergePatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonMergePatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+
+    fn export(value: &mut Value, mutation: Mutation<Value>) -> Value {
+        let original = value.clone();
+        let JsonMergePatch(patch) = JsonMergePatch::from_mutation(value, mutation).unwrap();
+        let mut replica = original.clone();
+        let mutation = JsonMergePatch(patch.clone()).into_mutation(&original);
+        if let Some(mutation) = mutation {
+            Json::mutate(&mut replica, mutation, &mut Default::default()).unwrap();
+        }
+        assert_eq!(&replica, value);
+        patch
+    }
+
+    #[test]
+    fn export_objects() {
+        let mut value = json!({"a": {"b": 1, "c": 2}, "d": "x", "e": 1});
+        let patch = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(a.b, json!(3)),
+                delete!(e),
+                replace!(f, json!({"g": true})),
+                append!(d, json!("y")),
+            ),
+        );
+        assert_eq!(patch, json!({"a": {"b": 3}, "e": null, "f": {"g": true}, "d": "xy"}));
+        assert_eq!(value, json!({"a": {"b": 3, "c": 2}, "d": "xy", "f": {"g": true}}));
+
+        let patch = export(&mut value, replace!(a, json!({"b": 3, "h": 4})));
+        assert_eq!(patch, json!({"a": {"c": null, "h": 4}}));
+
+        let patch = export(
+            &mut value,
+            batch!(_, replace!(a.b, json!(5)), replace!(a, json!({"b": 3}))),
+        );
+        assert_eq!(patch, json!({"a": {"h": null}}));
+
+        let patch = export(&mut value, replace!(d, json!("xy")));
+        assert_eq!(patch, json!({}));
+    }
+
+    #[test]
+    fn export_arrays() {
+        let mut value = json!({"a": {"list": [{"x": 1}, {"x": 2}]}, "b": [1, 2, 3]});
+        let patch = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(a.list.-1.x, json!(20)),
+                truncate!(b, 2),
+                append!(b, json!([4])),
+            ),
+        );
+        assert_eq!(patch, json!({"a": {"list": [{"x": 1}, {"x": 20}]}, "b": [1, 4]}));
+
+        let patch = export(&mut value, replace!(_, json!([1])));
+        assert_eq!(patch, json!([1]));
+    }
+
+    #[test]
+    fn import_patch() {
+        let value = json!({"a": {"b": 1, "c": 2}, "d": [1], "e": 1});
+        let patch =
+            JsonMergePatch(json!({"a": {"b": null, "x": {"y": null, "z": 1}}, "d": {"k": null}, "e": null, "n": null}));
+        assert_eq!(
+            patch.into_mutation(&value),
+            Some(batch!(
+                _,
+                delete!(a.b),
+                replace!(a.x, json!({"z": 1})),
+                replace!(d, json!({})),
+                delete!(e),
+            )),
+        );
+
+        let patch = JsonMergePatch(json!({"n": null}));
+        assert_eq!(patch.into_mutation(&value), None);
+
+        let patch = JsonMergePatch(json!("x"));
+        assert_eq!(patch.into_mutation(&value), Some(replace!(_, json!("x"))));
+    }
+}
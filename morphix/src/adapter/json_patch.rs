@@ -0,0 +1,878 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use serde::de::Error as _;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::update::resolve;
+use crate::adapter::Json;
+use crate::{Adapter, Mutation, MutationError, MutationKind, Path, PathSegment};
+
+/// A JSON Patch document as defined in [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902).
+///
+/// [`JsonPatch`] converts between morphix mutations and the operations of a JSON Patch document,
+/// which can be sent as `application/json-patch+json`.
+///
+/// Unlike morphix paths, JSON pointers cannot address elements from the end of an array, and
+/// cannot tell an array index from a numeric object key. Both conversions are therefore performed
+/// against the document the mutations apply to, which is patched along the way.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::{Json, JsonPatch};
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+/// use serde_json::json;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     tags: Vec<String>,
+/// }
+///
+/// let mut data = Data { tags: vec!["a".into()] };
+/// let mut replica = serde_json::to_value(&data).unwrap();
+/// let Json(mutation) = observe!(data => {
+///     data.tags.push("b".into());
+/// }).unwrap();
+///
+/// let patch = JsonPatch::from_mutation(&mut replica, mutation.unwrap()).unwrap();
+/// assert_eq!(
+///     serde_json::to_value(&patch).unwrap(),
+///     json!([{"op": "add", "path": "/tags/-", "value": "b"}]),
+/// );
+/// assert_eq!(replica, json!({"tags": ["a", "b"]}));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonPatch(pub Vec<PatchOperation>);
+
+/// A single operation of a [`JsonPatch`].
+///
+/// All paths are [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOperation {
+    /// Adds a value to an object or inserts it into an array.
+    Add {
+        /// The location to add the value at.
+        path: String,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes the value at the target location.
+    Remove {
+        /// The location of the value to remove.
+        path: String,
+    },
+    /// Replaces the value at the target location.
+    Replace {
+        /// The location of the value to replace.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes the value at a location and adds it to the target location.
+    Move {
+        /// The location of the value to move.
+        from: String,
+        /// The location to move the value to.
+        path: String,
+    },
+    /// Copies the value at a location to the target location.
+    Copy {
+        /// The location of the value to copy.
+        from: String,
+        /// The location to copy the value to.
+        path: String,
+    },
+    /// Tests that the value at the target location is equal to a specified value.
+    Test {
+        /// The location of the value to test.
+        path: String,
+        /// The expected value.
+        value: Value,
+    },
+}
+
+/// Error type for converting a [`JsonPatch`] into mutations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsonPatchError {
+    /// A JSON pointer is malformed.
+    InvalidPointer {
+        /// The malformed pointer.
+        pointer: String,
+    },
+    /// A `test` operation failed.
+    TestFailed {
+        /// The location of the tested value.
+        path: String,
+    },
+    /// An operation could not be applied to the document.
+    Mutation(MutationError),
+}
+
+impl Display for JsonPatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPointer { pointer } => write!(f, "invalid JSON pointer {pointer:?}"),
+            Self::TestFailed { path } => write!(f, "test failed at {path:?}"),
+            Self::Mutation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for JsonPatchError {}
+
+impl From<MutationError> for JsonPatchError {
+    fn from(error: MutationError) -> Self {
+        Self::Mutation(error)
+    }
+}
+
+impl JsonPatch {
+    /// Converts a mutation into JSON Patch operations.
+    ///
+    /// `value` is the document before the mutation, and is updated to the document after it.
+    ///
+    /// | Mutation | Operations |
+    /// | --- | --- |
+    /// | [`Replace`](MutationKind::Replace) | `add` for a new object key, `replace` otherwise |
+    /// | [`Delete`](MutationKind::Delete) | `remove` |
+    /// | [`Append`](MutationKind::Append) | `add` at `/-` for each element |
+    /// | [`Truncate`](MutationKind::Truncate) | `remove` for each trailing index, starting from the last one |
+    /// | [`Splice`](MutationKind::Splice) | `remove` for each removed index, then `add` for each inserted element |
+    /// | [`Move`](MutationKind::Move) | `move` |
+    /// | [`Add`](MutationKind::Add) | `add` at `/-` for each inserted member |
+    /// | [`Remove`](MutationKind::Remove) | `remove` for each removed member |
+    /// | [`Batch`](MutationKind::Batch) | the operations of each mutation in order |
+    ///
+    /// Mutations without a JSON Patch counterpart, such as appending to a string or an
+    /// [`Increment`](MutationKind::Increment), are converted into a `replace` of the new value.
+    pub fn from_mutation(value: &mut Value, mutation: Mutation<Value>) -> Result<Self, MutationError> {
+        let mut operations = vec![];
+        for (path, kind) in mutation.flatten() {
+            let path = resolve(value, &path)?;
+            export_kind(value, path, kind, &mut operations)?;
+        }
+        Ok(Self(operations))
+    }
+
+    /// Converts the operations into a mutation.
+    ///
+    /// `value` is the document the patch applies to, and is updated to the patched document. The
+    /// returned mutation uses [`Positive`](PathSegment::Positive) indices and can be applied with
+    /// [`Json::mutate`](Adapter::mutate) to other copies of the same document.
+    ///
+    /// | Operation | Mutations |
+    /// | --- | --- |
+    /// | `add` | [`Replace`](MutationKind::Replace) for an object key, [`Append`](MutationKind::Append) at the end of an array, [`Splice`](MutationKind::Splice) otherwise |
+    /// | `remove` | [`Delete`](MutationKind::Delete) for an object key, [`Truncate`](MutationKind::Truncate) of the last element, [`Splice`](MutationKind::Splice) otherwise |
+    /// | `replace` | [`Replace`](MutationKind::Replace) |
+    /// | `move` | [`Move`](MutationKind::Move) within the same array, `remove` followed by `add` otherwise |
+    /// | `copy` | `add` of the copied value |
+    /// | `test` | none, fails if the values are not equal |
+    ///
+    /// Returns [`None`] if the patch has no effect.
+    ///
+    /// The patch is applied atomically: if any operation fails, `value` is left unchanged.
+    pub fn into_mutation(self, value: &mut Value) -> Result<Option<Mutation<Value>>, JsonPatchError> {
+        let mut patched = value.clone();
+        let mut mutations = vec![];
+        for operation in self.0 {
+            import(&mut patched, operation, &mut mutations)?;
+        }
+        *value = patched;
+        Ok(match mutations.len() {
+            0 => None,
+            1 => mutations.pop(),
+            _ => Some(Mutation {
+                path: Path::new(),
+                kind: MutationKind::Batch(mutations),
+            }),
+        })
+    }
+}
+
+/// Builds a JSON pointer from a path without [`Negative`](PathSegment::Negative) segments.
+fn to_pointer(path: &[PathSegment]) -> String {
+    let mut pointer = String::new();
+    for segment in path {
+        pointer.push('/');
+        match segment {
+            PathSegment::String(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Positive(index) => pointer.push_str(&index.to_string()),
+            PathSegment::Negative(_) => unreachable!("negative indices are resolved before building pointers"),
+        }
+    }
+    pointer
+}
+
+/// Splits a JSON pointer into unescaped reference tokens.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, JsonPatchError> {
+    let error = || JsonPatchError::InvalidPointer {
+        pointer: pointer.to_string(),
+    };
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    let rest = pointer.strip_prefix('/').ok_or_else(error)?;
+    rest.split('/')
+        .map(|token| {
+            let mut output = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(char) = chars.next() {
+                match char {
+                    '~' => match chars.next() {
+                        Some('0') => output.push('~'),
+                        Some('1') => output.push('/'),
+                        _ => return Err(error()),
+                    },
+                    char => output.push(char),
+                }
+            }
+            Ok(output)
+        })
+        .collect()
+}
+
+/// Parses an array index token, which must not have leading zeros.
+fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+fn get_mut<'a>(mut value: &'a mut Value, path: &[PathSegment]) -> Option<&'a mut Value> {
+    for segment in path {
+        value = Json::get_mut(value, segment, false)?;
+    }
+    Some(value)
+}
+
+fn export_kind(
+    value: &mut Value,
+    path: Vec<PathSegment>,
+    kind: MutationKind<Value>,
+    operations: &mut Vec<PatchOperation>,
+) -> Result<(), MutationError> {
+    let pointer = to_pointer(&path);
+    let element = |index: usize| format!("{pointer}/{index}");
+    let target = get_mut(value, &path);
+    let array_len = match &target {
+        Some(Value::Array(vec)) => Some(vec.len()),
+        _ => None,
+    };
+    let mut replace_after = false;
+    let mut pending = vec![];
+    match (&kind, array_len) {
+        (MutationKind::Replace(new_value), _) => {
+            let is_new_key = !path.is_empty() && target.is_none();
+            pending.push(if is_new_key {
+                PatchOperation::Add {
+                    path: pointer.clone(),
+                    value: new_value.clone(),
+                }
+            } else {
+                PatchOperation::Replace {
+                    path: pointer.clone(),
+                    value: new_value.clone(),
+                }
+            });
+        }
+        (MutationKind::Delete, _) => pending.push(PatchOperation::Remove { path: pointer.clone() }),
+        (MutationKind::Append(Value::Array(elements)), Some(_)) => {
+            pending.extend(elements.iter().map(|element| PatchOperation::Add {
+                path: format!("{pointer}/-"),
+                value: element.clone(),
+            }));
+        }
+        (MutationKind::Truncate(truncate_len), Some(len)) if *truncate_len <= len => {
+            pending.extend(
+                (len - truncate_len..len)
+                    .rev()
+                    .map(|index| PatchOperation::Remove { path: element(index) }),
+            );
+        }
+        (
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert: Value::Array(elements),
+            },
+            Some(_),
+        ) => {
+            pending.extend((0..*delete_len).map(|_| PatchOperation::Remove { path: element(*index) }));
+            pending.extend(elements.iter().enumerate().map(|(offset, value)| PatchOperation::Add {
+                path: element(index + offset),
+                value: value.clone(),
+            }));
+        }
+        (MutationKind::Move { from, to }, Some(_)) => pending.push(PatchOperation::Move {
+            from: element(*from),
+            path: element(*to),
+        }),
+        #[cfg(feature = "set")]
+        (MutationKind::Add(_) | MutationKind::Remove(_), _) => {
+            return export_members(target, &pointer, path, kind, operations);
+        }
+        _ => replace_after = true,
+    }
+    Json::mutate(
+        value,
+        Mutation {
+            path: path.clone().into(),
+            kind,
+        },
+        &mut Default::default(),
+    )?;
+    if replace_after {
+        let new_value = get_mut(value, &path).map(|value| value.clone()).unwrap_or_default();
+        pending.push(PatchOperation::Replace {
+            path: pointer,
+            value: new_value,
+        });
+    }
+    operations.extend(pending);
+    Ok(())
+}
+
+#[cfg(feature = "set")]
+fn export_members(
+    target: Option<&mut Value>,
+    pointer: &str,
+    path: Vec<PathSegment>,
+    kind: MutationKind<Value>,
+    operations: &mut Vec<PatchOperation>,
+) -> Result<(), MutationError> {
+    let Some(target) = target else {
+        return Err(MutationError::IndexError { path: path.into() });
+    };
+    match kind {
+        MutationKind::Add(members) => {
+            let Some(Value::Array(inserted)) = Json::add_members(target, members) else {
+                return Err(MutationError::OperationError { path: path.into() });
+            };
+            operations.extend(inserted.into_iter().map(|value| PatchOperation::Add {
+                path: format!("{pointer}/-"),
+                value,
+            }));
+        }
+        MutationKind::Remove(members) => {
+            // Removing from the highest index keeps the lower indices valid.
+            let indices = match (&*target, &members) {
+                (Value::Array(vec), Value::Array(removed)) => (0..vec.len())
+                    .rev()
+                    .filter(|index| removed.contains(&vec[*index]))
+                    .collect::<Vec<_>>(),
+                _ => return Err(MutationError::OperationError { path: path.into() }),
+            };
+            Json::remove_members(target, &members);
+            operations.extend(indices.into_iter().map(|index| PatchOperation::Remove {
+                path: format!("{pointer}/{index}"),
+            }));
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+/// Resolves reference tokens into path segments, requiring every location to exist.
+fn locate(value: &Value, tokens: &[String], pointer: &str) -> Result<Vec<PathSegment>, JsonPatchError> {
+    let mut value = value;
+    let mut path = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let (segment, inner) = match value {
+            Value::Array(vec) => {
+                let index = parse_index(token).ok_or_else(|| JsonPatchError::InvalidPointer {
+                    pointer: pointer.to_string(),
+                })?;
+                (PathSegment::Positive(index), vec.get(index))
+            }
+            Value::Object(map) => (PathSegment::String(token.clone().into()), map.get(token)),
+            _ => (PathSegment::String(token.clone().into()), None),
+        };
+        path.push(segment);
+        value = inner.ok_or_else(|| MutationError::IndexError {
+            path: path.clone().into(),
+        })?;
+    }
+    Ok(path)
+}
+
+fn push(
+    value: &mut Value,
+    path: Vec<PathSegment>,
+    kind: MutationKind<Value>,
+    mutations: &mut Vec<Mutation<Value>>,
+) -> Result<(), JsonPatchError> {
+    let mutation = Mutation {
+        path: path.into(),
+        kind,
+    };
+    Json::mutate(value, mutation.clone(), &mut Default::default())?;
+    mutations.push(mutation);
+    Ok(())
+}
+
+fn import_add(
+    value: &mut Value,
+    pointer: &str,
+    new_value: Value,
+    mutations: &mut Vec<Mutation<Value>>,
+) -> Result<(), JsonPatchError> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent)) = tokens.split_last() else {
+        return push(value, vec![], MutationKind::Replace(new_value), mutations);
+    };
+    let mut path = locate(value, parent, pointer)?;
+    let kind = match get_mut(value, &path) {
+        Some(Value::Array(vec)) => {
+            let index = match &**last {
+                "-" => vec.len(),
+                token => parse_index(token).filter(|index| *index <= vec.len()).ok_or_else(|| {
+                    JsonPatchError::InvalidPointer {
+                        pointer: pointer.to_string(),
+                    }
+                })?,
+            };
+            if index == vec.len() {
+                MutationKind::Append(Value::Array(vec![new_value]))
+            } else {
+                MutationKind::Splice {
+                    index,
+                    delete_len: 0,
+                    insert: Value::Array(vec![new_value]),
+                }
+            }
+        }
+        Some(Value::Object(_)) => {
+            path.push(PathSegment::String(last.clone().into()));
+            MutationKind::Replace(new_value)
+        }
+        _ => {
+            path.push(PathSegment::String(last.clone().into()));
+            return Err(MutationError::IndexError { path: path.into() }.into());
+        }
+    };
+    push(value, path, kind, mutations)
+}
+
+/// Removes the value at `pointer` and returns it.
+fn import_remove(
+    value: &mut Value,
+    pointer: &str,
+    mutations: &mut Vec<Mutation<Value>>,
+) -> Result<Value, JsonPatchError> {
+    let tokens = parse_pointer(pointer)?;
+    let mut path = locate(value, &tokens, pointer)?;
+    let Some(segment) = path.pop() else {
+        return Err(MutationError::OperationError { path: Path::new() }.into());
+    };
+    let (removed, kind) = match (get_mut(value, &path), segment) {
+        (Some(Value::Array(vec)), PathSegment::Positive(index)) => {
+            let kind = if index + 1 == vec.len() {
+                MutationKind::Truncate(1)
+            } else {
+                MutationKind::Splice {
+                    index,
+                    delete_len: 1,
+                    insert: Value::Array(vec![]),
+                }
+            };
+            (vec[index].clone(), kind)
+        }
+        (Some(Value::Object(map)), PathSegment::String(key)) => {
+            let removed = map[&*key].clone();
+            path.push(PathSegment::String(key));
+            (removed, MutationKind::Delete)
+        }
+        _ => unreachable!("located paths point to arrays or objects"),
+    };
+    push(value, path, kind, mutations)?;
+    Ok(removed)
+}
+
+fn import(
+    value: &mut Value,
+    operation: PatchOperation,
+    mutations: &mut Vec<Mutation<Value>>,
+) -> Result<(), JsonPatchError> {
+    match operation {
+        PatchOperation::Add { path, value: new_value } => import_add(value, &path, new_value, mutations),
+        PatchOperation::Remove { path } => import_remove(value, &path, mutations).map(drop),
+        PatchOperation::Replace { path, value: new_value } => {
+            let path = locate(value, &parse_pointer(&path)?, &path)?;
+            push(value, path, MutationKind::Replace(new_value), mutations)
+        }
+        PatchOperation::Move { from, path } => {
+            if from == path {
+                return Ok(());
+            }
+            let from_tokens = parse_pointer(&from)?;
+            let path_tokens = parse_pointer(&path)?;
+            if let (Some((from_last, from_parent)), Some((path_last, path_parent))) =
+                (from_tokens.split_last(), path_tokens.split_last())
+                && from_parent == path_parent
+            {
+                let parent = locate(value, from_parent, &from)?;
+                if let Some(Value::Array(vec)) = get_mut(value, &parent) {
+                    let len = vec.len();
+                    let from_index = parse_index(from_last).filter(|index| *index < len);
+                    let to_index = match &**path_last {
+                        "-" => len.checked_sub(1),
+                        token => parse_index(token).filter(|index| *index < len),
+                    };
+                    let (Some(from), Some(to)) = (from_index, to_index) else {
+                        return Err(JsonPatchError::InvalidPointer { pointer: path });
+                    };
+                    return push(value, parent, MutationKind::Move { from, to }, mutations);
+                }
+            }
+            if path_tokens.starts_with(&from_tokens) {
+                return Err(JsonPatchError::InvalidPointer { pointer: path });
+            }
+            let moved = import_remove(value, &from, mutations)?;
+            import_add(value, &path, moved, mutations)
+        }
+        PatchOperation::Copy { from, path } => {
+            let from_path = locate(value, &parse_pointer(&from)?, &from)?;
+            let copied = get_mut(value, &from_path).cloned().unwrap_or_default();
+            import_add(value, &path, copied, mutations)
+        }
+        PatchOperation::Test { path, value: expected } => {
+            let located = locate(value, &parse_pointer(&path)?, &path);
+            match located.ok().and_then(|located| get_mut(value, &located)) {
+                Some(actual) if *actual == expected => Ok(()),
+                _ => Err(JsonPatchError::TestFailed { path }),
+            }
+        }
+    }
+}
+
+impl Serialize for PatchOperation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (op, from, path, value) = match self {
+            Self::Add { path, value } => ("add", None, path, Some(value)),
+            Self::Remove { path } => ("remove", None, path, None),
+            Self::Replace { path, value } => ("replace", None, path, Some(value)),
+            Self::Move { from, path } => ("move", Some(from), path, None),
+            Self::Copy { from, path } => ("copy", Some(from), path, None),
+            Self::Test { path, value } => ("test", None, path, Some(value)),
+        };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("op", op)?;
+        if let Some(from) = from {
+            map.serialize_entry("from", from)?;
+        }
+        map.serialize_entry("path", path)?;
+        if let Some(value) = value {
+            map.serialize_entry("value", value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PatchOperation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn field<E: serde::de::Error>(map: &mut Map<String, Value>, key: &'static str) -> Result<Value, E> {
+            map.remove(key).ok_or_else(|| E::missing_field(key))
+        }
+
+        fn string<E: serde::de::Error>(map: &mut Map<String, Value>, key: &'static str) -> Result<String, E> {
+            match field(map, key)? {
+                Value::String(str) => Ok(str),
+                _ => Err(E::custom(format_args!("field `{key}` must be a string"))),
+            }
+        }
+
+        let Value::Object(mut map) = Value::deserialize(deserializer)? else {
+            return Err(D::Error::custom("a JSON Patch operation must be an object"));
+        };
+        let op = string(&mut map, "op")?;
+        Ok(match &*op {
+            "add" => Self::Add {
+                path: string(&mut map, "path")?,
+                value: field(&mut map, "value")?,
+            },
+            "remove" => Self::Remove {
+                path: string(&mut map, "path")?,
+            },
+            "replace" => Self::Replace {
+                path: string(&mut map, "path")?,
+                value: field(&mut map, "value")?,
+            },
+            "move" => Self::Move {
+                from: string(&mut map, "from")?,
+                path: string(&mut map, "path")?,
+            },
+            "copy" => Self::Copy {
+                from: string(&mut map, "from")?,
+                path: string(&mut map, "path")?,
+            },
+            "test" => Self::Test {
+                path: string(&mut map, "path")?,
+                value: field(&mut map, "value")?,
+            },
+            op => {
+                return Err(D::Error::unknown_variant(
+                    op,
+                    &["add", "remove", "replace", "move", "copy", "test"],
+                ));
+            }
+        })
+    }
+}
+
+impl Serialize for JsonPatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonPatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+
+    fn from_json(value: Value) -> JsonPatch {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn export_sequence() {
+        let mut value = json!({"a": [1, 2, 3], "s": "x"});
+        let mutation = batch!(
+            _,
+            truncate!(a, 2),
+            append!(a, json!([4, 5])),
+            replace!(a.-1, json!(50)),
+            splice!(a, 0, 1, json!([6, 7])),
+            mv!(a, 0, 3),
+            append!(s, json!("y")),
+        );
+        let patch = JsonPatch::from_mutation(&mut value, mutation).unwrap();
+        assert_eq!(value, json!({"a": [7, 4, 50, 6], "s": "xy"}));
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {"op": "remove", "path": "/a/2"},
+                {"op": "remove", "path": "/a/1"},
+                {"op": "add", "path": "/a/-", "value": 4},
+                {"op": "add", "path": "/a/-", "value": 5},
+                {"op": "replace", "path": "/a/2", "value": 50},
+                {"op": "remove", "path": "/a/0"},
+                {"op": "add", "path": "/a/0", "value": 6},
+                {"op": "add", "path": "/a/1", "value": 7},
+                {"op": "move", "from": "/a/0", "path": "/a/3"},
+                {"op": "replace", "path": "/s", "value": "xy"},
+            ]),
+        );
+    }
+
+    #[test]
+    fn export_object() {
+        let mut value = json!({"a/b": {"c~": 1}, "n": 1, "t": ["x", "y"]});
+        let mutation = batch!(
+            _,
+            replace!(n, json!(2)),
+            replace!(m, json!(3)),
+            delete!(n),
+            increment!(m, json!(2)),
+            add!(t, json!(["y", "z"])),
+            remove!(t, json!(["x"])),
+        );
+        let patch = JsonPatch::from_mutation(&mut value, mutation).unwrap();
+        assert_eq!(value, json!({"a/b": {"c~": 1}, "m": 5, "t": ["y", "z"]}));
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {"op": "replace", "path": "/n", "value": 2},
+                {"op": "add", "path": "/m", "value": 3},
+                {"op": "remove", "path": "/n"},
+                {"op": "replace", "path": "/m", "value": 5},
+                {"op": "add", "path": "/t/-", "value": "z"},
+                {"op": "remove", "path": "/t/0"},
+            ]),
+        );
+
+        let mutation = Mutation {
+            path: vec!["a/b".to_string().into(), "c~".to_string().into()].into(),
+            kind: MutationKind::Replace(json!(2)),
+        };
+        let patch = JsonPatch::from_mutation(&mut value, mutation).unwrap();
+        assert_eq!(
+            patch.0,
+            vec![PatchOperation::Replace {
+                path: "/a~1b/c~0".into(),
+                value: json!(2),
+            }],
+        );
+    }
+
+    #[test]
+    fn export_error() {
+        let mut value = json!({"a": [1]});
+        let error = JsonPatch::from_mutation(&mut value, replace!(a.-2, json!(0))).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into(), PathSegment::Negative(2)].into(),
+            },
+        );
+        let error = JsonPatch::from_mutation(&mut value, truncate!(a, 2)).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::TruncateError {
+                path: vec!["a".into()].into(),
+                actual_len: 1,
+                truncate_len: 2,
+            },
+        );
+    }
+
+    #[test]
+    fn import_operations() {
+        let original = json!({"foo": ["bar", "baz", "qux"], "obj": {"0": 1}});
+        let mut value = original.clone();
+        let patch = from_json(json!([
+            {"op": "add", "path": "/foo/1", "value": "new"},
+            {"op": "add", "path": "/foo/-", "value": "end"},
+            {"op": "add", "path": "/obj/0", "value": 2},
+            {"op": "remove", "path": "/foo/0"},
+            {"op": "remove", "path": "/foo/3"},
+            {"op": "replace", "path": "/obj", "value": {"k": "v"}},
+            {"op": "move", "from": "/foo/0", "path": "/foo/-"},
+            {"op": "move", "from": "/obj/k", "path": "/foo/0"},
+            {"op": "copy", "from": "/foo/1", "path": "/copy"},
+            {"op": "test", "path": "/copy", "value": "baz"},
+        ]));
+        let mutation = patch.into_mutation(&mut value).unwrap().unwrap();
+        assert_eq!(
+            value,
+            json!({"foo": ["v", "baz", "qux", "new"], "obj": {}, "copy": "baz"})
+        );
+        assert_eq!(
+            mutation,
+            batch!(
+                _,
+                splice!(foo, 1, 0, json!(["new"])),
+                append!(foo, json!(["end"])),
+                Mutation {
+                    path: vec!["obj".into(), "0".into()].into(),
+                    kind: MutationKind::Replace(json!(2)),
+                },
+                splice!(foo, 0, 1, json!([])),
+                truncate!(foo, 1),
+                replace!(obj, json!({"k": "v"})),
+                mv!(foo, 0, 2),
+                delete!(obj.k),
+                splice!(foo, 0, 0, json!(["v"])),
+                replace!(copy, json!("baz")),
+            ),
+        );
+
+        let mut replica = original;
+        Json::mutate(&mut replica, mutation, &mut Default::default()).unwrap();
+        assert_eq!(replica, value);
+    }
+
+    #[test]
+    fn import_error() {
+        let mut value = json!({"a": [1, 2]});
+        let patch = from_json(json!([{"op": "test", "path": "/a/0", "value": 2}]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::TestFailed { path: "/a/0".into() },
+        );
+
+        let patch = from_json(json!([{"op": "add", "path": "/a/01", "value": 0}]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::InvalidPointer {
+                pointer: "/a/01".into()
+            },
+        );
+
+        let patch = from_json(json!([{"op": "remove", "path": "a"}]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::InvalidPointer { pointer: "a".into() },
+        );
+
+        let patch = from_json(json!([{"op": "replace", "path": "/b", "value": 0}]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::Mutation(MutationError::IndexError {
+                path: vec!["b".into()].into()
+            }),
+        );
+
+        let error = serde_json::from_value::<JsonPatch>(json!([{"op": "jump", "path": ""}])).unwrap_err();
+        assert!(error.to_string().contains("unknown variant `jump`"));
+    }
+
+    #[test]
+    fn import_atomic() {
+        let mut value = json!({"a": [1, 2]});
+        let patch = from_json(json!([
+            {"op": "add", "path": "/a/-", "value": 3},
+            {"op": "replace", "path": "/b", "value": 0},
+            {"op": "remove", "path": "/a/0"},
+            {"op": "test", "path": "/a/0", "value": 0},
+        ]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::Mutation(MutationError::IndexError {
+                path: vec!["b".into()].into()
+            }),
+        );
+        assert_eq!(value, json!({"a": [1, 2]}));
+
+        let patch = from_json(json!([
+            {"op": "add", "path": "/a/-", "value": 3},
+            {"op": "remove", "path": "/a/0"},
+            {"op": "test", "path": "/a/0", "value": 0},
+        ]));
+        assert_eq!(
+            patch.into_mutation(&mut value).unwrap_err(),
+            JsonPatchError::TestFailed { path: "/a/0".into() },
+        );
+        assert_eq!(value, json!({"a": [1, 2]}));
+    }
+
+    #[test]
+    fn round_trip() {
+        let original = json!({"items": [{"id": 1}, {"id": 2}], "title": "a"});
+        let mut value = original.clone();
+        let mutation = batch!(
+            _,
+            replace!(items.-2.id, json!(10)),
+            append!(items, json!([{"id": 3}])),
+            replace!(title, json!("b")),
+        );
+        let patch = JsonPatch::from_mutation(&mut value, mutation).unwrap();
+        let mut replica = original;
+        let mutation = patch.into_mutation(&mut replica).unwrap().unwrap();
+        assert_eq!(replica, value);
+        assert_eq!(
+            mutation,
+            batch!(
+                _,
+                replace!(items.0.id, json!(10)),
+                append!(items, json!([{"id": 3}])),
+                replace!(title, json!("b")),
+            ),
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Helpers for adding and removing set members in the sequences of adapter values.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::mem::take;
+
+/// Hashes a set member with a fixed hasher, so that equal members get equal hashes across calls.
+pub(crate) fn hash_member<T: Hash + ?Sized>(member: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    member.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Set of borrowed sequence elements, used by [`Adapter::add_members`](super::Adapter::add_members) and
+/// [`Adapter::remove_members`](super::Adapter::remove_members) to look up members without comparing every pair of elements.
+///
+/// Elements are bucketed by `hash` and only compared with `eq` within a bucket, so equal elements
+/// must have equal hashes. Values which do not implement [`Hash`] may hash only part of an element,
+/// e.g. its kind and length.
+pub(crate) struct MemberSet<'a, T, H, E> {
+    buckets: HashMap<u64, Vec<&'a T>>,
+    hash: H,
+    eq: E,
+}
+
+impl<'a, T, H, E> MemberSet<'a, T, H, E>
+where
+    H: Fn(&T) -> u64,
+    E: Fn(&T, &T) -> bool,
+{
+    /// Creates a set of `members`, compared with `hash` and `eq`.
+    pub(crate) fn new(members: impl IntoIterator<Item = &'a T>, hash: H, eq: E) -> Self {
+        let mut set = Self {
+            buckets: HashMap::new(),
+            hash,
+            eq,
+        };
+        for member in members {
+            set.insert(member);
+        }
+        set
+    }
+
+    /// Returns whether an element equal to `member` is in the set.
+    pub(crate) fn contains(&self, member: &T) -> bool {
+        self.buckets
+            .get(&(self.hash)(member))
+            .is_some_and(|bucket| bucket.iter().any(|element| (self.eq)(element, member)))
+    }
+
+    /// Inserts `member` into the set, returning whether no equal element was present.
+    pub(crate) fn insert(&mut self, member: &'a T) -> bool {
+        let bucket = self.buckets.entry((self.hash)(member)).or_default();
+        if bucket.iter().any(|element| (self.eq)(element, member)) {
+            return false;
+        }
+        bucket.push(member);
+        true
+    }
+}
+
+/// Appends the members of `rhs` absent from `lhs` to `lhs` and returns them, comparing members
+/// as in [`MemberSet`].
+pub(crate) fn add_members_by<T: Clone>(
+    lhs: &mut Vec<T>,
+    rhs: Vec<T>,
+    hash: impl Fn(&T) -> u64,
+    eq: impl Fn(&T, &T) -> bool,
+) -> Vec<T> {
+    let mut set = MemberSet::new(lhs.iter(), hash, eq);
+    let is_added = rhs.iter().map(|member| set.insert(member)).collect::<Vec<_>>();
+    let added = rhs
+        .into_iter()
+        .zip(is_added)
+        .filter_map(|(member, is_added)| is_added.then_some(member))
+        .collect::<Vec<_>>();
+    lhs.extend_from_slice(&added);
+    added
+}
+
+/// Removes the members of `rhs` from `lhs` and returns them, comparing members as in
+/// [`MemberSet`].
+pub(crate) fn remove_members_by<T>(
+    lhs: &mut Vec<T>,
+    rhs: &[T],
+    hash: impl Fn(&T) -> u64,
+    eq: impl Fn(&T, &T) -> bool,
+) -> Vec<T> {
+    let set = MemberSet::new(rhs, hash, eq);
+    let (removed, retained) = take(lhs).into_iter().partition(|member| set.contains(member));
+    *lhs = retained;
+    removed
+}
@@ -2,19 +2,83 @@
 //!
 //! This module provides the [`Adapter`] trait and implementations for various serialization
 //! formats. Adapters bridge the gap between morphix's internal mutation representation and
-//! external formats like JSON or YAML.
+//! external formats like JSON, YAML or MessagePack.
 
 use std::mem::take;
 
 use crate::{Mutation, MutationError, MutationKind, Mutations, Path, PathSegment};
 
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "dynamodb")]
+mod dynamodb;
 #[cfg(feature = "json")]
 mod json;
+#[cfg(feature = "json-merge-patch")]
+mod json_merge_patch;
+#[cfg(feature = "json-patch")]
+mod json_patch;
+#[cfg(all(
+    feature = "set",
+    any(
+        feature = "bson",
+        feature = "cbor",
+        feature = "json",
+        feature = "msgpack",
+        feature = "toml",
+        feature = "yaml"
+    )
+))]
+mod members;
+#[cfg(feature = "mongo-update")]
+mod mongo_update;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(any(
+    feature = "bson",
+    feature = "cbor",
+    feature = "json",
+    feature = "msgpack",
+    feature = "toml",
+    feature = "yaml"
+))]
+mod scalars;
+#[cfg(feature = "sql")]
+mod sql;
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(any(
+    feature = "dynamodb",
+    feature = "json-patch",
+    feature = "mongo-update",
+    feature = "sql"
+))]
+mod update;
 #[cfg(feature = "yaml")]
 mod yaml;
 
+#[cfg(feature = "bson")]
+pub use self::bson::Bson;
+#[cfg(feature = "cbor")]
+pub use cbor::{Cbor, CborError};
+#[cfg(feature = "dynamodb")]
+pub use dynamodb::{DynamoDbUpdate, DynamoDbUpdateError};
 #[cfg(feature = "json")]
 pub use json::Json;
+#[cfg(feature = "json-merge-patch")]
+pub use json_merge_patch::JsonMergePatch;
+#[cfg(feature = "json-patch")]
+pub use json_patch::{JsonPatch, JsonPatchError, PatchOperation};
+#[cfg(feature = "mongo-update")]
+pub use mongo_update::{MongoUpdate, MongoUpdateError};
+#[cfg(feature = "msgpack")]
+pub use msgpack::{MessagePack, MessagePackError};
+#[cfg(feature = "sql")]
+pub use sql::{SqlDialect, SqlParam, SqlUpdate, SqlUpdateError};
+#[cfg(feature = "toml")]
+pub use toml::Toml;
 #[cfg(feature = "yaml")]
 pub use yaml::Yaml;
 
@@ -26,12 +90,16 @@ pub use yaml::Yaml;
 ///
 /// ## Type Parameters
 ///
-/// - [`Value`](Adapter::Value): Type used to represent [`Replace`](MutationKind::Replace) and
-///   [`Append`](MutationKind::Append) values.
+/// - [`Value`](Adapter::Value): Type used to represent [`Replace`](MutationKind::Replace),
+///   [`Append`](MutationKind::Append), [`Splice`](MutationKind::Splice),
+///   [`Increment`](MutationKind::Increment), [`Add`](MutationKind::Add) and
+///   [`Remove`](MutationKind::Remove) values.
 /// - [`Error`](Adapter::Error): Error type for serialization / deserialization operations.
 pub trait Adapter: Sized {
-    /// Type used to represent [`Replace`](MutationKind::Replace) and
-    /// [`Append`](MutationKind::Append) values.
+    /// Type used to represent [`Replace`](MutationKind::Replace),
+    /// [`Append`](MutationKind::Append), [`Splice`](MutationKind::Splice),
+    /// [`Increment`](MutationKind::Increment), [`Add`](MutationKind::Add) and
+    /// [`Remove`](MutationKind::Remove) values.
     type Value;
 
     /// Error type for serialization / deserialization operations.
@@ -106,7 +174,7 @@ pub trait Adapter: Sized {
     /// Returns the appendable length of a value.
     ///
     /// This method returns the current length of a value that can be used with
-    /// [`append`](Adapter::append) operations.
+    /// [`append`](Adapter::append) and [`splice`](Adapter::splice) operations.
     ///
     /// ## Returns
     ///
@@ -116,7 +184,7 @@ pub trait Adapter: Sized {
     /// ## Note
     ///
     /// For strings, the returned length represents the char count, not the byte length.
-    #[cfg(feature = "append")]
+    #[cfg(any(feature = "append", feature = "splice"))]
     fn len(value: &Self::Value) -> Option<usize>;
 
     /// Truncates a value by removing elements from the end.
@@ -141,74 +209,200 @@ pub trait Adapter: Sized {
     #[cfg(feature = "truncate")]
     fn truncate(value: &mut Self::Value, truncate_len: usize) -> Option<usize>;
 
+    /// Replaces a range of elements in the middle of a value.
+    ///
+    /// This method performs an operation similar to [`Vec::splice`], removing `delete_len`
+    /// elements starting at `index` from `value` and inserting the elements of `insert_value` in
+    /// their place.
+    ///
+    /// ## Parameters
+    ///
+    /// - `value`: The value to splice
+    /// - `index`: The index of the first element to remove
+    /// - `delete_len`: The number of elements to remove
+    /// - `insert_value`: The value whose elements are inserted at `index`
+    ///
+    /// ## Returns
+    ///
+    /// - `Some(insert_len)`: The length of the inserted portion
+    /// - `None`: If the operation is not supported (e.g., incompatible types between `value` and
+    ///   `insert_value`), or if the removed range is out of bounds
+    ///
+    /// ## Note
+    ///
+    /// For strings, `index`, `delete_len` and the returned length are counted in chars, not bytes.
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize>;
+
+    /// Moves an element of a value to another position.
+    ///
+    /// This method removes the element at `from` and inserts it at `to`, shifting the elements in
+    /// between. Both indices are counted from the start of the value, and `to` refers to the
+    /// position after removal. For ordered maps, the indices refer to the position of the entries.
+    ///
+    /// ## Returns
+    ///
+    /// - `Some(())`: If the element was moved successfully
+    /// - `None`: If the operation is not supported on this value type, or if either index is out of
+    ///   bounds
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()>;
+
+    /// Adds a delta to a value.
+    ///
+    /// This method performs an operation similar to [`AddAssign`](std::ops::AddAssign), adding
+    /// `delta` to `value`. It is used to apply [`Increment`](MutationKind::Increment) mutations and
+    /// to merge consecutive increments of the same value.
+    ///
+    /// ## Returns
+    ///
+    /// - `Some(())`: If the delta was added successfully
+    /// - `None`: If the operation is not supported (e.g., incompatible types between `value` and
+    ///   `delta`), or if the result is out of range
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()>;
+
+    /// Inserts members into a set.
+    ///
+    /// This method performs an operation similar to [`HashSet::extend`](std::collections::HashSet),
+    /// adding each element of `members` that is not yet present in `value`. It is used to apply
+    /// [`Add`](MutationKind::Add) mutations.
+    ///
+    /// ## Returns
+    ///
+    /// - `Some(inserted)`: The members which were not present before, as a sequence
+    /// - `None`: If the operation is not supported (e.g., `value` or `members` is not a sequence)
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value>;
+
+    /// Removes members from a set.
+    ///
+    /// This method removes each element of `members` from `value`, ignoring elements that are not
+    /// present. It is used to apply [`Remove`](MutationKind::Remove) mutations and to cancel
+    /// out consecutive [`Add`](MutationKind::Add) and [`Remove`](MutationKind::Remove) mutations of
+    /// the same members.
+    ///
+    /// ## Returns
+    ///
+    /// - `Some(removed)`: The members which were present before, as a sequence
+    /// - `None`: If the operation is not supported (e.g., `value` or `members` is not a sequence)
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value>;
+
     /// Applies a [Mutation] to an existing value.
     fn mutate(
-        mut value: &mut Self::Value,
-        mut mutation: Mutation<Self::Value>,
+        value: &mut Self::Value,
+        mutation: Mutation<Self::Value>,
         path_stack: &mut Path<false>,
     ) -> Result<(), MutationError> {
-        let is_replace = matches!(mutation.kind, MutationKind::Replace { .. });
+        apply_mutation::<Self>(value, mutation, path_stack)
+    }
+}
+
+/// Default implementation of [`Adapter::mutate`].
+///
+/// Adapters overriding [`Adapter::mutate`] can delegate to this function once they have reached
+/// the target value.
+fn apply_mutation<A: Adapter>(
+    mut value: &mut A::Value,
+    mut mutation: Mutation<A::Value>,
+    path_stack: &mut Path<false>,
+) -> Result<(), MutationError> {
+    let is_replace = matches!(mutation.kind, MutationKind::Replace { .. });
+    #[cfg(feature = "delete")]
+    let is_delete = matches!(mutation.kind, MutationKind::Delete);
+
+    while let Some(segment) = mutation.path.pop() {
+        let is_last_segment = mutation.path.is_empty();
         #[cfg(feature = "delete")]
-        let is_delete = matches!(mutation.kind, MutationKind::Delete);
-
-        while let Some(segment) = mutation.path.pop() {
-            let is_last_segment = mutation.path.is_empty();
-            #[cfg(feature = "delete")]
-            if is_last_segment && is_delete {
-                match Self::delete(value, &segment) {
-                    Some(_) => return Ok(()),
-                    None => {
-                        path_stack.push(segment);
-                        return Err(MutationError::IndexError { path: take(path_stack) });
-                    }
+        if is_last_segment && is_delete {
+            match A::delete(value, &segment) {
+                Some(_) => return Ok(()),
+                None => {
+                    path_stack.push(segment);
+                    return Err(MutationError::IndexError { path: take(path_stack) });
                 }
             }
-            let inner_value = Self::get_mut(value, &segment, is_replace && is_last_segment);
-            path_stack.push(segment);
-            let Some(inner_value) = inner_value else {
-                return Err(MutationError::IndexError { path: take(path_stack) });
-            };
-            value = inner_value;
         }
-        #[cfg(feature = "delete")]
-        if is_delete {
+        let inner_value = A::get_mut(value, &segment, is_replace && is_last_segment);
+        path_stack.push(segment);
+        let Some(inner_value) = inner_value else {
             return Err(MutationError::IndexError { path: take(path_stack) });
-        }
+        };
+        value = inner_value;
+    }
+    #[cfg(feature = "delete")]
+    if is_delete {
+        return Err(MutationError::IndexError { path: take(path_stack) });
+    }
 
-        match mutation.kind {
-            MutationKind::Replace(replace_value) => {
-                *value = replace_value;
+    match mutation.kind {
+        MutationKind::Replace(replace_value) => {
+            *value = replace_value;
+        }
+        #[cfg(feature = "append")]
+        MutationKind::Append(append_value) => {
+            if A::append(value, append_value).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
             }
-            #[cfg(feature = "append")]
-            MutationKind::Append(append_value) => {
-                if Self::append(value, append_value).is_none() {
-                    return Err(MutationError::OperationError { path: take(path_stack) });
-                }
+        }
+        #[cfg(feature = "truncate")]
+        MutationKind::Truncate(truncate_len) => {
+            let Some(remaining) = A::truncate(value, truncate_len) else {
+                return Err(MutationError::OperationError { path: take(path_stack) });
+            };
+            if remaining > 0 {
+                return Err(MutationError::TruncateError {
+                    path: take(path_stack),
+                    actual_len: truncate_len - remaining,
+                    truncate_len,
+                });
             }
-            #[cfg(feature = "truncate")]
-            MutationKind::Truncate(truncate_len) => {
-                let Some(remaining) = Self::truncate(value, truncate_len) else {
-                    return Err(MutationError::OperationError { path: take(path_stack) });
-                };
-                if remaining > 0 {
-                    return Err(MutationError::TruncateError {
-                        path: take(path_stack),
-                        actual_len: truncate_len - remaining,
-                        truncate_len,
-                    });
-                }
+        }
+        #[cfg(feature = "splice")]
+        MutationKind::Splice {
+            index,
+            delete_len,
+            insert,
+        } => {
+            if A::splice(value, index, delete_len, insert).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
             }
-            #[cfg(feature = "delete")]
-            MutationKind::Delete => unreachable!(),
-            MutationKind::Batch(mutations) => {
-                let len = path_stack.len();
-                for mutation in mutations {
-                    Self::mutate(value, mutation, path_stack)?;
-                    path_stack.truncate(len);
-                }
+        }
+        #[cfg(feature = "move")]
+        MutationKind::Move { from, to } => {
+            if A::move_index(value, from, to).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
+            }
+        }
+        #[cfg(feature = "increment")]
+        MutationKind::Increment(delta) => {
+            if A::increment(value, delta).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
+            }
+        }
+        #[cfg(feature = "set")]
+        MutationKind::Add(members) => {
+            if A::add_members(value, members).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
+            }
+        }
+        #[cfg(feature = "set")]
+        MutationKind::Remove(members) => {
+            if A::remove_members(value, &members).is_none() {
+                return Err(MutationError::OperationError { path: take(path_stack) });
+            }
+        }
+        #[cfg(feature = "delete")]
+        MutationKind::Delete => unreachable!(),
+        MutationKind::Batch(mutations) => {
+            let len = path_stack.len();
+            for mutation in mutations {
+                A::mutate(value, mutation, path_stack)?;
+                path_stack.truncate(len);
             }
         }
-
-        Ok(())
     }
+
+    Ok(())
 }
@@ -0,0 +1,383 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use bson::{Bson as Value, Document};
+
+use super::update::{Update, export_root, get, register, resolve};
+use crate::adapter::Bson;
+use crate::{Adapter, Mutation, MutationError, MutationKind, PathSegment};
+
+/// A MongoDB update document, as accepted by `updateOne` and `updateMany`.
+///
+/// [`MongoUpdate`] converts morphix mutations into update operators such as `$set`, `$unset` and
+/// `$push`. Fields are addressed with dotted paths, and array elements with their positions, so
+/// that [`Negative`](PathSegment::Negative) indices are resolved against the document the
+/// mutations apply to, which is updated along the way.
+///
+/// ## Example
+///
+/// ```
+/// use bson::doc;
+/// use morphix::adapter::{Bson, MongoUpdate};
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut data = Data { name: "a".into(), tags: vec!["x".into()] };
+/// let mut replica = bson::to_bson(&data).unwrap();
+/// let Bson(mutation) = observe!(data => {
+///     data.name = "b".into();
+///     data.tags.push("y".into());
+/// }).unwrap();
+///
+/// let MongoUpdate(update) = MongoUpdate::from_mutation(&mut replica, mutation.unwrap()).unwrap();
+/// assert_eq!(update, doc! {
+///     "$set": { "name": "b" },
+///     "$push": { "tags": { "$each": ["y"] } },
+/// });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MongoUpdate(pub Document);
+
+/// Error type for converting mutations into a [`MongoUpdate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MongoUpdateError {
+    /// A key cannot be used as a field name, as it is empty, contains a `.` or starts with a `$`.
+    InvalidKey {
+        /// The offending key.
+        key: String,
+    },
+    /// The root of the document is not a document.
+    InvalidRoot,
+    /// A mutation could not be applied to the document.
+    Mutation(MutationError),
+}
+
+impl Display for MongoUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey { key } => write!(f, "invalid field name {key:?}"),
+            Self::InvalidRoot => write!(f, "the root of the document is not a document"),
+            Self::Mutation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for MongoUpdateError {}
+
+impl From<MutationError> for MongoUpdateError {
+    fn from(error: MutationError) -> Self {
+        Self::Mutation(error)
+    }
+}
+
+/// An update operator on a single field.
+enum Operator {
+    /// `$set` to the final value of the field, or `$unset` if it no longer exists.
+    Set,
+    /// `$push` with `$each`.
+    #[cfg(feature = "append")]
+    Push(Vec<Value>),
+    /// `$pop` of the last element.
+    #[cfg(feature = "truncate")]
+    Pop,
+    /// `$push` with an empty `$each` and a `$slice` to the given length.
+    #[cfg(feature = "truncate")]
+    Slice(i64),
+    /// `$inc`.
+    #[cfg(feature = "increment")]
+    Inc(Value),
+    /// `$addToSet` with `$each`.
+    #[cfg(feature = "set")]
+    AddToSet(Vec<Value>),
+    /// `$pull` with `$in`.
+    #[cfg(feature = "set")]
+    Pull(Vec<Value>),
+}
+
+impl MongoUpdate {
+    /// Converts a mutation into an update document.
+    ///
+    /// `value` is the document before the mutation, and is updated to the document after it.
+    ///
+    /// | Mutation | Operator |
+    /// | --- | --- |
+    /// | [`Replace`](MutationKind::Replace) | `$set` |
+    /// | [`Delete`](MutationKind::Delete) | `$unset` |
+    /// | [`Append`](MutationKind::Append) to an array | `$push` with `$each` |
+    /// | [`Truncate`](MutationKind::Truncate) of an array | `$pop` for a single element, `$push` with `$slice` otherwise |
+    /// | [`Increment`](MutationKind::Increment) of a number | `$inc` |
+    /// | [`Add`](MutationKind::Add) | `$addToSet` with `$each` |
+    /// | [`Remove`](MutationKind::Remove) | `$pull` with `$in` |
+    ///
+    /// Other mutations, such as appending to a string, are converted into a `$set` of the new
+    /// value. As MongoDB rejects updates touching a field and one of its descendants, mutations at
+    /// overlapping paths are merged into a `$set` of the outermost field, except for consecutive
+    /// appends to the same array. A mutation of the root is converted into a `$set` or `$unset` of
+    /// each changed top-level field.
+    pub fn from_mutation(value: &mut Value, mutation: Mutation<Value>) -> Result<Self, MongoUpdateError> {
+        let mut updates = vec![];
+        export(value, mutation, &mut updates)?;
+        let mut document = Document::new();
+        for (path, operator) in updates {
+            let (name, argument) = match operator {
+                Operator::Set => match get(value, &path) {
+                    Some(value) => ("$set", value.clone()),
+                    None => ("$unset", Value::String(String::new())),
+                },
+                #[cfg(feature = "append")]
+                Operator::Push(each) => ("$push", bson::bson!({ "$each": each })),
+                #[cfg(feature = "truncate")]
+                Operator::Pop => ("$pop", Value::Int32(1)),
+                #[cfg(feature = "truncate")]
+                Operator::Slice(len) => ("$push", bson::bson!({ "$each": [], "$slice": len })),
+                #[cfg(feature = "increment")]
+                Operator::Inc(delta) => ("$inc", delta),
+                #[cfg(feature = "set")]
+                Operator::AddToSet(each) => ("$addToSet", bson::bson!({ "$each": each })),
+                #[cfg(feature = "set")]
+                Operator::Pull(members) => ("$pull", bson::bson!({ "$in": members })),
+            };
+            let Value::Document(fields) = document.entry(name.into()).or_insert_with(|| Document::new().into()) else {
+                unreachable!("operators are documents");
+            };
+            fields.insert(to_field(&path), argument);
+        }
+        Ok(Self(document))
+    }
+}
+
+/// Builds a dotted field name from a path without [`Negative`](PathSegment::Negative) segments.
+fn to_field(path: &[PathSegment]) -> String {
+    let mut field = String::new();
+    for segment in path {
+        if !field.is_empty() {
+            field.push('.');
+        }
+        match segment {
+            PathSegment::String(key) => field.push_str(key),
+            PathSegment::Positive(index) => field.push_str(&index.to_string()),
+            PathSegment::Negative(_) => unreachable!("negative indices are resolved before building fields"),
+        }
+    }
+    field
+}
+
+fn check_key(key: &str) -> Result<(), MongoUpdateError> {
+    if key.is_empty() || key.contains('.') || key.starts_with('$') {
+        return Err(MongoUpdateError::InvalidKey { key: key.to_string() });
+    }
+    Ok(())
+}
+
+/// Replaces [`Negative`](PathSegment::Negative) segments of a path with the corresponding
+/// [`Positive`](PathSegment::Positive) indices in `value`, and checks that keys are valid field
+/// names.
+fn resolve_field(value: &Value, path: &[PathSegment]) -> Result<Vec<PathSegment>, MongoUpdateError> {
+    let path = resolve(value, path)?;
+    for segment in &path {
+        if let PathSegment::String(key) = segment {
+            check_key(key)?;
+        }
+    }
+    Ok(path)
+}
+
+impl Update for Operator {
+    const SET: Self = Self::Set;
+
+    fn merge(&mut self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "append")]
+            (Self::Push(each), Self::Push(other)) => {
+                each.extend(other.iter().cloned());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn export(
+    value: &mut Value,
+    mutation: Mutation<Value>,
+    updates: &mut Vec<(Vec<PathSegment>, Operator)>,
+) -> Result<(), MongoUpdateError> {
+    for (path, kind) in mutation.flatten() {
+        if path.is_empty() {
+            let keys = export_root::<Bson>(value, kind)?.ok_or(MongoUpdateError::InvalidRoot)?;
+            for key in keys {
+                check_key(&key)?;
+                register(updates, vec![key.into()], Operator::Set);
+            }
+        } else {
+            let path = resolve_field(value, &path)?;
+            export_kind(value, path, kind, updates)?;
+        }
+    }
+    Ok(())
+}
+
+fn export_kind(
+    value: &mut Value,
+    path: Vec<PathSegment>,
+    kind: MutationKind<Value>,
+    updates: &mut Vec<(Vec<PathSegment>, Operator)>,
+) -> Result<(), MongoUpdateError> {
+    let operator = match (&kind, get(value, &path)) {
+        #[cfg(feature = "append")]
+        (MutationKind::Append(Value::Array(each)), Some(Value::Array(_))) => Operator::Push(each.clone()),
+        #[cfg(feature = "truncate")]
+        (MutationKind::Truncate(1), Some(Value::Array(vec))) if !vec.is_empty() => Operator::Pop,
+        #[cfg(feature = "truncate")]
+        (MutationKind::Truncate(len), Some(Value::Array(vec))) => {
+            Operator::Slice(vec.len().saturating_sub(*len) as i64)
+        }
+        #[cfg(feature = "increment")]
+        (
+            MutationKind::Increment(delta @ (Value::Int32(_) | Value::Int64(_) | Value::Double(_))),
+            Some(Value::Int32(_) | Value::Int64(_) | Value::Double(_)),
+        ) => Operator::Inc(delta.clone()),
+        #[cfg(feature = "set")]
+        (MutationKind::Add(Value::Array(each)), Some(Value::Array(_))) => Operator::AddToSet(each.clone()),
+        #[cfg(feature = "set")]
+        (MutationKind::Remove(Value::Array(members)), Some(Value::Array(_))) => Operator::Pull(members.clone()),
+        _ => Operator::Set,
+    };
+    Bson::mutate(
+        value,
+        Mutation {
+            path: path.clone().into(),
+            kind,
+        },
+        &mut Default::default(),
+    )?;
+    register(updates, path, operator);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::{bson, doc};
+    use morphix_test_utils::*;
+
+    use super::*;
+
+    fn export(value: &mut Value, mutation: Mutation<Value>) -> Document {
+        MongoUpdate::from_mutation(value, mutation).unwrap().0
+    }
+
+    #[test]
+    fn export_operators() {
+        let mut value = bson!({"a": {"b": 1}, "c": "x", "d": [1, 2, 3], "e": [1], "f": 1, "g": [1, 2]});
+        let update = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(a.b, bson!(2)),
+                delete!(c),
+                append!(d, bson!([4])),
+                append!(d, bson!([5])),
+                truncate!(e, 1),
+                increment!(f, bson!(2)),
+                add!(g, bson!([2, 3])),
+            ),
+        );
+        assert_eq!(
+            update,
+            doc! {
+                "$set": { "a.b": 2 },
+                "$unset": { "c": "" },
+                "$push": { "d": { "$each": [4, 5] } },
+                "$pop": { "e": 1 },
+                "$inc": { "f": 2 },
+                "$addToSet": { "g": { "$each": [2, 3] } },
+            },
+        );
+        assert_eq!(
+            value,
+            bson!({"a": {"b": 2}, "d": [1, 2, 3, 4, 5], "e": [], "f": 3, "g": [1, 2, 3]})
+        );
+
+        let update = export(&mut value, batch!(_, truncate!(d, 2), remove!(g, bson!([1]))));
+        assert_eq!(
+            update,
+            doc! {
+                "$push": { "d": { "$each": [], "$slice": 3_i64 } },
+                "$pull": { "g": { "$in": [1] } },
+            },
+        );
+    }
+
+    #[test]
+    fn export_positions() {
+        let mut value = bson!({"list": [{"x": 1}, {"x": 2}, {"x": 3}], "s": "a"});
+        let update = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(list.-1.x, bson!(30)),
+                replace!(list.0.x, bson!(10)),
+                append!(s, bson!("b"))
+            ),
+        );
+        assert_eq!(update, doc! { "$set": { "list.2.x": 30, "list.0.x": 10, "s": "ab" } });
+    }
+
+    #[test]
+    fn export_overlaps() {
+        let mut value = bson!({"a": {"b": 1, "c": 2}, "d": [1, 2]});
+        let update = export(
+            &mut value,
+            batch!(
+                _,
+                replace!(a.b, bson!(3)),
+                delete!(a.c),
+                replace!(a, bson!({"b": 4})),
+                truncate!(d, 1),
+                append!(d, bson!([3])),
+            ),
+        );
+        assert_eq!(update, doc! { "$set": { "a": { "b": 4 }, "d": [1, 3] } });
+
+        let update = export(&mut value, batch!(_, replace!(e, bson!({})), delete!(e)));
+        assert_eq!(update, doc! { "$unset": { "e": "" } });
+    }
+
+    #[test]
+    fn export_root() {
+        let mut value = bson!({"_id": 1, "a": 1, "b": 2});
+        let update = export(&mut value, replace!(_, bson!({"_id": 1, "a": 3, "c": 4})));
+        assert_eq!(update, doc! { "$set": { "a": 3, "c": 4 }, "$unset": { "b": "" } });
+
+        let error = MongoUpdate::from_mutation(&mut value, replace!(_, bson!([]))).unwrap_err();
+        assert_eq!(error, MongoUpdateError::InvalidRoot);
+    }
+
+    #[test]
+    fn export_errors() {
+        let mut value = bson!({"a": {}});
+        for key in ["x.y", "$x", ""] {
+            let mutation = Mutation {
+                path: vec!["a".into(), key.into()].into(),
+                kind: MutationKind::Replace(bson!(1)),
+            };
+            let error = MongoUpdate::from_mutation(&mut value, mutation).unwrap_err();
+            assert_eq!(error, MongoUpdateError::InvalidKey { key: key.into() });
+        }
+
+        let error = MongoUpdate::from_mutation(&mut value, replace!(a.-1, bson!(1))).unwrap_err();
+        assert_eq!(
+            error,
+            MongoUpdateError::Mutation(MutationError::IndexError {
+                path: vec!["a".into(), PathSegment::Negative(1)].into(),
+            }),
+        );
+    }
+}
@@ -0,0 +1,674 @@
+use std::error::Error;
+use std::fmt::Display;
+
+#[cfg(any(feature = "append", feature = "truncate", feature = "splice"))]
+use rmpv::Utf8String;
+use rmpv::Value;
+use serde::Deserialize;
+
+use self::value::to_value;
+#[cfg(feature = "set")]
+use crate::adapter::members::{add_members_by, hash_member, remove_members_by};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
+use crate::wire::Versioned;
+use crate::{Adapter, Mutation, Mutations, PathSegment};
+
+mod value;
+
+/// MessagePack adapter for morphix mutation serialization.
+///
+/// [`MessagePack`] implements the [`Adapter`] trait using [`rmpv::Value`]. Values are serialized
+/// with [`rmp_serde`], using maps for structs so that fields can be addressed by name.
+///
+/// Besides the values, the whole mutation can be encoded into a single MessagePack frame with
+/// [`to_vec`](MessagePack::to_vec), and decoded with [`from_slice`](MessagePack::from_slice).
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::MessagePack;
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     value: i32,
+/// }
+///
+/// let mut data = Data { value: 42 };
+/// let patch: MessagePack = observe!(data => {
+///     data.value += 1;
+/// }).unwrap();
+///
+/// let bytes = patch.to_vec().unwrap();
+/// assert_eq!(MessagePack::from_slice(&bytes).unwrap(), patch);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessagePack(pub Option<Mutation<Value>>);
+
+impl From<Option<Mutation<Value>>> for MessagePack {
+    fn from(mutation: Option<Mutation<Value>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<MessagePack> for Option<Mutation<Value>> {
+    fn from(MessagePack(mutation): MessagePack) -> Self {
+        mutation
+    }
+}
+
+/// Error type for decoding a [`MessagePack`] frame.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MessagePackError {
+    /// The frame is not valid MessagePack, or does not encode a mutation in the current version of
+    /// the [wire format](crate::wire).
+    Decode(rmp_serde::decode::Error),
+    /// The frame has bytes after the encoded mutation.
+    TrailingBytes,
+}
+
+impl Display for MessagePackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Decode(error) => error.fmt(f),
+            Self::TrailingBytes => write!(f, "trailing bytes after mutation"),
+        }
+    }
+}
+
+impl Error for MessagePackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<rmp_serde::decode::Error> for MessagePackError {
+    fn from(error: rmp_serde::decode::Error) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl MessagePack {
+    /// Encodes the mutation into a single MessagePack frame.
+    ///
+    /// The frame holds the mutation in the compact form of the [wire format](crate::wire), wrapped
+    /// in [`Versioned`], where an empty mutation is `nil`. For example, a
+    /// [`Delete`](crate::MutationKind::Delete) at `.a` is encoded as `[1, [["a"], 5]]`.
+    ///
+    /// Returns an error if a [`Negative`](PathSegment::Negative) index does not fit in an `i64`.
+    pub fn to_vec(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        rmp_serde::to_vec(&Versioned(&self.0))
+    }
+
+    /// Decodes a mutation from a MessagePack frame produced by [`to_vec`](MessagePack::to_vec).
+    pub fn from_slice(mut bytes: &[u8]) -> Result<Self, MessagePackError> {
+        let mut deserializer = rmp_serde::Deserializer::new(&mut bytes);
+        let Versioned(mutation) = Versioned::deserialize(&mut deserializer)?;
+        if !bytes.is_empty() {
+            return Err(MessagePackError::TrailingBytes);
+        }
+        Ok(Self(mutation))
+    }
+}
+
+impl Adapter for MessagePack {
+    type Value = Value;
+    type Error = rmp_serde::encode::Error;
+
+    fn from_mutations(mutation: Mutations) -> Result<Self, Self::Error> {
+        Ok(Self(
+            mutation
+                .into_inner()
+                .map(|mutation| mutation.try_map(|value| to_value(&*value)))
+                .transpose()?,
+        ))
+    }
+
+    fn get_mut<'a>(
+        value: &'a mut Self::Value,
+        segment: &PathSegment,
+        allow_create: bool,
+    ) -> Option<&'a mut Self::Value> {
+        match (value, segment) {
+            (Value::Array(vec), PathSegment::Positive(index)) => vec.get_mut(*index),
+            (Value::Array(vec), PathSegment::Negative(index)) => {
+                vec.len().checked_sub(*index).and_then(|i| vec.get_mut(i))
+            }
+            (Value::Map(map), segment) => {
+                let key = segment.as_key()?;
+                let index = match key_position(map, &key) {
+                    Some(index) => index,
+                    None if allow_create => {
+                        map.push((new_key(map, &key), Value::Nil));
+                        map.len() - 1
+                    }
+                    None => return None,
+                };
+                Some(&mut map[index].1)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "delete")]
+    fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
+        match (value, segment) {
+            (Value::Map(map), segment) => {
+                let key = segment.as_key()?;
+                let index = key_position(map, &key)?;
+                Some(map.remove(index).1)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "append")]
+    fn append(value: &mut Self::Value, append_value: Self::Value) -> Option<usize> {
+        match (value, append_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let rhs = rhs.as_str()?;
+                string_mut(lhs, |lhs| {
+                    *lhs += rhs;
+                    Some(rhs.chars().count())
+                })
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let len = rhs.len();
+                lhs.extend(rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "append", feature = "splice"))]
+    fn len(value: &Self::Value) -> Option<usize> {
+        match value {
+            Value::String(str) => Some(str.as_str()?.chars().count()),
+            Value::Array(vec) => Some(vec.len()),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "truncate")]
+    fn truncate(value: &mut Self::Value, mut truncate_len: usize) -> Option<usize> {
+        match value {
+            Value::String(str) => string_mut(str, |str| {
+                let mut chars = str.char_indices();
+                let mut new_len = str.len();
+                while truncate_len > 0
+                    && let Some((index, _)) = chars.next_back()
+                {
+                    truncate_len -= 1;
+                    new_len = index;
+                }
+                str.truncate(new_len);
+                Some(truncate_len)
+            }),
+            Value::Array(vec) => {
+                let actual_len = vec.len();
+                let new_len = actual_len.saturating_sub(truncate_len);
+                vec.truncate(new_len);
+                Some(truncate_len.saturating_sub(actual_len))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (value, insert_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let rhs = rhs.as_str()?;
+                string_mut(lhs, |lhs| {
+                    let start = char_index(lhs, index)?;
+                    let end = start + char_index(&lhs[start..], delete_len)?;
+                    lhs.replace_range(start..end, rhs);
+                    Some(rhs.chars().count())
+                })
+            }
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let len = rhs.len();
+                lhs.splice(index..end, rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match value {
+            Value::Array(vec) => move_element(vec, from, to),
+            Value::Map(map) => move_element(map, from, to),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(value, delta, |value, delta| match (value, delta) {
+            (Value::F32(lhs), Value::F32(rhs)) => {
+                *lhs += rhs;
+                Some(())
+            }
+            (lhs, rhs) => {
+                *lhs = add_numbers(lhs, &rhs)?;
+                Some(())
+            }
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let added = add_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(added))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Array(lhs), Value::Array(rhs)) => {
+                let removed = remove_members_by(lhs, rhs, member_hash, |lhs, rhs| lhs == rhs);
+                Some(Value::Array(removed))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the index of the entry whose key has the string form `key`, which is how
+/// [`PathSegment::from_key`] represents integer and boolean keys.
+fn key_position(map: &[(Value, Value)], key: &str) -> Option<usize> {
+    map.iter().position(|(k, _)| match k {
+        Value::String(k) => k.as_str() == Some(key),
+        Value::Integer(k) => k.to_string() == key,
+        Value::Boolean(k) => k.to_string() == key,
+        _ => false,
+    })
+}
+
+/// Creates the key of a new entry from its string form, as an integer if the map already has
+/// integer keys.
+fn new_key(map: &[(Value, Value)], key: &str) -> Value {
+    if map.iter().any(|(k, _)| k.is_i64() || k.is_u64()) {
+        if let Ok(key) = key.parse::<u64>() {
+            return key.into();
+        }
+        if let Ok(key) = key.parse::<i64>() {
+            return key.into();
+        }
+    }
+    key.into()
+}
+
+/// Runs `f` on the contents of a valid UTF-8 string, or returns [`None`] if it is not valid UTF-8.
+#[cfg(any(feature = "append", feature = "truncate", feature = "splice"))]
+fn string_mut<T>(utf8: &mut Utf8String, f: impl FnOnce(&mut String) -> Option<T>) -> Option<T> {
+    if !utf8.is_str() {
+        return None;
+    }
+    let mut str = std::mem::replace(utf8, "".into())
+        .into_str()
+        .expect("string is valid UTF-8");
+    let result = f(&mut str);
+    *utf8 = str.into();
+    result
+}
+
+/// Hashes a set member for [`add_members_by`] and [`remove_members_by`], since [`Value`] does not
+/// implement [`Hash`](std::hash::Hash). Compound values are only hashed by their length.
+#[cfg(feature = "set")]
+fn member_hash(value: &Value) -> u64 {
+    let discriminant = std::mem::discriminant(value);
+    match value {
+        Value::Boolean(bool) => hash_member(&(discriminant, bool)),
+        Value::Integer(integer) => hash_member(&(discriminant, integer.as_i64(), integer.as_u64())),
+        Value::String(str) => hash_member(&(discriminant, str.as_bytes())),
+        Value::Binary(bytes) => hash_member(&(discriminant, bytes)),
+        Value::Array(vec) => hash_member(&(discriminant, vec.len())),
+        Value::Map(map) => hash_member(&(discriminant, map.len())),
+        Value::Ext(tag, bytes) => hash_member(&(discriminant, tag, bytes)),
+        _ => hash_member(&discriminant),
+    }
+}
+
+#[cfg(feature = "move")]
+fn move_element<T>(vec: &mut Vec<T>, from: usize, to: usize) -> Option<()> {
+    if from >= vec.len() || to >= vec.len() {
+        return None;
+    }
+    let element = vec.remove(from);
+    vec.insert(to, element);
+    Some(())
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        let Value::Integer(integer) = self else {
+            return None;
+        };
+        integer
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| integer.as_u64().map(i128::from))
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        match u64::try_from(value) {
+            Ok(value) => Some(value.into()),
+            Err(_) => i64::try_from(value).ok().map(Value::from),
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        self.as_f64()
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(Value::F64(value))
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Value {
+    type Number = Self;
+
+    fn duration_delta(&self) -> Option<[&Self; 2]> {
+        match self {
+            Value::Map(map) => match &map[..] {
+                [(key, Value::Array(vec))] if key.as_str() == Some(DURATION_DELTA_KEY) => {
+                    Some(<&[_; 2]>::try_from(&vec[..]).ok()?.each_ref())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Self; 2]> {
+        match self {
+            Value::Array(vec) => Some(<&mut [_; 2]>::try_from(&mut vec[..]).ok()?.each_mut()),
+            Value::Map(map) => duration_fields(map.iter_mut().map(|(key, value)| (&*key, value)), |key| key.as_str()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::MutationError;
+
+    fn map<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Map(entries.into_iter().map(|(key, value)| (key.into(), value)).collect())
+    }
+
+    fn array<const N: usize>(elements: [Value; N]) -> Value {
+        Value::Array(elements.into())
+    }
+
+    fn from_json(json: serde_json::Value) -> Value {
+        to_value(&json).unwrap()
+    }
+
+    #[test]
+    fn from_mutations() {
+        use crate::observe::{ObserveExt, SerializeObserverExt};
+
+        let mut ranges = vec![0..1, 1..2];
+        let mut ob = ranges.__observe();
+        ob.push(2..3);
+        let MessagePack(mutation) = ob.flush::<MessagePack>().unwrap();
+        assert_eq!(
+            mutation,
+            Some(append!(_, array([map([("start", 2.into()), ("end", 3.into())])]))),
+        );
+    }
+
+    #[test]
+    fn apply_set() {
+        let mut value = from_json(json!({"a": 1}));
+        MessagePack::mutate(&mut value, replace!(_, from_json(json!({}))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({})));
+
+        let mut value = from_json(json!({}));
+        MessagePack::mutate(&mut value, replace!(a, from_json(json!(1))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": 1})));
+
+        let mut value = from_json(json!({"a": 1}));
+        MessagePack::mutate(&mut value, replace!(a, from_json(json!(2))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": 2})));
+
+        let error = MessagePack::mutate(
+            &mut from_json(json!({})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into()].into()
+            }
+        );
+
+        let error = MessagePack::mutate(
+            &mut from_json(json!({"a": 1})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into(), "b".into()].into(),
+            }
+        );
+
+        let error = MessagePack::mutate(
+            &mut from_json(json!({"a": []})),
+            replace!(a.b, from_json(json!(3))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into(), "b".into()].into(),
+            }
+        );
+
+        let mut value = from_json(json!({"a": {}}));
+        MessagePack::mutate(&mut value, replace!(a.b, from_json(json!(3))), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"a": {"b": 3}})));
+
+        let mut value = from_json(json!({"0": 1, "1": 2}));
+        MessagePack::mutate(&mut value, replace!(0, from_json(json!(3))), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, delete!(1), &mut Default::default()).unwrap();
+        assert_eq!(value, from_json(json!({"0": 3})));
+
+        let mut value = Value::Map(vec![(42.into(), 1.into())]);
+        MessagePack::mutate(&mut value, replace!(42, Value::from(2)), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, replace!(7, Value::from(3)), &mut Default::default()).unwrap();
+        assert_eq!(value, Value::Map(vec![(42.into(), 2.into()), (7.into(), 3.into())]));
+    }
+
+    #[test]
+    fn apply_get_mut_delete() {
+        let mut value = map([("a", 1.into()), ("b", array([1.into(), 2.into()]))]);
+        MessagePack::mutate(&mut value, replace!(c.d, Value::from(true)), &mut Default::default()).unwrap_err();
+        MessagePack::mutate(&mut value, replace!(c, map([])), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, replace!(b.-1, Value::from(3)), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, delete!(a), &mut Default::default()).unwrap();
+        assert_eq!(value, map([("b", array([1.into(), 3.into()])), ("c", map([]))]));
+
+        let error = MessagePack::mutate(&mut value, delete!(a), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["a".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_append_truncate() {
+        let mut value = map([("s", "ab".into()), ("v", array([1.into()]))]);
+        MessagePack::mutate(&mut value, append!(s, Value::from("cdé")), &mut Default::default()).unwrap();
+        MessagePack::mutate(
+            &mut value,
+            append!(v, array([2.into(), 3.into()])),
+            &mut Default::default(),
+        )
+        .unwrap();
+        MessagePack::mutate(&mut value, truncate!(s, 2), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, truncate!(v, 1), &mut Default::default()).unwrap();
+        assert_eq!(value, map([("s", "abc".into()), ("v", array([1.into(), 2.into()]))]));
+
+        let error = MessagePack::mutate(&mut value, truncate!(v, 3), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::TruncateError {
+                path: vec!["v".into()].into(),
+                actual_len: 2,
+                truncate_len: 3
+            }
+        );
+    }
+
+    #[test]
+    fn apply_increment() {
+        let mut value = map([
+            ("n", 1.into()),
+            ("f", 0.5f64.into()),
+            ("d", from_json(json!({"secs": 1, "nanos": 900_000_000}))),
+        ]);
+        MessagePack::mutate(&mut value, increment!(n, Value::from(-3)), &mut Default::default()).unwrap();
+        MessagePack::mutate(&mut value, increment!(f, Value::from(1)), &mut Default::default()).unwrap();
+        MessagePack::mutate(
+            &mut value,
+            increment!(d, from_json(json!({"$duration": [0, 200_000_000]}))),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            map([
+                ("n", (-2).into()),
+                ("f", 1.5f64.into()),
+                ("d", from_json(json!({"secs": 2, "nanos": 100_000_000}))),
+            ]),
+        );
+
+        let error = MessagePack::mutate(
+            &mut value,
+            increment!(d, from_json(json!({"secs": 0, "nanos": 200_000_000}))),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["d".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn wire_encoding() {
+        let patch = MessagePack(Some(batch!(
+            a,
+            replace!(b.0, Value::from("x")),
+            append!(c.-1, array([1.into()])),
+            truncate!(_, 2),
+            splice!(d, 1, 2, Value::from("y")),
+            mv!(e, 0, 3),
+            delete!(f),
+            increment!(g, Value::from(1)),
+            add!(h, array([2.into()])),
+            remove!(h, array([3.into()])),
+        )));
+        let bytes = patch.to_vec().unwrap();
+        assert_eq!(MessagePack::from_slice(&bytes).unwrap(), patch);
+
+        assert_eq!(MessagePack(None).to_vec().unwrap(), [0x92, 0x01, 0xc0]);
+        assert_eq!(
+            MessagePack(Some(delete!(a))).to_vec().unwrap(),
+            [0x92, 0x01, 0x92, 0x91, 0xa1, b'a', 0x05],
+        );
+
+        assert!(matches!(
+            MessagePack::from_slice(&[0x92, 0x01, 0xc0, 0xc0]),
+            Err(MessagePackError::TrailingBytes)
+        ));
+        assert!(matches!(
+            MessagePack::from_slice(&[0x92, 0x02, 0xc0]),
+            Err(MessagePackError::Decode(_))
+        ));
+        assert!(matches!(
+            MessagePack::from_slice(&[0x92, 0x01, 0x92, 0x90, 0x0a]),
+            Err(MessagePackError::Decode(_))
+        ));
+        assert!(matches!(
+            MessagePack::from_slice(&[0x92, 0x01, 0x93, 0x90, 0x05, 0xc0]),
+            Err(MessagePackError::Decode(_))
+        ));
+        assert!(matches!(
+            MessagePack::from_slice(&[0x92]),
+            Err(MessagePackError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn to_value_named() {
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        enum Shape {
+            Point,
+            Circle(f32),
+            Rect { w: u8, h: u8 },
+        }
+
+        #[derive(Serialize)]
+        struct Data {
+            name: &'static str,
+            tags: Option<Vec<char>>,
+            shapes: Vec<Shape>,
+            bytes: Value,
+            ext: Value,
+            big: i128,
+        }
+
+        let data = Data {
+            name: "a",
+            tags: Some(vec!['x']),
+            shapes: vec![Shape::Point, Shape::Circle(0.5), Shape::Rect { w: 1, h: 2 }],
+            bytes: Value::Binary(vec![1, 2]),
+            ext: Value::Ext(3, vec![4]),
+            big: -1,
+        };
+        let bytes = rmp_serde::to_vec_named(&data).unwrap();
+        let expected = rmpv::decode::read_value(&mut &bytes[..]).unwrap();
+        assert_eq!(to_value(&data).unwrap(), expected);
+    }
+}
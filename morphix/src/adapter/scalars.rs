@@ -0,0 +1,116 @@
+//! Helpers for splicing strings and incrementing numbers in adapter values.
+
+#[cfg(feature = "increment")]
+pub(crate) use crate::general::counter::DURATION_DELTA_KEY;
+
+/// Returns the byte index of the `n`-th char of `str`, or its length if `n` equals the char count.
+#[cfg(feature = "splice")]
+pub(crate) fn char_index(str: &str, n: usize) -> Option<usize> {
+    str.char_indices().map(|(index, _)| index).chain([str.len()]).nth(n)
+}
+
+#[cfg(feature = "increment")]
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Adapter values which can be incremented as numbers.
+#[cfg(feature = "increment")]
+pub(crate) trait Number: Sized {
+    /// Returns the value if it is an integer.
+    fn as_integer(&self) -> Option<i128>;
+
+    /// Creates an integer value, or returns [`None`] if `value` is out of range.
+    fn from_integer(value: i128) -> Option<Self>;
+
+    /// Returns the value as a float if it is a number.
+    fn as_float(&self) -> Option<f64>;
+
+    /// Creates a float value, or returns [`None`] if `value` cannot be represented.
+    fn from_float(value: f64) -> Option<Self>;
+}
+
+/// Adds two numbers, keeping integers if both of them are integers.
+///
+/// Returns [`None`] if either value is not a number, or if the sum of two integers is out of range.
+#[cfg(feature = "increment")]
+pub(crate) fn add_numbers<N: Number>(lhs: &N, rhs: &N) -> Option<N> {
+    if let (Some(lhs), Some(rhs)) = (lhs.as_integer(), rhs.as_integer()) {
+        return N::from_integer(lhs.checked_add(rhs)?);
+    }
+    N::from_float(lhs.as_float()? + rhs.as_float()?)
+}
+
+/// Adapter values which can hold a duration.
+///
+/// [`Duration`](std::time::Duration) is serialized as `{"secs": secs, "nanos": nanos}` and
+/// `chrono::TimeDelta` as `[secs, nanos]`. Their deltas are marked as
+/// `{"$duration": [secs, nanos]}`, see [`DURATION_DELTA_KEY`].
+#[cfg(feature = "increment")]
+pub(crate) trait DurationValue {
+    /// The number type of the seconds and nanoseconds.
+    type Number: Number;
+
+    /// Returns the seconds and nanoseconds of a marked duration delta.
+    fn duration_delta(&self) -> Option<[&Self::Number; 2]>;
+
+    /// Returns the seconds and nanoseconds of a serialized duration.
+    fn duration_mut(&mut self) -> Option<[&mut Self::Number; 2]>;
+
+    /// Writes back a number returned by [`add_durations`].
+    fn set_number(number: &mut Self::Number, value: Self::Number) {
+        *number = value;
+    }
+}
+
+/// Increments `value` by a marked duration delta, or by `add` for any other delta.
+///
+/// Only deltas marked as durations are added as durations, so that a sequence or map of numbers
+/// which merely looks like a duration is never incremented as one.
+#[cfg(feature = "increment")]
+pub(crate) fn increment_by<V: DurationValue>(
+    value: &mut V,
+    delta: V,
+    add: impl FnOnce(&mut V, V) -> Option<()>,
+) -> Option<()> {
+    let Some([delta_secs, delta_nanos]) = delta.duration_delta() else {
+        return add(value, delta);
+    };
+    let [secs, nanos] = value.duration_mut()?;
+    let (new_secs, new_nanos) = add_durations(secs, nanos, delta_secs, delta_nanos)?;
+    V::set_number(secs, new_secs);
+    V::set_number(nanos, new_nanos);
+    Some(())
+}
+
+/// Returns the `secs` and `nanos` values of map entries which have exactly these two keys.
+#[cfg(feature = "increment")]
+pub(crate) fn duration_fields<'a, K, V: 'a>(
+    entries: impl IntoIterator<Item = (K, &'a mut V)>,
+    key: impl Fn(&K) -> Option<&str>,
+) -> Option<[&'a mut V; 2]> {
+    let (mut secs, mut nanos) = (None, None);
+    for (k, value) in entries {
+        match key(&k)? {
+            "secs" if secs.is_none() => secs = Some(value),
+            "nanos" if nanos.is_none() => nanos = Some(value),
+            _ => return None,
+        }
+    }
+    Some([secs?, nanos?])
+}
+
+/// Adds two durations represented by whole seconds and sub-second nanoseconds.
+///
+/// The nanoseconds of both operands must be within `0..1_000_000_000`, so that a negative duration
+/// always has negative seconds and non-negative nanoseconds, like `chrono::TimeDelta` and the
+/// deltas of [`Duration`](std::time::Duration). Returns [`None`] for operands in any other form,
+/// rather than guessing their sign. The result is normalised in the same way.
+#[cfg(feature = "increment")]
+fn add_durations<N: Number>(secs: &N, nanos: &N, delta_secs: &N, delta_nanos: &N) -> Option<(N, N)> {
+    let nanos_of = |nanos: &N| nanos.as_integer().filter(|nanos| (0..NANOS_PER_SEC).contains(nanos));
+    let nanos = nanos_of(nanos)? + nanos_of(delta_nanos)?;
+    let secs = secs
+        .as_integer()?
+        .checked_add(delta_secs.as_integer()?)?
+        .checked_add(nanos / NANOS_PER_SEC)?;
+    Some((N::from_integer(secs)?, N::from_integer(nanos % NANOS_PER_SEC)?))
+}
@@ -0,0 +1,539 @@
+use std::error::Error;
+use std::fmt::Display;
+
+#[cfg(feature = "increment")]
+use serde_json::Number;
+use serde_json::Value;
+
+use super::update::{get, resolve};
+use crate::adapter::Json;
+use crate::{Adapter, Mutation, MutationError, MutationKind, PathSegment};
+
+/// SQL dialects supported by [`SqlUpdate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    /// PostgreSQL, with documents stored in `jsonb` columns.
+    Postgres,
+    /// SQLite, with documents stored as JSON text.
+    Sqlite,
+}
+
+/// A parameter of an [`SqlUpdate`] expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqlParam {
+    /// A `text` value, such as a serialized JSON value or an SQLite JSON path.
+    Text(String),
+    /// A `text[]` value, used for PostgreSQL paths.
+    TextArray(Vec<String>),
+}
+
+/// A parameterized SQL expression computing the new value of a document column.
+///
+/// [`SqlUpdate`] compiles morphix mutations into nested calls of the JSON functions of the
+/// database, so that an observed value can be persisted with a single `UPDATE` statement instead
+/// of serializing the whole document:
+///
+/// ```sql
+/// UPDATE items SET data = <expression> WHERE id = <placeholder after the params>
+/// ```
+///
+/// Placeholders are numbered from 1, as `$1, $2, ...` for PostgreSQL and `?1, ?2, ...` for
+/// SQLite, and correspond to [`params`](SqlUpdate::params) in order.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::{Json, SqlDialect, SqlParam, SqlUpdate};
+/// use morphix::{Observe, observe};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Data {
+///     title: String,
+///     count: i32,
+/// }
+///
+/// let mut data = Data { title: "a".into(), count: 0 };
+/// let mut replica = serde_json::to_value(&data).unwrap();
+/// let Json(mutation) = observe!(data => {
+///     data.title = "b".into();
+/// }).unwrap();
+///
+/// let update = SqlUpdate::from_mutation(SqlDialect::Postgres, "data", &mut replica, mutation.unwrap()).unwrap();
+/// assert_eq!(update.expression, "jsonb_set(data, $1::text[], $2::text::jsonb)");
+/// assert_eq!(
+///     update.params,
+///     [SqlParam::TextArray(vec!["title".into()]), SqlParam::Text(r#""b""#.into())],
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlUpdate {
+    /// The expression evaluating to the updated document.
+    pub expression: String,
+    /// The parameters of the expression.
+    pub params: Vec<SqlParam>,
+}
+
+/// Error type for converting mutations into an [`SqlUpdate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqlUpdateError {
+    /// A key cannot be addressed by an SQLite JSON path, as it contains a `"`.
+    InvalidKey {
+        /// The offending key.
+        key: String,
+    },
+    /// A mutation could not be applied to the document.
+    Mutation(MutationError),
+}
+
+impl Display for SqlUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidKey { key } => write!(f, "key {key:?} cannot be addressed by a JSON path"),
+            Self::Mutation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for SqlUpdateError {}
+
+impl From<MutationError> for SqlUpdateError {
+    fn from(error: MutationError) -> Self {
+        Self::Mutation(error)
+    }
+}
+
+/// An update of the value at a single path.
+enum Operation {
+    /// Sets the value to its final value in the document.
+    Set,
+    #[cfg(feature = "delete")]
+    Delete,
+    #[cfg(feature = "append")]
+    AppendArray(Vec<Value>),
+    #[cfg(feature = "append")]
+    AppendString(String),
+    #[cfg(feature = "truncate")]
+    TruncateArray(usize),
+    #[cfg(feature = "truncate")]
+    TruncateString(usize),
+    #[cfg(feature = "increment")]
+    Increment(Number),
+}
+
+impl SqlUpdate {
+    /// Converts a mutation into an expression updating the document stored in `column`.
+    ///
+    /// `column` is inserted verbatim into the expression. `value` is the document before the
+    /// mutation, and is updated to the document after it.
+    ///
+    /// | Mutation | PostgreSQL | SQLite |
+    /// | --- | --- | --- |
+    /// | [`Replace`](MutationKind::Replace) | `jsonb_set` | `json_set` |
+    /// | [`Delete`](MutationKind::Delete) | `#-` | `json_remove` |
+    /// | [`Append`](MutationKind::Append) to an array | `\|\|` | `json_insert` at `[#]` |
+    /// | [`Append`](MutationKind::Append) to a string | `\|\|` on text | `\|\|` on text |
+    /// | [`Truncate`](MutationKind::Truncate) of an array | `jsonb_array_elements` | `json_each` |
+    /// | [`Truncate`](MutationKind::Truncate) of a string | `left` | `substr` |
+    /// | [`Increment`](MutationKind::Increment) of a number | `+` on `numeric` | `+` |
+    ///
+    /// Other mutations, such as a [`Splice`](MutationKind::Splice), are converted into a
+    /// replacement of the new value. Mutations reading the current value are wrapped in a
+    /// subquery, so that the expression grows linearly with the number of mutations.
+    pub fn from_mutation(
+        dialect: SqlDialect,
+        column: &str,
+        value: &mut Value,
+        mutation: Mutation<Value>,
+    ) -> Result<Self, SqlUpdateError> {
+        let mut update = Self {
+            expression: column.to_string(),
+            params: vec![],
+        };
+        for (path, kind) in mutation.flatten() {
+            let path = resolve(value, &path)?;
+            update.export_kind(dialect, value, path, kind)?;
+        }
+        Ok(update)
+    }
+
+    fn export_kind(
+        &mut self,
+        dialect: SqlDialect,
+        value: &mut Value,
+        path: Vec<PathSegment>,
+        kind: MutationKind<Value>,
+    ) -> Result<(), SqlUpdateError> {
+        let operation = match (&kind, get(value, &path)) {
+            #[cfg(feature = "delete")]
+            (MutationKind::Delete, _) => Operation::Delete,
+            #[cfg(feature = "append")]
+            (MutationKind::Append(Value::Array(vec)), Some(Value::Array(_))) => Operation::AppendArray(vec.clone()),
+            #[cfg(feature = "append")]
+            (MutationKind::Append(Value::String(str)), Some(Value::String(_))) => Operation::AppendString(str.clone()),
+            #[cfg(feature = "truncate")]
+            (MutationKind::Truncate(len), Some(Value::Array(_))) => Operation::TruncateArray(*len),
+            #[cfg(feature = "truncate")]
+            (MutationKind::Truncate(len), Some(Value::String(_))) => Operation::TruncateString(*len),
+            #[cfg(feature = "increment")]
+            (MutationKind::Increment(Value::Number(delta)), Some(Value::Number(_))) => {
+                Operation::Increment(delta.clone())
+            }
+            _ => Operation::Set,
+        };
+        Json::mutate(
+            value,
+            Mutation {
+                path: path.clone().into(),
+                kind,
+            },
+            &mut Default::default(),
+        )?;
+        let root = path.is_empty();
+        let set = |document: &str, path: &str, new_value: &str| match dialect {
+            SqlDialect::Postgres if root => new_value.to_string(),
+            SqlDialect::Postgres => format!("jsonb_set({document}, {path}, {new_value})"),
+            SqlDialect::Sqlite => format!("json_set({document}, {path}, {new_value})"),
+        };
+        let document = std::mem::take(&mut self.expression);
+        self.expression = match (dialect, operation) {
+            (_, Operation::Set) => {
+                let new_value = get(value, &path).expect("mutated values exist").clone();
+                // PostgreSQL paths are not needed to replace the root, and unused parameters would
+                // have no type.
+                let path = match dialect {
+                    SqlDialect::Postgres if root => String::new(),
+                    _ => self.path(dialect, &path, "")?,
+                };
+                let new_value = self.json(dialect, &new_value);
+                set(&document, &path, &new_value)
+            }
+            #[cfg(feature = "delete")]
+            (SqlDialect::Postgres, Operation::Delete) => {
+                let path = self.path(dialect, &path, "")?;
+                format!("({document} #- {path})")
+            }
+            #[cfg(feature = "delete")]
+            (SqlDialect::Sqlite, Operation::Delete) => {
+                let path = self.path(dialect, &path, "")?;
+                format!("json_remove({document}, {path})")
+            }
+            #[cfg(feature = "append")]
+            (SqlDialect::Postgres, Operation::AppendArray(vec)) if root => {
+                let vec = self.json(dialect, &Value::Array(vec));
+                format!("({document} || {vec})")
+            }
+            #[cfg(feature = "append")]
+            (SqlDialect::Postgres, Operation::AppendArray(vec)) => {
+                let path = self.path(dialect, &path, "")?;
+                let vec = self.json(dialect, &Value::Array(vec));
+                bind(&document, |document| {
+                    set(document, &path, &format!("({document} #> {path}) || {vec}"))
+                })
+            }
+            #[cfg(feature = "append")]
+            (SqlDialect::Sqlite, Operation::AppendArray(vec)) => {
+                let end = self.path(dialect, &path, "[#]")?;
+                let mut expression = format!("json_insert({document}");
+                for element in vec {
+                    let element = self.json(dialect, &element);
+                    expression += &format!(", {end}, {element}");
+                }
+                expression + ")"
+            }
+            #[cfg(feature = "append")]
+            (SqlDialect::Postgres, Operation::AppendString(str)) => {
+                let path = self.path(dialect, &path, "")?;
+                let str = self.text(dialect, str);
+                bind(&document, |document| {
+                    set(document, &path, &format!("to_jsonb(({document} #>> {path}) || {str})"))
+                })
+            }
+            #[cfg(feature = "append")]
+            (SqlDialect::Sqlite, Operation::AppendString(str)) => {
+                let path = self.path(dialect, &path, "")?;
+                let str = self.text(dialect, str);
+                bind(&document, |document| {
+                    set(document, &path, &format!("json_extract({document}, {path}) || {str}"))
+                })
+            }
+            #[cfg(feature = "truncate")]
+            (SqlDialect::Postgres, Operation::TruncateArray(len)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    let elements = format!(
+                        "(SELECT coalesce(jsonb_agg(e ORDER BY i), '[]') \
+                         FROM jsonb_array_elements({document} #> {path}) WITH ORDINALITY AS a(e, i) \
+                         WHERE i <= jsonb_array_length({document} #> {path}) - {len})"
+                    );
+                    set(document, &path, &elements)
+                })
+            }
+            #[cfg(feature = "truncate")]
+            (SqlDialect::Sqlite, Operation::TruncateArray(len)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    let elements = format!(
+                        "json((SELECT json_group_array(value) FROM json_each({document}, {path}) \
+                         WHERE key < json_array_length({document}, {path}) - {len}))"
+                    );
+                    set(document, &path, &elements)
+                })
+            }
+            #[cfg(feature = "truncate")]
+            (SqlDialect::Postgres, Operation::TruncateString(len)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    set(
+                        document,
+                        &path,
+                        &format!("to_jsonb(left({document} #>> {path}, -{len}))"),
+                    )
+                })
+            }
+            #[cfg(feature = "truncate")]
+            (SqlDialect::Sqlite, Operation::TruncateString(len)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    let str = format!("json_extract({document}, {path})");
+                    set(document, &path, &format!("substr({str}, 1, length({str}) - {len})"))
+                })
+            }
+            #[cfg(feature = "increment")]
+            (SqlDialect::Postgres, Operation::Increment(delta)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    set(
+                        document,
+                        &path,
+                        &format!("to_jsonb(({document} #>> {path})::numeric + {delta})"),
+                    )
+                })
+            }
+            #[cfg(feature = "increment")]
+            (SqlDialect::Sqlite, Operation::Increment(delta)) => {
+                let path = self.path(dialect, &path, "")?;
+                bind(&document, |document| {
+                    set(document, &path, &format!("json_extract({document}, {path}) + {delta}"))
+                })
+            }
+        };
+        Ok(())
+    }
+
+    fn param(&mut self, dialect: SqlDialect, param: SqlParam) -> String {
+        self.params.push(param);
+        match dialect {
+            SqlDialect::Postgres => format!("${}", self.params.len()),
+            SqlDialect::Sqlite => format!("?{}", self.params.len()),
+        }
+    }
+
+    #[cfg(feature = "append")]
+    fn text(&mut self, dialect: SqlDialect, text: String) -> String {
+        let param = self.param(dialect, SqlParam::Text(text));
+        match dialect {
+            SqlDialect::Postgres => format!("{param}::text"),
+            SqlDialect::Sqlite => param,
+        }
+    }
+
+    fn json(&mut self, dialect: SqlDialect, value: &Value) -> String {
+        let param = self.param(dialect, SqlParam::Text(value.to_string()));
+        match dialect {
+            SqlDialect::Postgres => format!("{param}::text::jsonb"),
+            SqlDialect::Sqlite => format!("json({param})"),
+        }
+    }
+
+    /// Adds a path parameter, with `suffix` appended to SQLite paths.
+    fn path(&mut self, dialect: SqlDialect, path: &[PathSegment], suffix: &str) -> Result<String, SqlUpdateError> {
+        Ok(match dialect {
+            SqlDialect::Postgres => {
+                let path = path
+                    .iter()
+                    .map(|segment| match segment {
+                        PathSegment::String(key) => key.to_string(),
+                        PathSegment::Positive(index) => index.to_string(),
+                        PathSegment::Negative(_) => unreachable!("negative indices are resolved before building paths"),
+                    })
+                    .collect();
+                let param = self.param(dialect, SqlParam::TextArray(path));
+                format!("{param}::text[]")
+            }
+            SqlDialect::Sqlite => {
+                let mut str = String::from("$");
+                for segment in path {
+                    match segment {
+                        PathSegment::String(key) if key.contains('"') => {
+                            return Err(SqlUpdateError::InvalidKey { key: key.to_string() });
+                        }
+                        PathSegment::String(key) => str += &format!(".\"{key}\""),
+                        PathSegment::Positive(index) => str += &format!("[{index}]"),
+                        PathSegment::Negative(_) => unreachable!("negative indices are resolved before building paths"),
+                    }
+                }
+                self.param(dialect, SqlParam::Text(str + suffix))
+            }
+        })
+    }
+}
+
+/// Binds `document` to a name in a subquery, so that `body` can refer to it several times without
+/// repeating the expression.
+#[cfg(any(feature = "append", feature = "increment", feature = "truncate"))]
+fn bind(document: &str, body: impl FnOnce(&str) -> String) -> String {
+    format!(
+        "(SELECT {} FROM (SELECT {document} AS document) AS t)",
+        body("document")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+
+    /// Runs the update against an in-memory SQLite database and checks it against the replica.
+    fn export_sqlite(value: &mut Value, mutation: Mutation<Value>) -> SqlUpdate {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        connection.execute("CREATE TABLE items (data TEXT)", ()).unwrap();
+        connection
+            .execute("INSERT INTO items VALUES (?1)", [value.to_string()])
+            .unwrap();
+        let update = SqlUpdate::from_mutation(SqlDialect::Sqlite, "data", value, mutation).unwrap();
+        let params = update.params.iter().map(|param| match param {
+            SqlParam::Text(text) => text.clone(),
+            SqlParam::TextArray(_) => unreachable!("SQLite paths are text"),
+        });
+        connection
+            .execute(
+                &format!("UPDATE items SET data = {}", update.expression),
+                rusqlite::params_from_iter(params),
+            )
+            .unwrap();
+        let data: String = connection
+            .query_row("SELECT data FROM items", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(&serde_json::from_str::<Value>(&data).unwrap(), value);
+        update
+    }
+
+    fn text(text: &str) -> SqlParam {
+        SqlParam::Text(text.into())
+    }
+
+    #[test]
+    fn export_sqlite_objects() {
+        let mut value = json!({"a": {"b": 1, "c": "x"}, "d": 1, "e.f": true});
+        let update = export_sqlite(
+            &mut value,
+            batch!(
+                _,
+                replace!(a.b, json!({"g": [1]})),
+                delete!(d),
+                append!(a.c, json!("yé"))
+            ),
+        );
+        assert_eq!(
+            update.expression,
+            "(SELECT json_set(document, ?4, json_extract(document, ?4) || ?5) \
+             FROM (SELECT json_remove(json_set(data, ?1, json(?2)), ?3) AS document) AS t)",
+        );
+        assert_eq!(
+            update.params,
+            [
+                text(r#"$."a"."b""#),
+                text(r#"{"g":[1]}"#),
+                text(r#"$."d""#),
+                text(r#"$."a"."c""#),
+                text("yé"),
+            ],
+        );
+        assert_eq!(value, json!({"a": {"b": {"g": [1]}, "c": "xyé"}, "e.f": true}));
+
+        let update = export_sqlite(&mut value, replace!(_, json!({"e.f": false})));
+        assert_eq!(update.expression, "json_set(data, ?1, json(?2))");
+        assert_eq!(update.params, [text("$"), text(r#"{"e.f":false}"#)]);
+    }
+
+    #[test]
+    fn export_sqlite_arrays() {
+        let mut value = json!({"a": [[1], {"b": "c"}, "d"], "s": "abcé", "n": [1, 2.5]});
+        export_sqlite(
+            &mut value,
+            batch!(
+                _,
+                append!(a, json!([[2], "e"])),
+                truncate!(a, 3),
+                truncate!(s, 2),
+                increment!(n.0, json!(2)),
+                increment!(n.-1, json!(-0.5)),
+                replace!(a.-1.b, json!(null)),
+            ),
+        );
+        assert_eq!(value, json!({"a": [[1], {"b": null}], "s": "ab", "n": [3, 2.0]}));
+
+        let update = export_sqlite(&mut value, append!(a, json!([3])));
+        assert_eq!(update.expression, "json_insert(data, ?1, json(?2))");
+        assert_eq!(update.params, [text(r#"$."a"[#]"#), text("3")]);
+
+        export_sqlite(&mut value, batch!(_, splice!(a, 0, 1, json!([0, 1])), truncate!(a, 4)));
+        assert_eq!(value, json!({"a": [], "s": "ab", "n": [3, 2.0]}));
+    }
+
+    #[test]
+    fn export_postgres() {
+        let mut value = json!({"a": [1], "b": {"c": "x"}, "d": 1});
+        let update = SqlUpdate::from_mutation(
+            SqlDialect::Postgres,
+            "data",
+            &mut value,
+            batch!(_, append!(a, json!([2])), delete!(b.c), increment!(d, json!(2))),
+        )
+        .unwrap();
+        assert_eq!(
+            update.expression,
+            "(SELECT jsonb_set(document, $4::text[], to_jsonb((document #>> $4::text[])::numeric + 2)) \
+             FROM (SELECT ((SELECT jsonb_set(document, $1::text[], (document #> $1::text[]) || $2::text::jsonb) \
+             FROM (SELECT data AS document) AS t) #- $3::text[]) AS document) AS t)",
+        );
+        assert_eq!(
+            update.params,
+            [
+                SqlParam::TextArray(vec!["a".into()]),
+                text("[2]"),
+                SqlParam::TextArray(vec!["b".into(), "c".into()]),
+                SqlParam::TextArray(vec!["d".into()]),
+            ],
+        );
+        assert_eq!(value, json!({"a": [1, 2], "b": {}, "d": 3}));
+
+        let update =
+            SqlUpdate::from_mutation(SqlDialect::Postgres, "data", &mut value, replace!(_, json!({}))).unwrap();
+        assert_eq!(update.expression, "$1::text::jsonb");
+        assert_eq!(update.params, [text("{}")]);
+    }
+
+    #[test]
+    fn export_errors() {
+        let mut value = json!({"a": []});
+        let mutation = Mutation {
+            path: vec!["a\"b".into()].into(),
+            kind: MutationKind::Replace(json!(1)),
+        };
+        let error = SqlUpdate::from_mutation(SqlDialect::Sqlite, "data", &mut value, mutation).unwrap_err();
+        assert_eq!(error, SqlUpdateError::InvalidKey { key: "a\"b".into() });
+
+        let error = SqlUpdate::from_mutation(SqlDialect::Sqlite, "data", &mut value, replace!(a.-1, json!(1)));
+        assert_eq!(
+            error.unwrap_err(),
+            SqlUpdateError::Mutation(MutationError::IndexError {
+                path: vec!["a".into(), PathSegment::Negative(1)].into(),
+            }),
+        );
+    }
+}
@@ -0,0 +1,787 @@
+use std::mem::take;
+
+use toml_edit::ser::{Error, ValueSerializer};
+use toml_edit::{Array, Item, Table, Value};
+
+use crate::adapter::apply_mutation;
+#[cfg(feature = "set")]
+use crate::adapter::members::{MemberSet, hash_member};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
+use crate::{Adapter, Mutation, MutationError, MutationKind, Mutations, Path, PathSegment};
+
+/// TOML adapter for morphix mutation serialization.
+///
+/// [`Toml`] implements the [`Adapter`] trait using [`toml_edit::Item`], so that mutations can be
+/// applied to a [`DocumentMut`](toml_edit::DocumentMut) in place, keeping the comments and the
+/// layout of everything they do not touch:
+///
+/// - Replaced values keep their surrounding whitespace and trailing comments.
+/// - Replaced tables and arrays of tables keep their `[table]` and `[[array]]` form, while new
+///   tables are written inline.
+/// - Elements appended to a multi-line array are placed on their own lines.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::Toml;
+/// use morphix::{Adapter, Observe, observe};
+/// use serde::Serialize;
+/// use toml_edit::DocumentMut;
+///
+/// #[derive(Serialize, Observe)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut document = "\
+/// ## Server address
+/// host = \"localhost\"
+/// port = 8080 # default port
+/// tags = [\"web\"]
+/// ".parse::<DocumentMut>().unwrap();
+///
+/// let mut config = Config {
+///     host: "localhost".to_string(),
+///     port: 8080,
+///     tags: vec!["web".to_string()],
+/// };
+/// let Toml(mutation) = observe!(config => {
+///     config.port = 8081;
+///     config.tags.push("api".to_string());
+/// }).unwrap();
+///
+/// Toml::mutate(document.as_item_mut(), mutation.unwrap(), &mut Default::default()).unwrap();
+/// assert_eq!(document.to_string(), "\
+/// ## Server address
+/// host = \"localhost\"
+/// port = 8081 # default port
+/// tags = [\"web\", \"api\"]
+/// ");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Toml(pub Option<Mutation<Item>>);
+
+impl From<Option<Mutation<Item>>> for Toml {
+    fn from(mutation: Option<Mutation<Item>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<Toml> for Option<Mutation<Item>> {
+    fn from(Toml(mutation): Toml) -> Self {
+        mutation
+    }
+}
+
+impl Adapter for Toml {
+    type Value = Item;
+    type Error = Error;
+
+    fn from_mutations(mutation: Mutations) -> Result<Self, Self::Error> {
+        Ok(Self(
+            mutation
+                .into_inner()
+                .map(|mutation| {
+                    mutation.try_map(|value| erased_serde::serialize(&*value, ValueSerializer::new()).map(Item::Value))
+                })
+                .transpose()?,
+        ))
+    }
+
+    /// Gets a mutable reference to a table entry.
+    ///
+    /// Array elements are not stored as [`Item`]s by `toml_edit`, so they are not reachable
+    /// through this method. [`mutate`](Toml::mutate) takes care of them separately.
+    fn get_mut<'a>(
+        value: &'a mut Self::Value,
+        segment: &PathSegment,
+        allow_create: bool,
+    ) -> Option<&'a mut Self::Value> {
+        let table = value.as_table_like_mut()?;
+        let key = segment.as_key()?;
+        if allow_create {
+            Some(table.entry(&key).or_insert(Item::None))
+        } else {
+            table.get_mut(&key)
+        }
+    }
+
+    #[cfg(feature = "delete")]
+    fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
+        value.as_table_like_mut()?.remove(&segment.as_key()?)
+    }
+
+    #[cfg(feature = "append")]
+    fn append(value: &mut Self::Value, append_value: Self::Value) -> Option<usize> {
+        match (value, append_value) {
+            (Item::Value(lhs @ Value::String(_)), Item::Value(Value::String(rhs))) => string_mut(lhs, |lhs| {
+                *lhs += rhs.value();
+                Some(rhs.value().chars().count())
+            }),
+            (Item::Value(Value::Array(lhs)), Item::Value(Value::Array(rhs))) => {
+                let len = rhs.len();
+                // Elements of a multi-line array are placed on their own lines.
+                let decor = lhs
+                    .iter()
+                    .last()
+                    .map(|element| element.decor().clone())
+                    .filter(|decor| {
+                        decor
+                            .prefix()
+                            .and_then(|prefix| prefix.as_str())
+                            .is_some_and(|prefix| prefix.contains('\n'))
+                    });
+                for mut element in rhs {
+                    match &decor {
+                        Some(decor) => {
+                            *element.decor_mut() = decor.clone();
+                            lhs.push_formatted(element);
+                        }
+                        None => lhs.push(element),
+                    }
+                }
+                Some(len)
+            }
+            (Item::ArrayOfTables(lhs), Item::Value(Value::Array(rhs))) => {
+                let tables = into_tables(rhs)?;
+                let len = tables.len();
+                for table in tables {
+                    lhs.push(table);
+                }
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(any(feature = "append", feature = "splice"))]
+    fn len(value: &Self::Value) -> Option<usize> {
+        match value {
+            Item::Value(Value::String(str)) => Some(str.value().chars().count()),
+            Item::Value(Value::Array(array)) => Some(array.len()),
+            Item::ArrayOfTables(tables) => Some(tables.len()),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "truncate")]
+    fn truncate(value: &mut Self::Value, mut truncate_len: usize) -> Option<usize> {
+        match value {
+            Item::Value(str @ Value::String(_)) => string_mut(str, |str| {
+                let mut chars = str.char_indices();
+                let mut new_len = str.len();
+                while truncate_len > 0
+                    && let Some((index, _)) = chars.next_back()
+                {
+                    truncate_len -= 1;
+                    new_len = index;
+                }
+                str.truncate(new_len);
+                Some(truncate_len)
+            }),
+            Item::Value(Value::Array(array)) => {
+                let actual_len = array.len();
+                for index in (actual_len.saturating_sub(truncate_len)..actual_len).rev() {
+                    array.remove(index);
+                }
+                Some(truncate_len.saturating_sub(actual_len))
+            }
+            Item::ArrayOfTables(tables) => {
+                let actual_len = tables.len();
+                for index in (actual_len.saturating_sub(truncate_len)..actual_len).rev() {
+                    tables.remove(index);
+                }
+                Some(truncate_len.saturating_sub(actual_len))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (value, insert_value) {
+            (Item::Value(lhs @ Value::String(_)), Item::Value(Value::String(rhs))) => string_mut(lhs, |lhs| {
+                let start = char_index(lhs, index)?;
+                let end = start + char_index(&lhs[start..], delete_len)?;
+                lhs.replace_range(start..end, rhs.value());
+                Some(rhs.value().chars().count())
+            }),
+            (Item::Value(Value::Array(lhs)), Item::Value(Value::Array(rhs))) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let len = rhs.len();
+                for index in (index..end).rev() {
+                    lhs.remove(index);
+                }
+                for (offset, element) in rhs.into_iter().enumerate() {
+                    lhs.insert(index + offset, element);
+                }
+                Some(len)
+            }
+            (Item::ArrayOfTables(lhs), Item::Value(Value::Array(rhs))) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let tables = into_tables(rhs)?;
+                let len = tables.len();
+                for index in (index..end).rev() {
+                    lhs.remove(index);
+                }
+                for (offset, table) in tables.into_iter().enumerate() {
+                    lhs.insert(index + offset, table);
+                }
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match value {
+            Item::Value(Value::Array(array)) => {
+                if from >= array.len() || to >= array.len() {
+                    return None;
+                }
+                let element = array.remove(from);
+                array.insert_formatted(to, element);
+                Some(())
+            }
+            Item::ArrayOfTables(tables) => {
+                if from >= tables.len() || to >= tables.len() {
+                    return None;
+                }
+                let table = tables.remove(from);
+                tables.insert(to, table);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(value, delta, |value, delta| match (value, delta) {
+            (Item::Value(lhs @ (Value::Integer(_) | Value::Float(_))), Item::Value(rhs)) => {
+                let sum = add_numbers(lhs, &rhs)?;
+                set_value(lhs, sum);
+                Some(())
+            }
+            _ => None,
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Item::Value(Value::Array(lhs)), Item::Value(Value::Array(rhs))) => {
+                let mut set = MemberSet::new(lhs.iter(), member_hash, value_eq);
+                let is_inserted = rhs.iter().map(|member| set.insert(member)).collect::<Vec<_>>();
+                let mut inserted = Array::new();
+                for (member, is_inserted) in rhs.into_iter().zip(is_inserted) {
+                    if is_inserted {
+                        lhs.push(member.clone());
+                        inserted.push(member);
+                    }
+                }
+                Some(Item::Value(Value::Array(inserted)))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Item::Value(Value::Array(lhs)), Item::Value(Value::Array(rhs))) => {
+                let set = MemberSet::new(rhs.iter(), member_hash, value_eq);
+                let mut removed = Array::new();
+                lhs.retain(|element| {
+                    let is_removed = set.contains(element);
+                    if is_removed {
+                        removed.push(element.clone());
+                    }
+                    !is_removed
+                });
+                Some(Item::Value(Value::Array(removed)))
+            }
+            _ => None,
+        }
+    }
+
+    fn mutate(
+        value: &mut Self::Value,
+        mut mutation: Mutation<Self::Value>,
+        path_stack: &mut Path<false>,
+    ) -> Result<(), MutationError> {
+        let Some(segment) = mutation.path.pop() else {
+            return match mutation.kind {
+                MutationKind::Replace(replace_value) => {
+                    replace(value, replace_value);
+                    Ok(())
+                }
+                kind => apply_mutation::<Self>(
+                    value,
+                    Mutation {
+                        path: Path::new(),
+                        kind,
+                    },
+                    path_stack,
+                ),
+            };
+        };
+        let is_last_segment = mutation.path.is_empty();
+        #[cfg(feature = "delete")]
+        if is_last_segment && matches!(mutation.kind, MutationKind::Delete) {
+            let deleted = Self::delete(value, &segment);
+            path_stack.push(segment);
+            return match deleted {
+                Some(_) => Ok(()),
+                None => Err(MutationError::IndexError { path: take(path_stack) }),
+            };
+        }
+        let is_replace = matches!(mutation.kind, MutationKind::Replace(_));
+        path_stack.push(segment.clone());
+        if is_sequence(value) {
+            return with_element(value, &segment, |element| Self::mutate(element, mutation, path_stack))
+                .unwrap_or_else(|| Err(MutationError::IndexError { path: take(path_stack) }));
+        }
+        let Some(inner_value) = Self::get_mut(value, &segment, is_replace && is_last_segment) else {
+            return Err(MutationError::IndexError { path: take(path_stack) });
+        };
+        Self::mutate(inner_value, mutation, path_stack)
+    }
+}
+
+/// Replaces `value` with `new_value` and returns the old value.
+///
+/// The decor of a replaced value is kept, and a table or an array of tables replaced with an
+/// inline counterpart keeps its original form.
+fn replace(value: &mut Item, new_value: Item) -> Item {
+    let new_value = match (&*value, new_value) {
+        (Item::Value(old_value), Item::Value(mut new_value)) => {
+            *new_value.decor_mut() = old_value.decor().clone();
+            Item::Value(new_value)
+        }
+        (Item::Table(old_table), Item::Value(Value::InlineTable(new_table))) => {
+            let mut new_table = new_table.into_table();
+            *new_table.decor_mut() = old_table.decor().clone();
+            new_table.set_position(old_table.position());
+            Item::Table(new_table)
+        }
+        (Item::ArrayOfTables(_), Item::Value(Value::Array(array))) => match into_tables(array.clone()) {
+            Some(new_tables) => Item::ArrayOfTables(new_tables.into_iter().collect()),
+            None => Item::Value(Value::Array(array)),
+        },
+        (_, new_value) => new_value,
+    };
+    std::mem::replace(value, new_value)
+}
+
+fn is_sequence(value: &Item) -> bool {
+    matches!(value, Item::Value(Value::Array(_)) | Item::ArrayOfTables(_))
+}
+
+/// Runs `f` on an array element, temporarily moved out of the array as an [`Item`].
+///
+/// An element left as [`Item::None`] is removed from the array, and an array of tables whose
+/// element is no longer a table is converted into an inline array.
+fn with_element<T>(value: &mut Item, segment: &PathSegment, f: impl FnOnce(&mut Item) -> T) -> Option<T> {
+    let resolve = |len: usize| match segment {
+        PathSegment::Positive(index) => Some(*index).filter(|index| *index < len),
+        PathSegment::Negative(index) => len.checked_sub(*index),
+        PathSegment::String(_) => None,
+    };
+    match value {
+        Item::Value(Value::Array(array)) => {
+            let index = resolve(array.len())?;
+            let element = array.get_mut(index)?;
+            let mut item = Item::Value(std::mem::replace(element, Value::from(false)));
+            let result = f(&mut item);
+            match item.into_value() {
+                Ok(new_element) => *element = new_element,
+                Err(_) => {
+                    array.remove(index);
+                }
+            }
+            Some(result)
+        }
+        Item::ArrayOfTables(tables) => {
+            let index = resolve(tables.len())?;
+            let mut item = Item::Table(take(tables.get_mut(index)?));
+            let result = f(&mut item);
+            match item.into_table() {
+                Ok(table) => *tables.get_mut(index)? = table,
+                Err(Item::None) => {
+                    tables.remove(index);
+                }
+                Err(item) => {
+                    let mut array = take(tables).into_array();
+                    array.replace_formatted(index, item.into_value().ok()?);
+                    *value = Item::Value(Value::Array(array));
+                }
+            }
+            Some(result)
+        }
+        _ => None,
+    }
+}
+
+/// Converts an array of inline tables into standard tables, or returns [`None`] if any element is
+/// not a table.
+fn into_tables(array: Array) -> Option<Vec<Table>> {
+    array
+        .into_iter()
+        .map(|element| match element {
+            Value::InlineTable(table) => Some(table.into_table()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Sets a value, keeping its decor.
+#[cfg(any(feature = "append", feature = "truncate", feature = "splice", feature = "increment"))]
+fn set_value(value: &mut Value, new_value: impl Into<Value>) {
+    let mut new_value = new_value.into();
+    *new_value.decor_mut() = value.decor().clone();
+    *value = new_value;
+}
+
+/// Runs `f` on a copy of a string value, and writes it back if `f` succeeds.
+#[cfg(any(feature = "append", feature = "truncate", feature = "splice"))]
+fn string_mut<T>(value: &mut Value, f: impl FnOnce(&mut String) -> Option<T>) -> Option<T> {
+    let mut str = value.as_str()?.to_owned();
+    let result = f(&mut str)?;
+    set_value(value, str);
+    Some(result)
+}
+
+#[cfg(feature = "set")]
+fn value_eq(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::String(lhs), Value::String(rhs)) => lhs.value() == rhs.value(),
+        (Value::Integer(lhs), Value::Integer(rhs)) => lhs.value() == rhs.value(),
+        (Value::Float(lhs), Value::Float(rhs)) => lhs.value() == rhs.value(),
+        (Value::Boolean(lhs), Value::Boolean(rhs)) => lhs.value() == rhs.value(),
+        (Value::Datetime(lhs), Value::Datetime(rhs)) => lhs.value() == rhs.value(),
+        (Value::Array(lhs), Value::Array(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(lhs, rhs)| value_eq(lhs, rhs))
+        }
+        (Value::InlineTable(lhs), Value::InlineTable(rhs)) => {
+            lhs.len() == rhs.len()
+                && lhs
+                    .iter()
+                    .all(|(key, lhs)| rhs.get(key).is_some_and(|rhs| value_eq(lhs, rhs)))
+        }
+        _ => false,
+    }
+}
+
+/// Hashes a set member for [`MemberSet`] consistently with [`value_eq`]. Compound values are only
+/// hashed by their length.
+#[cfg(feature = "set")]
+fn member_hash(value: &Value) -> u64 {
+    let discriminant = std::mem::discriminant(value);
+    match value {
+        Value::String(str) => hash_member(&(discriminant, str.value())),
+        Value::Integer(integer) => hash_member(&(discriminant, integer.value())),
+        Value::Boolean(bool) => hash_member(&(discriminant, bool.value())),
+        Value::Array(array) => hash_member(&(discriminant, array.len())),
+        Value::InlineTable(table) => hash_member(&(discriminant, table.len())),
+        _ => hash_member(&discriminant),
+    }
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        self.as_integer().map(i128::from)
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        i64::try_from(value).ok().map(Value::from)
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Integer(integer) => Some(*integer.value() as f64),
+            Value::Float(float) => Some(*float.value()),
+            _ => None,
+        }
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value.into())
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Item {
+    type Number = Value;
+
+    fn duration_delta(&self) -> Option<[&Value; 2]> {
+        let Item::Value(Value::InlineTable(table)) = self else {
+            return None;
+        };
+        let (Some(Value::Array(array)), 1) = (table.get(DURATION_DELTA_KEY), table.len()) else {
+            return None;
+        };
+        let (Some(secs), Some(nanos), 2) = (array.get(0), array.get(1), array.len()) else {
+            return None;
+        };
+        Some([secs, nanos])
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Value; 2]> {
+        if let Item::Value(Value::Array(array)) = self {
+            let mut iter = array.iter_mut();
+            let (Some(secs), Some(nanos), None) = (iter.next(), iter.next(), iter.next()) else {
+                return None;
+            };
+            return Some([secs, nanos]);
+        }
+        let entries = self
+            .as_table_like_mut()?
+            .iter_mut()
+            .map(|(key, item)| Some((key, item.as_value_mut()?)))
+            .collect::<Option<Vec<_>>>()?;
+        duration_fields(entries, |key| Some(key.get()))
+    }
+
+    fn set_number(number: &mut Value, value: Value) {
+        set_value(number, value);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use morphix_test_utils::*;
+    use toml_edit::{DocumentMut, InlineTable, value};
+
+    use super::*;
+    use crate::MutationError;
+
+    const DOCUMENT: &str = r#"# Config
+title = "demo" # the title
+
+[server]
+host = "localhost"
+ports = [
+    8080,
+]
+
+[[users]]
+name = "a" # first
+
+[[users]]
+name = "b"
+"#;
+
+    fn array<V: Into<Value>, const N: usize>(elements: [V; N]) -> Item {
+        Item::Value(Value::Array(elements.into_iter().collect()))
+    }
+
+    fn user(name: &str) -> Value {
+        Value::InlineTable(InlineTable::from_iter([("name", name)]))
+    }
+
+    fn apply(mutation: Mutation<Item>) -> String {
+        let mut document = DOCUMENT.parse::<DocumentMut>().unwrap();
+        Toml::mutate(document.as_item_mut(), mutation, &mut Default::default()).unwrap();
+        document.to_string()
+    }
+
+    #[test]
+    fn apply_in_place() {
+        assert_eq!(
+            apply(batch!(
+                _,
+                replace!(title, value("morphix")),
+                replace!(server.host, value("example.com")),
+                append!(server.ports, array([8081])),
+                replace!(users.-1.name, value("c")),
+                append!(users.0.name, value("!")),
+            )),
+            r#"# Config
+title = "morphix" # the title
+
+[server]
+host = "example.com"
+ports = [
+    8080,
+    8081,
+]
+
+[[users]]
+name = "a!" # first
+
+[[users]]
+name = "c"
+"#,
+        );
+    }
+
+    #[test]
+    fn apply_increment() {
+        let mut document = "count = 1 # views\nelapsed = { secs = 1, nanos = 900000000 }\ndelta = [1, 2]\n"
+            .parse::<DocumentMut>()
+            .unwrap();
+        let duration = |secs: i64, nanos: i64| {
+            Item::Value(Value::InlineTable(InlineTable::from_iter([(
+                "$duration",
+                Value::Array([secs, nanos].into_iter().collect()),
+            )])))
+        };
+        Toml::mutate(
+            document.as_item_mut(),
+            increment!(count, value(2)),
+            &mut Default::default(),
+        )
+        .unwrap();
+        Toml::mutate(
+            document.as_item_mut(),
+            increment!(elapsed, duration(0, 200_000_000)),
+            &mut Default::default(),
+        )
+        .unwrap();
+        Toml::mutate(
+            document.as_item_mut(),
+            increment!(delta, duration(-1, 0)),
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            "count = 3 # views\nelapsed = { secs = 2, nanos = 100000000 }\ndelta = [0, 2]\n",
+        );
+
+        let error = Toml::mutate(
+            document.as_item_mut(),
+            increment!(delta, array([3, 4])),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["delta".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_add_remove() {
+        let mut document = DOCUMENT.parse::<DocumentMut>().unwrap();
+        let ports = |document: &DocumentMut| {
+            document["server"]["ports"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|port| port.as_integer().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let mutation = add!(server.ports, array([8080, 8082, 8082]));
+        Toml::mutate(document.as_item_mut(), mutation, &mut Default::default()).unwrap();
+        assert_eq!(ports(&document), [8080, 8082]);
+
+        let mutation = remove!(server.ports, array([8080, 8083]));
+        Toml::mutate(document.as_item_mut(), mutation, &mut Default::default()).unwrap();
+        assert_eq!(ports(&document), [8082]);
+    }
+
+    #[test]
+    fn apply_tables() {
+        let server = InlineTable::from_iter([("host", value("example.com").into_value().unwrap())]);
+        assert_eq!(
+            apply(batch!(
+                _,
+                replace!(server, Item::Value(Value::InlineTable(server))),
+                replace!(users, array([user("x"), user("y"), user("z")])),
+                replace!(limits, Item::Value(user("n"))),
+            )),
+            r#"# Config
+title = "demo" # the title
+limits = { name = "n" }
+
+[server]
+host = "example.com"
+
+[[users]]
+name = "x"
+
+[[users]]
+name = "y"
+
+[[users]]
+name = "z"
+"#,
+        );
+        assert_eq!(
+            apply(batch!(
+                _,
+                truncate!(users, 1),
+                append!(users, array([user("c")])),
+                splice!(users, 0, 1, array([user("d"), user("e")])),
+                mv!(users, 2, 0),
+            )),
+            r#"# Config
+title = "demo" # the title
+
+[server]
+host = "localhost"
+ports = [
+    8080,
+]
+
+[[users]]
+name = "c"
+
+[[users]]
+name = "d"
+
+[[users]]
+name = "e"
+"#,
+        );
+    }
+
+    #[test]
+    fn apply_errors() {
+        let mut document = DOCUMENT.parse::<DocumentMut>().unwrap();
+        let error = Toml::mutate(document.as_item_mut(), delete!(server.missing), &mut Default::default()).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["server".into(), "missing".into()].into()
+            }
+        );
+        let error = Toml::mutate(
+            document.as_item_mut(),
+            replace!(users.2.name, value("x")),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::IndexError {
+                path: vec!["users".into(), 2.into()].into()
+            }
+        );
+        let error = Toml::mutate(
+            document.as_item_mut(),
+            append!(users.0, value("x")),
+            &mut Default::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["users".into(), 0.into()].into()
+            }
+        );
+        assert_eq!(document.to_string(), DOCUMENT);
+    }
+}
@@ -0,0 +1,159 @@
+//! Helpers shared by the adapters which export mutations as updates of a document, such as
+//! [`DynamoDbUpdate`](super::DynamoDbUpdate) or [`JsonPatch`](super::JsonPatch).
+
+#[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+use super::Adapter;
+#[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+use crate::{Mutation, MutationKind, Path};
+use crate::{MutationError, PathSegment};
+
+/// Documents which updates are exported against.
+pub(super) trait Document: Clone + PartialEq {
+    /// Returns the elements if the value is an array.
+    fn as_array(&self) -> Option<&[Self]>;
+
+    /// Returns the value of `key` if the value is an object.
+    fn get_key(&self, key: &str) -> Option<&Self>;
+
+    /// Returns the keys if the value is an object.
+    #[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+    fn keys(&self) -> Option<impl Iterator<Item = &String>>;
+}
+
+#[cfg(any(feature = "dynamodb", feature = "json-patch", feature = "sql"))]
+impl Document for serde_json::Value {
+    fn as_array(&self) -> Option<&[Self]> {
+        self.as_array().map(Vec::as_slice)
+    }
+
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        self.as_object()?.get(key)
+    }
+
+    #[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+    fn keys(&self) -> Option<impl Iterator<Item = &String>> {
+        self.as_object().map(serde_json::Map::keys)
+    }
+}
+
+#[cfg(feature = "mongo-update")]
+impl Document for bson::Bson {
+    fn as_array(&self) -> Option<&[Self]> {
+        self.as_array().map(Vec::as_slice)
+    }
+
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        self.as_document()?.get(key)
+    }
+
+    fn keys(&self) -> Option<impl Iterator<Item = &String>> {
+        self.as_document().map(bson::Document::keys)
+    }
+}
+
+/// Returns the value at a path without [`Negative`](PathSegment::Negative) segments.
+pub(super) fn get<'a, V: Document>(mut value: &'a V, path: &[PathSegment]) -> Option<&'a V> {
+    for segment in path {
+        value = match (value.as_array(), segment) {
+            (Some(vec), PathSegment::Positive(index)) => vec.get(*index)?,
+            (None, PathSegment::String(key)) => value.get_key(key)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Replaces [`Negative`](PathSegment::Negative) segments of a path with the corresponding
+/// [`Positive`](PathSegment::Positive) indices in `value`.
+pub(super) fn resolve<V: Document>(value: &V, path: &[PathSegment]) -> Result<Vec<PathSegment>, MutationError> {
+    let mut value = Some(value);
+    let mut resolved = Vec::with_capacity(path.len());
+    for segment in path {
+        let segment = match (value.and_then(V::as_array), segment) {
+            (Some(vec), PathSegment::Negative(index)) if *index <= vec.len() => {
+                PathSegment::Positive(vec.len() - index)
+            }
+            (_, PathSegment::Negative(_)) => {
+                resolved.push(segment.clone());
+                return Err(MutationError::IndexError { path: resolved.into() });
+            }
+            (_, segment) => segment.clone(),
+        };
+        value = value.and_then(|value| get(value, std::slice::from_ref(&segment)));
+        resolved.push(segment);
+    }
+    Ok(resolved)
+}
+
+/// Updates which are registered at non-overlapping paths.
+#[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+pub(super) trait Update {
+    /// The update which sets the whole value at its path.
+    const SET: Self;
+
+    /// Merges `other` at the same path into `self`, returning `false` if they cannot be merged.
+    fn merge(&mut self, other: &Self) -> bool;
+}
+
+/// Registers an update at `path`, merging it with the updates at overlapping paths.
+///
+/// The registered paths never overlap each other, so at most one of them is an ancestor of `path`
+/// or `path` itself.
+#[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+pub(super) fn register<U: Update>(updates: &mut Vec<(Vec<PathSegment>, U)>, path: Vec<PathSegment>, update: U) {
+    if let Some((_, other)) = updates.iter_mut().find(|(other, _)| *other == path)
+        && other.merge(&update)
+    {
+        return;
+    }
+    let mut top = path;
+    let mut overlaps = false;
+    for (other, _) in updates.iter() {
+        if other.starts_with(&top) || top.starts_with(other) {
+            overlaps = true;
+            if other.len() < top.len() {
+                top = other.clone();
+            }
+        }
+    }
+    if !overlaps {
+        updates.push((top, update));
+        return;
+    }
+    updates.retain(|(other, _)| !other.starts_with(&top));
+    updates.push((top, U::SET));
+}
+
+/// Applies a mutation to the root `value` and returns the keys whose values changed, or [`None`]
+/// without applying it if the root is not an object.
+///
+/// Returns [`None`] as well if the root is no longer an object after the mutation.
+#[cfg(any(feature = "dynamodb", feature = "mongo-update"))]
+pub(super) fn export_root<A: Adapter<Value: Document>>(
+    value: &mut A::Value,
+    kind: MutationKind<A::Value>,
+) -> Result<Option<Vec<String>>, MutationError> {
+    if value.keys().is_none() {
+        return Ok(None);
+    }
+    let old_value = value.clone();
+    A::mutate(
+        value,
+        Mutation {
+            path: Path::new(),
+            kind,
+        },
+        &mut Default::default(),
+    )?;
+    let (Some(old_keys), Some(new_keys)) = (old_value.keys(), value.keys()) else {
+        return Ok(None);
+    };
+    let removed_keys = old_keys.filter(|key| value.get_key(key).is_none());
+    Ok(Some(
+        new_keys
+            .chain(removed_keys)
+            .filter(|key| old_value.get_key(key) != value.get_key(key))
+            .cloned()
+            .collect(),
+    ))
+}
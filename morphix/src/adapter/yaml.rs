@@ -1,6 +1,12 @@
 use serde_yaml_ng::value::Serializer;
-use serde_yaml_ng::{Error, Value};
+use serde_yaml_ng::{Error, Mapping, Value};
 
+#[cfg(feature = "set")]
+use crate::adapter::members::{add_members_by, hash_member, remove_members_by};
+#[cfg(feature = "splice")]
+use crate::adapter::scalars::char_index;
+#[cfg(feature = "increment")]
+use crate::adapter::scalars::{DURATION_DELTA_KEY, DurationValue, Number, add_numbers, duration_fields, increment_by};
 use crate::{Adapter, Mutation, Mutations, PathSegment};
 
 /// YAML adapter for morphix mutation serialization.
@@ -35,6 +41,18 @@ use crate::{Adapter, Mutation, Mutations, PathSegment};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Yaml(pub Option<Mutation<Value>>);
 
+impl From<Option<Mutation<Value>>> for Yaml {
+    fn from(mutation: Option<Mutation<Value>>) -> Self {
+        Self(mutation)
+    }
+}
+
+impl From<Yaml> for Option<Mutation<Value>> {
+    fn from(Yaml(mutation): Yaml) -> Self {
+        mutation
+    }
+}
+
 impl Adapter for Yaml {
     type Value = Value;
     type Error = Error;
@@ -43,7 +61,7 @@ impl Adapter for Yaml {
         Ok(Self(
             mutation
                 .into_inner()
-                .map(|mutation| mutation.try_map(&mut |value| erased_serde::serialize(&*value, Serializer)))
+                .map(|mutation| mutation.try_map(|value| erased_serde::serialize(&*value, Serializer)))
                 .transpose()?,
         ))
     }
@@ -58,11 +76,12 @@ impl Adapter for Yaml {
             (Value::Sequence(vec), PathSegment::Negative(index)) => {
                 vec.len().checked_sub(*index).and_then(|i| vec.get_mut(i))
             }
-            (Value::Mapping(map), PathSegment::String(key)) => {
-                if allow_create {
-                    Some(map.entry(Value::String(key.to_string())).or_insert(Value::Null))
-                } else {
-                    map.get_mut(&**key)
+            (Value::Mapping(map), segment) => {
+                let key = segment.as_key()?;
+                match map_key(map, &key) {
+                    Some(key) => map.get_mut(&key),
+                    None if allow_create => Some(map.entry(new_key(map, &key)).or_insert(Value::Null)),
+                    None => None,
                 }
             }
             _ => None,
@@ -72,7 +91,7 @@ impl Adapter for Yaml {
     #[cfg(feature = "delete")]
     fn delete(value: &mut Self::Value, segment: &PathSegment) -> Option<Self::Value> {
         match (value, segment) {
-            (Value::Mapping(map), PathSegment::String(key)) => map.remove(&**key),
+            (Value::Mapping(map), segment) => map.remove(&map_key(map, &segment.as_key()?)?),
             _ => None,
         }
     }
@@ -94,7 +113,7 @@ impl Adapter for Yaml {
         }
     }
 
-    #[cfg(feature = "append")]
+    #[cfg(any(feature = "append", feature = "splice"))]
     fn len(value: &Self::Value) -> Option<usize> {
         match value {
             Value::String(str) => Some(str.chars().count()),
@@ -127,4 +146,163 @@ impl Adapter for Yaml {
             _ => None,
         }
     }
+
+    #[cfg(feature = "splice")]
+    fn splice(value: &mut Self::Value, index: usize, delete_len: usize, insert_value: Self::Value) -> Option<usize> {
+        match (value, insert_value) {
+            (Value::String(lhs), Value::String(rhs)) => {
+                let start = char_index(lhs, index)?;
+                let end = start + char_index(&lhs[start..], delete_len)?;
+                let len = rhs.chars().count();
+                lhs.replace_range(start..end, &rhs);
+                Some(len)
+            }
+            (Value::Sequence(lhs), Value::Sequence(rhs)) => {
+                let end = index.checked_add(delete_len).filter(|end| *end <= lhs.len())?;
+                let len = rhs.len();
+                lhs.splice(index..end, rhs);
+                Some(len)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "move")]
+    fn move_index(value: &mut Self::Value, from: usize, to: usize) -> Option<()> {
+        match value {
+            Value::Sequence(vec) => {
+                if from >= vec.len() || to >= vec.len() {
+                    return None;
+                }
+                let element = vec.remove(from);
+                vec.insert(to, element);
+                Some(())
+            }
+            Value::Mapping(map) => {
+                if from >= map.len() || to >= map.len() {
+                    return None;
+                }
+                let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+                let entry = entries.remove(from);
+                entries.insert(to, entry);
+                map.extend(entries);
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "increment")]
+    fn increment(value: &mut Self::Value, delta: Self::Value) -> Option<()> {
+        increment_by(value, delta, |value, delta| match (value, delta) {
+            (lhs @ Value::Number(_), rhs @ Value::Number(_)) => {
+                *lhs = add_numbers(lhs, &rhs)?;
+                Some(())
+            }
+            _ => None,
+        })
+    }
+
+    #[cfg(feature = "set")]
+    fn add_members(value: &mut Self::Value, members: Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Sequence(lhs), Value::Sequence(rhs)) => {
+                let added = add_members_by(lhs, rhs, hash_member, |lhs, rhs| lhs == rhs);
+                Some(Value::Sequence(added))
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "set")]
+    fn remove_members(value: &mut Self::Value, members: &Self::Value) -> Option<Self::Value> {
+        match (value, members) {
+            (Value::Sequence(lhs), Value::Sequence(rhs)) => {
+                let removed = remove_members_by(lhs, rhs, hash_member, |lhs, rhs| lhs == rhs);
+                Some(Value::Sequence(removed))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the key whose string form is `key`, which is how [`PathSegment::from_key`] represents
+/// integer and boolean keys.
+fn map_key(map: &Mapping, key: &str) -> Option<Value> {
+    map.keys()
+        .find(|k| match k {
+            Value::String(k) => k == key,
+            Value::Number(k) => k.to_string() == key,
+            Value::Bool(k) => k.to_string() == key,
+            _ => false,
+        })
+        .cloned()
+}
+
+/// Creates the key of a new entry from its string form, as an integer if the map already has
+/// integer keys.
+fn new_key(map: &Mapping, key: &str) -> Value {
+    if map.keys().any(|k| k.is_i64() || k.is_u64()) {
+        if let Ok(key) = key.parse::<u64>() {
+            return key.into();
+        }
+        if let Ok(key) = key.parse::<i64>() {
+            return key.into();
+        }
+    }
+    key.into()
+}
+
+#[cfg(feature = "increment")]
+impl Number for Value {
+    fn as_integer(&self) -> Option<i128> {
+        let Value::Number(number) = self else {
+            return None;
+        };
+        number
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| number.as_u64().map(i128::from))
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        match u64::try_from(value) {
+            Ok(value) => Some(value.into()),
+            Err(_) => i64::try_from(value).ok().map(Value::from),
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => number.as_f64(),
+            _ => None,
+        }
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value.into())
+    }
+}
+
+#[cfg(feature = "increment")]
+impl DurationValue for Value {
+    type Number = Self;
+
+    fn duration_delta(&self) -> Option<[&Self; 2]> {
+        match self {
+            Value::Mapping(map) if map.len() == 1 => match map.get(DURATION_DELTA_KEY)? {
+                Value::Sequence(vec) => Some(<&[_; 2]>::try_from(&vec[..]).ok()?.each_ref()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn duration_mut(&mut self) -> Option<[&mut Self; 2]> {
+        match self {
+            Value::Sequence(vec) => Some(<&mut [_; 2]>::try_from(&mut vec[..]).ok()?.each_mut()),
+            Value::Mapping(map) => duration_fields(map.iter_mut(), |key| key.as_str()),
+            _ => None,
+        }
+    }
 }
@@ -17,6 +17,29 @@ enum BatchMutationKind<A: Adapter> {
         append_len: usize,
         append_value: Option<A::Value>,
     },
+    #[cfg(feature = "splice")]
+    Splice {
+        index: usize,
+        delete_len: usize,
+        insert_len: usize,
+        insert_value: A::Value,
+    },
+    #[cfg(feature = "increment")]
+    Increment(A::Value),
+    /// Disjoint sets of members to be removed from and added to a set.
+    #[cfg(feature = "set")]
+    AddRemove {
+        add_value: Option<A::Value>,
+        remove_value: Option<A::Value>,
+    },
+    /// Mutations which cannot be merged with each other, kept in their original order.
+    ///
+    /// A single [`Splice`](BatchMutationKind::Splice) window cannot be combined with element paths
+    /// or with disjoint edits without knowing the length of the target, and a
+    /// [`Move`](MutationKind::Move) changes which element every index refers to, so such nodes fall
+    /// back to recording incoming mutations as-is.
+    #[cfg(any(feature = "splice", feature = "move"))]
+    Unmerged(Vec<Mutation<A::Value>>),
 }
 
 enum BatchChildren<A: Adapter> {
@@ -121,13 +144,37 @@ impl<A: Adapter> BatchTree<A> {
         mut mutation: Mutation<A::Value>,
         path_stack: &mut Path<false>,
     ) -> Result<(), MutationError> {
-        if let BatchMutationKind::Replace(value) = &mut self.kind {
-            A::mutate(value, mutation, path_stack)?;
-            return Ok(());
-        }
-
         let mut batch = self;
-        while let Some(mut segment) = mutation.path.pop() {
+        loop {
+            match &mut batch.kind {
+                BatchMutationKind::Replace(value) => {
+                    A::mutate(value, mutation, path_stack)?;
+                    return Ok(());
+                }
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(mutations)
+                    if !mutation.path.is_empty()
+                        || !matches!(mutation.kind, MutationKind::Replace(_) | MutationKind::Batch(_)) =>
+                {
+                    mutations.push(mutation);
+                    return Ok(());
+                }
+                _ => {}
+            }
+
+            let Some(mut segment) = mutation.path.pop() else {
+                break;
+            };
+
+            // Element paths cannot be located relative to a splice window without knowing the
+            // length of the target.
+            #[cfg(feature = "splice")]
+            if let BatchMutationKind::Splice { .. } = batch.kind {
+                mutation.path.push(segment);
+                batch.unmerged().push(mutation);
+                return Ok(());
+            }
+
             #[cfg(any(feature = "append", feature = "truncate"))]
             if let PathSegment::Negative(index) = &mut segment
                 && let BatchMutationKind::TruncateAppend {
@@ -161,10 +208,6 @@ impl<A: Adapter> BatchTree<A> {
                 (PathSegment::Negative(key), BatchChildren::Negative(children)) => children.entry(key).or_default(),
                 _ => return Err(MutationError::IndexError { path: take(path_stack) }),
             };
-            if let BatchMutationKind::Replace(value) = &mut batch.kind {
-                A::mutate(value, mutation, path_stack)?;
-                return Ok(());
-            }
         }
 
         match mutation.kind {
@@ -190,8 +233,23 @@ impl<A: Adapter> BatchTree<A> {
             #[cfg(feature = "append")]
             MutationKind::Append(value) => match &mut batch.kind {
                 BatchMutationKind::Replace(_) => unreachable!(),
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(_) => unreachable!(),
                 #[cfg(feature = "delete")]
                 BatchMutationKind::Delete => return Err(MutationError::OperationError { path: take(path_stack) }),
+                #[cfg(feature = "increment")]
+                BatchMutationKind::Increment(_) => {
+                    return Err(MutationError::OperationError { path: take(path_stack) });
+                }
+                #[cfg(feature = "set")]
+                BatchMutationKind::AddRemove { .. } => {
+                    return Err(MutationError::OperationError { path: take(path_stack) });
+                }
+                #[cfg(feature = "splice")]
+                BatchMutationKind::Splice { .. } => batch.unmerged().push(Mutation {
+                    path: Default::default(),
+                    kind: MutationKind::Append(value),
+                }),
                 BatchMutationKind::None => {
                     let Some(append_len) = A::len(&value) else {
                         return Err(MutationError::OperationError { path: take(path_stack) });
@@ -228,8 +286,23 @@ impl<A: Adapter> BatchTree<A> {
             #[cfg(feature = "truncate")]
             MutationKind::Truncate(len) => match &mut batch.kind {
                 BatchMutationKind::Replace(_) => unreachable!(),
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(_) => unreachable!(),
                 #[cfg(feature = "delete")]
                 BatchMutationKind::Delete => return Err(MutationError::OperationError { path: take(path_stack) }),
+                #[cfg(feature = "increment")]
+                BatchMutationKind::Increment(_) => {
+                    return Err(MutationError::OperationError { path: take(path_stack) });
+                }
+                #[cfg(feature = "set")]
+                BatchMutationKind::AddRemove { .. } => {
+                    return Err(MutationError::OperationError { path: take(path_stack) });
+                }
+                #[cfg(feature = "splice")]
+                BatchMutationKind::Splice { .. } => batch.unmerged().push(Mutation {
+                    path: Default::default(),
+                    kind: MutationKind::Truncate(len),
+                }),
                 BatchMutationKind::None => {
                     if len == 0 {
                         return Ok(());
@@ -273,11 +346,183 @@ impl<A: Adapter> BatchTree<A> {
                     }
                 }
             },
+
+            #[cfg(feature = "splice")]
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            } => {
+                let Some(delete_end) = index.checked_add(delete_len) else {
+                    return Err(MutationError::OperationError { path: take(path_stack) });
+                };
+                match &mut batch.kind {
+                    BatchMutationKind::Replace(_) | BatchMutationKind::Unmerged(_) => unreachable!(),
+                    #[cfg(feature = "delete")]
+                    BatchMutationKind::Delete => return Err(MutationError::OperationError { path: take(path_stack) }),
+                    BatchMutationKind::None if batch.children.is_none() => {
+                        let Some(insert_len) = A::len(&insert) else {
+                            return Err(MutationError::OperationError { path: take(path_stack) });
+                        };
+                        if delete_len == 0 && insert_len == 0 {
+                            return Ok(());
+                        }
+                        batch.kind = BatchMutationKind::Splice {
+                            index,
+                            delete_len,
+                            insert_len,
+                            insert_value: insert,
+                        };
+                    }
+                    BatchMutationKind::Splice {
+                        index: splice_index,
+                        delete_len: splice_delete_len,
+                        insert_len: splice_insert_len,
+                        insert_value,
+                    } if index <= *splice_index + *splice_insert_len && delete_end >= *splice_index => {
+                        // The incoming range overlaps or touches the window, so the elements it
+                        // removes outside of the window are original elements adjacent to it.
+                        let splice_end = *splice_index + *splice_insert_len;
+                        let start = index.max(*splice_index) - *splice_index;
+                        let end = delete_end.min(splice_end) - *splice_index;
+                        let Some(len) = A::splice(insert_value, start, end - start, insert) else {
+                            return Err(MutationError::OperationError { path: take(path_stack) });
+                        };
+                        *splice_delete_len += splice_index.saturating_sub(index);
+                        *splice_delete_len += delete_end.saturating_sub(splice_end);
+                        *splice_insert_len = *splice_insert_len - (end - start) + len;
+                        *splice_index = index.min(*splice_index);
+                        if *splice_delete_len == 0 && *splice_insert_len == 0 {
+                            batch.kind = BatchMutationKind::None;
+                        }
+                    }
+                    _ => batch.unmerged().push(Mutation {
+                        path: Default::default(),
+                        kind: MutationKind::Splice {
+                            index,
+                            delete_len,
+                            insert,
+                        },
+                    }),
+                }
+            }
+
+            #[cfg(feature = "move")]
+            MutationKind::Move { from, to } => match &mut batch.kind {
+                BatchMutationKind::Replace(_) | BatchMutationKind::Unmerged(_) => unreachable!(),
+                #[cfg(feature = "delete")]
+                BatchMutationKind::Delete => return Err(MutationError::OperationError { path: take(path_stack) }),
+                _ => batch.unmerged().push(Mutation {
+                    path: Default::default(),
+                    kind: MutationKind::Move { from, to },
+                }),
+            },
+
+            #[cfg(feature = "increment")]
+            MutationKind::Increment(delta) => match &mut batch.kind {
+                BatchMutationKind::Replace(_) => unreachable!(),
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(_) => unreachable!(),
+                BatchMutationKind::None if batch.children.is_none() => {
+                    batch.kind = BatchMutationKind::Increment(delta);
+                }
+                BatchMutationKind::Increment(value) => {
+                    if A::increment(value, delta).is_none() {
+                        return Err(MutationError::OperationError { path: take(path_stack) });
+                    }
+                }
+                _ => return Err(MutationError::OperationError { path: take(path_stack) }),
+            },
+
+            #[cfg(feature = "set")]
+            MutationKind::Add(members) => match &mut batch.kind {
+                BatchMutationKind::Replace(_) => unreachable!(),
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(_) => unreachable!(),
+                BatchMutationKind::None if batch.children.is_none() => {
+                    batch.kind = BatchMutationKind::AddRemove {
+                        add_value: Some(members),
+                        remove_value: None,
+                    };
+                }
+                BatchMutationKind::AddRemove {
+                    add_value,
+                    remove_value,
+                } => {
+                    if let Some(remove_value) = remove_value
+                        && A::remove_members(remove_value, &members).is_none()
+                    {
+                        return Err(MutationError::OperationError { path: take(path_stack) });
+                    }
+                    match add_value {
+                        Some(add_value) => {
+                            if A::add_members(add_value, members).is_none() {
+                                return Err(MutationError::OperationError { path: take(path_stack) });
+                            }
+                        }
+                        None => *add_value = Some(members),
+                    }
+                }
+                _ => return Err(MutationError::OperationError { path: take(path_stack) }),
+            },
+
+            #[cfg(feature = "set")]
+            MutationKind::Remove(members) => match &mut batch.kind {
+                BatchMutationKind::Replace(_) => unreachable!(),
+                #[cfg(any(feature = "splice", feature = "move"))]
+                BatchMutationKind::Unmerged(_) => unreachable!(),
+                BatchMutationKind::None if batch.children.is_none() => {
+                    batch.kind = BatchMutationKind::AddRemove {
+                        add_value: None,
+                        remove_value: Some(members),
+                    };
+                }
+                BatchMutationKind::AddRemove {
+                    add_value,
+                    remove_value,
+                } => {
+                    if let Some(add_value) = add_value
+                        && A::remove_members(add_value, &members).is_none()
+                    {
+                        return Err(MutationError::OperationError { path: take(path_stack) });
+                    }
+                    match remove_value {
+                        Some(remove_value) => {
+                            if A::add_members(remove_value, members).is_none() {
+                                return Err(MutationError::OperationError { path: take(path_stack) });
+                            }
+                        }
+                        None => *remove_value = Some(members),
+                    }
+                }
+                _ => return Err(MutationError::OperationError { path: take(path_stack) }),
+            },
         }
 
         Ok(())
     }
 
+    /// Converts this node into an [`Unmerged`](BatchMutationKind::Unmerged) node, preserving the
+    /// mutations accumulated so far, and returns the list of recorded mutations.
+    #[cfg(any(feature = "splice", feature = "move"))]
+    fn unmerged(&mut self) -> &mut Vec<Mutation<A::Value>> {
+        if !matches!(self.kind, BatchMutationKind::Unmerged(_)) {
+            let mutations = match self.dump().into_inner() {
+                None => vec![],
+                Some(Mutation {
+                    path,
+                    kind: MutationKind::Batch(batch),
+                }) if path.is_empty() => batch,
+                Some(mutation) => vec![mutation],
+            };
+            self.kind = BatchMutationKind::Unmerged(mutations);
+        }
+        let BatchMutationKind::Unmerged(mutations) = &mut self.kind else {
+            unreachable!()
+        };
+        mutations
+    }
+
     /// Dumps all accumulated mutations as a single optimized mutation.
     ///
     /// - Returns [`None`] if no mutations have been accumulated.
@@ -312,6 +557,37 @@ impl<A: Adapter> BatchTree<A> {
                     mutations.extend(MutationKind::Append(value));
                 }
             }
+            #[cfg(feature = "splice")]
+            BatchMutationKind::Splice {
+                index,
+                delete_len,
+                insert_len: _,
+                insert_value,
+            } => mutations.extend(MutationKind::Splice {
+                index,
+                delete_len,
+                insert: insert_value,
+            }),
+            #[cfg(feature = "increment")]
+            BatchMutationKind::Increment(value) => mutations.extend(MutationKind::Increment(value)),
+            #[cfg(feature = "set")]
+            BatchMutationKind::AddRemove {
+                add_value,
+                remove_value,
+            } => {
+                if let Some(value) = remove_value {
+                    mutations.extend(MutationKind::Remove(value));
+                }
+                if let Some(value) = add_value {
+                    mutations.extend(MutationKind::Add(value));
+                }
+            }
+            #[cfg(any(feature = "splice", feature = "move"))]
+            BatchMutationKind::Unmerged(batch) => {
+                for mutation in batch {
+                    mutations.extend(mutation);
+                }
+            }
         }
         mutations
     }
@@ -538,4 +814,180 @@ mod test {
         batch.load(delete!(foo)).unwrap();
         batch.load(append!(foo, json!("test"))).unwrap_err();
     }
+
+    #[test]
+    fn merge_increment() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(increment!(foo, json!(2))).unwrap();
+        batch.load(increment!(foo, json!(-5))).unwrap();
+        batch.load(increment!(bar, json!(1.5))).unwrap();
+        assert_eq!(
+            batch.dump().into_inner(),
+            Some(batch!(_, increment!(bar, json!(1.5)), increment!(foo, json!(-3)))),
+        );
+    }
+
+    #[test]
+    fn increment_after_replace() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(replace!(foo, json!(1))).unwrap();
+        batch.load(increment!(foo, json!(2))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!(3))));
+
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(increment!(foo, json!(2))).unwrap();
+        batch.load(replace!(foo, json!(1))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!(1))));
+    }
+
+    #[test]
+    fn append_after_increment() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(increment!(foo, json!(1))).unwrap();
+        batch.load(append!(foo, json!("test"))).unwrap_err();
+    }
+
+    #[test]
+    fn merge_add_remove() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(add!(foo, json!(["a", "b"]))).unwrap();
+        batch.load(remove!(foo, json!(["b", "c"]))).unwrap();
+        batch.load(add!(foo, json!(["c", "d"]))).unwrap();
+        batch.load(remove!(foo, json!(["e"]))).unwrap();
+        assert_eq!(
+            batch.dump().into_inner(),
+            Some(batch!(
+                foo,
+                remove!(_, json!(["b", "e"])),
+                add!(_, json!(["a", "c", "d"]))
+            )),
+        );
+    }
+
+    #[test]
+    fn add_after_replace() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(replace!(foo, json!(["a"]))).unwrap();
+        batch.load(add!(foo, json!(["a", "b"]))).unwrap();
+        batch.load(remove!(foo, json!(["a"]))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!(["b"]))));
+    }
+
+    #[test]
+    fn append_after_add() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(add!(foo, json!(["a"]))).unwrap();
+        batch.load(append!(foo, json!(["b"]))).unwrap_err();
+    }
+
+    #[test]
+    fn merge_splice() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 1, 1, json!([]))).unwrap();
+        batch.load(splice!(foo, 1, 0, json!(["a", "b"]))).unwrap();
+        batch.load(splice!(foo, 0, 2, json!(["c"]))).unwrap();
+        batch.load(splice!(foo, 2, 1, json!([]))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(splice!(foo, 0, 3, json!(["c", "b"]))));
+    }
+
+    #[test]
+    fn merge_text_splice() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 2, 1, json!("世界"))).unwrap();
+        batch.load(splice!(foo, 3, 0, json!("，"))).unwrap();
+        batch.load(splice!(foo, 1, 2, json!(""))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(splice!(foo, 1, 2, json!("，界"))));
+    }
+
+    #[test]
+    fn splice_cancelled() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 2, 0, json!([1, 2]))).unwrap();
+        batch.load(splice!(foo, 2, 2, json!([]))).unwrap();
+        assert_eq!(batch.dump().into_inner(), None);
+    }
+
+    #[test]
+    fn splice_range_overflow() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 1, 0, json!([1]))).unwrap();
+        let error = batch.load(splice!(foo, usize::MAX, 1, json!([]))).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::OperationError {
+                path: vec!["foo".into()].into()
+            }
+        );
+    }
+
+    #[test]
+    fn disjoint_splice() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 1, 1, json!([]))).unwrap();
+        batch.load(splice!(foo, 3, 0, json!([1]))).unwrap();
+        assert_eq!(
+            batch.dump().into_inner(),
+            Some(batch!(foo, splice!(_, 1, 1, json!([])), splice!(_, 3, 0, json!([1])))),
+        );
+    }
+
+    #[test]
+    fn splice_then_element() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(append!(foo, json!([1]))).unwrap();
+        batch.load(splice!(foo, 0, 1, json!([]))).unwrap();
+        batch.load(replace!(foo.0, json!(2))).unwrap();
+        batch.load(truncate!(foo, 1)).unwrap();
+        assert_eq!(
+            batch.dump().into_inner(),
+            Some(batch!(
+                foo,
+                append!(_, json!([1])),
+                splice!(_, 0, 1, json!([])),
+                replace!(0, json!(2)),
+                truncate!(_, 1),
+            )),
+        );
+    }
+
+    #[test]
+    fn replace_after_splice() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(splice!(foo, 0, 1, json!([]))).unwrap();
+        batch.load(append!(foo, json!([1]))).unwrap();
+        batch.load(replace!(foo, json!([2]))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!([2]))));
+    }
+
+    #[test]
+    fn element_then_move() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(replace!(foo.-1, json!(1))).unwrap();
+        batch.load(mv!(foo, 0, 2)).unwrap();
+        batch.load(mv!(foo, 1, 0)).unwrap();
+        batch.load(replace!(foo.-1, json!(2))).unwrap();
+        assert_eq!(
+            batch.dump().into_inner(),
+            Some(batch!(
+                foo,
+                replace!(-1, json!(1)),
+                mv!(_, 0, 2),
+                mv!(_, 1, 0),
+                replace!(-1, json!(2)),
+            )),
+        );
+    }
+
+    #[test]
+    fn replace_after_move() {
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(mv!(foo, 0, 1)).unwrap();
+        batch.load(replace!(foo, json!([2]))).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!([2]))));
+
+        let mut batch = BatchTree::<Json>::new();
+        batch.load(replace!(foo, json!([1, 2]))).unwrap();
+        batch.load(mv!(foo, 0, 1)).unwrap();
+        assert_eq!(batch.dump().into_inner(), Some(replace!(foo, json!([2, 1]))));
+    }
 }
@@ -27,6 +27,12 @@ pub enum MutationError {
         /// The requested truncation length.
         truncate_len: usize,
     },
+    /// Concurrent mutations at the specified path cannot be transformed against each other.
+    #[cfg(feature = "transform")]
+    ConflictError {
+        /// The path where the mutations conflict.
+        path: Path<false>,
+    },
 }
 
 impl Display for MutationError {
@@ -49,8 +55,58 @@ impl Display for MutationError {
                     "cannot truncate at {path}: actual length {actual_len} is less than truncate length {truncate_len}"
                 )
             }
+            #[cfg(feature = "transform")]
+            Self::ConflictError { path } => {
+                write!(f, "concurrent mutations at {path} cannot be transformed")
+            }
         }
     }
 }
 
 impl Error for MutationError {}
+
+/// Error type for parsing and converting [`Path`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathError {
+    /// A path string is malformed.
+    SyntaxError {
+        /// The malformed path string.
+        input: String,
+        /// The byte offset where parsing failed.
+        position: usize,
+    },
+    /// A JSON pointer is malformed.
+    InvalidPointer {
+        /// The malformed pointer.
+        pointer: String,
+    },
+    /// A path with a [`Negative`](crate::PathSegment::Negative) index cannot be represented as a
+    /// JSON pointer.
+    NegativeIndex {
+        /// The path containing the negative index.
+        path: Path<false>,
+    },
+    /// A map key cannot be converted into a path segment.
+    InvalidKey {
+        /// The reason why the key is invalid.
+        message: String,
+    },
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SyntaxError { input, position } => {
+                write!(f, "invalid path {input:?} at position {position}")
+            }
+            Self::InvalidPointer { pointer } => write!(f, "invalid JSON pointer {pointer:?}"),
+            Self::NegativeIndex { path } => {
+                write!(f, "path {path} with a negative index cannot be a JSON pointer")
+            }
+            Self::InvalidKey { message } => write!(f, "invalid map key: {message}"),
+        }
+    }
+}
+
+impl Error for PathError {}
@@ -0,0 +1,296 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::Mutations;
+use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, SerializeHandler, Snapshot};
+#[cfg(feature = "inverse")]
+use crate::helper::Captured;
+use crate::helper::{AsDeref, Capture, Invalidate, Zero};
+
+/// A general observer that reports additive changes of counters as increments.
+///
+/// [`CounterObserver`] works like [`SnapshotObserver`](super::SnapshotObserver), but
+/// [`AddAssign`](std::ops::AddAssign) and [`SubAssign`](std::ops::SubAssign) do not invalidate the
+/// observer. If the value was only changed through these operators, the difference from the
+/// snapshot is reported as an [`Increment`](crate::MutationKind::Increment) mutation, so that
+/// concurrent updates of the same counter from different writers are not lost. Any other mutation
+/// falls back to a [`Replace`](crate::MutationKind::Replace).
+///
+/// Without the `increment` feature, [`CounterObserver`] always produces
+/// [`Replace`](crate::MutationKind::Replace) mutations.
+///
+/// ## Derive Usage
+///
+/// Can be used via the `#[morphix(counter)]` attribute in derive macros:
+///
+/// ```
+/// # use morphix::Observe;
+/// # use serde::Serialize;
+/// #[derive(Serialize, Observe)]
+/// struct MyStruct {
+///     #[morphix(counter)]
+///     views: u64,         // `views += 1` is reported as an increment
+/// }
+/// ```
+///
+/// ## Supported Types
+///
+/// All types implementing [`Counter`], which includes numeric primitives,
+/// [`Duration`](std::time::Duration) and [`chrono::TimeDelta`] (with the `chrono` feature).
+pub type CounterObserver<'ob, S, D = Zero> = GeneralObserver<'ob, CounterHandler<<S as AsDeref<D>>::Target>, S, D>;
+
+/// A trait for values whose changes can be expressed as a delta.
+///
+/// [`Counter`] is used by [`CounterObserver`] to compute the
+/// [`Increment`](crate::MutationKind::Increment) between a snapshot and the current value.
+pub trait Counter: Snapshot {
+    /// The type of the difference between two values.
+    type Delta: Serialize + 'static;
+
+    /// Computes the delta that, added to `snapshot`, yields the current value.
+    ///
+    /// Returns [`None`] if the delta cannot be represented, in which case a
+    /// [`Replace`](crate::MutationKind::Replace) mutation is produced instead.
+    fn delta(&self, snapshot: &Self::Snapshot) -> Option<Self::Delta>;
+}
+
+pub struct CounterHandler<T: Snapshot + ?Sized> {
+    snapshot: T::Snapshot,
+    replaced: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Snapshot + ?Sized> Invalidate<T> for CounterHandler<T> {
+    fn invalidate(&mut self, _: &T) {
+        self.replaced = true;
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &T) -> Option<Mutations> {
+        value.eq_snapshot(&self.snapshot).then(Mutations::new)
+    }
+}
+
+impl<T: Snapshot + ?Sized> GeneralHandler for CounterHandler<T> {
+    type Target = T;
+
+    const TRACK_INCREMENT: bool = true;
+
+    fn observe(value: &T) -> Self {
+        Self {
+            snapshot: value.to_snapshot(),
+            replaced: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Counter<Snapshot: Capture> + Serialize + ?Sized + 'static> SerializeHandler for CounterHandler<T> {
+    unsafe fn flush(&mut self, value: &T) -> Mutations {
+        let old = std::mem::replace(self, Self::observe(value));
+        if value.eq_snapshot(&old.snapshot) {
+            return Mutations::new();
+        }
+        let mutations = old.forward(value);
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(Mutations::replace_owned(Captured::new(&old.snapshot)));
+        mutations
+    }
+}
+
+impl<T: Counter + Serialize + ?Sized + 'static> CounterHandler<T> {
+    fn forward(&self, value: &T) -> Mutations {
+        #[cfg(feature = "increment")]
+        if !self.replaced
+            && let Some(delta) = value.delta(&self.snapshot)
+        {
+            return Mutations::increment(delta);
+        }
+        Mutations::replace(value)
+    }
+}
+
+impl<T: Snapshot + ?Sized> DebugHandler for CounterHandler<T> {
+    const NAME: &'static str = "CounterObserver";
+}
+
+macro_rules! impl_counter_integer {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Counter for $ty {
+                type Delta = i64;
+
+                fn delta(&self, snapshot: &Self) -> Option<i64> {
+                    let delta = i128::try_from(*self).ok()?.checked_sub(i128::try_from(*snapshot).ok()?)?;
+                    i64::try_from(delta).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_counter_integer! {
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128,
+}
+
+macro_rules! impl_counter_float {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Counter for $ty {
+                type Delta = Self;
+
+                fn delta(&self, snapshot: &Self) -> Option<Self> {
+                    Some(self - snapshot).filter(|delta| delta.is_finite())
+                }
+            }
+        )*
+    };
+}
+
+impl_counter_float! {
+    f32, f64,
+}
+
+/// The key of the single-entry map a duration delta is serialized as.
+///
+/// Durations are serialized as plain maps or sequences of numbers, so their deltas are wrapped in
+/// `{"$duration": [secs, nanos]}` to tell adapters to add them as durations.
+pub(crate) const DURATION_DELTA_KEY: &str = "$duration";
+
+/// A signed duration, the delta of [`Duration`] and [`chrono::TimeDelta`].
+///
+/// The nanoseconds are always within `0..1_000_000_000`, so a negative delta has negative seconds.
+pub struct DurationDelta {
+    secs: i64,
+    nanos: u32,
+}
+
+impl DurationDelta {
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        const NANOS_PER_SEC: i128 = 1_000_000_000;
+        Some(Self {
+            secs: i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?,
+            nanos: nanos.rem_euclid(NANOS_PER_SEC) as u32,
+        })
+    }
+}
+
+impl Serialize for DurationDelta {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut state = serializer.serialize_map(Some(1))?;
+        state.serialize_entry(DURATION_DELTA_KEY, &(self.secs, self.nanos))?;
+        state.end()
+    }
+}
+
+impl Counter for Duration {
+    type Delta = DurationDelta;
+
+    fn delta(&self, snapshot: &Self) -> Option<DurationDelta> {
+        DurationDelta::from_nanos(self.as_nanos() as i128 - snapshot.as_nanos() as i128)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Counter for chrono::TimeDelta {
+    type Delta = DurationDelta;
+
+    fn delta(&self, snapshot: &Self) -> Option<DurationDelta> {
+        let delta = self.checked_sub(snapshot)?;
+        DurationDelta::from_nanos(i128::from(delta.num_seconds()) * 1_000_000_000 + i128::from(delta.subsec_nanos()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use super::*;
+    use crate::adapter::Json;
+    use crate::helper::QuasiObserver;
+    use crate::observe::{Observer, SerializeObserverExt};
+
+    #[test]
+    fn increment_integer() {
+        let mut value = 42u32;
+        let mut ob = CounterObserver::<u32>::observe(&mut value);
+        ob += 3;
+        ob -= 1;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!(2))));
+
+        ob -= 4;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!(-4))));
+
+        ob += 1;
+        ob -= 1;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+        assert_eq!(value, 40);
+    }
+
+    #[test]
+    fn increment_float() {
+        let mut value = 1.5f64;
+        let mut ob = CounterObserver::<f64>::observe(&mut value);
+        ob -= 2.0;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!(-2.0))));
+    }
+
+    #[test]
+    fn increment_duration() {
+        let mut value = Duration::from_millis(1500);
+        let mut ob = CounterObserver::<Duration>::observe(&mut value);
+        ob -= Duration::from_millis(700);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!({"$duration": [-1, 300_000_000]}))));
+
+        ob += Duration::from_secs(2);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!({"$duration": [2, 0]}))));
+    }
+
+    #[test]
+    fn increment_time_delta() {
+        let mut value = chrono::TimeDelta::seconds(5);
+        let mut ob = CounterObserver::<chrono::TimeDelta>::observe(&mut value);
+        ob -= chrono::TimeDelta::milliseconds(5500);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(increment!(_, json!({"$duration": [-6, 500_000_000]}))));
+    }
+
+    #[test]
+    fn assign_triggers_replace() {
+        let mut value = 42u32;
+        let mut ob = CounterObserver::<u32>::observe(&mut value);
+        ob += 1;
+        *ob.tracked_mut() = 50;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!(50))));
+
+        *ob.tracked_mut() *= 2;
+        ob += 1;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!(101))));
+
+        *ob.tracked_mut() = 101;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn unrepresentable_delta() {
+        let mut value = 0u64;
+        let mut ob = CounterObserver::<u64>::observe(&mut value);
+        ob += u64::MAX;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!(u64::MAX))));
+    }
+}
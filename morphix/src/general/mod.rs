@@ -6,6 +6,7 @@
 //! field-level control. Direct use of types from this module is typically only needed for advanced
 //! use cases.
 
+pub(crate) mod counter;
 mod noop;
 mod observer;
 mod pointer;
@@ -13,6 +14,7 @@ mod shallow;
 pub(crate) mod snapshot;
 mod unsize;
 
+pub use counter::{Counter, CounterObserver};
 pub use noop::NoopObserver;
 pub use observer::{DebugHandler, GeneralHandler, GeneralObserver, ReplaceHandler, SerializeHandler};
 pub use pointer::PointerObserver;
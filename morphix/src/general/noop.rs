@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+#[cfg(feature = "inverse")]
+use crate::Mutations;
 use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, ReplaceHandler};
 use crate::helper::{AsDeref, Invalidate, Zero};
 
@@ -34,6 +36,11 @@ pub struct NoopHandler<T: ?Sized>(PhantomData<T>);
 
 impl<T: ?Sized> Invalidate<T> for NoopHandler<T> {
     fn invalidate(&mut self, _: &T) {}
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, _: &T) -> Option<Mutations> {
+        Some(Mutations::new())
+    }
 }
 
 impl<T: ?Sized> GeneralHandler for NoopHandler<T> {
@@ -49,6 +49,14 @@ pub trait GeneralHandler: Invalidate<Self::Target> {
     /// The observed value type that this handler tracks changes for.
     type Target: ?Sized;
 
+    /// Whether [`AddAssign`](std::ops::AddAssign) and [`SubAssign`](std::ops::SubAssign) on the
+    /// observed value are tracked by the handler itself.
+    ///
+    /// If `true`, [`GeneralObserver`] applies these operators without invalidating the handler, so
+    /// that the handler can tell them apart from other mutations at flush time. Defaults to
+    /// `false`.
+    const TRACK_INCREMENT: bool = false;
+
     /// Implementation for [`Observer::observe`].
     fn observe(value: &Self::Target) -> Self;
 }
@@ -97,6 +105,15 @@ pub trait ReplaceHandler: GeneralHandler {
     ///
     /// See also [`SerializeObserver::flush`].
     unsafe fn is_replace(&self, value: &Self::Target) -> bool;
+
+    /// Returns the inverse of the [`Replace`](crate::MutationKind::Replace) mutation produced by
+    /// the next flush.
+    ///
+    /// Defaults to [`Invalidate::inverse`].
+    #[cfg(feature = "inverse")]
+    fn replace_inverse(&self, value: &Self::Target) -> Option<Mutations> {
+        Invalidate::inverse(self, value)
+    }
 }
 
 impl<H> SerializeHandler for H
@@ -105,13 +122,17 @@ where
     H::Target: Serialize + 'static,
 {
     unsafe fn flush(&mut self, value: &Self::Target) -> Mutations {
-        let is_replace = unsafe { ReplaceHandler::is_replace(self, value) };
-        *self = H::observe(value);
-        if is_replace {
-            Mutations::replace(value)
-        } else {
-            Mutations::new()
+        if !unsafe { ReplaceHandler::is_replace(self, value) } {
+            *self = H::observe(value);
+            return Mutations::new();
         }
+        #[cfg(feature = "inverse")]
+        let inverse = self.replace_inverse(value);
+        *self = H::observe(value);
+        let mutations = Mutations::replace(value);
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(inverse);
+        mutations
     }
 }
 
@@ -215,6 +236,11 @@ where
     fn invalidate(this: &mut Self) {
         H::invalidate(&mut this.handler, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.handler, head.as_deref())
+    }
 }
 
 impl<'ob, H, S: ?Sized, D, T: ?Sized> Observer for GeneralObserver<'ob, H, S, D>
@@ -408,9 +434,34 @@ macro_rules! impl_ops_assign {
     };
 }
 
-impl_ops_assign! {
+macro_rules! impl_ops_increment {
+    ($($trait:ident => $method:ident),* $(,)?) => {
+        $(
+            impl<'ob, H, S: ?Sized, D, T: ?Sized, U> std::ops::$trait<U> for GeneralObserver<'ob, H, S, D>
+            where
+                S: AsDerefMut<D, Target = T>,
+                H: GeneralHandler<Target = T>,
+                D: Unsigned,
+                T: std::ops::$trait<U>,
+            {
+                fn $method(&mut self, rhs: U) {
+                    if H::TRACK_INCREMENT {
+                        self.untracked_mut().$method(rhs);
+                    } else {
+                        self.tracked_mut().$method(rhs);
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_ops_increment! {
     AddAssign => add_assign,
     SubAssign => sub_assign,
+}
+
+impl_ops_assign! {
     MulAssign => mul_assign,
     DivAssign => div_assign,
     RemAssign => rem_assign,
@@ -511,6 +562,28 @@ impl_partial_eq! {
     chrono::TimeDelta, chrono::Utc, chrono::Weekday, chrono::WeekdaySet,
 }
 
+#[cfg(feature = "bigdecimal")]
+impl_partial_eq! {
+    bigdecimal::BigDecimal,
+}
+
+#[cfg(feature = "jiff")]
+impl_partial_eq! {
+    jiff::SignedDuration, jiff::Timestamp, jiff::Zoned,
+    jiff::civil::Date, jiff::civil::DateTime, jiff::civil::ISOWeekDate, jiff::civil::Time,
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_partial_eq! {
+    rust_decimal::Decimal,
+}
+
+#[cfg(feature = "time")]
+impl_partial_eq! {
+    time::Date, time::Duration, time::Month, time::OffsetDateTime, time::PrimitiveDateTime,
+    time::Time, time::UtcOffset, time::Weekday,
+}
+
 #[cfg(feature = "uuid")]
 impl_partial_eq! {
     uuid::Uuid, uuid::NonNilUuid,
@@ -548,6 +621,28 @@ impl_partial_ord! {
     chrono::TimeDelta, chrono::WeekdaySet,
 }
 
+#[cfg(feature = "bigdecimal")]
+impl_partial_ord! {
+    bigdecimal::BigDecimal,
+}
+
+#[cfg(feature = "jiff")]
+impl_partial_ord! {
+    jiff::SignedDuration, jiff::Timestamp, jiff::Zoned,
+    jiff::civil::Date, jiff::civil::DateTime, jiff::civil::ISOWeekDate, jiff::civil::Time,
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_partial_ord! {
+    rust_decimal::Decimal,
+}
+
+#[cfg(feature = "time")]
+impl_partial_ord! {
+    time::Date, time::Duration, time::OffsetDateTime, time::PrimitiveDateTime,
+    time::Time, time::UtcOffset,
+}
+
 #[cfg(feature = "uuid")]
 impl_partial_ord! {
     uuid::Uuid,
@@ -1,9 +1,11 @@
 use std::ptr::NonNull;
 
+#[cfg(feature = "inverse")]
+use crate::Mutations;
 use crate::Observe;
 use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, ReplaceHandler};
 use crate::helper::macros::default_impl_ref_observe;
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Unsigned};
+use crate::helper::{AsDeref, AsDerefMut, Captor, Capture, Invalidate, Unsigned};
 use crate::observe::DefaultSpec;
 
 /// A general observer implementation for reference types.
@@ -32,23 +34,32 @@ pub type PointerObserver<'ob, S, D> = GeneralObserver<'ob, PointerHandler<<S as
 
 pub struct PointerHandler<T: ?Sized> {
     ptr: Option<NonNull<T>>,
+    captor: Captor,
 }
 
-impl<T: ?Sized> Invalidate<T> for PointerHandler<T> {
-    fn invalidate(&mut self, _: &T) {}
+impl<T: Capture + ?Sized> Invalidate<T> for PointerHandler<T> {
+    fn invalidate(&mut self, value: &T) {
+        self.captor.capture(value);
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, _: &T) -> Option<Mutations> {
+        self.captor.inverse()
+    }
 }
 
-impl<T: ?Sized> GeneralHandler for PointerHandler<T> {
+impl<T: Capture + ?Sized> GeneralHandler for PointerHandler<T> {
     type Target = T;
 
     fn observe(value: &T) -> Self {
         Self {
             ptr: Some(NonNull::from(value)),
+            captor: Captor::default(),
         }
     }
 }
 
-impl<T: ?Sized> ReplaceHandler for PointerHandler<T> {
+impl<T: Capture + ?Sized> ReplaceHandler for PointerHandler<T> {
     unsafe fn is_replace(&self, value: &T) -> bool {
         !std::ptr::eq(
             value,
@@ -59,7 +70,7 @@ impl<T: ?Sized> ReplaceHandler for PointerHandler<T> {
     }
 }
 
-impl<T: ?Sized> DebugHandler for PointerHandler<T> {
+impl<T: Capture + ?Sized> DebugHandler for PointerHandler<T> {
     const NAME: &'static str = "PointerObserver";
 }
 
@@ -1,7 +1,9 @@
 use std::marker::PhantomData;
 
+#[cfg(feature = "inverse")]
+use crate::Mutations;
 use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, ReplaceHandler};
-use crate::helper::{AsDeref, Invalidate, Zero};
+use crate::helper::{AsDeref, Captor, Capture, Invalidate, Zero};
 
 /// A general observer that tracks any mutation access as a change.
 ///
@@ -41,32 +43,40 @@ pub type ShallowObserver<'ob, S, D = Zero> = GeneralObserver<'ob, ShallowHandler
 
 pub struct ShallowHandler<T: ?Sized> {
     mutated: bool,
+    captor: Captor,
     phantom: PhantomData<T>,
 }
 
-impl<T: ?Sized> Invalidate<T> for ShallowHandler<T> {
-    fn invalidate(&mut self, _: &T) {
+impl<T: Capture + ?Sized> Invalidate<T> for ShallowHandler<T> {
+    fn invalidate(&mut self, value: &T) {
         self.mutated = true;
+        self.captor.capture(value);
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, _: &T) -> Option<Mutations> {
+        self.captor.inverse()
     }
 }
 
-impl<T: ?Sized> GeneralHandler for ShallowHandler<T> {
+impl<T: Capture + ?Sized> GeneralHandler for ShallowHandler<T> {
     type Target = T;
 
     fn observe(_value: &T) -> Self {
         Self {
             mutated: false,
+            captor: Captor::default(),
             phantom: PhantomData,
         }
     }
 }
 
-impl<T: ?Sized> ReplaceHandler for ShallowHandler<T> {
+impl<T: Capture + ?Sized> ReplaceHandler for ShallowHandler<T> {
     unsafe fn is_replace(&self, _value: &T) -> bool {
         self.mutated
     }
 }
 
-impl<T: ?Sized> DebugHandler for ShallowHandler<T> {
+impl<T: Capture + ?Sized> DebugHandler for ShallowHandler<T> {
     const NAME: &'static str = "ShallowObserver";
 }
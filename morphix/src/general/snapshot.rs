@@ -2,9 +2,13 @@ use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::num::NonZero;
 
+#[cfg(feature = "inverse")]
+use crate::Mutations;
 use crate::Observe;
 use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, ReplaceHandler};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Unsigned, Zero};
+#[cfg(feature = "inverse")]
+use crate::helper::Captured;
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Unsigned, Zero};
 use crate::observe::RefObserve;
 
 /// A general observer that uses snapshot comparison to detect actual value changes.
@@ -68,6 +72,10 @@ pub type SnapshotObserver<'ob, S, D = Zero> = GeneralObserver<'ob, SnapshotHandl
 /// [`&mut T`](reference), the associated [`Snapshot`](Snapshot::Snapshot) type is `T::Snapshot`
 /// rather than `Self`. This means [`Snapshot`] performs a "deep copy" through indirections,
 /// capturing the underlying value rather than the pointer itself.
+///
+/// With the `inverse` feature, the snapshot is serialized as the inverse of a
+/// [`Replace`](crate::MutationKind::Replace) mutation, so it should serialize like the value it was
+/// taken from.
 pub trait Snapshot {
     /// The snapshot type used for comparison.
     ///
@@ -94,6 +102,13 @@ pub struct SnapshotHandler<T: Snapshot + ?Sized> {
 
 impl<T: Snapshot + ?Sized> Invalidate<T> for SnapshotHandler<T> {
     fn invalidate(&mut self, _: &T) {}
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &T) -> Option<Mutations> {
+        value
+            .eq_snapshot(unsafe { self.snapshot.assume_init_ref() })
+            .then(Mutations::new)
+    }
 }
 
 impl<T: Snapshot + ?Sized> GeneralHandler for SnapshotHandler<T> {
@@ -107,11 +122,17 @@ impl<T: Snapshot + ?Sized> GeneralHandler for SnapshotHandler<T> {
     }
 }
 
-impl<T: Snapshot + ?Sized> ReplaceHandler for SnapshotHandler<T> {
+impl<T: Snapshot<Snapshot: Capture> + ?Sized> ReplaceHandler for SnapshotHandler<T> {
     unsafe fn is_replace(&self, value: &T) -> bool {
         // SAFETY: only called from `flush`, where the observer contains a valid pointer
         !value.eq_snapshot(unsafe { self.snapshot.assume_init_ref() })
     }
+
+    #[cfg(feature = "inverse")]
+    fn replace_inverse(&self, _: &T) -> Option<Mutations> {
+        let snapshot = unsafe { self.snapshot.assume_init_ref() };
+        Some(Mutations::replace_owned(Captured::new(snapshot)))
+    }
 }
 
 impl<T: Snapshot + ?Sized> DebugHandler for SnapshotHandler<T> {
@@ -179,6 +200,23 @@ impl_snapshot_observe! {
     chrono::TimeDelta, chrono::Utc, chrono::Weekday, chrono::WeekdaySet,
 }
 
+#[cfg(feature = "jiff")]
+impl_snapshot_observe! {
+    jiff::SignedDuration, jiff::Timestamp,
+    jiff::civil::Date, jiff::civil::DateTime, jiff::civil::ISOWeekDate, jiff::civil::Time,
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_snapshot_observe! {
+    rust_decimal::Decimal,
+}
+
+#[cfg(feature = "time")]
+impl_snapshot_observe! {
+    time::Date, time::Duration, time::Month, time::OffsetDateTime, time::PrimitiveDateTime,
+    time::Time, time::UtcOffset, time::Weekday,
+}
+
 #[cfg(feature = "uuid")]
 impl_snapshot_observe! {
     uuid::Uuid, uuid::NonNilUuid,
@@ -226,7 +264,50 @@ generic_impl_snapshot_observe! {
     impl [T] _ for std::marker::PhantomData<T>;
 }
 
+#[cfg(feature = "bigdecimal")]
+generic_impl_snapshot_observe! {
+    impl _ for bigdecimal::BigDecimal;
+}
+
 #[cfg(feature = "chrono")]
 generic_impl_snapshot_observe! {
     impl [Tz: chrono::TimeZone] _ for chrono::DateTime<Tz>;
 }
+
+#[cfg(feature = "jiff")]
+generic_impl_snapshot_observe! {
+    impl _ for jiff::Zoned;
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use crate::adapter::Json;
+    use crate::helper::QuasiObserver;
+    use crate::observe::{ObserveExt, SerializeObserverExt};
+
+    #[test]
+    fn option_decimal() {
+        let mut value = Some(rust_decimal::Decimal::new(150, 2));
+        let mut ob = value.__observe();
+        *ob.tracked_mut() = Some(rust_decimal::Decimal::new(150, 2));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+
+        *ob.tracked_mut() = Some(rust_decimal::Decimal::new(175, 2));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!("1.75"))));
+    }
+
+    #[test]
+    fn vec_timestamp() {
+        let mut value = vec![jiff::Timestamp::UNIX_EPOCH, jiff::Timestamp::UNIX_EPOCH];
+        let mut ob = value.__observe();
+        *ob[0].tracked_mut() = jiff::Timestamp::UNIX_EPOCH;
+        *ob[1].tracked_mut() = jiff::Timestamp::from_second(60).unwrap();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(-1, json!("1970-01-01T00:01:00Z"))));
+    }
+}
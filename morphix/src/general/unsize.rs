@@ -4,7 +4,7 @@ use std::ptr::NonNull;
 use serde::Serialize;
 
 use crate::general::{DebugHandler, GeneralHandler, GeneralObserver, SerializeHandler};
-use crate::helper::{AsDeref, Invalidate, Zero};
+use crate::helper::{AsDeref, Captor, Capture, Invalidate, Zero};
 use crate::{MutationKind, Mutations};
 
 pub type UnsizeObserver<'ob, S, D = Zero> = GeneralObserver<'ob, UnsizeHandler<<S as AsDeref<D>>::Target>, S, D>;
@@ -19,28 +19,36 @@ pub trait Unsize {
 
 pub struct UnsizeHandler<T: ?Sized> {
     raw_parts: Option<(NonNull<()>, usize)>,
+    captor: Captor,
     phantom: PhantomData<*const T>,
 }
 
 impl<T: ?Sized> Invalidate<T> for UnsizeHandler<T>
 where
-    T: Unsize,
+    T: Unsize + Capture,
 {
     fn invalidate(&mut self, value: &T) {
         self.raw_parts
             .get_or_insert_with(|| (NonNull::from(value).cast::<()>(), value.len()));
+        self.captor.capture(value);
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, _: &T) -> Option<Mutations> {
+        self.captor.inverse()
     }
 }
 
 impl<T: ?Sized> GeneralHandler for UnsizeHandler<T>
 where
-    T: Unsize,
+    T: Unsize + Capture,
 {
     type Target = T;
 
     fn observe(_: &T) -> Self {
         Self {
             raw_parts: None,
+            captor: Captor::default(),
             phantom: PhantomData,
         }
     }
@@ -51,6 +59,16 @@ where
     T: Unsize<Slice: Serialize> + Serialize + 'static,
 {
     unsafe fn flush(&mut self, value: &T) -> Mutations {
+        let mutations = self.diff(value);
+        self.captor.attach(mutations)
+    }
+}
+
+impl<T: ?Sized> UnsizeHandler<T>
+where
+    T: Unsize<Slice: Serialize> + Serialize + 'static,
+{
+    fn diff(&mut self, value: &T) -> Mutations {
         let Some((old_addr, old_len)) = self.raw_parts.take() else {
             return Mutations::new();
         };
@@ -75,7 +93,7 @@ where
     }
 }
 
-impl<T: Unsize + ?Sized> DebugHandler for UnsizeHandler<T> {
+impl<T: Unsize + Capture + ?Sized> DebugHandler for UnsizeHandler<T> {
     const NAME: &'static str = "UnsizeObserver";
 }
 
@@ -0,0 +1,702 @@
+//! [`Capture`] bound and [`Captured`] values for inverse mutations.
+//!
+//! With the `inverse` feature, observers keep the values they displace (the old value of a
+//! replaced field, the removed tail of a truncated vector, the old entry of a removed map key),
+//! so that [`flush`](crate::observe::SerializeObserver::flush) can return the inverse of every
+//! mutation alongside it. The displaced values are captured into owned trees of the serde data
+//! model, so that the observed types do not have to implement [`Clone`].
+
+#[cfg(feature = "inverse")]
+use std::fmt::Display;
+
+#[cfg(feature = "inverse")]
+use serde::ser::{
+    Error, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct,
+    SerializeTupleVariant,
+};
+#[cfg(feature = "inverse")]
+use serde::{Serialize, Serializer};
+
+use crate::Mutations;
+
+/// Values which observers can capture when they are displaced.
+///
+/// With the `inverse` feature, observers capture the values they displace to build inverse
+/// mutations, so observed values must be [`Serialize`](serde::Serialize). Without it, every type
+/// can be captured.
+#[cfg(feature = "inverse")]
+pub trait Capture: Serialize {}
+
+#[cfg(feature = "inverse")]
+impl<T: Serialize + ?Sized> Capture for T {}
+
+/// Values which observers can capture when they are displaced.
+///
+/// With the `inverse` feature, observers capture the values they displace to build inverse
+/// mutations, so observed values must be `Serialize`. Without it, every type can be captured.
+#[cfg(not(feature = "inverse"))]
+pub trait Capture {}
+
+#[cfg(not(feature = "inverse"))]
+impl<T: ?Sized> Capture for T {}
+
+/// An owned copy of a value in the serde data model.
+///
+/// [`Captured::new`] serializes the value into a tree, which serializes back to the same output
+/// through any serializer. If the value fails to serialize, the error is reported when the
+/// captured value is serialized.
+#[cfg(feature = "inverse")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Captured(Result<Content, String>);
+
+#[cfg(feature = "inverse")]
+impl Captured {
+    /// Captures the current state of `value`.
+    pub fn new<T: Serialize + ?Sized>(value: &T) -> Self {
+        Self(value.serialize(ContentSerializer).map_err(|error| error.0))
+    }
+
+    /// Captures the values of `mutations`, so that they can be cloned and outlive the observed
+    /// values they refer to.
+    pub(crate) fn mutations(mutations: Mutations) -> Mutations<Self> {
+        mutations.without_inverse().map(|value| Self::new(&value))
+    }
+
+    /// Turns captured mutations back into [`Mutations`].
+    pub(crate) fn release(mutations: Mutations<Self>) -> Mutations {
+        mutations.map(|value| Box::new(value) as Box<dyn erased_serde::Serialize>)
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl Serialize for Captured {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            Ok(content) => content.serialize(serializer),
+            Err(message) => Err(S::Error::custom(message)),
+        }
+    }
+}
+
+/// The value of an observed value before its first replacement since the last flush.
+///
+/// Without the `inverse` feature, nothing is captured.
+#[derive(Default)]
+pub struct Captor {
+    /// The inverse which restores the captured value, or `Some(None)` if it is unknown.
+    #[cfg(feature = "inverse")]
+    captured: Option<Option<Mutations<Captured>>>,
+}
+
+impl Captor {
+    /// Captures `value` unless a value has been captured since the last flush.
+    pub fn capture<T: Capture + ?Sized>(&mut self, value: &T) {
+        self.capture_with(value, || Some(Mutations::new()));
+    }
+
+    /// Captures `value` unless a value has been captured since the last flush, where `changes`
+    /// returns the inverse of the changes already made to `value`.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    pub fn capture_with<T: Capture + ?Sized>(&mut self, value: &T, changes: impl FnOnce() -> Option<Mutations>) {
+        #[cfg(feature = "inverse")]
+        if self.captured.is_none() {
+            self.captured = Some(changes().map(|changes| {
+                let mut inverse = Mutations::replace_owned(Captured::new(value));
+                inverse.extend(changes);
+                Captured::mutations(inverse)
+            }));
+        }
+    }
+
+    /// Returns the inverse of the changes since the last flush, which restores the captured value.
+    /// Returns an empty inverse if nothing has been captured.
+    #[cfg(feature = "inverse")]
+    pub fn inverse(&self) -> Option<Mutations> {
+        match &self.captured {
+            Some(captured) => captured.clone().map(Captured::release),
+            None => Some(Mutations::new()),
+        }
+    }
+
+    /// Attaches the inverse of `mutations` to them, and resets the captor.
+    ///
+    /// Non-empty `mutations` without a captured value have an unknown inverse.
+    pub fn attach(&mut self, mutations: Mutations) -> Mutations {
+        #[cfg(feature = "inverse")]
+        {
+            let captured = self.captured.take();
+            if !mutations.is_empty() {
+                return mutations.with_inverse(captured.flatten().map(Captured::release));
+            }
+        }
+        mutations
+    }
+}
+
+#[cfg(feature = "inverse")]
+#[derive(Debug, Clone, PartialEq)]
+enum Content {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Content>),
+    Unit,
+    UnitStruct(&'static str),
+    UnitVariant(&'static str, u32, &'static str),
+    NewtypeStruct(&'static str, Box<Content>),
+    NewtypeVariant(&'static str, u32, &'static str, Box<Content>),
+    Seq(Vec<Content>),
+    Tuple(Vec<Content>),
+    TupleStruct(&'static str, Vec<Content>),
+    TupleVariant(&'static str, u32, &'static str, Vec<Content>),
+    Map(Vec<(Content, Content)>),
+    Struct(&'static str, Vec<(&'static str, Content)>),
+    StructVariant(&'static str, u32, &'static str, Vec<(&'static str, Content)>),
+}
+
+#[cfg(feature = "inverse")]
+impl Serialize for Content {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Content::Bool(value) => serializer.serialize_bool(*value),
+            Content::I8(value) => serializer.serialize_i8(*value),
+            Content::I16(value) => serializer.serialize_i16(*value),
+            Content::I32(value) => serializer.serialize_i32(*value),
+            Content::I64(value) => serializer.serialize_i64(*value),
+            Content::I128(value) => serializer.serialize_i128(*value),
+            Content::U8(value) => serializer.serialize_u8(*value),
+            Content::U16(value) => serializer.serialize_u16(*value),
+            Content::U32(value) => serializer.serialize_u32(*value),
+            Content::U64(value) => serializer.serialize_u64(*value),
+            Content::U128(value) => serializer.serialize_u128(*value),
+            Content::F32(value) => serializer.serialize_f32(*value),
+            Content::F64(value) => serializer.serialize_f64(*value),
+            Content::Char(value) => serializer.serialize_char(*value),
+            Content::String(value) => serializer.serialize_str(value),
+            Content::Bytes(value) => serializer.serialize_bytes(value),
+            Content::None => serializer.serialize_none(),
+            Content::Some(value) => serializer.serialize_some(value),
+            Content::Unit => serializer.serialize_unit(),
+            Content::UnitStruct(name) => serializer.serialize_unit_struct(name),
+            Content::UnitVariant(name, index, variant) => serializer.serialize_unit_variant(name, *index, variant),
+            Content::NewtypeStruct(name, value) => serializer.serialize_newtype_struct(name, value),
+            Content::NewtypeVariant(name, index, variant, value) => {
+                serializer.serialize_newtype_variant(name, *index, variant, value)
+            }
+            Content::Seq(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
+            Content::Tuple(elements) => {
+                let mut tuple = serializer.serialize_tuple(elements.len())?;
+                for element in elements {
+                    tuple.serialize_element(element)?;
+                }
+                tuple.end()
+            }
+            Content::TupleStruct(name, fields) => {
+                let mut tuple = serializer.serialize_tuple_struct(name, fields.len())?;
+                for field in fields {
+                    tuple.serialize_field(field)?;
+                }
+                tuple.end()
+            }
+            Content::TupleVariant(name, index, variant, fields) => {
+                let mut tuple = serializer.serialize_tuple_variant(name, *index, variant, fields.len())?;
+                for field in fields {
+                    tuple.serialize_field(field)?;
+                }
+                tuple.end()
+            }
+            Content::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Content::Struct(name, fields) => {
+                let mut state = serializer.serialize_struct(name, fields.len())?;
+                for (key, value) in fields {
+                    state.serialize_field(key, value)?;
+                }
+                state.end()
+            }
+            Content::StructVariant(name, index, variant, fields) => {
+                let mut state = serializer.serialize_struct_variant(name, *index, variant, fields.len())?;
+                for (key, value) in fields {
+                    state.serialize_field(key, value)?;
+                }
+                state.end()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "inverse")]
+#[derive(Debug)]
+struct CaptureError(String);
+
+#[cfg(feature = "inverse")]
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl std::error::Error for CaptureError {}
+
+#[cfg(feature = "inverse")]
+impl Error for CaptureError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self(message.to_string())
+    }
+}
+
+#[cfg(feature = "inverse")]
+struct ContentSerializer;
+
+#[cfg(feature = "inverse")]
+impl Serializer for ContentSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, value: bool) -> Result<Content, CaptureError> {
+        Ok(Content::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Content, CaptureError> {
+        Ok(Content::I8(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Content, CaptureError> {
+        Ok(Content::I16(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Content, CaptureError> {
+        Ok(Content::I32(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Content, CaptureError> {
+        Ok(Content::I64(value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Content, CaptureError> {
+        Ok(Content::I128(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Content, CaptureError> {
+        Ok(Content::U8(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Content, CaptureError> {
+        Ok(Content::U16(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Content, CaptureError> {
+        Ok(Content::U32(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Content, CaptureError> {
+        Ok(Content::U64(value))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Content, CaptureError> {
+        Ok(Content::U128(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Content, CaptureError> {
+        Ok(Content::F32(value))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Content, CaptureError> {
+        Ok(Content::F64(value))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Content, CaptureError> {
+        Ok(Content::Char(value))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Content, CaptureError> {
+        Ok(Content::String(value.to_owned()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Content, CaptureError> {
+        Ok(Content::Bytes(value.to_owned()))
+    }
+
+    fn serialize_none(self) -> Result<Content, CaptureError> {
+        Ok(Content::None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Content, CaptureError> {
+        Ok(Content::Some(Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_unit(self) -> Result<Content, CaptureError> {
+        Ok(Content::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Content, CaptureError> {
+        Ok(Content::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<Content, CaptureError> {
+        Ok(Content::UnitVariant(name, index, variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Content, CaptureError> {
+        Ok(Content::NewtypeStruct(name, Box::new(value.serialize(self)?)))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Content, CaptureError> {
+        Ok(Content::NewtypeVariant(
+            name,
+            index,
+            variant,
+            Box::new(value.serialize(self)?),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, CaptureError> {
+        Ok(SeqSerializer::new(SeqKind::Seq, len.unwrap_or_default()))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, CaptureError> {
+        Ok(SeqSerializer::new(SeqKind::Tuple, len))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<SeqSerializer, CaptureError> {
+        Ok(SeqSerializer::new(SeqKind::TupleStruct(name), len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, CaptureError> {
+        Ok(SeqSerializer::new(SeqKind::TupleVariant(name, index, variant), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, CaptureError> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<StructSerializer, CaptureError> {
+        Ok(StructSerializer {
+            variant: None,
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, CaptureError> {
+        Ok(StructSerializer {
+            variant: Some((index, variant)),
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+#[cfg(feature = "inverse")]
+enum SeqKind {
+    Seq,
+    Tuple,
+    TupleStruct(&'static str),
+    TupleVariant(&'static str, u32, &'static str),
+}
+
+#[cfg(feature = "inverse")]
+struct SeqSerializer {
+    kind: SeqKind,
+    elements: Vec<Content>,
+}
+
+#[cfg(feature = "inverse")]
+impl SeqSerializer {
+    fn new(kind: SeqKind, len: usize) -> Self {
+        Self {
+            kind,
+            elements: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        self.elements.push(value.serialize(ContentSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, CaptureError> {
+        Ok(match self.kind {
+            SeqKind::Seq => Content::Seq(self.elements),
+            SeqKind::Tuple => Content::Tuple(self.elements),
+            SeqKind::TupleStruct(name) => Content::TupleStruct(name, self.elements),
+            SeqKind::TupleVariant(name, index, variant) => Content::TupleVariant(name, index, variant, self.elements),
+        })
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeSeq for SeqSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeTuple for SeqSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeTupleStruct for SeqSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeTupleVariant for SeqSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+#[cfg(feature = "inverse")]
+struct MapSerializer {
+    entries: Vec<(Content, Content)>,
+    key: Option<Content>,
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeMap for MapSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), CaptureError> {
+        self.key = Some(key.serialize(ContentSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), CaptureError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| CaptureError::custom("serialize_value called before serialize_key"))?;
+        self.entries.push((key, value.serialize(ContentSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        Ok(Content::Map(self.entries))
+    }
+}
+
+#[cfg(feature = "inverse")]
+struct StructSerializer {
+    variant: Option<(u32, &'static str)>,
+    name: &'static str,
+    fields: Vec<(&'static str, Content)>,
+}
+
+#[cfg(feature = "inverse")]
+impl StructSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CaptureError> {
+        self.fields.push((key, value.serialize(ContentSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Content, CaptureError> {
+        Ok(match self.variant {
+            None => Content::Struct(self.name, self.fields),
+            Some((index, variant)) => Content::StructVariant(self.name, index, variant, self.fields),
+        })
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeStruct for StructSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CaptureError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl SerializeStructVariant for StructSerializer {
+    type Ok = Content;
+    type Error = CaptureError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), CaptureError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Content, CaptureError> {
+        self.finish()
+    }
+}
+
+/// Asserts that the forward mutation of `flushed` turns `before` into `after`, and that its inverse
+/// turns `after` back into `before`.
+#[cfg(all(test, feature = "inverse"))]
+#[track_caller]
+pub(crate) fn assert_inverse(
+    before: serde_json::Value,
+    after: serde_json::Value,
+    flushed: (crate::adapter::Json, Option<crate::adapter::Json>),
+) {
+    use crate::Adapter;
+    use crate::adapter::Json;
+
+    let (Json(forward), Some(Json(inverse))) = flushed else {
+        panic!("inverse is unknown");
+    };
+    let mut replica = before.clone();
+    if let Some(forward) = forward {
+        Json::mutate(&mut replica, forward, &mut Default::default()).unwrap();
+    }
+    assert_eq!(replica, after, "forward");
+    if let Some(inverse) = inverse {
+        Json::mutate(&mut replica, inverse, &mut Default::default()).unwrap();
+    }
+    assert_eq!(replica, before, "inverse");
+}
+
+#[cfg(all(test, feature = "inverse"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Unit,
+        Newtype(u8),
+        Tuple(i32, String),
+        Struct { x: f64, tag: Option<char> },
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = (
+            vec![Shape::Unit, Shape::Newtype(1), Shape::Tuple(-2, "b".into())],
+            Shape::Struct { x: 0.5, tag: Some('c') },
+            BTreeMap::from([("k", ())]),
+        );
+        let captured = Captured::new(&value);
+        assert_eq!(
+            serde_json::to_value(&captured).unwrap(),
+            serde_json::to_value(&value).unwrap(),
+        );
+        assert_eq!(
+            serde_json::to_value(&captured).unwrap(),
+            json!([["Unit", {"Newtype": 1}, {"Tuple": [-2, "b"]}], {"Struct": {"x": 0.5, "tag": "c"}}, {"k": null}]),
+        );
+    }
+
+    #[test]
+    fn error() {
+        struct Failing;
+
+        impl Serialize for Failing {
+            fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+                Err(S::Error::custom("failing"))
+            }
+        }
+
+        let captured = Captured::new(&Failing);
+        let error = serde_json::to_value(&captured).unwrap_err();
+        assert_eq!(error.to_string(), "failing");
+    }
+}
@@ -26,7 +26,7 @@ macro_rules! spec_impl_observe {
 
         impl<T> $helper<$crate::observe::DefaultSpec> for T
         where
-            T: $crate::observe::Observe<Spec = $crate::observe::DefaultSpec>,
+            T: $crate::observe::Observe<Spec = $crate::observe::DefaultSpec> + $crate::helper::Capture,
         {
             type Observer<'ob, S, D $(, const $arg: $arg_ty)*>
                 = $default<T::Observer<'ob, T, Zero>, S, D>
@@ -78,7 +78,7 @@ macro_rules! spec_impl_observe_from_ref {
 
         impl<T> $helper<$crate::observe::DefaultSpec> for T
         where
-            T: $crate::observe::RefObserve<Spec = $crate::observe::DefaultSpec>,
+            T: $crate::observe::RefObserve<Spec = $crate::observe::DefaultSpec> + $crate::helper::Capture,
         {
             type Observer<'ob, S, D $(, const $arg: $arg_ty)*>
                 = $default<T::Observer<'ob, T, Zero>, S, D>
@@ -130,7 +130,7 @@ macro_rules! spec_impl_ref_observe {
 
         impl<T> $helper<$crate::observe::DefaultSpec> for T
         where
-            T: $crate::observe::RefObserve<Spec = $crate::observe::DefaultSpec>,
+            T: $crate::observe::RefObserve<Spec = $crate::observe::DefaultSpec> + $crate::helper::Capture,
         {
             type Observer<'ob, S, D $(, const $arg: $arg_ty)*>
                 = $default<$($arg,)* T::Observer<'ob, T, Zero>, S, D>
@@ -157,7 +157,11 @@ macro_rules! spec_impl_ref_observe {
 macro_rules! default_impl_ref_observe {
     ($(impl $([$($gen:tt)*])? RefObserve for $ty:ty $(where { $($where:tt)+ })?;)*) => {
         $(
-            impl <$($($gen)*)?> $crate::observe::RefObserve for $ty {
+            impl <$($($gen)*)?> $crate::observe::RefObserve for $ty
+            where
+                Self: $crate::helper::Capture,
+                $($($where)+)?
+            {
                 type Observer<'ob, S, D>
                     = $crate::general::PointerObserver<'ob, S, D>
                 where
@@ -10,14 +10,23 @@
 //! - [`QuasiObserver`] - Enables consistent operations between observers and normal references via
 //!   autoref-based specialization
 //! - [`Pointer`] - Internal pointer type for observer dereference chains
+//! - [`Capture`] - Values which observers capture to build inverse mutations
+//! - [`Captor`] - Storage for the value displaced by the first replacement since the last flush
 
+mod capture;
 pub mod deref;
 pub(crate) mod macros;
 mod pointer;
 pub mod quasi;
+pub(crate) mod reorder;
 pub mod shallow;
 pub mod unsigned;
 
+#[cfg(feature = "inverse")]
+pub use capture::Captured;
+#[cfg(all(test, feature = "inverse"))]
+pub(crate) use capture::assert_inverse;
+pub use capture::{Captor, Capture};
 pub use deref::{AsDeref, AsDerefCoinductive, AsDerefMut, AsDerefMutCoinductive};
 pub use pointer::Pointer;
 pub use quasi::{Invalidate, QuasiObserver};
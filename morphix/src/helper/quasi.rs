@@ -9,15 +9,16 @@
 //! value.
 //!
 //! [`Invalidate`] is the companion trait for types that carry internal tracking state (diff
-//! trackers, inner observer containers). It provides a single
-//! [`invalidate`](Invalidate::invalidate) entry point used by the fallback invalidation
-//! mechanism in [`Pointer`].
+//! trackers, inner observer containers). It provides the [`invalidate`](Invalidate::invalidate)
+//! entry point used by the fallback invalidation mechanism in [`Pointer`].
 //!
 //! See the [Observer Mechanism](https://github.com/shigma/morphix#observer-mechanism) section in
 //! the README for a detailed overview.
 
 use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "inverse")]
+use crate::Mutations;
 use crate::helper::{AsDeref, AsDerefMut, AsDerefMutCoinductive, Pointer, Unsigned, Zero};
 
 /// Enables [`tracked_mut`](QuasiObserver::tracked_mut) and
@@ -125,6 +126,22 @@ pub trait QuasiObserver: AsDerefMutCoinductive<Self::OuterDepth, Target: Deref<T
     /// invalidates child observers.
     fn invalidate(this: &mut Self);
 
+    /// Returns the inverse of the mutations recorded since the last flush, without resetting them.
+    ///
+    /// Composite observers call this before the observed value is removed or replaced (e.g., by
+    /// [`Vec::truncate`] or [`HashMap::remove`](std::collections::HashMap::remove)), so that the
+    /// inverse of their own mutations can restore the value as of the last flush: the value is
+    /// captured as it is now, and the returned mutations, applied to it, revert the changes
+    /// recorded by this observer.
+    ///
+    /// `head` refers to the observed value, as the internal pointer may be stale at this point.
+    /// Returns [`None`] if the inverse is unknown, which is also the default implementation.
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        let _ = (this, head);
+        None
+    }
+
     /// Returns an immutable reference to the observed value.
     ///
     /// The [`observe!`](crate::observe!) macro calls this method on both sides of comparison
@@ -246,4 +263,14 @@ pub trait Invalidate<T: ?Sized> {
     /// The post-invalidation state is **not** the "initial" state (which would be the clean state
     /// right after `observe`), but rather a state that signals "all granular tracking is lost."
     fn invalidate(&mut self, value: &T);
+
+    /// Returns the inverse of the changes recorded since the last flush, given the current
+    /// `value`, or [`None`] if it is unknown.
+    ///
+    /// See [`QuasiObserver::inverse`]. The default implementation returns [`None`].
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &T) -> Option<Mutations> {
+        let _ = value;
+        None
+    }
 }
@@ -0,0 +1,140 @@
+//! Helpers for describing rearrangements of sequences as [`Move`](crate::MutationKind::Move)
+//! mutations.
+//!
+//! A rearrangement is described by a permutation `perm`, where `perm[i]` is the previous index of
+//! the element now at index `i`.
+
+/// Rearranges `slice` in place so that the element previously at `perm[i]` ends up at index `i`.
+///
+/// Only the first `perm.len()` elements of `slice` are affected.
+pub(crate) fn permute<T>(slice: &mut [T], perm: &[usize]) {
+    let mut perm = perm.to_vec();
+    for i in 0..perm.len() {
+        let mut j = i;
+        loop {
+            let k = perm[j];
+            perm[j] = j;
+            if k == i {
+                break;
+            }
+            slice.swap(j, k);
+            j = k;
+        }
+    }
+}
+
+/// Returns the indices of a longest increasing subsequence of `perm`.
+fn longest_increasing_subsequence(perm: &[usize]) -> Vec<usize> {
+    // `tails[k]` is the index of the smallest tail of all increasing subsequences of length `k + 1`.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![usize::MAX; perm.len()];
+    for (i, &value) in perm.iter().enumerate() {
+        let k = tails.partition_point(|&j| perm[j] < value);
+        if k > 0 {
+            prev[i] = tails[k - 1];
+        }
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut result = vec![0; tails.len()];
+    let mut i = tails.last().copied().unwrap_or(usize::MAX);
+    for slot in result.iter_mut().rev() {
+        *slot = i;
+        i = prev[i];
+    }
+    result
+}
+
+/// Computes a sequence of `(from, to)` moves which applies the rearrangement described by `perm`.
+///
+/// Each move removes the element at `from` and inserts it at `to`. Elements of a longest increasing
+/// subsequence of `perm` are left in place, so the number of moves is minimal.
+///
+/// Returns [`None`] if `limit` or more moves would be needed. Computing the moves takes time
+/// proportional to the length times the number of moves, so callers fall back to replacing the
+/// whole sequence instead.
+pub(crate) fn permutation_moves(perm: &[usize], limit: usize) -> Option<Vec<(usize, usize)>> {
+    let lis = longest_increasing_subsequence(perm);
+    if perm.len() - lis.len() >= limit {
+        return None;
+    }
+    let mut stay = vec![false; perm.len()];
+    for i in lis {
+        stay[i] = true;
+    }
+    // `order[j]` is the previous index of the element currently at index `j`.
+    let mut order = (0..perm.len()).collect::<Vec<_>>();
+    let mut moves = vec![];
+    for (i, &value) in perm.iter().enumerate() {
+        if stay[i] {
+            continue;
+        }
+        // Elements placed so far keep their final relative order, so the element belongs right
+        // after its final predecessor.
+        let from = order.iter().position(|&v| v == value).unwrap();
+        order.remove(from);
+        let to = match i {
+            0 => 0,
+            _ => order.iter().position(|&v| v == perm[i - 1]).unwrap() + 1,
+        };
+        order.insert(to, value);
+        if from != to {
+            moves.push((from, to));
+        }
+    }
+    Some(moves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_moves(len: usize, moves: &[(usize, usize)]) -> Vec<usize> {
+        let mut vec = (0..len).collect::<Vec<_>>();
+        for &(from, to) in moves {
+            let value = vec.remove(from);
+            vec.insert(to, value);
+        }
+        vec
+    }
+
+    #[test]
+    fn permute_in_place() {
+        let mut slice = ['a', 'b', 'c', 'd', 'e'];
+        permute(&mut slice, &[3, 0, 4, 1, 2]);
+        assert_eq!(slice, ['d', 'a', 'e', 'b', 'c']);
+
+        let mut slice = ['a', 'b', 'c', 'd'];
+        permute(&mut slice, &[1, 0]);
+        assert_eq!(slice, ['b', 'a', 'c', 'd']);
+    }
+
+    #[test]
+    fn minimal_moves() {
+        assert_eq!(permutation_moves(&[0, 1, 2], 3), Some(vec![]));
+        assert_eq!(permutation_moves(&[1, 0], 2), Some(vec![(1, 0)]));
+        assert_eq!(permutation_moves(&[3, 1, 2, 0], 4), Some(vec![(3, 0), (1, 3)]));
+        assert_eq!(permutation_moves(&[1, 2, 3, 4, 0], 5), Some(vec![(0, 4)]));
+        assert_eq!(permutation_moves(&[4, 0, 1, 2, 3], 5), Some(vec![(4, 0)]));
+    }
+
+    #[test]
+    fn moves_reproduce_permutation() {
+        let perms: &[&[usize]] = &[&[2, 0, 1], &[4, 3, 2, 1, 0], &[1, 3, 0, 4, 2], &[5, 0, 4, 1, 3, 2]];
+        for perm in perms {
+            let moves = permutation_moves(perm, perm.len()).unwrap();
+            assert_eq!(apply_moves(perm.len(), &moves), *perm);
+        }
+    }
+
+    #[test]
+    fn moves_over_limit() {
+        assert_eq!(permutation_moves(&[0, 1, 2], 0), None);
+        assert_eq!(permutation_moves(&[3, 1, 2, 0], 2), None);
+        assert_eq!(permutation_moves(&[4, 3, 2, 1, 0], 4), None);
+        assert_eq!(permutation_moves(&[4, 3, 2, 1, 0], 5).map(|moves| moves.len()), Some(4));
+    }
+}
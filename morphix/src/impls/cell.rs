@@ -0,0 +1,560 @@
+use std::cell::{Cell, RefCell, RefMut};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{LockResult, Mutex, MutexGuard, PoisonError, RwLock, RwLockWriteGuard};
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::Mutations;
+use crate::general::{Snapshot, SnapshotObserver};
+use crate::helper::{AsDeref, AsDerefMut, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::observe::{DefaultSpec, Observe, Observer, RefObserve, RefObserver, SerializeObserver};
+
+/// Types that guard a value behind a runtime borrow or lock.
+trait Lock: Sized {
+    type Value;
+
+    type ReadGuard<'a>: Deref<Target = Self::Value>
+    where
+        Self: 'a;
+
+    type WriteGuard<'a>: DerefMut<Target = Self::Value>
+    where
+        Self: 'a;
+
+    /// Acquires shared access for serialization, failing with the same message as serde.
+    fn read(&self) -> Result<Self::ReadGuard<'_>, &'static str>;
+
+    /// Acquires exclusive access, ignoring poisoning.
+    fn write(&self) -> Self::WriteGuard<'_>;
+}
+
+impl<T> Lock for RefCell<T> {
+    type Value = T;
+
+    type ReadGuard<'a>
+        = std::cell::Ref<'a, T>
+    where
+        Self: 'a;
+
+    type WriteGuard<'a>
+        = RefMut<'a, T>
+    where
+        Self: 'a;
+
+    fn read(&self) -> Result<Self::ReadGuard<'_>, &'static str> {
+        self.try_borrow().map_err(|_| "already mutably borrowed")
+    }
+
+    fn write(&self) -> Self::WriteGuard<'_> {
+        self.borrow_mut()
+    }
+}
+
+impl<T> Lock for Mutex<T> {
+    type Value = T;
+
+    type ReadGuard<'a>
+        = MutexGuard<'a, T>
+    where
+        Self: 'a;
+
+    type WriteGuard<'a>
+        = MutexGuard<'a, T>
+    where
+        Self: 'a;
+
+    fn read(&self) -> Result<Self::ReadGuard<'_>, &'static str> {
+        self.lock().map_err(|_| "lock poison error while serializing")
+    }
+
+    fn write(&self) -> Self::WriteGuard<'_> {
+        self.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Lock for RwLock<T> {
+    type Value = T;
+
+    type ReadGuard<'a>
+        = std::sync::RwLockReadGuard<'a, T>
+    where
+        Self: 'a;
+
+    type WriteGuard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        Self: 'a;
+
+    fn read(&self) -> Result<Self::ReadGuard<'_>, &'static str> {
+        self.read().map_err(|_| "lock poison error while serializing")
+    }
+
+    fn write(&self) -> Self::WriteGuard<'_> {
+        self.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A mutation value that holds shared access to its lock while being serialized.
+struct Locked<L> {
+    lock: *const L,
+    value: Box<dyn erased_serde::Serialize>,
+}
+
+impl<L: Lock> Serialize for Locked<L> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let _guard = unsafe { &*self.lock }.read().map_err(S::Error::custom)?;
+        self.value.serialize(serializer)
+    }
+}
+
+/// Observer implementation for [`RefCell<T>`], [`Mutex<T>`] and [`RwLock<T>`].
+///
+/// The observed value is reached through tracked guards: [`borrow_mut`](Self::borrow_mut) for
+/// [`RefCell<T>`], [`lock`](Self::lock) for [`Mutex<T>`] and [`write`](Self::write) for
+/// [`RwLock<T>`]. Each guard dereferences to the default observer of `T`, which keeps its state
+/// across guards, so the next flush emits granular mutations instead of a whole-value replace.
+///
+/// Read-only access does not need a guard; methods like [`RefCell::borrow`] and [`Mutex::lock`]
+/// are reachable through [`Deref`] as usual, and changes made through them are not tracked.
+///
+/// ## Flushing
+///
+/// Flushing acquires exclusive access to the value, so it panics if a [`RefCell<T>`] is currently
+/// borrowed, and blocks while a [`Mutex<T>`] or [`RwLock<T>`] is held elsewhere. The returned
+/// mutations acquire shared access again while their values are serialized.
+///
+/// ## Limitations
+///
+/// Only changes made through this observer are tracked. When the value is shared, e.g. as
+/// `Arc<Mutex<T>>`, changes made through other clones of the [`Arc`](std::sync::Arc) are not seen,
+/// and the inner observer may report mutations that no longer match the value. Mark such fields
+/// with `#[morphix(snapshot)]` to detect all changes by comparing snapshots instead.
+pub struct LockObserver<O, S: ?Sized, D = Zero> {
+    ptr: Pointer<S>,
+    mutated: bool,
+    inner: Option<O>,
+    phantom: PhantomData<D>,
+}
+
+/// Observer implementation for [`RefCell<T>`], see [`LockObserver`].
+pub type RefCellObserver<O, S, D = Zero> = LockObserver<O, S, D>;
+
+/// Observer implementation for [`Mutex<T>`], see [`LockObserver`].
+pub type MutexObserver<O, S, D = Zero> = LockObserver<O, S, D>;
+
+/// Observer implementation for [`RwLock<T>`], see [`LockObserver`].
+pub type RwLockObserver<O, S, D = Zero> = LockObserver<O, S, D>;
+
+/// A guard returned by the methods of [`LockObserver`].
+///
+/// It keeps the underlying borrow or lock alive and dereferences to the observer of the guarded
+/// value.
+pub struct LockObserverGuard<'a, G, O> {
+    inner: &'a mut O,
+    guard: G,
+}
+
+impl<'a, G, O> LockObserverGuard<'a, G, O>
+where
+    G: DerefMut<Target = O::Head>,
+    O: Observer<InnerDepth = Zero, Head: Sized>,
+{
+    fn new(inner: &'a mut Option<O>, mut guard: G) -> Self {
+        let inner = match inner {
+            Some(ob) => {
+                unsafe { O::relocate(ob, &mut guard) };
+                ob
+            }
+            None => inner.insert(O::observe(&mut guard)),
+        };
+        Self { inner, guard }
+    }
+
+    fn from_result(inner: &'a mut Option<O>, result: LockResult<G>) -> LockResult<Self> {
+        match result {
+            Ok(guard) => Ok(Self::new(inner, guard)),
+            Err(error) => Err(PoisonError::new(Self::new(inner, error.into_inner()))),
+        }
+    }
+}
+
+impl<G, O> Deref for LockObserverGuard<'_, G, O> {
+    type Target = O;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner
+    }
+}
+
+impl<G, O> DerefMut for LockObserverGuard<'_, G, O> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner
+    }
+}
+
+impl<G, O> Debug for LockObserverGuard<'_, G, O>
+where
+    G: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LockObserverGuard").field(&self.guard).finish()
+    }
+}
+
+impl<O, S: ?Sized, D> Deref for LockObserver<O, S, D> {
+    type Target = Pointer<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+impl<O, S: ?Sized, D> DerefMut for LockObserver<O, S, D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mutated = true;
+        self.inner = None;
+        &mut self.ptr
+    }
+}
+
+impl<O, S: ?Sized, D> QuasiObserver for LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D>,
+{
+    type Head = S;
+    type OuterDepth = Succ<Zero>;
+    type InnerDepth = D;
+
+    fn invalidate(this: &mut Self) {
+        this.mutated = true;
+        this.inner = None;
+    }
+}
+
+impl<O, S: ?Sized, D> Observer for LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target: Lock<Value = O::Head>>,
+    O: Observer<InnerDepth = Zero, Head: Sized>,
+{
+    fn observe(head: &mut Self::Head) -> Self {
+        Self {
+            ptr: Pointer::new(head),
+            mutated: false,
+            inner: None,
+            phantom: PhantomData,
+        }
+    }
+
+    unsafe fn relocate(this: &mut Self, head: &mut Self::Head) {
+        Pointer::set(&this.ptr, head);
+    }
+}
+
+impl<O, S: ?Sized, D> RefObserver for LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target: Lock<Value = O::Head>>,
+    O: Observer<InnerDepth = Zero, Head: Sized>,
+{
+    fn observe(head: &Self::Head) -> Self {
+        Self {
+            ptr: Pointer::new(head),
+            mutated: false,
+            inner: None,
+            phantom: PhantomData,
+        }
+    }
+
+    unsafe fn relocate(this: &mut Self, head: &Self::Head) {
+        Pointer::set(&this.ptr, head);
+    }
+}
+
+impl<O, S: ?Sized, D> SerializeObserver for LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target: Lock<Value = O::Head> + Serialize + 'static>,
+    O: Observer<InnerDepth = Zero, Head: Sized> + SerializeObserver,
+{
+    unsafe fn flush(this: &mut Self) -> Mutations {
+        let lock = (*this.ptr).as_deref();
+        if this.mutated {
+            this.mutated = false;
+            return Mutations::replace(lock);
+        }
+        let Some(ob) = &mut this.inner else {
+            return Mutations::new();
+        };
+        let mut guard = lock.write();
+        unsafe { O::relocate(ob, &mut guard) };
+        let mutations = unsafe { SerializeObserver::flush(ob) };
+        drop(guard);
+        mutations.map(|value| Box::new(Locked { lock, value }) as Box<dyn erased_serde::Serialize>)
+    }
+}
+
+impl<O, S: ?Sized, D, T> LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = RefCell<T>>,
+    O: Observer<InnerDepth = Zero, Head = T>,
+{
+    /// Mutably borrows the wrapped value, returning a guard to its observer.
+    ///
+    /// See [`RefCell::borrow_mut`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the value is currently borrowed.
+    pub fn borrow_mut(&mut self) -> LockObserverGuard<'_, RefMut<'_, T>, O> {
+        LockObserverGuard::new(&mut self.inner, (*self.ptr).as_deref().borrow_mut())
+    }
+}
+
+impl<O, S: ?Sized, D, T> LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Mutex<T>>,
+    O: Observer<InnerDepth = Zero, Head = T>,
+{
+    /// Acquires the mutex, returning a guard to the observer of the protected value.
+    ///
+    /// See [`Mutex::lock`].
+    pub fn lock(&mut self) -> LockResult<LockObserverGuard<'_, MutexGuard<'_, T>, O>> {
+        LockObserverGuard::from_result(&mut self.inner, (*self.ptr).as_deref().lock())
+    }
+}
+
+impl<O, S: ?Sized, D, T> LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = RwLock<T>>,
+    O: Observer<InnerDepth = Zero, Head = T>,
+{
+    /// Locks the value with exclusive write access, returning a guard to its observer.
+    ///
+    /// See [`RwLock::write`].
+    pub fn write(&mut self) -> LockResult<LockObserverGuard<'_, RwLockWriteGuard<'_, T>, O>> {
+        LockObserverGuard::from_result(&mut self.inner, (*self.ptr).as_deref().write())
+    }
+}
+
+impl<O, S: ?Sized, D> Debug for LockObserver<O, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D>,
+    S::Target: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LockObserver").field(&self.untracked_ref()).finish()
+    }
+}
+
+macro_rules! impl_lock {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl<T: Observe> Observe for $ty<T> {
+                type Observer<'ob, S, D>
+                    = LockObserver<T::Observer<'ob, T, Zero>, S, D>
+                where
+                    Self: 'ob,
+                    D: Unsigned,
+                    S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+                type Spec = DefaultSpec;
+            }
+
+            impl<T: Observe> RefObserve for $ty<T> {
+                type Observer<'ob, S, D>
+                    = LockObserver<T::Observer<'ob, T, Zero>, S, D>
+                where
+                    Self: 'ob,
+                    D: Unsigned,
+                    S: AsDeref<D, Target = Self> + ?Sized + 'ob;
+
+                type Spec = DefaultSpec;
+            }
+        )*
+    };
+}
+
+impl_lock!(RefCell, Mutex, RwLock);
+
+impl<T: Snapshot> Snapshot for RefCell<T> {
+    type Snapshot = T::Snapshot;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.borrow().to_snapshot()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        self.borrow().eq_snapshot(snapshot)
+    }
+}
+
+impl<T: Snapshot> Snapshot for Mutex<T> {
+    type Snapshot = T::Snapshot;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        Lock::write(self).to_snapshot()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        Lock::write(self).eq_snapshot(snapshot)
+    }
+}
+
+impl<T: Snapshot> Snapshot for RwLock<T> {
+    type Snapshot = T::Snapshot;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.read().unwrap_or_else(PoisonError::into_inner).to_snapshot()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        self.read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .eq_snapshot(snapshot)
+    }
+}
+
+impl<T: Snapshot + Copy> Snapshot for Cell<T> {
+    type Snapshot = T::Snapshot;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.get().to_snapshot()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        self.get().eq_snapshot(snapshot)
+    }
+}
+
+impl<T: Snapshot + Copy> Observe for Cell<T> {
+    type Observer<'ob, S, D>
+        = SnapshotObserver<'ob, S, D>
+    where
+        Self: 'ob,
+        D: Unsigned,
+        S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+    type Spec = DefaultSpec;
+}
+
+impl<T: Snapshot + Copy> RefObserve for Cell<T> {
+    type Observer<'ob, S, D>
+        = SnapshotObserver<'ob, S, D>
+    where
+        Self: 'ob,
+        D: Unsigned,
+        S: AsDeref<D, Target = Self> + ?Sized + 'ob;
+
+    type Spec = DefaultSpec;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex, RwLock};
+
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use crate::adapter::Json;
+    use crate::helper::QuasiObserver;
+    use crate::observe::{ObserveExt, SerializeObserverExt};
+
+    #[test]
+    fn ref_cell() {
+        let mut value: RefCell<Vec<i32>> = RefCell::new(vec![1]);
+        let mut ob = value.__observe();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+
+        ob.borrow_mut().push(2);
+        ob.borrow_mut().push(3);
+        assert_eq!(*ob.borrow(), vec![1, 2, 3]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!([2, 3]))));
+
+        *ob.borrow_mut()[0].tracked_mut() = 4;
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(-3, json!(4))));
+
+        *ob.tracked_mut() = RefCell::new(vec![5]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([5]))));
+    }
+
+    #[test]
+    fn shared_ref_cell() {
+        let mut value = Rc::new(RefCell::new(String::from("foo")));
+        let other = value.clone();
+        let mut ob = value.__observe();
+        ob.borrow_mut().push_str("bar");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!("bar"))));
+        assert_eq!(*other.borrow(), "foobar");
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn ref_cell_borrowed() {
+        let mut value: Rc<RefCell<Vec<i32>>> = Rc::new(RefCell::new(vec![1]));
+        let other = value.clone();
+        let mut ob = value.__observe();
+        ob.borrow_mut().push(2);
+        let _guard = other.borrow();
+        let _ = ob.flush::<Json>();
+    }
+
+    #[test]
+    fn mutex() {
+        let mut value: Arc<Mutex<Vec<i32>>> = Arc::new(Mutex::new(vec![1]));
+        let other = value.clone();
+        let mut ob = value.__observe();
+        std::thread::spawn(move || other.lock().unwrap().push(2))
+            .join()
+            .unwrap();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+
+        ob.lock().unwrap().push(3);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!([3]))));
+    }
+
+    #[test]
+    fn rw_lock() {
+        let mut value: RwLock<(i32, String, bool)> = RwLock::new((1, String::from("a"), true));
+        let mut ob = value.__observe();
+        *ob.write().unwrap().0.tracked_mut() += 1;
+        ob.write().unwrap().1.push('b');
+        assert_eq!(ob.read().unwrap().0, 2);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, replace!(0, json!(2)), append!(1, json!("b")))));
+    }
+
+    #[test]
+    fn cell() {
+        let mut value: Cell<i32> = Cell::new(1);
+        let mut ob = value.__observe();
+        ob.set(2);
+        ob.set(1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+
+        ob.set(3);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!(3))));
+    }
+}
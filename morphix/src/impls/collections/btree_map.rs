@@ -11,11 +11,16 @@ use std::ops::{Deref, DerefMut, Index, IndexMut, RangeBounds};
 
 use serde::Serialize;
 
+use super::flat_replace;
+#[cfg(feature = "inverse")]
+use super::insert_restore;
 use crate::general::Snapshot;
+#[cfg(feature = "inverse")]
+use crate::helper::Captor;
 use crate::helper::macros::default_impl_ref_observe;
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
-use crate::{MutationKind, Mutations, Observe, PathSegment};
+use crate::{MutationKind, Mutations, Observe, PathError, PathSegment};
 
 enum ValueState {
     /// Key existed in the original map and was overwritten via
@@ -31,6 +36,10 @@ enum ValueState {
 struct BTreeMapObserverState<K, O> {
     mutated: bool,
     diff: BTreeMap<K, ValueState>,
+    /// Values of the original map displaced since the last flush, together with the inverses of
+    /// their observers.
+    #[cfg(feature = "inverse")]
+    captured: BTreeMap<K, Captor>,
     /// Boxed to ensure pointer stability: [`BTreeMap`] node splits move entries between nodes
     /// via `memcpy`, which would invalidate references to inline values. [`Box`] adds a layer
     /// of indirection so that only the pointer is moved, not the observer itself.
@@ -42,6 +51,8 @@ impl<K, O> Default for BTreeMapObserverState<K, O> {
         Self {
             mutated: false,
             diff: Default::default(),
+            #[cfg(feature = "inverse")]
+            captured: Default::default(),
             inner: Default::default(),
         }
     }
@@ -49,10 +60,63 @@ impl<K, O> Default for BTreeMapObserverState<K, O> {
 
 impl<K, O> BTreeMapObserverState<K, O>
 where
-    K: Ord,
+    K: Clone + Ord + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
 {
-    fn mark_deleted(&mut self, key: K) {
-        self.inner.get_mut().remove(&key);
+    /// Captures the original `value` of `key` with the inverse of its observer `ob`, unless it has
+    /// been captured since the last flush.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture(&mut self, key: &K, value: &O::Head, ob: Option<&O>) {
+        #[cfg(feature = "inverse")]
+        self.captured
+            .entry(key.clone())
+            .or_default()
+            .capture_with(value, || match ob {
+                Some(ob) => O::inverse(ob, value),
+                None => Some(Mutations::new()),
+            });
+    }
+
+    /// Returns the mutations which restore the keys of the original map from the current `map`,
+    /// leaving the inverses of the value observers aside.
+    #[cfg(feature = "inverse")]
+    fn inverse_structure(&self, map: &BTreeMap<K, O::Head>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if self.mutated {
+            for key in map.keys() {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, MutationKind::Delete);
+                }
+            }
+        }
+        for (key, value_state) in &self.diff {
+            let segment = PathSegment::from_key(key).ok()?;
+            match value_state {
+                ValueState::Inserted => mutations.insert(segment, MutationKind::Delete),
+                ValueState::Replaced | ValueState::Deleted => {
+                    insert_restore(&mut mutations, segment, self.captured.get(key)?.inverse()?);
+                }
+            }
+        }
+        Some(mutations)
+    }
+
+    /// Attaches the inverse of the changes to the keys since the last flush to `mutations`, given
+    /// the current `map`, and resets the captured values.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn attach(&mut self, map: &BTreeMap<K, O::Head>, mutations: Mutations) -> Mutations {
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(self.inverse_structure(map));
+        #[cfg(feature = "inverse")]
+        self.captured.clear();
+        mutations
+    }
+
+    fn mark_deleted(&mut self, key: K, value: &O::Head) {
+        let ob = self.inner.get_mut().remove(&key);
+        if !self.diff.contains_key(&key) {
+            self.capture(&key, value, ob.as_deref());
+        }
         match self.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Inserted) {
@@ -70,18 +134,32 @@ where
 
 impl<K, O> Invalidate<BTreeMap<K, O::Head>> for BTreeMapObserverState<K, O>
 where
-    K: Clone + Ord,
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    K: Clone + Ord + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
 {
     fn invalidate(&mut self, map: &BTreeMap<K, O::Head>) {
         if !self.mutated {
             self.mutated = true;
-            for key in map.keys() {
-                self.mark_deleted(key.clone());
+            for (key, value) in map {
+                self.mark_deleted(key.clone(), value);
             }
         }
         self.inner.get_mut().clear();
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, map: &BTreeMap<K, O::Head>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if !self.mutated {
+            for (key, ob) in unsafe { &*self.inner.get() } {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, O::inverse(ob, map.get(key)?)?);
+                }
+            }
+        }
+        mutations.extend(self.inverse_structure(map)?);
+        Some(mutations)
+    }
 }
 
 /// Iterator produced by [`BTreeMapObserver::extract_if`].
@@ -98,7 +176,9 @@ where
 #[rustversion::since(1.91)]
 impl<K, V, O, R, F> Iterator for ExtractIf<'_, K, V, O, R, F>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
     R: RangeBounds<K>,
     F: FnMut(&K, &mut V) -> bool,
 {
@@ -107,7 +187,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let (key, value) = self.inner.next()?;
         if let Some(state) = &mut self.state {
-            state.mark_deleted(key.clone());
+            state.mark_deleted(key.clone(), &value);
         }
         Some((key, value))
     }
@@ -120,7 +200,9 @@ where
 #[rustversion::since(1.91)]
 impl<K, V, O, R, F> FusedIterator for ExtractIf<'_, K, V, O, R, F>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
     R: RangeBounds<K>,
     F: FnMut(&K, &mut V) -> bool,
 {
@@ -147,6 +229,8 @@ where
 /// [`get_mut`](Self::get_mut)) require `K: Clone` because the observer maintains its own
 /// [`BTreeMap`] of cloned keys to track per-key observers independently of the observed map's
 /// internal storage.
+///
+/// Keys are converted into path segments as described in [Map keys](super#map-keys).
 pub struct BTreeMapObserver<K, O, S: ?Sized, D = Zero> {
     ptr: Pointer<S>,
     state: BTreeMapObserverState<K, O>,
@@ -171,10 +255,11 @@ impl<K, O, S: ?Sized, D> DerefMut for BTreeMapObserver<K, O, S, D> {
 
 impl<K, V, O, S: ?Sized, D> QuasiObserver for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
 {
     type Head = S;
     type OuterDepth = Succ<Zero>;
@@ -183,6 +268,11 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<K, O, S: ?Sized, D> Observer for BTreeMapObserver<K, O, S, D>
@@ -190,8 +280,8 @@ where
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Ord,
+    O::Head: Sized + Capture,
+    K: Clone + Ord + Capture,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let this = Self {
@@ -214,9 +304,13 @@ where
     S: AsDerefMut<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Ord + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Ord + 'static,
 {
-    unsafe fn partial_flush(&mut self) -> Mutations {
+    unsafe fn partial_flush(&mut self) -> Result<Mutations, PathError> {
+        #[cfg(feature = "inverse")]
+        let inverse = self.state.inverse_structure((*self.ptr).as_deref());
+        #[cfg(feature = "inverse")]
+        self.state.captured.clear();
         let diff = std::mem::take(&mut self.state.diff);
         let mut inner = std::mem::take(self.state.inner.get_mut());
         let mut mutations = Mutations::new();
@@ -224,9 +318,9 @@ where
             match value_state {
                 ValueState::Deleted => {
                     #[cfg(feature = "delete")]
-                    mutations.insert(key, MutationKind::Delete);
+                    mutations.insert(PathSegment::from_key(&key)?, MutationKind::Delete);
                     #[cfg(not(feature = "delete"))]
-                    return Mutations::replace((*self).untracked_ref());
+                    return Ok(Mutations::replace((*self).untracked_ref()));
                 }
                 ValueState::Replaced | ValueState::Inserted => {
                     inner.remove(&key);
@@ -234,19 +328,25 @@ where
                         .untracked_ref()
                         .get(&key)
                         .expect("replaced key not found in observed map");
-                    mutations.insert(key, Mutations::replace(value));
+                    mutations.insert(PathSegment::from_key(&key)?, Mutations::replace(value));
                 }
             }
         }
+        // The inverse of the keys is attached before the value mutations, so that the inverses of
+        // the values are applied first.
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
         for (key, mut ob) in inner {
             let value = self
                 .untracked_mut()
                 .get_mut(&key)
                 .expect("observer key not found in observed map");
             unsafe { O::relocate(&mut ob, value) }
-            mutations.insert(key, unsafe { O::flush(&mut ob) });
+            mutations.insert(PathSegment::from_key(&key)?, unsafe { O::flush(&mut ob) });
         }
-        mutations
+        Ok(mutations)
     }
 }
 
@@ -256,39 +356,34 @@ where
     S: AsDerefMut<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Ord + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Ord + 'static,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            // Fall back to replacing the whole map if a key cannot be used as a path segment.
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
+        let map = (*this.ptr).as_deref();
+        let mutations = this.state.attach(map, Mutations::replace(map));
         this.state.mutated = false;
         this.state.diff.clear();
         this.state.inner.get_mut().clear();
-        Mutations::replace((*this).untracked_ref())
+        mutations
     }
 
     unsafe fn flat_flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
-        this.state.mutated = false;
         this.state.inner.get_mut().clear();
         // After DerefMut, diff contains only Deleted entries representing original keys.
         // Emit Replace for each current key, Delete for original keys no longer present.
-        let mut diff = std::mem::take(&mut this.state.diff);
         let map = (*this.ptr).as_deref();
-        let mut mutations = Mutations::new().with_replace(true);
-        for (key, value) in map {
-            diff.remove(key);
-            mutations.insert(key.clone(), Mutations::replace(value));
-        }
-        for (key, _) in diff {
-            #[cfg(feature = "delete")]
-            mutations.insert(key, MutationKind::Delete);
-            #[cfg(not(feature = "delete"))]
-            unreachable!("delete feature is not enabled");
-        }
+        let deleted = this.state.diff.keys().filter(|key| !map.contains_key(*key));
+        let mutations = flat_replace(map, deleted).unwrap_or_else(|_| Mutations::replace(map));
+        let mutations = this.state.attach(map, mutations);
+        this.state.mutated = false;
+        this.state.diff.clear();
         mutations
     }
 }
@@ -298,8 +393,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Ord,
+    O::Head: Sized + Capture,
+    K: Clone + Ord + Capture,
 {
     /// See [`BTreeMap::get`].
     pub fn get<Q>(&self, key: &Q) -> Option<&O>
@@ -325,8 +420,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Ord,
+    O::Head: Sized + Capture,
+    K: Clone + Ord + Capture,
 {
     fn __force_all(&mut self) -> &mut BTreeMap<K, Box<O>> {
         let map = (*self.ptr).as_deref_mut();
@@ -365,8 +460,8 @@ where
 
     /// See [`BTreeMap::clear`].
     pub fn clear(&mut self) {
-        self.state.inner.get_mut().clear();
         if (*self).untracked_ref().is_empty() {
+            self.state.inner.get_mut().clear();
             self.untracked_mut().clear()
         } else {
             self.tracked_mut().clear()
@@ -380,7 +475,12 @@ where
         }
         let key_cloned = key.clone();
         let old_value = (*self.ptr).as_deref_mut().insert(key_cloned, value);
-        self.state.inner.get_mut().remove(&key);
+        let ob = self.state.inner.get_mut().remove(&key);
+        if let Some(old_value) = &old_value
+            && !self.state.diff.contains_key(&key)
+        {
+            self.state.capture(&key, old_value, ob.as_deref());
+        }
         match self.state.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Deleted) {
@@ -408,7 +508,7 @@ where
             return self.tracked_mut().remove(key);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().remove_entry(key)?;
-        self.state.mark_deleted(key);
+        self.state.mark_deleted(key, &old_value);
         Some(old_value)
     }
 
@@ -422,7 +522,7 @@ where
             return self.tracked_mut().remove_entry(key);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().remove_entry(key)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -432,7 +532,7 @@ where
             return self.tracked_mut().pop_first();
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().pop_first()?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -442,7 +542,7 @@ where
             return self.tracked_mut().pop_last();
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().pop_last()?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -477,8 +577,8 @@ where
             return self.tracked_mut().split_off(key);
         }
         let split = (*self.ptr).as_deref_mut().split_off(key);
-        for key in split.keys().cloned() {
-            self.state.mark_deleted(key);
+        for (key, value) in &split {
+            self.state.mark_deleted(key.clone(), value);
         }
         split
     }
@@ -512,10 +612,11 @@ where
 
 impl<K, V, O, S: ?Sized, D> Debug for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     BTreeMap<K, V>: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -525,10 +626,11 @@ where
 
 impl<K, V, O, S: ?Sized, D> PartialEq<BTreeMap<K, V>> for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     BTreeMap<K, V>: PartialEq,
 {
     fn eq(&self, other: &BTreeMap<K, V>) -> bool {
@@ -539,14 +641,16 @@ where
 impl<K1, K2, V1, V2, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<BTreeMapObserver<K2, O2, S2, D2>>
     for BTreeMapObserver<K1, O1, S1, D1>
 where
-    K1: Clone + Ord,
-    K2: Clone + Ord,
+    K1: Clone + Ord + Capture,
+    K2: Clone + Ord + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = BTreeMap<K1, V1>>,
     S2: AsDeref<D2, Target = BTreeMap<K2, V2>>,
     O1: Observer<InnerDepth = Zero, Head = V1>,
+    V1: Capture,
     O2: Observer<InnerDepth = Zero, Head = V2>,
+    V2: Capture,
     BTreeMap<K1, V1>: PartialEq<BTreeMap<K2, V2>>,
 {
     fn eq(&self, other: &BTreeMapObserver<K2, O2, S2, D2>) -> bool {
@@ -556,20 +660,22 @@ where
 
 impl<K, V, O, S: ?Sized, D> Eq for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     BTreeMap<K, V>: Eq,
 {
 }
 
 impl<K, V, O, S: ?Sized, D> PartialOrd<BTreeMap<K, V>> for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     BTreeMap<K, V>: PartialOrd,
 {
     fn partial_cmp(&self, other: &BTreeMap<K, V>) -> Option<std::cmp::Ordering> {
@@ -580,14 +686,16 @@ where
 impl<K1, K2, V1, V2, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<BTreeMapObserver<K2, O2, S2, D2>>
     for BTreeMapObserver<K1, O1, S1, D1>
 where
-    K1: Clone + Ord,
-    K2: Clone + Ord,
+    K1: Clone + Ord + Capture,
+    K2: Clone + Ord + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = BTreeMap<K1, V1>>,
     S2: AsDeref<D2, Target = BTreeMap<K2, V2>>,
     O1: Observer<InnerDepth = Zero, Head = V1>,
+    V1: Capture,
     O2: Observer<InnerDepth = Zero, Head = V2>,
+    V2: Capture,
     BTreeMap<K1, V1>: PartialOrd<BTreeMap<K2, V2>>,
 {
     fn partial_cmp(&self, other: &BTreeMapObserver<K2, O2, S2, D2>) -> Option<std::cmp::Ordering> {
@@ -597,10 +705,11 @@ where
 
 impl<K, V, O, S: ?Sized, D> Ord for BTreeMapObserver<K, O, S, D>
 where
-    K: Clone + Ord,
+    K: Clone + Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     BTreeMap<K, V>: Ord,
 {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
@@ -613,7 +722,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Borrow<Q> + Clone + Ord,
+    V: Capture,
+    K: Borrow<Q> + Clone + Ord + Capture,
     Q: Ord,
 {
     type Output = O;
@@ -628,7 +738,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Borrow<Q> + Clone + Ord,
+    V: Capture,
+    K: Borrow<Q> + Clone + Ord + Capture,
     Q: Ord,
 {
     fn index_mut(&mut self, index: &'q Q) -> &mut Self::Output {
@@ -643,8 +754,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Ord,
+    O::Head: Sized + Capture,
+    K: Clone + Ord + Capture,
 {
     fn extend<I: IntoIterator<Item = (K, O::Head)>>(&mut self, iter: I) {
         for (key, value) in iter {
@@ -653,7 +764,7 @@ where
     }
 }
 
-impl<K: Clone + Ord, V: Observe> Observe for BTreeMap<K, V> {
+impl<K: Clone + Ord + Capture, V: Observe + Capture> Observe for BTreeMap<K, V> {
     type Observer<'ob, S, D>
         = BTreeMapObserver<K, V::Observer<'ob, V, Zero>, S, D>
     where
@@ -1072,4 +1183,54 @@ mod tests {
             Some(batch!(_, replace!(c, json!(30)), delete!(a), delete!(b)))
         );
     }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(
+            &mut super::BTreeMapObserver<String, crate::impls::StringObserver<'_, String>, BTreeMap<String, String>>,
+        );
+        let cases: [Case; 5] = [
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.remove("a");
+                ob.insert("d".into(), "z".into());
+            },
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.insert("a".into(), "y".into());
+                ob.get_mut("a").unwrap().push('?');
+                ob.get_mut("c").unwrap().push('!');
+            },
+            |ob| {
+                ob.get_mut("c").unwrap().push('!');
+                ob.pop_first();
+                ob.split_off("b");
+            },
+            |ob| {
+                ob.get_mut("b").unwrap().push('!');
+                ob.clear();
+                ob.insert("b".into(), "y".into());
+            },
+            |ob| {
+                ob.pop_last();
+                ob.insert("c".into(), "w".into());
+                *ob.tracked_mut() = BTreeMap::from([("d".into(), "z".into())]);
+            },
+        ];
+        for mutate in cases {
+            let before = BTreeMap::from([
+                ("a".to_string(), "x".to_string()),
+                ("b".into(), "y".into()),
+                ("c".into(), "z".into()),
+            ]);
+            let mut map = before.clone();
+            let mut ob = map.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(map), flushed);
+        }
+    }
 }
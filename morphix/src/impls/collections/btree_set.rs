@@ -5,70 +5,32 @@ use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
-use std::mem::MaybeUninit;
 use std::ops::{Deref, DerefMut, RangeBounds};
 
 use serde::Serialize;
 
+use super::set::{SetMember, SetObserverState};
 use crate::general::Snapshot;
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
 use crate::{Mutations, Observe};
 
-struct BTreeSetObserverState<T> {
-    /// The last element (inclusive) of the common prefix between the old and current set.
-    /// `None` means the prefix is empty (either the set was initially empty, or all original
-    /// elements have been moved to the tail).
-    boundary: Option<T>,
-    /// Number of elements in the *original* set (at last flush/observe) that are strictly
-    /// after `boundary`. This becomes `truncate_len` on flush.
-    truncate_len: usize,
-}
-
-impl<T> Default for BTreeSetObserverState<T> {
-    fn default() -> Self {
-        Self {
-            boundary: None,
-            truncate_len: 0,
-        }
-    }
-}
-
-impl<T: Clone + Ord> BTreeSetObserverState<T> {
-    /// Move all original elements in `[v, boundary]` from the prefix into the tail,
-    /// then shrink `boundary` to the predecessor of `v`.
-    fn shrink_boundary(&mut self, v: &T, set: &BTreeSet<T>) {
-        let boundary = match &self.boundary {
-            Some(boundary) if boundary >= v => boundary,
-            _ => return,
-        };
-        self.truncate_len += set.range(v..=boundary).count();
-        self.boundary = set.range(..v).next_back().cloned();
-    }
-}
-
-impl<T: Ord> Invalidate<BTreeSet<T>> for BTreeSetObserverState<T> {
-    fn invalidate(&mut self, set: &BTreeSet<T>) {
-        if let Some(boundary) = self.boundary.take() {
-            self.truncate_len += set.range(..=boundary).count();
-        }
-    }
-}
-
 /// Observer implementation for [`BTreeSet<T>`].
 ///
-/// Tracks granular mutations by maintaining a prefix boundary. Elements up to and including
-/// the boundary are unchanged from the last flush; elements beyond the boundary form the
-/// "tail" region that will be emitted as [`Truncate`](crate::MutationKind::Truncate) /
-/// [`Append`](crate::MutationKind::Append) mutations.
+/// With the `set` feature, tracks the members inserted into and removed from the set since the last
+/// flush, which are emitted as [`Remove`](crate::MutationKind::Remove) /
+/// [`Add`](crate::MutationKind::Add) mutations. Inserting a member that was removed since the last
+/// flush (or vice versa) cancels out, and the size of the emitted mutations does not depend on where
+/// the members are located in the set. Without it, any change replaces the whole set.
 ///
 /// ## Limitations
 ///
-/// Most methods require `T: Clone` because the observer stores the boundary element.
+/// With the `set` feature, most methods require `T: Clone` (see [`SetMember`]) because the observer
+/// stores the inserted and removed members.
 pub struct BTreeSetObserver<'ob, T, S: ?Sized, D = Zero> {
     ptr: Pointer<S>,
-    state: BTreeSetObserverState<T>,
+    state: SetObserverState<BTreeSet<T>>,
     phantom: PhantomData<&'ob mut D>,
 }
 
@@ -90,7 +52,7 @@ impl<'ob, T, S: ?Sized, D> DerefMut for BTreeSetObserver<'ob, T, S, D> {
 
 impl<'ob, T, S: ?Sized, D> QuasiObserver for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
 {
@@ -101,21 +63,23 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<'ob, T, S: ?Sized, D> Observer for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeSet<T>>,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let this = Self {
-            state: BTreeSetObserverState {
-                boundary: head.as_deref_mut().last().cloned(),
-                truncate_len: 0,
-            },
             ptr: Pointer::new(head),
+            state: Default::default(),
             phantom: PhantomData,
         };
         Pointer::register_state::<_, D>(&this.ptr, &this.state);
@@ -129,49 +93,18 @@ where
 
 impl<'ob, T, S: ?Sized, D> SerializeObserver for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Serialize + Clone + Ord + 'static,
+    T: Serialize + SetMember + Ord + 'static,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
-        let set = (*this.ptr).as_deref();
-        let truncate_len = std::mem::replace(&mut this.state.truncate_len, 0);
-        let boundary = std::mem::replace(&mut this.state.boundary, set.last().cloned());
-
-        let prefix_len = match &boundary {
-            Some(b) => set.range(..=b).count(),
-            None => 0,
-        };
-        if prefix_len == 0 && truncate_len > 0 {
-            return Mutations::replace(set);
-        }
-
-        let mut mutations = Mutations::new();
-
-        #[cfg(feature = "truncate")]
-        if truncate_len > 0 {
-            mutations.extend(crate::MutationKind::Truncate(truncate_len));
-        }
-
-        #[cfg(feature = "append")]
-        {
-            // BTreeSet has no contiguous slice representation, so we collect the appended
-            // elements into an owned Vec and box it as the Append value.
-            let appended: Vec<T> = set.iter().skip(prefix_len).cloned().collect();
-            if !appended.is_empty() {
-                mutations.extend(crate::MutationKind::Append(
-                    Box::new(appended) as Box<dyn erased_serde::Serialize>
-                ));
-            }
-        }
-
-        mutations
+        this.state.flush((*this.ptr).as_deref())
     }
 }
 
 impl<'ob, T, S: ?Sized, D> BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: SetMember + Ord + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeSet<T>>,
 {
@@ -185,30 +118,57 @@ where
 
     delegate_methods! { nonempty_mut() as BTreeSet =>
         pub fn clear(&mut self);
-        pub fn pop_first(&mut self) -> Option<T>;
+    }
+
+    /// See [`BTreeSet::pop_first`].
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.state.mutated {
+            return self.tracked_mut().pop_first();
+        }
+        let value = (*self.ptr).as_deref_mut().pop_first()?;
+        self.state.mark_removed(&value);
+        Some(value)
     }
 
     /// See [`BTreeSet::pop_last`].
     pub fn pop_last(&mut self) -> Option<T> {
-        let set = (*self.ptr).as_deref();
-        if let Some(last) = set.last() {
-            self.state.shrink_boundary(last, set);
+        if self.state.mutated {
+            return self.tracked_mut().pop_last();
         }
-        self.untracked_mut().pop_last()
+        let value = (*self.ptr).as_deref_mut().pop_last()?;
+        self.state.mark_removed(&value);
+        Some(value)
     }
 
     /// See [`BTreeSet::insert`].
     pub fn insert(&mut self, value: T) -> bool {
-        let set = (*self.ptr).as_deref();
-        self.state.shrink_boundary(&value, set);
-        self.untracked_mut().insert(value)
+        if self.state.mutated {
+            return self.tracked_mut().insert(value);
+        }
+        if (*self.ptr).as_deref().contains(&value) {
+            return false;
+        }
+        self.state.mark_added(&value);
+        (*self.ptr).as_deref_mut().insert(value)
     }
 
     /// See [`BTreeSet::replace`].
     pub fn replace(&mut self, value: T) -> Option<T> {
-        let set = (*self.ptr).as_deref();
-        self.state.shrink_boundary(&value, set);
-        self.untracked_mut().replace(value)
+        if self.state.mutated {
+            return self.tracked_mut().replace(value);
+        }
+        match (*self.ptr).as_deref_mut().take(&value) {
+            Some(old_value) => {
+                self.state.mark_replaced(&old_value, &value);
+                (*self.ptr).as_deref_mut().insert(value);
+                Some(old_value)
+            }
+            None => {
+                self.state.mark_added(&value);
+                (*self.ptr).as_deref_mut().insert(value);
+                None
+            }
+        }
     }
 
     /// See [`BTreeSet::remove`].
@@ -217,11 +177,14 @@ where
         T: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        let set = (*self.ptr).as_deref();
-        if let Some(found) = set.get(value) {
-            self.state.shrink_boundary(found, set);
+        if self.state.mutated {
+            return self.tracked_mut().remove(value);
         }
-        self.untracked_mut().remove(value)
+        let Some(value) = (*self.ptr).as_deref_mut().take(value) else {
+            return false;
+        };
+        self.state.mark_removed(&value);
+        true
     }
 
     /// See [`BTreeSet::take`].
@@ -230,11 +193,12 @@ where
         T: Borrow<Q>,
         Q: Ord + ?Sized,
     {
-        let set = (*self.ptr).as_deref();
-        if let Some(found) = set.get(value) {
-            self.state.shrink_boundary(found, set);
+        if self.state.mutated {
+            return self.tracked_mut().take(value);
         }
-        self.untracked_mut().take(value)
+        let value = (*self.ptr).as_deref_mut().take(value)?;
+        self.state.mark_removed(&value);
+        Some(value)
     }
 
     /// See [`BTreeSet::retain`].
@@ -248,147 +212,132 @@ where
 
     /// See [`BTreeSet::append`].
     pub fn append(&mut self, other: &mut BTreeSet<T>) {
-        let set = (*self.ptr).as_deref();
-        if let Some(first) = other.first() {
-            self.state.shrink_boundary(first, set);
+        if self.state.mutated {
+            return self.tracked_mut().append(other);
+        }
+        let set = (*self.ptr).as_deref_mut();
+        for value in other.iter() {
+            if !set.contains(value) {
+                self.state.mark_added(value);
+            }
         }
-        self.untracked_mut().append(other);
+        set.append(other);
     }
 
     /// See [`BTreeSet::split_off`].
-    pub fn split_off(&mut self, value: &T) -> BTreeSet<T> {
-        let set = (*self.ptr).as_deref();
-        if let Some(found) = set.get(value) {
-            self.state.shrink_boundary(found, set);
+    pub fn split_off<Q>(&mut self, value: &Q) -> BTreeSet<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if self.state.mutated {
+            return self.tracked_mut().split_off(value);
+        }
+        let other = (*self.ptr).as_deref_mut().split_off(value);
+        for value in other.iter() {
+            self.state.mark_removed(value);
         }
-        self.untracked_mut().split_off(value)
+        other
     }
 
     /// See [`BTreeSet::extract_if`].
     #[rustversion::since(1.91)]
-    pub fn extract_if<F, R>(&mut self, range: R, pred: F) -> ExtractIf<'_, 'ob, T, S, D, R, F>
+    pub fn extract_if<F, R>(&mut self, range: R, pred: F) -> ExtractIf<'_, T, R, F>
     where
         R: RangeBounds<T>,
         F: FnMut(&T) -> bool,
     {
-        let set = unsafe { Pointer::as_mut(&self.ptr).as_deref_mut() };
-        let inner = MaybeUninit::new(set.extract_if(range, pred));
-        ExtractIf {
-            inner,
-            ob: self,
-            first_extracted: None,
-            extracted_count: 0,
-        }
+        let inner = (*self.ptr).as_deref_mut().extract_if(range, pred);
+        let state = if self.state.mutated {
+            None
+        } else {
+            Some(&mut self.state)
+        };
+        ExtractIf { inner, state }
     }
 }
 
-impl<'ob, T, S: ?Sized, D, U> Extend<U> for BTreeSetObserver<'ob, T, S, D>
+impl<'ob, T, S: ?Sized, D> Extend<T> for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: SetMember + Ord + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = BTreeSet<T>>,
-    BTreeSet<T>: Extend<U>,
 {
-    fn extend<I: IntoIterator<Item = U>>(&mut self, iter: I) {
-        self.tracked_mut().extend(iter);
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
     }
 }
 
-/// Iterator produced by [`BTreeSetObserver::extract_if`].
-#[rustversion::since(1.91)]
-pub struct ExtractIf<'a, 'ob, T, S: ?Sized, D, R, F>
+impl<'a, 'ob, T, S: ?Sized, D> Extend<&'a T> for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Copy + Ord + Capture + 'a,
     D: Unsigned,
-    S: AsDeref<D, Target = BTreeSet<T>>,
+    S: AsDerefMut<D, Target = BTreeSet<T>>,
 {
-    /// Wrapped in [`MaybeUninit`] (a union) to prevent SB from deep-retagging the internal mutable
-    /// references inside stdlib's [`ExtractIf`](std::collections::btree_set::ExtractIf) when
-    /// [`Drop::drop`] is entered. SB does not recurse into unions during retagging, so the strongly
-    /// protected Unique tag from the [`drop_in_place`](std::ptr::drop_in_place) shim won't cover
-    /// those inner references, allowing subsequent [`Pointer`]-based reads of the [`BTreeSet`]
-    /// after the inner iterator is dropped.
-    inner: MaybeUninit<std::collections::btree_set::ExtractIf<'a, T, R, F>>,
-    ob: &'a mut BTreeSetObserver<'ob, T, S, D>,
-    first_extracted: Option<T>,
-    extracted_count: usize,
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(*value);
+        }
+    }
 }
 
+/// Iterator produced by [`BTreeSetObserver::extract_if`].
 #[rustversion::since(1.91)]
-impl<T, S: ?Sized, D, R, F> Drop for ExtractIf<'_, '_, T, S, D, R, F>
+pub struct ExtractIf<'a, T, R, F>
 where
-    T: Clone + Ord,
-    D: Unsigned,
-    S: AsDeref<D, Target = BTreeSet<T>>,
+    F: FnMut(&T) -> bool,
 {
-    fn drop(&mut self) {
-        unsafe { self.inner.assume_init_drop() }
-        let Some(first) = &self.first_extracted else {
-            return;
-        };
-        let set = (*self.ob.ptr).as_deref();
-        self.ob.state.shrink_boundary(first, set);
-        self.ob.state.truncate_len += self.extracted_count;
-    }
+    inner: std::collections::btree_set::ExtractIf<'a, T, R, F>,
+    state: Option<&'a mut SetObserverState<BTreeSet<T>>>,
 }
 
 #[rustversion::since(1.91)]
-impl<T, S: ?Sized, D, R, F> Iterator for ExtractIf<'_, '_, T, S, D, R, F>
+impl<T, R, F> Iterator for ExtractIf<'_, T, R, F>
 where
-    T: Clone + Ord,
-    D: Unsigned,
-    S: AsDeref<D, Target = BTreeSet<T>>,
+    T: SetMember + Ord,
     R: RangeBounds<T>,
     F: FnMut(&T) -> bool,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let value = unsafe { self.inner.assume_init_mut() }.next()?;
-        if let Some(boundary) = &mut self.ob.state.boundary
-            && value <= *boundary
-        {
-            self.extracted_count += 1;
-            if self.first_extracted.is_none() {
-                self.first_extracted = Some(value.clone());
-            }
+        let value = self.inner.next()?;
+        if let Some(state) = &mut self.state {
+            state.mark_removed(&value);
         }
         Some(value)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        unsafe { self.inner.assume_init_ref() }.size_hint()
+        self.inner.size_hint()
     }
 }
 
 #[rustversion::since(1.91)]
-impl<T, S: ?Sized, D, R, F> FusedIterator for ExtractIf<'_, '_, T, S, D, R, F>
+impl<T, R, F> FusedIterator for ExtractIf<'_, T, R, F>
 where
-    T: Clone + Ord,
-    D: Unsigned,
-    S: AsDeref<D, Target = BTreeSet<T>>,
+    T: SetMember + Ord,
     R: RangeBounds<T>,
     F: FnMut(&T) -> bool,
 {
 }
 
 #[rustversion::since(1.91)]
-impl<T, S: ?Sized, D, R, F> Debug for ExtractIf<'_, '_, T, S, D, R, F>
+impl<T, R, F> Debug for ExtractIf<'_, T, R, F>
 where
-    T: Clone + Ord + Debug,
-    D: Unsigned,
-    S: AsDeref<D, Target = BTreeSet<T>>,
-    R: RangeBounds<T>,
+    T: Debug,
     F: FnMut(&T) -> bool,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        unsafe { self.inner.assume_init_ref() }.fmt(f)
+        self.inner.fmt(f)
     }
 }
 
 impl<'ob, T, S: ?Sized, D> Debug for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
     BTreeSet<T>: Debug,
@@ -400,7 +349,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> PartialEq<BTreeSet<T>> for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
     BTreeSet<T>: PartialEq,
@@ -413,8 +362,8 @@ where
 impl<'ob, T1, T2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<BTreeSetObserver<'ob, T2, S2, D2>>
     for BTreeSetObserver<'ob, T1, S1, D1>
 where
-    T1: Clone + Ord,
-    T2: Clone + Ord,
+    T1: Ord + Capture,
+    T2: Ord + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = BTreeSet<T1>>,
@@ -428,7 +377,7 @@ where
 
 impl<'ob, T, S, D> Eq for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
     BTreeSet<T>: Eq,
@@ -437,7 +386,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> PartialOrd<BTreeSet<T>> for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
     BTreeSet<T>: PartialOrd,
@@ -450,8 +399,8 @@ where
 impl<'ob, T1, T2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<BTreeSetObserver<'ob, T2, S2, D2>>
     for BTreeSetObserver<'ob, T1, S1, D1>
 where
-    T1: Clone + Ord,
-    T2: Clone + Ord,
+    T1: Ord + Capture,
+    T2: Ord + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = BTreeSet<T1>>,
@@ -465,7 +414,7 @@ where
 
 impl<'ob, T, S, D> Ord for BTreeSetObserver<'ob, T, S, D>
 where
-    T: Clone + Ord,
+    T: Ord + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = BTreeSet<T>>,
     BTreeSet<T>: Ord,
@@ -475,7 +424,7 @@ where
     }
 }
 
-impl<T: Clone + Ord> Observe for BTreeSet<T> {
+impl<T: SetMember + Ord + Capture> Observe for BTreeSet<T> {
     type Observer<'ob, S, D>
         = BTreeSetObserver<'ob, T, S, D>
     where
@@ -526,44 +475,49 @@ mod tests {
     }
 
     #[test]
-    fn insert_append() {
+    fn insert_as_add() {
         let mut set = BTreeSet::from([1, 2, 3]);
         let mut ob = set.__observe();
-        ob.insert(4);
         ob.insert(5);
+        ob.insert(4);
+        ob.insert(3);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(append!(_, json!([4, 5]))));
+        assert_eq!(mutation, Some(add!(_, json!([4, 5]))));
     }
 
     #[test]
-    fn remove_last_as_truncate() {
+    fn remove_as_remove() {
         let mut set = BTreeSet::from([1, 2, 3]);
         let mut ob = set.__observe();
         ob.remove(&3);
+        ob.remove(&4);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(truncate!(_, 1)));
+        assert_eq!(mutation, Some(remove!(_, json!([3]))));
     }
 
     #[test]
-    fn remove_middle() {
-        let mut set = BTreeSet::from([1, 2, 3, 4, 5]);
+    fn insert_small_element() {
+        let mut set = BTreeSet::from(["b".to_string(), "c".to_string(), "d".to_string()]);
         let mut ob = set.__observe();
-        ob.remove(&3);
+        ob.insert("a".to_string());
+        ob.remove("c");
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 3), append!(_, json!([4, 5])))));
+        assert_eq!(
+            mutation,
+            Some(batch!(_, remove!(_, json!(["c"])), add!(_, json!(["a"]))))
+        );
     }
 
     #[test]
-    fn insert_middle_then_append() {
+    fn insert_then_remove_cancelled() {
         let mut set = BTreeSet::from([1, 3, 5]);
         let mut ob = set.__observe();
         ob.insert(2);
-        ob.insert(6);
+        ob.remove(&2);
+        ob.remove(&3);
+        ob.insert(3);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(
-            mutation,
-            Some(batch!(_, truncate!(_, 2), append!(_, json!([2, 3, 5, 6]))))
-        );
+        assert_eq!(mutation, None);
     }
 
     #[test]
@@ -589,8 +543,9 @@ mod tests {
         let mut set = BTreeSet::from([1, 2, 3]);
         let mut ob = set.__observe();
         *ob.tracked_mut() = BTreeSet::from([4, 5]);
+        ob.insert(6);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([4, 5]))));
+        assert_eq!(mutation, Some(replace!(_, json!([4, 5, 6]))));
     }
 
     #[test]
@@ -605,22 +560,23 @@ mod tests {
     }
 
     #[test]
-    fn pop_first() {
-        let mut set = BTreeSet::from([1, 2, 3]);
+    fn pop_first_and_last() {
+        let mut set = BTreeSet::from([1, 2, 3, 4]);
         let mut ob = set.__observe();
         assert_eq!(ob.pop_first(), Some(1));
+        assert_eq!(ob.pop_last(), Some(4));
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([2, 3]))));
+        assert_eq!(mutation, Some(remove!(_, json!([1, 4]))));
     }
 
     #[test]
-    fn pop_last_as_truncate() {
+    fn append_and_split_off() {
         let mut set = BTreeSet::from([1, 2, 3]);
         let mut ob = set.__observe();
-        assert_eq!(ob.pop_last(), Some(3));
-        assert_eq!(ob.pop_last(), Some(2));
+        ob.append(&mut BTreeSet::from([0, 2, 4]));
+        assert_eq!(ob.split_off(&3), BTreeSet::from([3, 4]));
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(truncate!(_, 2)));
+        assert_eq!(mutation, Some(batch!(_, remove!(_, json!([3])), add!(_, json!([0])))));
     }
 
     #[test]
@@ -633,22 +589,55 @@ mod tests {
     }
 
     #[test]
-    fn retain_truncate() {
+    fn retain_as_remove() {
         let mut set = BTreeSet::from([1, 2, 3, 4, 5]);
         let mut ob = set.__observe();
         ob.retain(|v| *v % 2 == 1);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 4), append!(_, json!([3, 5])))));
+        assert_eq!(mutation, Some(remove!(_, json!([2, 4]))));
     }
 
     #[test]
-    fn extract_if_drop() {
+    fn extract_if_partial() {
         let mut set = BTreeSet::from([1, 2, 3, 4, 5]);
         let mut ob = set.__observe();
-        let mut iter = ob.extract_if(.., |v| *v % 2 == 0);
-        assert_eq!(iter.next(), Some(2));
-        drop(iter);
+        {
+            let mut iter = ob.extract_if(.., |v| *v % 2 == 0);
+            assert_eq!(iter.next(), Some(2));
+        }
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 4), append!(_, json!([3, 4, 5])))));
+        assert_eq!(mutation, Some(remove!(_, json!([2]))));
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::BTreeSetObserver<'_, i32, BTreeSet<i32>>);
+        let cases: [Case; 3] = [
+            |ob| {
+                ob.remove(&3);
+                ob.insert(4);
+            },
+            |ob| {
+                ob.remove(&2);
+                ob.remove(&3);
+                ob.insert(2);
+            },
+            |ob| {
+                ob.insert(4);
+                ob.clear();
+                ob.insert(5);
+            },
+        ];
+        for mutate in cases {
+            let before = BTreeSet::from([1, 2, 3]);
+            let mut set = before.clone();
+            let mut ob = set.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(set), flushed);
+        }
     }
 }
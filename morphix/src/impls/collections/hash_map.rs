@@ -5,18 +5,23 @@ use std::cell::UnsafeCell;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, TryReserveError};
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 use std::iter::FusedIterator;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut, Index, IndexMut};
 
 use serde::Serialize;
 
+use super::flat_replace;
+#[cfg(feature = "inverse")]
+use super::insert_restore;
 use crate::general::Snapshot;
+#[cfg(feature = "inverse")]
+use crate::helper::Captor;
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
-use crate::{MutationKind, Mutations, Observe, PathSegment};
+use crate::{MutationKind, Mutations, Observe, PathError, PathSegment};
 
 enum ValueState {
     /// Key existed in the original map and was overwritten via [`insert`](HashMapObserver::insert).
@@ -31,6 +36,10 @@ enum ValueState {
 struct HashMapObserverState<K, O> {
     mutated: bool,
     diff: HashMap<K, ValueState>,
+    /// Values of the original map displaced since the last flush, together with the inverses of
+    /// their observers.
+    #[cfg(feature = "inverse")]
+    captured: HashMap<K, Captor>,
     /// Boxed to ensure pointer stability: [`HashMap`] rehashing moves all entries to a new
     /// allocation, which would invalidate references to inline values. [`Box`] adds a layer
     /// of indirection so that only the pointer is moved, not the observer itself.
@@ -42,33 +51,109 @@ impl<K, O> Default for HashMapObserverState<K, O> {
         Self {
             mutated: false,
             diff: Default::default(),
+            #[cfg(feature = "inverse")]
+            captured: Default::default(),
             inner: Default::default(),
         }
     }
 }
 
-impl<K, O> Invalidate<HashMap<K, O::Head>> for HashMapObserverState<K, O>
+impl<K, O, M> Invalidate<M> for HashMapObserverState<K, O>
 where
-    K: Clone + Eq + Hash,
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
+    M: HashMapLike<Key = K, Value = O::Head>,
 {
-    fn invalidate(&mut self, map: &HashMap<K, O::Head>) {
+    fn invalidate(&mut self, map: &M) {
         if !self.mutated {
             self.mutated = true;
-            for key in map.keys() {
-                self.mark_deleted(key.clone());
+            for (key, value) in map.iter() {
+                self.mark_deleted(key.clone(), value);
             }
         }
         self.inner.get_mut().clear();
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, map: &M) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if !self.mutated {
+            for (key, ob) in unsafe { &*self.inner.get() } {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, O::inverse(ob, map.get(key)?)?);
+                }
+            }
+        }
+        mutations.extend(self.inverse_structure(map)?);
+        Some(mutations)
+    }
 }
 
 impl<K, O> HashMapObserverState<K, O>
 where
-    K: Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
 {
-    fn mark_deleted(&mut self, key: K) {
-        self.inner.get_mut().remove(&key);
+    /// Captures the original `value` of `key` with the inverse of its observer `ob`, unless it has
+    /// been captured since the last flush.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture(&mut self, key: &K, value: &O::Head, ob: Option<&O>) {
+        #[cfg(feature = "inverse")]
+        self.captured
+            .entry(key.clone())
+            .or_default()
+            .capture_with(value, || match ob {
+                Some(ob) => O::inverse(ob, value),
+                None => Some(Mutations::new()),
+            });
+    }
+
+    /// Returns the mutations which restore the keys of the original map from the current `map`,
+    /// leaving the inverses of the value observers aside.
+    #[cfg(feature = "inverse")]
+    fn inverse_structure<M>(&self, map: &M) -> Option<Mutations>
+    where
+        M: HashMapLike<Key = K>,
+    {
+        let mut mutations = Mutations::new();
+        if self.mutated {
+            for (key, _) in map.iter() {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, MutationKind::Delete);
+                }
+            }
+        }
+        for (key, value_state) in &self.diff {
+            let segment = PathSegment::from_key(key).ok()?;
+            match value_state {
+                ValueState::Inserted => mutations.insert(segment, MutationKind::Delete),
+                ValueState::Replaced | ValueState::Deleted => {
+                    insert_restore(&mut mutations, segment, self.captured.get(key)?.inverse()?);
+                }
+            }
+        }
+        Some(mutations)
+    }
+
+    /// Attaches the inverse of the changes to the keys since the last flush to `mutations`, given
+    /// the current `map`, and resets the captured values.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn attach<M>(&mut self, map: &M, mutations: Mutations) -> Mutations
+    where
+        M: HashMapLike<Key = K>,
+    {
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(self.inverse_structure(map));
+        #[cfg(feature = "inverse")]
+        self.captured.clear();
+        mutations
+    }
+
+    fn mark_deleted(&mut self, key: K, value: &O::Head) {
+        let ob = self.inner.get_mut().remove(&key);
+        if !self.diff.contains_key(&key) {
+            self.capture(&key, value, ob.as_deref());
+        }
         match self.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Inserted) {
@@ -84,26 +169,210 @@ where
     }
 }
 
+/// Hash maps that can be observed by [`HashMapObserver`].
+///
+/// Implemented for [`HashMap<K, V, S>`] and, with the `hashbrown` feature, for
+/// [`hashbrown::HashMap<K, V, S>`], so that both share the same observer.
+pub trait HashMapLike {
+    /// The type of the keys.
+    type Key: Eq + Hash;
+
+    /// The type of the values.
+    type Value;
+
+    /// The error returned by [`try_reserve`](Self::try_reserve).
+    type TryReserveError;
+
+    /// The iterator returned by [`extract_if`](Self::extract_if).
+    type ExtractIf<'a, F>: Iterator<Item = (Self::Key, Self::Value)>
+    where
+        Self: 'a,
+        F: FnMut(&Self::Key, &mut Self::Value) -> bool;
+
+    /// See [`HashMap::is_empty`].
+    fn is_empty(&self) -> bool;
+
+    /// See [`HashMap::iter`].
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)>;
+
+    /// See [`HashMap::iter_mut`].
+    fn iter_mut(&mut self) -> impl Iterator<Item = (&Self::Key, &mut Self::Value)>;
+
+    /// See [`HashMap::get_key_value`].
+    fn get_key_value<Q>(&self, key: &Q) -> Option<(&Self::Key, &Self::Value)>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashMap::get`].
+    fn get<Q>(&self, key: &Q) -> Option<&Self::Value>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashMap::get_mut`].
+    fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut Self::Value>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashMap::insert`].
+    fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value>;
+
+    /// See [`HashMap::remove`].
+    fn remove<Q>(&mut self, key: &Q) -> Option<Self::Value>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashMap::remove_entry`].
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(Self::Key, Self::Value)>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashMap::clear`].
+    fn clear(&mut self);
+
+    /// See [`HashMap::extract_if`].
+    fn extract_if<F>(&mut self, pred: F) -> Self::ExtractIf<'_, F>
+    where
+        F: FnMut(&Self::Key, &mut Self::Value) -> bool;
+
+    /// See [`HashMap::reserve`].
+    fn reserve(&mut self, additional: usize);
+
+    /// See [`HashMap::try_reserve`].
+    fn try_reserve(&mut self, additional: usize) -> Result<(), Self::TryReserveError>;
+
+    /// See [`HashMap::shrink_to_fit`].
+    fn shrink_to_fit(&mut self);
+
+    /// See [`HashMap::shrink_to`].
+    fn shrink_to(&mut self, min_capacity: usize);
+}
+
+macro_rules! impl_hash_map_like {
+    ($($module:ident)::+, $error:ty) => {
+        impl<K: Eq + Hash, V, H: BuildHasher> HashMapLike for $($module)::+::HashMap<K, V, H> {
+            type Key = K;
+            type Value = V;
+            type TryReserveError = $error;
+            type ExtractIf<'a, F>
+                = $($module)::+::ExtractIf<'a, K, V, F>
+            where
+                Self: 'a,
+                F: FnMut(&K, &mut V) -> bool;
+
+            fn is_empty(&self) -> bool {
+                $($module)::+::HashMap::is_empty(self)
+            }
+
+            fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+                $($module)::+::HashMap::iter(self)
+            }
+
+            fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+                $($module)::+::HashMap::iter_mut(self)
+            }
+
+            fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+            where
+                K: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashMap::get_key_value(self, key)
+            }
+
+            fn get<Q>(&self, key: &Q) -> Option<&V>
+            where
+                K: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashMap::get(self, key)
+            }
+
+            fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+            where
+                K: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashMap::get_mut(self, key)
+            }
+
+            fn insert(&mut self, key: K, value: V) -> Option<V> {
+                $($module)::+::HashMap::insert(self, key, value)
+            }
+
+            fn remove<Q>(&mut self, key: &Q) -> Option<V>
+            where
+                K: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashMap::remove(self, key)
+            }
+
+            fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+            where
+                K: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashMap::remove_entry(self, key)
+            }
+
+            fn clear(&mut self) {
+                $($module)::+::HashMap::clear(self)
+            }
+
+            fn extract_if<F>(&mut self, pred: F) -> Self::ExtractIf<'_, F>
+            where
+                F: FnMut(&K, &mut V) -> bool,
+            {
+                $($module)::+::HashMap::extract_if(self, pred)
+            }
+
+            fn reserve(&mut self, additional: usize) {
+                $($module)::+::HashMap::reserve(self, additional)
+            }
+
+            fn try_reserve(&mut self, additional: usize) -> Result<(), $error> {
+                $($module)::+::HashMap::try_reserve(self, additional)
+            }
+
+            fn shrink_to_fit(&mut self) {
+                $($module)::+::HashMap::shrink_to_fit(self)
+            }
+
+            fn shrink_to(&mut self, min_capacity: usize) {
+                $($module)::+::HashMap::shrink_to(self, min_capacity)
+            }
+        }
+    };
+}
+
+impl_hash_map_like!(std::collections::hash_map, TryReserveError);
+#[cfg(feature = "hashbrown")]
+impl_hash_map_like!(hashbrown::hash_map, hashbrown::TryReserveError);
+
 /// Iterator produced by [`HashMapObserver::extract_if`].
-pub struct ExtractIf<'a, K, V, O, F>
-where
-    F: FnMut(&K, &mut V) -> bool,
-{
-    inner: std::collections::hash_map::ExtractIf<'a, K, V, F>,
+pub struct ExtractIf<'a, K, O, I> {
+    inner: I,
     state: Option<&'a mut HashMapObserverState<K, O>>,
 }
 
-impl<K, V, O, F> Iterator for ExtractIf<'_, K, V, O, F>
+impl<K, V, O, I> Iterator for ExtractIf<'_, K, O, I>
 where
-    K: Clone + Eq + Hash,
-    F: FnMut(&K, &mut V) -> bool,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
+    I: Iterator<Item = (K, V)>,
 {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
         let (key, value) = self.inner.next()?;
         if let Some(state) = &mut self.state {
-            state.mark_deleted(key.clone());
+            state.mark_deleted(key.clone(), &value);
         }
         Some((key, value))
     }
@@ -113,25 +382,22 @@ where
     }
 }
 
-impl<K, V, O, F> FusedIterator for ExtractIf<'_, K, V, O, F>
+impl<K, V, O, I> FusedIterator for ExtractIf<'_, K, O, I>
 where
-    K: Clone + Eq + Hash,
-    F: FnMut(&K, &mut V) -> bool,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
+    I: FusedIterator<Item = (K, V)>,
 {
 }
 
-impl<K, V, O, F> Debug for ExtractIf<'_, K, V, O, F>
-where
-    K: Debug,
-    V: Debug,
-    F: FnMut(&K, &mut V) -> bool,
-{
+impl<K, O, I: Debug> Debug for ExtractIf<'_, K, O, I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.inner.fmt(f)
     }
 }
 
-/// Observer implementation for [`HashMap<K, V>`].
+/// Observer implementation for [`HashMap<K, V>`] and other [`HashMapLike`] maps.
 ///
 /// ## Limitations
 ///
@@ -139,6 +405,8 @@ where
 /// [`get_mut`](Self::get_mut)) require `K: Clone` because the observer maintains its own
 /// [`HashMap`] of cloned keys to track per-key observers independently of the observed map's
 /// internal storage.
+///
+/// Keys are converted into path segments as described in [Map keys](super#map-keys).
 pub struct HashMapObserver<K, O, S: ?Sized, D = Zero> {
     ptr: Pointer<S>,
     state: HashMapObserverState<K, O>,
@@ -161,12 +429,13 @@ impl<K, O, S: ?Sized, D> DerefMut for HashMapObserver<K, O, S, D> {
     }
 }
 
-impl<K, O, S: ?Sized, D> QuasiObserver for HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> QuasiObserver for HashMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
-    S: AsDeref<D, Target = HashMap<K, O::Head>>,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    S: AsDeref<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
 {
     type Head = S;
     type OuterDepth = Succ<Zero>;
@@ -175,15 +444,21 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
-impl<K, O, S: ?Sized, D> Observer for HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> Observer for HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDeref<D, Target = HashMap<K, O::Head>>,
+    S: AsDeref<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let this = Self {
@@ -200,15 +475,20 @@ where
     }
 }
 
-impl<K, O, S: ?Sized, D> HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, O::Head>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head> + Serialize + 'static,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Eq + Hash + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Eq + Hash + 'static,
 {
-    unsafe fn partial_flush(&mut self) -> Mutations {
+    unsafe fn partial_flush(&mut self) -> Result<Mutations, PathError> {
+        #[cfg(feature = "inverse")]
+        let inverse = self.state.inverse_structure((*self.ptr).as_deref());
+        #[cfg(feature = "inverse")]
+        self.state.captured.clear();
         let diff = std::mem::take(&mut self.state.diff);
         let mut inner = std::mem::take(self.state.inner.get_mut());
         let mut mutations = Mutations::new();
@@ -216,9 +496,9 @@ where
             match value_state {
                 ValueState::Deleted => {
                     #[cfg(feature = "delete")]
-                    mutations.insert(key, MutationKind::Delete);
+                    mutations.insert(PathSegment::from_key(&key)?, MutationKind::Delete);
                     #[cfg(not(feature = "delete"))]
-                    return Mutations::replace((*self).untracked_ref());
+                    return Ok(Mutations::replace((*self).untracked_ref()));
                 }
                 ValueState::Replaced | ValueState::Inserted => {
                     inner.remove(&key);
@@ -226,87 +506,92 @@ where
                         .untracked_ref()
                         .get(&key)
                         .expect("replaced key not found in observed map");
-                    mutations.insert(key, Mutations::replace(value));
+                    mutations.insert(PathSegment::from_key(&key)?, Mutations::replace(value));
                 }
             }
         }
+        // The inverse of the keys is attached before the value mutations, so that the inverses of
+        // the values are applied first.
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
         for (key, mut ob) in inner {
             let value = self
                 .untracked_mut()
                 .get_mut(&key)
                 .expect("observer key not found in observed map");
             unsafe { O::relocate(&mut ob, value) }
-            mutations.insert(key, unsafe { O::flush(&mut ob) });
+            mutations.insert(PathSegment::from_key(&key)?, unsafe { O::flush(&mut ob) });
         }
-        mutations
+        Ok(mutations)
     }
 }
 
-impl<K, O, S: ?Sized, D> SerializeObserver for HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> SerializeObserver for HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, O::Head>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head> + Serialize + 'static,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Eq + Hash + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Eq + Hash + 'static,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            // Fall back to replacing the whole map if a key cannot be used as a path segment.
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
+        let map = (*this.ptr).as_deref();
+        let mutations = this.state.attach(map, Mutations::replace(map));
         this.state.mutated = false;
         this.state.diff.clear();
         this.state.inner.get_mut().clear();
-        Mutations::replace((*this).untracked_ref())
+        mutations
     }
 
     unsafe fn flat_flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
-        this.state.mutated = false;
         this.state.inner.get_mut().clear();
         // After DerefMut, diff contains only Deleted entries representing original keys.
         // Emit Replace for each current key, Delete for original keys no longer present.
-        let mut diff = std::mem::take(&mut this.state.diff);
         let map = (*this.ptr).as_deref();
-        let mut mutations = Mutations::new().with_replace(true);
-        for (key, value) in map {
-            diff.remove(key);
-            mutations.insert(key.clone(), Mutations::replace(value));
-        }
-        for (key, _) in diff {
-            #[cfg(feature = "delete")]
-            mutations.insert(key, MutationKind::Delete);
-            #[cfg(not(feature = "delete"))]
-            unreachable!("delete feature is not enabled");
-        }
+        let deleted = this.state.diff.keys().filter(|key| map.get(*key).is_none());
+        let mutations = flat_replace(map.iter(), deleted).unwrap_or_else(|_| Mutations::replace(map));
+        let mutations = this.state.attach(map, mutations);
+        this.state.mutated = false;
+        this.state.diff.clear();
         mutations
     }
 }
 
-impl<K, O, S: ?Sized, D, V> HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, V, M> HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, V>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Clone + Eq + Hash,
+    V: Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     delegate_methods! { untracked_mut() as HashMap =>
         pub fn reserve(&mut self, additional: usize);
-        pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>;
+        pub fn try_reserve(&mut self, additional: usize) -> Result<(), M::TryReserveError>;
         pub fn shrink_to_fit(&mut self);
         pub fn shrink_to(&mut self, min_capacity: usize);
     }
 }
 
-impl<K, O, S: ?Sized, D> HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, O::Head>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     /// See [`HashMap::get`].
     pub fn get<Q>(&self, key: &Q) -> Option<&O>
@@ -327,13 +612,14 @@ where
     }
 }
 
-impl<K, O, S: ?Sized, D> HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, O::Head>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn __force_all(&mut self) -> &mut HashMap<K, Box<O>> {
         let map = (*self.ptr).as_deref_mut();
@@ -372,8 +658,8 @@ where
 
     /// See [`HashMap::clear`].
     pub fn clear(&mut self) {
-        self.state.inner.get_mut().clear();
         if (*self).untracked_ref().is_empty() {
+            self.state.inner.get_mut().clear();
             self.untracked_mut().clear()
         } else {
             self.tracked_mut().clear()
@@ -390,7 +676,12 @@ where
         }
         let key_cloned = key.clone();
         let old_value = (*self.ptr).as_deref_mut().insert(key_cloned, value);
-        self.state.inner.get_mut().remove(&key);
+        let ob = self.state.inner.get_mut().remove(&key);
+        if let Some(old_value) = &old_value
+            && !self.state.diff.contains_key(&key)
+        {
+            self.state.capture(&key, old_value, ob.as_deref());
+        }
         match self.state.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Deleted) {
@@ -418,7 +709,7 @@ where
             return self.tracked_mut().remove(key);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().remove_entry(key)?;
-        self.state.mark_deleted(key);
+        self.state.mark_deleted(key, &old_value);
         Some(old_value)
     }
 
@@ -432,7 +723,7 @@ where
             return self.tracked_mut().remove_entry(key);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().remove_entry(key)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -446,8 +737,9 @@ where
     }
 
     /// See [`HashMap::extract_if`].
-    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, K, O::Head, O, F>
+    pub fn extract_if<'a, F>(&'a mut self, pred: F) -> ExtractIf<'a, K, O, M::ExtractIf<'a, F>>
     where
+        M: 'a,
         K: Eq + Hash,
         F: FnMut(&K, &mut O::Head) -> bool,
     {
@@ -477,66 +769,77 @@ where
     }
 }
 
-impl<K, V, O, S: ?Sized, D> Debug for HashMapObserver<K, O, S, D>
+impl<K, V, O, S: ?Sized, D, M> Debug for HashMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
-    S: AsDeref<D, Target = HashMap<K, V>>,
+    S: AsDeref<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    HashMap<K, V>: Debug,
+    V: Capture,
+    M: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("HashMapObserver").field(&self.untracked_ref()).finish()
     }
 }
 
-impl<K, V, O, S: ?Sized, D> PartialEq<HashMap<K, V>> for HashMapObserver<K, O, S, D>
+impl<K, V, O, S: ?Sized, D, M> PartialEq<M> for HashMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
-    S: AsDeref<D, Target = HashMap<K, V>>,
+    S: AsDeref<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    HashMap<K, V>: PartialEq,
+    V: Capture,
+    M: PartialEq,
 {
-    fn eq(&self, other: &HashMap<K, V>) -> bool {
+    fn eq(&self, other: &M) -> bool {
         self.untracked_ref().eq(other)
     }
 }
 
-impl<K1, K2, V1, V2, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<HashMapObserver<K2, O2, S2, D2>>
+impl<K1, K2, V1, V2, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2, M1, M2> PartialEq<HashMapObserver<K2, O2, S2, D2>>
     for HashMapObserver<K1, O1, S1, D1>
 where
-    K1: Clone + Eq + Hash,
-    K2: Clone + Eq + Hash,
+    K1: Clone + Eq + Hash + Capture,
+    K2: Clone + Eq + Hash + Capture,
     D1: Unsigned,
     D2: Unsigned,
-    S1: AsDeref<D1, Target = HashMap<K1, V1>>,
-    S2: AsDeref<D2, Target = HashMap<K2, V2>>,
+    S1: AsDeref<D1, Target = M1>,
+    S2: AsDeref<D2, Target = M2>,
     O1: Observer<InnerDepth = Zero, Head = V1>,
+    V1: Capture,
     O2: Observer<InnerDepth = Zero, Head = V2>,
-    HashMap<K1, V1>: PartialEq<HashMap<K2, V2>>,
+    V2: Capture,
+    M1: HashMapLike<Key = K1, Value = V1> + PartialEq<M2>,
+    M2: HashMapLike<Key = K2, Value = V2>,
 {
     fn eq(&self, other: &HashMapObserver<K2, O2, S2, D2>) -> bool {
         self.untracked_ref().eq(other.untracked_ref())
     }
 }
 
-impl<K, V, O, S: ?Sized, D> Eq for HashMapObserver<K, O, S, D>
+impl<K, V, O, S: ?Sized, D, M> Eq for HashMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
-    S: AsDeref<D, Target = HashMap<K, V>>,
+    S: AsDeref<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    HashMap<K, V>: Eq,
+    V: Capture,
+    M: Eq,
 {
 }
 
-impl<'q, K, O, S: ?Sized, D, V, Q: ?Sized> Index<&'q Q> for HashMapObserver<K, O, S, D>
+impl<'q, K, O, S: ?Sized, D, V, Q: ?Sized, M> Index<&'q Q> for HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, V>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Borrow<Q> + Clone + Eq + Hash,
+    V: Capture,
+    K: Borrow<Q> + Clone + Eq + Hash + Capture,
     Q: Eq + Hash,
 {
     type Output = O;
@@ -546,12 +849,14 @@ where
     }
 }
 
-impl<'q, K, O, S: ?Sized, D, V, Q: ?Sized> IndexMut<&'q Q> for HashMapObserver<K, O, S, D>
+impl<'q, K, O, S: ?Sized, D, V, Q: ?Sized, M> IndexMut<&'q Q> for HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, V>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = V>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Borrow<Q> + Clone + Eq + Hash,
+    V: Capture,
+    K: Borrow<Q> + Clone + Eq + Hash + Capture,
     Q: Eq + Hash,
 {
     fn index_mut(&mut self, index: &'q Q) -> &mut Self::Output {
@@ -561,13 +866,14 @@ where
 
 // TODO: this inserts elements one by one, which is much slower than `HashMap::extend`.
 // Consider a bulk-insert approach that updates `diff` in one pass.
-impl<K, O, S: ?Sized, D> Extend<(K, O::Head)> for HashMapObserver<K, O, S, D>
+impl<K, O, S: ?Sized, D, M> Extend<(K, O::Head)> for HashMapObserver<K, O, S, D>
 where
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashMap<K, O::Head>>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashMapLike<Key = K, Value = O::Head>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn extend<I: IntoIterator<Item = (K, O::Head)>>(&mut self, iter: I) {
         for (key, value) in iter {
@@ -576,55 +882,206 @@ where
     }
 }
 
-impl<K: Clone + Eq + Hash, V: Observe> Observe for HashMap<K, V> {
-    type Observer<'ob, S, D>
-        = HashMapObserver<K, V::Observer<'ob, V, Zero>, S, D>
-    where
-        Self: 'ob,
-        D: Unsigned,
-        S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
-
-    type Spec = DefaultSpec;
-}
-
-default_impl_ref_observe! {
-    impl [K, V] RefObserve for HashMap<K, V>;
-}
+macro_rules! impl_observe {
+    ($($map:ident)::+) => {
+        impl<K: Clone + Eq + Hash + Capture, V: Observe + Capture, H: BuildHasher> Observe for $($map)::+<K, V, H> {
+            type Observer<'ob, S, D>
+                = HashMapObserver<K, V::Observer<'ob, V, Zero>, S, D>
+            where
+                Self: 'ob,
+                D: Unsigned,
+                S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+            type Spec = DefaultSpec;
+        }
 
-impl<K, V> Snapshot for HashMap<K, V>
-where
-    K: Snapshot,
-    K::Snapshot: Eq + Hash,
-    V: Snapshot,
-{
-    type Snapshot = HashMap<K::Snapshot, V::Snapshot>;
+        default_impl_ref_observe! {
+            impl [K, V, H] RefObserve for $($map)::+<K, V, H>;
+        }
 
-    fn to_snapshot(&self) -> Self::Snapshot {
-        self.iter()
-            .map(|(key, value)| (key.to_snapshot(), value.to_snapshot()))
-            .collect()
-    }
+        impl<K, V, H> Snapshot for $($map)::+<K, V, H>
+        where
+            K: Snapshot,
+            K::Snapshot: Eq + Hash,
+            V: Snapshot,
+        {
+            type Snapshot = $($map)::+<K::Snapshot, V::Snapshot>;
+
+            fn to_snapshot(&self) -> Self::Snapshot {
+                self.iter()
+                    .map(|(key, value)| (key.to_snapshot(), value.to_snapshot()))
+                    .collect()
+            }
 
-    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
-        self.len() == snapshot.len()
-            && self
-                .iter()
-                .zip(snapshot.iter())
-                .all(|((key_a, value_a), (key_b, value_b))| key_a.eq_snapshot(key_b) && value_a.eq_snapshot(value_b))
-    }
+            fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+                self.len() == snapshot.len()
+                    && self.iter().zip(snapshot.iter()).all(|((key_a, value_a), (key_b, value_b))| {
+                        key_a.eq_snapshot(key_b) && value_a.eq_snapshot(value_b)
+                    })
+            }
+        }
+    };
 }
 
+impl_observe!(HashMap);
+#[cfg(feature = "hashbrown")]
+impl_observe!(hashbrown::HashMap);
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
 
     use morphix_test_utils::*;
     use serde_json::json;
 
-    use crate::adapter::Json;
+    use crate::adapter::{Cbor, Json, MessagePack, Yaml};
     use crate::helper::QuasiObserver;
-    use crate::observe::{ObserveExt, SerializeObserverExt};
-    use crate::{Mutation, MutationKind};
+    use crate::observe::{ObserveExt, SerializeObserver, SerializeObserverExt};
+    use crate::{Adapter, Mutation, MutationKind, Path};
+
+    #[test]
+    fn integer_keys() {
+        let mut map = HashMap::from([(1_u64, "x".to_string()), (2, "y".to_string())]);
+        let mut ob = map.__observe();
+        ob.get_mut(&1).unwrap().push('!');
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(Mutation {
+                path: vec!["1".into()].into(),
+                kind: MutationKind::Append(json!("!")),
+            }),
+        );
+        ob.insert(3, "z".to_string());
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(Mutation {
+                path: vec!["3".into()].into(),
+                kind: MutationKind::Replace(json!("z")),
+            }),
+        );
+    }
+
+    #[test]
+    fn integer_keys_through_json_pointer() {
+        let mut map = HashMap::from([(42_u64, "x".to_string()), (7, "y".to_string())]);
+        let mut value = serde_json::to_value(&map).unwrap();
+        let mut ob = map.__observe();
+        ob.get_mut(&42).unwrap().push('!');
+        ob.remove(&7);
+        let Json(mutation) = ob.flush().unwrap();
+        for (path, kind) in mutation.unwrap().flatten() {
+            let path = Path::from_json_pointer(&path.to_json_pointer().unwrap()).unwrap();
+            Json::mutate(&mut value, Mutation { path, kind }, &mut Default::default()).unwrap();
+        }
+        assert_eq!(value, json!({"42": "x!"}));
+    }
+
+    fn integer_keys_round_trip<A>(
+        to_value: fn(&HashMap<u64, String>) -> A::Value,
+        from_value: fn(A::Value) -> HashMap<u64, String>,
+    ) where
+        A: Adapter,
+        A::Error: Debug,
+        Option<Mutation<A::Value>>: From<A>,
+    {
+        let mut map = HashMap::from([(42_u64, "x".to_string()), (7, "y".to_string())]);
+        let mut value = to_value(&map);
+        let mut ob = map.__observe();
+        ob.get_mut(&42).unwrap().push('!');
+        ob.remove(&7);
+        ob.insert(3, "z".to_string());
+        let mutation = Option::from(ob.flush::<A>().unwrap()).unwrap();
+        A::mutate(&mut value, mutation, &mut Default::default()).unwrap();
+        assert_eq!(from_value(value), map);
+    }
+
+    #[test]
+    fn integer_keys_through_adapters() {
+        integer_keys_round_trip::<Json>(
+            |map| serde_json::to_value(map).unwrap(),
+            |value| serde_json::from_value(value).unwrap(),
+        );
+        integer_keys_round_trip::<Yaml>(
+            |map| serde_yaml_ng::to_value(map).unwrap(),
+            |value| serde_yaml_ng::from_value(value).unwrap(),
+        );
+        integer_keys_round_trip::<MessagePack>(
+            |map| rmpv::ext::to_value(map).unwrap(),
+            |value| rmpv::ext::from_value(value).unwrap(),
+        );
+        integer_keys_round_trip::<Cbor>(
+            |map| ciborium::Value::serialized(map).unwrap(),
+            |value| value.deserialized().unwrap(),
+        );
+    }
+
+    #[test]
+    fn composite_keys() {
+        let mut map = HashMap::from([((1, 2), "x".to_string())]);
+        let mut ob = map.__observe();
+        ob.get_mut(&(1, 2)).unwrap().push('!');
+        let mutations = unsafe { SerializeObserver::flush(&mut ob) };
+        assert!(mutations.is_replace());
+        assert_eq!(ob.untracked_ref().get(&(1, 2)), Some(&"x!".to_string()));
+        ob.insert((3, 4), "y".to_string());
+        let mutations = unsafe { SerializeObserver::flush(&mut ob) };
+        assert!(mutations.is_replace());
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn flush_flatten_composite_keys() {
+        let mut map = HashMap::from([((1, 2), "x".to_string())]);
+        let mut ob = map.__observe();
+        ob.get_mut(&(1, 2)).unwrap().push('!');
+        let mutations = unsafe { SerializeObserver::flat_flush(&mut ob) };
+        assert!(mutations.is_root_replace());
+        *ob.tracked_mut() = HashMap::from([((3, 4), "y".to_string())]);
+        let mutations = unsafe { SerializeObserver::flat_flush(&mut ob) };
+        assert!(mutations.is_root_replace());
+        let Json(mutation) = ob.flat_flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let mut map: HashMap<_, _, BuildHasherDefault<DefaultHasher>> = HashMap::default();
+        map.insert("a", "x".to_string());
+        let mut ob = map.__observe();
+        ob.get_mut("a").unwrap().push('!');
+        ob.insert("b", "y".to_string());
+        let Json(mutation) = ob.flush().unwrap();
+        let Some(Mutation {
+            kind: MutationKind::Batch(mut batch),
+            ..
+        }) = mutation
+        else {
+            panic!("expected a batch mutation");
+        };
+        batch.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(batch, vec![append!(a, json!("!")), replace!(b, json!("y"))]);
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown() {
+        let mut map = hashbrown::HashMap::from([("a", "x".to_string()), ("b", "y".to_string())]);
+        let mut ob = map.__observe();
+        ob.get_mut("a").unwrap().push('!');
+        ob.remove("b");
+        ob.insert("c", "z".to_string());
+        let Json(mutation) = ob.flush().unwrap();
+        let batch = sorted_mutations(mutation);
+        assert_eq!(batch, vec![append!(a, json!("!")), delete!(b), replace!(c, json!("z"))]);
+        *ob.tracked_mut() = hashbrown::HashMap::from([("d", "w".to_string())]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!({"d": "w"}))));
+    }
 
     #[test]
     fn remove_nonexistent_key() {
@@ -913,4 +1370,49 @@ mod tests {
         assert_eq!(batch[1], delete!(b));
         assert_eq!(batch[2], replace!(c, json!(30)));
     }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case =
+            fn(&mut super::HashMapObserver<String, crate::impls::StringObserver<'_, String>, HashMap<String, String>>);
+        let cases: [Case; 5] = [
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.remove("a");
+                ob.insert("c".into(), "z".into());
+            },
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.insert("a".into(), "y".into());
+                ob.get_mut("a").unwrap().push('?');
+                ob.get_mut("b").unwrap().push('!');
+            },
+            |ob| {
+                ob.insert("c".into(), "z".into());
+                ob.retain(|key, _| *key != "b");
+                ob.remove("c");
+            },
+            |ob| {
+                ob.get_mut("b").unwrap().push('!');
+                ob.clear();
+                ob.insert("b".into(), "y".into());
+            },
+            |ob| {
+                ob.remove("a");
+                ob.insert("a".into(), "y".into());
+                *ob.tracked_mut() = HashMap::from([("c".into(), "z".into())]);
+            },
+        ];
+        for mutate in cases {
+            let before = HashMap::from([("a".to_string(), "x".to_string()), ("b".into(), "y".into())]);
+            let mut map = before.clone();
+            let mut ob = map.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(map), flushed);
+        }
+    }
 }
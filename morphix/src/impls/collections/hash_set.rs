@@ -1,57 +1,279 @@
 //! Observer implementation for [`HashSet<T>`].
 
 use std::borrow::Borrow;
-use std::collections::hash_set::Drain;
 use std::collections::{HashSet, TryReserveError};
-use std::hash::Hash;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
+use serde::Serialize;
+
+use super::set::{SetMember, SetObserverState};
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::shallow::shallow_observer;
-use crate::helper::{AsDerefMut, QuasiObserver, Unsigned};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::observe::{DefaultSpec, Observer, SerializeObserver};
+use crate::{Mutations, Observe};
+
+/// Hash sets that can be observed by [`HashSetObserver`].
+///
+/// Implemented for [`HashSet<T, S>`] and, with the `hashbrown` feature, for
+/// [`hashbrown::HashSet<T, S>`], so that both share the same observer.
+pub trait HashSetLike {
+    /// The type of the members.
+    type Value: Eq + Hash;
+
+    /// The error returned by [`try_reserve`](Self::try_reserve).
+    type TryReserveError;
+
+    /// The iterator returned by [`drain`](Self::drain).
+    type Drain<'a>: Iterator<Item = Self::Value>
+    where
+        Self: 'a;
 
-shallow_observer! {
-    struct HashSetObserver<T>(HashSet<T>);
-}
+    /// The iterator returned by [`extract_if`](Self::extract_if).
+    type ExtractIf<'a, F>: Iterator<Item = Self::Value>
+    where
+        Self: 'a,
+        F: FnMut(&Self::Value) -> bool;
 
-default_impl_ref_observe! {
-    impl [T] RefObserve for HashSet<T>;
-}
+    /// See [`HashSet::is_empty`].
+    fn is_empty(&self) -> bool;
+
+    /// See [`HashSet::contains`].
+    fn contains<Q>(&self, value: &Q) -> bool
+    where
+        Self::Value: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashSet::insert`].
+    fn insert(&mut self, value: Self::Value) -> bool;
+
+    /// See [`HashSet::replace`].
+    fn replace(&mut self, value: Self::Value) -> Option<Self::Value>;
+
+    /// See [`HashSet::remove`].
+    fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        Self::Value: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashSet::take`].
+    fn take<Q>(&mut self, value: &Q) -> Option<Self::Value>
+    where
+        Self::Value: Borrow<Q>,
+        Q: Eq + Hash + ?Sized;
+
+    /// See [`HashSet::clear`].
+    fn clear(&mut self);
+
+    /// See [`HashSet::drain`].
+    fn drain(&mut self) -> Self::Drain<'_>;
+
+    /// See [`HashSet::extract_if`].
+    fn extract_if<F>(&mut self, pred: F) -> Self::ExtractIf<'_, F>
+    where
+        F: FnMut(&Self::Value) -> bool;
+
+    /// See [`HashSet::reserve`].
+    fn reserve(&mut self, additional: usize);
+
+    /// See [`HashSet::try_reserve`].
+    fn try_reserve(&mut self, additional: usize) -> Result<(), Self::TryReserveError>;
+
+    /// See [`HashSet::shrink_to_fit`].
+    fn shrink_to_fit(&mut self);
 
-struct LenGuard<'a, T> {
-    old_len: usize,
-    mutated: &'a mut bool,
-    inner: &'a mut HashSet<T>,
+    /// See [`HashSet::shrink_to`].
+    fn shrink_to(&mut self, min_capacity: usize);
 }
 
-impl<T> Drop for LenGuard<'_, T> {
-    fn drop(&mut self) {
-        if self.old_len != self.inner.len() {
-            *self.mutated = true;
+macro_rules! impl_hash_set_like {
+    ($($module:ident)::+, $error:ty) => {
+        impl<T: Eq + Hash, H: BuildHasher> HashSetLike for $($module)::+::HashSet<T, H> {
+            type Value = T;
+            type TryReserveError = $error;
+            type Drain<'a>
+                = $($module)::+::Drain<'a, T>
+            where
+                Self: 'a;
+            type ExtractIf<'a, F>
+                = $($module)::+::ExtractIf<'a, T, F>
+            where
+                Self: 'a,
+                F: FnMut(&T) -> bool;
+
+            fn is_empty(&self) -> bool {
+                $($module)::+::HashSet::is_empty(self)
+            }
+
+            fn contains<Q>(&self, value: &Q) -> bool
+            where
+                T: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashSet::contains(self, value)
+            }
+
+            fn insert(&mut self, value: T) -> bool {
+                $($module)::+::HashSet::insert(self, value)
+            }
+
+            fn replace(&mut self, value: T) -> Option<T> {
+                $($module)::+::HashSet::replace(self, value)
+            }
+
+            fn remove<Q>(&mut self, value: &Q) -> bool
+            where
+                T: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashSet::remove(self, value)
+            }
+
+            fn take<Q>(&mut self, value: &Q) -> Option<T>
+            where
+                T: Borrow<Q>,
+                Q: Eq + Hash + ?Sized,
+            {
+                $($module)::+::HashSet::take(self, value)
+            }
+
+            fn clear(&mut self) {
+                $($module)::+::HashSet::clear(self)
+            }
+
+            fn drain(&mut self) -> Self::Drain<'_> {
+                $($module)::+::HashSet::drain(self)
+            }
+
+            fn extract_if<F>(&mut self, pred: F) -> Self::ExtractIf<'_, F>
+            where
+                F: FnMut(&T) -> bool,
+            {
+                $($module)::+::HashSet::extract_if(self, pred)
+            }
+
+            fn reserve(&mut self, additional: usize) {
+                $($module)::+::HashSet::reserve(self, additional)
+            }
+
+            fn try_reserve(&mut self, additional: usize) -> Result<(), $error> {
+                $($module)::+::HashSet::try_reserve(self, additional)
+            }
+
+            fn shrink_to_fit(&mut self) {
+                $($module)::+::HashSet::shrink_to_fit(self)
+            }
+
+            fn shrink_to(&mut self, min_capacity: usize) {
+                $($module)::+::HashSet::shrink_to(self, min_capacity)
+            }
         }
-    }
+    };
+}
+
+impl_hash_set_like!(std::collections::hash_set, TryReserveError);
+#[cfg(feature = "hashbrown")]
+impl_hash_set_like!(hashbrown::hash_set, hashbrown::TryReserveError);
+
+/// Observer implementation for [`HashSet<T>`] and other [`HashSetLike`] sets.
+///
+/// With the `set` feature, tracks the members inserted into and removed from the set since the last
+/// flush, which are emitted as [`Remove`](crate::MutationKind::Remove) /
+/// [`Add`](crate::MutationKind::Add) mutations. Inserting a member that was removed since the last
+/// flush (or vice versa) cancels out. Without it, any change replaces the whole set.
+///
+/// ## Limitations
+///
+/// With the `set` feature, most methods require `T: Clone` (see [`SetMember`]) because the observer
+/// stores the inserted and removed members.
+pub struct HashSetObserver<'ob, T, S: ?Sized, D = Zero> {
+    ptr: Pointer<S>,
+    state: SetObserverState<HashSet<T>>,
+    phantom: PhantomData<&'ob mut D>,
 }
 
-impl<T> Deref for LenGuard<'_, T> {
-    type Target = HashSet<T>;
+impl<'ob, T, S: ?Sized, D> Deref for HashSetObserver<'ob, T, S, D> {
+    type Target = Pointer<S>;
 
     fn deref(&self) -> &Self::Target {
-        self.inner
+        &self.ptr
     }
 }
 
-impl<T> DerefMut for LenGuard<'_, T> {
+impl<'ob, T, S: ?Sized, D> DerefMut for HashSetObserver<'ob, T, S, D> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner
+        std::ptr::from_mut(self).expose_provenance();
+        Pointer::invalidate(&mut self.ptr);
+        &mut self.ptr
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> QuasiObserver for HashSetObserver<'ob, T, S, D>
+where
+    T: Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDeref<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture,
+{
+    type Head = S;
+    type OuterDepth = Succ<Zero>;
+    type InnerDepth = D;
+
+    fn invalidate(this: &mut Self) {
+        Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> Observer for HashSetObserver<'ob, T, S, D>
+where
+    T: Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDerefMut<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture,
+{
+    fn observe(head: &mut Self::Head) -> Self {
+        let this = Self {
+            ptr: Pointer::new(head),
+            state: Default::default(),
+            phantom: PhantomData,
+        };
+        Pointer::register_state::<_, D>(&this.ptr, &this.state);
+        this
+    }
+
+    unsafe fn relocate(this: &mut Self, head: &mut Self::Head) {
+        Pointer::set(this, head);
     }
 }
 
-impl<'ob, S: ?Sized, D, T> HashSetObserver<'ob, S, D>
+impl<'ob, T, S: ?Sized, D, M> SerializeObserver for HashSetObserver<'ob, T, S, D>
 where
+    T: Serialize + SetMember + Eq + Hash + 'static,
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashSet<T>>,
+    S: AsDeref<D, Target = M>,
+    M: HashSetLike<Value = T> + Serialize + 'static,
 {
-    fn nonempty_mut(&mut self) -> &mut HashSet<T> {
+    unsafe fn flush(this: &mut Self) -> Mutations {
+        this.state.flush((*this.ptr).as_deref())
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> HashSetObserver<'ob, T, S, D>
+where
+    T: SetMember + Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDerefMut<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture + 'ob,
+{
+    fn nonempty_mut(&mut self) -> &mut M {
         if (*self).untracked_ref().is_empty() {
             self.untracked_mut()
         } else {
@@ -59,86 +281,149 @@ where
         }
     }
 
-    fn guarded_mut(&mut self) -> LenGuard<'_, T> {
-        let inner = (*self.ptr).as_deref_mut();
-        LenGuard {
-            old_len: inner.len(),
-            mutated: &mut self.mutated,
-            inner,
+    delegate_methods! { nonempty_mut() as HashSet =>
+        pub fn drain(&mut self) -> M::Drain<'_>;
+        pub fn clear(&mut self);
+    }
+
+    delegate_methods! { untracked_mut() as HashSet =>
+        pub fn reserve(&mut self, additional: usize);
+        pub fn try_reserve(&mut self, additional: usize) -> Result<(), M::TryReserveError>;
+        pub fn shrink_to_fit(&mut self);
+        pub fn shrink_to(&mut self, min_capacity: usize);
+    }
+
+    /// See [`HashSet::insert`].
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.state.mutated {
+            return self.tracked_mut().insert(value);
+        }
+        if (*self.ptr).as_deref().contains(&value) {
+            return false;
         }
+        self.state.mark_added(&value);
+        (*self.ptr).as_deref_mut().insert(value)
     }
 
-    delegate_methods! { nonempty_mut() as HashSet =>
-        pub fn drain(&mut self) -> Drain<'_, T>;
-        pub fn clear(&mut self);
+    /// See [`HashSet::replace`].
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        if self.state.mutated {
+            return self.tracked_mut().replace(value);
+        }
+        match (*self.ptr).as_deref_mut().take(&value) {
+            Some(old_value) => {
+                self.state.mark_replaced(&old_value, &value);
+                (*self.ptr).as_deref_mut().insert(value);
+                Some(old_value)
+            }
+            None => {
+                self.state.mark_added(&value);
+                (*self.ptr).as_deref_mut().insert(value);
+                None
+            }
+        }
+    }
+
+    /// See [`HashSet::remove`].
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.state.mutated {
+            return self.tracked_mut().remove(value);
+        }
+        let Some(value) = (*self.ptr).as_deref_mut().take(value) else {
+            return false;
+        };
+        self.state.mark_removed(&value);
+        true
+    }
+
+    /// See [`HashSet::take`].
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.state.mutated {
+            return self.tracked_mut().take(value);
+        }
+        let value = (*self.ptr).as_deref_mut().take(value)?;
+        self.state.mark_removed(&value);
+        Some(value)
     }
 
-    delegate_methods! { guarded_mut() as HashSet =>
-        pub fn retain<F>(&mut self, f: F) where F: FnMut(&T) -> bool;
+    /// See [`HashSet::retain`].
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.extract_if(|v| !f(v)).for_each(drop);
     }
 
     /// See [`HashSet::extract_if`].
-    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, M::ExtractIf<'_, F>>
     where
         F: FnMut(&T) -> bool,
     {
-        ExtractIf {
-            inner: (*self.ptr).as_deref_mut().extract_if(pred),
-            mutated: &mut self.mutated,
-        }
+        let inner = (*self.ptr).as_deref_mut().extract_if(pred);
+        let state = if self.state.mutated {
+            None
+        } else {
+            Some(&mut self.state)
+        };
+        ExtractIf { inner, state }
     }
 }
 
-impl<'ob, S: ?Sized, D, T> HashSetObserver<'ob, S, D>
+impl<'ob, T, S: ?Sized, D, M> Extend<T> for HashSetObserver<'ob, T, S, D>
 where
+    T: SetMember + Eq + Hash + Capture,
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashSet<T>>,
-    T: Eq + Hash,
+    S: AsDerefMut<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture + 'ob,
 {
-    delegate_methods! { untracked_mut() as HashSet =>
-        pub fn reserve(&mut self, additional: usize);
-        pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>;
-        pub fn shrink_to_fit(&mut self);
-        pub fn shrink_to(&mut self, min_capacity: usize);
-    }
-
-    delegate_methods! { tracked_mut() as HashSet =>
-        pub fn replace(&mut self, value: T) -> Option<T>;
-    }
-
-    delegate_methods! { guarded_mut() as HashSet =>
-        pub fn insert(&mut self, value: T) -> bool;
-        pub fn remove<Q>(&mut self, value: &Q) -> bool where T: Borrow<Q>, Q: Hash + Eq + ?Sized;
-        pub fn take<Q>(&mut self, value: &Q) -> Option<T> where T: Borrow<Q>, Q: Hash + Eq + ?Sized;
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
     }
 }
 
-impl<'ob, S: ?Sized, D, T, U> Extend<U> for HashSetObserver<'ob, S, D>
+impl<'a, 'ob, T, S: ?Sized, D, M> Extend<&'a T> for HashSetObserver<'ob, T, S, D>
 where
+    T: Copy + Eq + Hash + Capture + 'a,
     D: Unsigned,
-    S: AsDerefMut<D, Target = HashSet<T>>,
-    HashSet<T>: Extend<U>,
+    S: AsDerefMut<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture + 'ob,
 {
-    fn extend<I: IntoIterator<Item = U>>(&mut self, iter: I) {
-        self.guarded_mut().extend(iter)
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(*value);
+        }
     }
 }
 
 /// Iterator produced by [`HashSetObserver::extract_if`].
-pub struct ExtractIf<'a, T, F> {
-    inner: std::collections::hash_set::ExtractIf<'a, T, F>,
-    mutated: &'a mut bool,
+pub struct ExtractIf<'a, T, I> {
+    inner: I,
+    state: Option<&'a mut SetObserverState<HashSet<T>>>,
 }
 
-impl<T, F: FnMut(&T) -> bool> Iterator for ExtractIf<'_, T, F> {
+impl<T, I> Iterator for ExtractIf<'_, T, I>
+where
+    T: SetMember + Eq + Hash,
+    I: Iterator<Item = T>,
+{
     type Item = T;
 
     fn next(&mut self) -> Option<T> {
-        let result = self.inner.next();
-        if result.is_some() {
-            *self.mutated = true;
+        let value = self.inner.next()?;
+        if let Some(state) = &mut self.state {
+            state.mark_removed(&value);
         }
-        result
+        Some(value)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -146,11 +431,99 @@ impl<T, F: FnMut(&T) -> bool> Iterator for ExtractIf<'_, T, F> {
     }
 }
 
+impl<T, I> FusedIterator for ExtractIf<'_, T, I>
+where
+    T: SetMember + Eq + Hash,
+    I: FusedIterator<Item = T>,
+{
+}
+
+impl<T, I: Debug> Debug for ExtractIf<'_, T, I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> Debug for HashSetObserver<'ob, T, S, D>
+where
+    T: Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDeref<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture,
+    M: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HashSetObserver").field(&self.untracked_ref()).finish()
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> PartialEq<M> for HashSetObserver<'ob, T, S, D>
+where
+    T: Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDeref<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture + PartialEq,
+{
+    fn eq(&self, other: &M) -> bool {
+        self.untracked_ref().eq(other)
+    }
+}
+
+impl<'ob, T, S1: ?Sized, S2: ?Sized, D1, D2, M1, M2> PartialEq<HashSetObserver<'ob, T, S2, D2>>
+    for HashSetObserver<'ob, T, S1, D1>
+where
+    T: Eq + Hash + Capture,
+    D1: Unsigned,
+    D2: Unsigned,
+    S1: AsDeref<D1, Target = M1>,
+    S2: AsDeref<D2, Target = M2>,
+    M1: HashSetLike<Value = T> + Capture + PartialEq<M2>,
+    M2: HashSetLike<Value = T> + Capture,
+{
+    fn eq(&self, other: &HashSetObserver<'ob, T, S2, D2>) -> bool {
+        self.untracked_ref().eq(other.untracked_ref())
+    }
+}
+
+impl<'ob, T, S: ?Sized, D, M> Eq for HashSetObserver<'ob, T, S, D>
+where
+    T: Eq + Hash + Capture,
+    D: Unsigned,
+    S: AsDeref<D, Target = M>,
+    M: HashSetLike<Value = T> + Capture + Eq,
+{
+}
+
+macro_rules! impl_observe {
+    ($($set:ident)::+) => {
+        impl<T: SetMember + Eq + Hash + Capture, H: BuildHasher> Observe for $($set)::+<T, H> {
+            type Observer<'ob, S, D>
+                = HashSetObserver<'ob, T, S, D>
+            where
+                Self: 'ob,
+                D: Unsigned,
+                S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+            type Spec = DefaultSpec;
+        }
+
+        default_impl_ref_observe! {
+            impl [T, H] RefObserve for $($set)::+<T, H>;
+        }
+    };
+}
+
+impl_observe!(HashSet);
+#[cfg(feature = "hashbrown")]
+impl_observe!(hashbrown::HashSet);
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
+    use std::hash::{BuildHasherDefault, DefaultHasher};
 
-    use serde_json::Value;
+    use morphix_test_utils::*;
+    use serde_json::{Value, json};
 
     use crate::adapter::Json;
     use crate::helper::QuasiObserver;
@@ -164,6 +537,17 @@ mod tests {
         }
     }
 
+    fn sorted_members(value: Value) -> Vec<i64> {
+        let mut members = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_i64().unwrap())
+            .collect::<Vec<_>>();
+        members.sort();
+        members
+    }
+
     #[test]
     fn no_change() {
         let mut set = HashSet::from([1, 2, 3]);
@@ -173,11 +557,35 @@ mod tests {
     }
 
     #[test]
-    fn insert_triggers_replace() {
+    fn insert_as_add() {
         let mut set = HashSet::from([1, 2]);
         let mut ob = set.__observe();
         ob.insert(3);
         let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(add!(_, json!([3]))));
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let mut set: HashSet<_, BuildHasherDefault<DefaultHasher>> = HashSet::from_iter([1, 2]);
+        let mut ob = set.__observe();
+        ob.insert(3);
+        ob.remove(&1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, remove!(_, json!([1])), add!(_, json!([3])))));
+    }
+
+    #[test]
+    #[cfg(feature = "hashbrown")]
+    fn hashbrown() {
+        let mut set = hashbrown::HashSet::from([1, 2]);
+        let mut ob = set.__observe();
+        ob.insert(3);
+        ob.remove(&1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, remove!(_, json!([1])), add!(_, json!([3])))));
+        ob.clear();
+        let Json(mutation) = ob.flush().unwrap();
         assert!(is_replace(&mutation));
     }
 
@@ -191,12 +599,12 @@ mod tests {
     }
 
     #[test]
-    fn remove_existing_triggers_replace() {
+    fn remove_as_remove() {
         let mut set = HashSet::from([1, 2, 3]);
         let mut ob = set.__observe();
         assert!(ob.remove(&2));
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        assert_eq!(mutation, Some(remove!(_, json!([2]))));
     }
 
     #[test]
@@ -209,12 +617,12 @@ mod tests {
     }
 
     #[test]
-    fn take_triggers_replace() {
+    fn take_as_remove() {
         let mut set = HashSet::from([1, 2, 3]);
         let mut ob = set.__observe();
         assert_eq!(ob.take(&2), Some(2));
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        assert_eq!(mutation, Some(remove!(_, json!([2]))));
     }
 
     #[test]
@@ -226,6 +634,31 @@ mod tests {
         assert_eq!(mutation, None);
     }
 
+    #[test]
+    fn insert_and_remove() {
+        let mut set = HashSet::from(["a".to_string(), "b".to_string()]);
+        let mut ob = set.__observe();
+        ob.insert("c".to_string());
+        ob.remove("a");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(_, remove!(_, json!(["a"])), add!(_, json!(["c"]))))
+        );
+    }
+
+    #[test]
+    fn insert_then_remove_cancelled() {
+        let mut set = HashSet::from([1, 2]);
+        let mut ob = set.__observe();
+        ob.insert(3);
+        ob.remove(&3);
+        ob.remove(&1);
+        ob.insert(1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
     #[test]
     fn clear_empty_no_mutation() {
         let mut set: HashSet<i32> = HashSet::new();
@@ -240,8 +673,9 @@ mod tests {
         let mut set = HashSet::from([1, 2, 3]);
         let mut ob = set.__observe();
         ob.clear();
+        ob.insert(4);
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        assert_eq!(mutation, Some(replace!(_, json!([4]))));
     }
 
     #[test]
@@ -250,7 +684,7 @@ mod tests {
         let mut ob = set.__observe();
         ob.insert(3);
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        assert_eq!(mutation, Some(add!(_, json!([3]))));
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, None);
     }
@@ -265,19 +699,26 @@ mod tests {
     }
 
     #[test]
-    fn extend_triggers_replace() {
+    fn extend_as_add() {
         let mut set = HashSet::from([1]);
         let mut ob = set.__observe();
-        ob.extend([2, 3, 4]);
+        ob.extend([1, 2, 3, 4]);
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        let Some(Mutation {
+            kind: MutationKind::Add(members),
+            ..
+        }) = mutation
+        else {
+            panic!("expected add mutation, got {mutation:?}");
+        };
+        assert_eq!(sorted_members(members), vec![2, 3, 4]);
     }
 
     #[test]
     fn extend_duplicates_no_mutation() {
         let mut set = HashSet::from([1, 2, 3]);
         let mut ob = set.__observe();
-        ob.extend([1, 2, 3]);
+        ob.extend(&[1, 2, 3]);
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, None);
     }
@@ -292,13 +733,20 @@ mod tests {
     }
 
     #[test]
-    fn retain_triggers_replace() {
+    fn retain_as_remove() {
         let mut set = HashSet::from([1, 2, 3, 4]);
         let mut ob = set.__observe();
         ob.retain(|&x| x % 2 == 0);
         assert_eq!(*ob.untracked_ref(), HashSet::from([2, 4]));
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        let Some(Mutation {
+            kind: MutationKind::Remove(members),
+            ..
+        }) = mutation
+        else {
+            panic!("expected remove mutation, got {mutation:?}");
+        };
+        assert_eq!(sorted_members(members), vec![1, 3]);
     }
 
     #[test]
@@ -311,13 +759,21 @@ mod tests {
     }
 
     #[test]
-    fn extract_if_triggers_replace() {
+    fn extract_if_as_remove() {
         let mut set = HashSet::from([1, 2, 3, 4]);
         let mut ob = set.__observe();
+        ob.insert(6);
         let extracted: HashSet<_> = ob.extract_if(|&x| x % 2 == 0).collect();
-        assert_eq!(extracted, HashSet::from([2, 4]));
+        assert_eq!(extracted, HashSet::from([2, 4, 6]));
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        let Some(Mutation {
+            kind: MutationKind::Remove(members),
+            ..
+        }) = mutation
+        else {
+            panic!("expected remove mutation, got {mutation:?}");
+        };
+        assert_eq!(sorted_members(members), vec![2, 4]);
     }
 
     #[test]
@@ -350,11 +806,23 @@ mod tests {
     }
 
     #[test]
-    fn replace_triggers_replace() {
+    fn replace_existing() {
         let mut set = HashSet::from([1, 2, 3]);
         let mut ob = set.__observe();
-        ob.replace(2);
+        assert_eq!(ob.replace(2), Some(2));
+        assert_eq!(ob.replace(4), None);
         let Json(mutation) = ob.flush().unwrap();
-        assert!(is_replace(&mutation));
+        let Some(Mutation {
+            kind: MutationKind::Batch(mutations),
+            ..
+        }) = mutation
+        else {
+            panic!("expected batch mutation, got {mutation:?}");
+        };
+        assert_eq!(mutations[0], remove!(_, json!([2])));
+        let MutationKind::Add(members) = mutations[1].kind.clone() else {
+            panic!("expected add mutation, got {:?}", mutations[1]);
+        };
+        assert_eq!(sorted_members(members), vec![2, 4]);
     }
 }
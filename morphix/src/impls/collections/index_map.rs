@@ -13,11 +13,17 @@ use indexmap::map::Entry;
 use indexmap::{Equivalent, IndexMap, TryReserveError};
 use serde::Serialize;
 
+use super::flat_replace;
+#[cfg(feature = "inverse")]
+use super::insert_restore;
 use crate::general::Snapshot;
+#[cfg(feature = "inverse")]
+use crate::helper::Captor;
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::reorder::{permutation_moves, permute};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
-use crate::{MutationKind, Mutations, Observe, PathSegment};
+use crate::{MutationKind, Mutations, Observe, PathError, PathSegment};
 
 enum ValueState {
     /// Key existed in the original map and was overwritten via
@@ -33,6 +39,13 @@ enum ValueState {
 struct IndexMapObserverState<K, O> {
     mutated: bool,
     diff: IndexMap<K, ValueState>,
+    /// Moves of entries, as `(from, to)` pairs in order. These are only recorded while `diff` is
+    /// empty, so that the indices refer to the original entries.
+    moves: Vec<(usize, usize)>,
+    /// Values of the original map displaced since the last flush, together with the inverses of
+    /// their observers.
+    #[cfg(feature = "inverse")]
+    captured: IndexMap<K, Captor>,
     /// Boxed to ensure pointer stability: [`IndexMap`] rehashing moves all entries to a new
     /// allocation, which would invalidate references to inline values. [`Box`] adds a layer
     /// of indirection so that only the pointer is moved, not the observer itself.
@@ -44,6 +57,9 @@ impl<K, O> Default for IndexMapObserverState<K, O> {
         Self {
             mutated: false,
             diff: Default::default(),
+            moves: Default::default(),
+            #[cfg(feature = "inverse")]
+            captured: Default::default(),
             inner: Default::default(),
         }
     }
@@ -51,26 +67,105 @@ impl<K, O> Default for IndexMapObserverState<K, O> {
 
 impl<K, O> Invalidate<IndexMap<K, O::Head>> for IndexMapObserverState<K, O>
 where
-    K: Clone + Eq + Hash,
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
 {
     fn invalidate(&mut self, map: &IndexMap<K, O::Head>) {
         if !self.mutated {
             self.mutated = true;
-            for key in map.keys() {
-                self.mark_deleted(key.clone());
+            self.moves.clear();
+            for (key, value) in map {
+                self.mark_deleted(key.clone(), value);
             }
         }
         self.inner.get_mut().clear();
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, map: &IndexMap<K, O::Head>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if !self.mutated {
+            for (key, ob) in unsafe { &*self.inner.get() } {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, O::inverse(ob, map.get(key)?)?);
+                }
+            }
+        }
+        mutations.extend(self.inverse_structure(map)?);
+        Some(mutations)
+    }
 }
 
 impl<K, O> IndexMapObserverState<K, O>
 where
-    K: Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
 {
-    fn mark_deleted(&mut self, key: K) {
-        self.inner.get_mut().swap_remove(&key);
+    /// Captures the original `value` of `key` with the inverse of its observer `ob`, unless it has
+    /// been captured since the last flush.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture(&mut self, key: &K, value: &O::Head, ob: Option<&O>) {
+        #[cfg(feature = "inverse")]
+        self.captured
+            .entry(key.clone())
+            .or_default()
+            .capture_with(value, || match ob {
+                Some(ob) => O::inverse(ob, value),
+                None => Some(Mutations::new()),
+            });
+    }
+
+    /// Returns the mutations which restore the keys of the original map from the current `map`,
+    /// leaving the inverses of the value observers aside.
+    ///
+    /// Restored entries are appended to the map rather than put back at their original indices.
+    /// Returns [`None`] if entries have been both moved and displaced, as the moves can then no
+    /// longer be reverted.
+    #[cfg(feature = "inverse")]
+    fn inverse_structure(&self, map: &IndexMap<K, O::Head>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if !self.moves.is_empty() && !self.diff.is_empty() {
+            return None;
+        }
+        if self.mutated {
+            for key in map.keys() {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(PathSegment::from_key(key).ok()?, MutationKind::Delete);
+                }
+            }
+        }
+        for (key, value_state) in &self.diff {
+            let segment = PathSegment::from_key(key).ok()?;
+            match value_state {
+                ValueState::Inserted => mutations.insert(segment, MutationKind::Delete),
+                ValueState::Replaced | ValueState::Deleted => {
+                    insert_restore(&mut mutations, segment, self.captured.get(key)?.inverse()?);
+                }
+            }
+        }
+        #[cfg(feature = "move")]
+        for &(from, to) in self.moves.iter().rev() {
+            mutations.extend(MutationKind::Move { from: to, to: from });
+        }
+        Some(mutations)
+    }
+
+    /// Attaches the inverse of the changes to the keys since the last flush to `mutations`, given
+    /// the current `map`, and resets the captured values.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn attach(&mut self, map: &IndexMap<K, O::Head>, mutations: Mutations) -> Mutations {
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(self.inverse_structure(map));
+        #[cfg(feature = "inverse")]
+        self.captured.clear();
+        mutations
+    }
+
+    fn mark_deleted(&mut self, key: K, value: &O::Head) {
+        let ob = self.inner.get_mut().swap_remove(&key);
+        if !self.diff.contains_key(&key) {
+            self.capture(&key, value, ob.as_deref());
+        }
         match self.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Inserted) {
@@ -99,7 +194,9 @@ where
 #[cfg_version(indexmap = "2.10")]
 impl<K, V, O, F> Iterator for ExtractIf<'_, K, V, O, F>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
     F: FnMut(&K, &mut V) -> bool,
 {
     type Item = (K, V);
@@ -107,7 +204,7 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let (key, value) = self.inner.next()?;
         if let Some(state) = &mut self.state {
-            state.mark_deleted(key.clone());
+            state.mark_deleted(key.clone(), &value);
         }
         Some((key, value))
     }
@@ -120,7 +217,9 @@ where
 #[cfg_version(indexmap = "2.10")]
 impl<K, V, O, F> FusedIterator for ExtractIf<'_, K, V, O, F>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
+    O: QuasiObserver<InnerDepth = Zero, Head = V>,
+    V: Capture,
     F: FnMut(&K, &mut V) -> bool,
 {
 }
@@ -143,6 +242,8 @@ where
 /// [`get_mut`](Self::get_mut)) require `K: Clone` because the observer maintains its own
 /// [`IndexMap`] of cloned keys to track per-key observers independently of the observed map's
 /// internal storage.
+///
+/// Keys are converted into path segments as described in [Map keys](super#map-keys).
 pub struct IndexMapObserver<K, O, S: ?Sized, D = Zero> {
     ptr: Pointer<S>,
     state: IndexMapObserverState<K, O>,
@@ -167,10 +268,11 @@ impl<K, O, S: ?Sized, D> DerefMut for IndexMapObserver<K, O, S, D> {
 
 impl<K, V, O, S: ?Sized, D> QuasiObserver for IndexMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
 {
     type Head = S;
     type OuterDepth = Succ<Zero>;
@@ -179,6 +281,11 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<K, O, S: ?Sized, D> Observer for IndexMapObserver<K, O, S, D>
@@ -186,8 +293,8 @@ where
     D: Unsigned,
     S: AsDeref<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let this = Self {
@@ -210,19 +317,27 @@ where
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Eq + Hash + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Eq + Hash + 'static,
 {
-    unsafe fn partial_flush(&mut self) -> Mutations {
+    unsafe fn partial_flush(&mut self) -> Result<Mutations, PathError> {
+        #[cfg(feature = "inverse")]
+        let inverse = self.state.inverse_structure((*self.ptr).as_deref());
+        #[cfg(feature = "inverse")]
+        self.state.captured.clear();
         let diff = std::mem::take(&mut self.state.diff);
         let mut inner = std::mem::take(self.state.inner.get_mut());
         let mut mutations = Mutations::new();
+        #[cfg(feature = "move")]
+        for (from, to) in self.state.moves.drain(..) {
+            mutations.extend(MutationKind::Move { from, to });
+        }
         for (key, value_state) in diff {
             match value_state {
                 ValueState::Deleted => {
                     #[cfg(feature = "delete")]
-                    mutations.insert(key, MutationKind::Delete);
+                    mutations.insert(PathSegment::from_key(&key)?, MutationKind::Delete);
                     #[cfg(not(feature = "delete"))]
-                    return Mutations::replace((*self).untracked_ref());
+                    return Ok(Mutations::replace((*self).untracked_ref()));
                 }
                 ValueState::Replaced | ValueState::Inserted => {
                     inner.swap_remove(&key);
@@ -230,19 +345,25 @@ where
                         .untracked_ref()
                         .get(&key)
                         .expect("replaced key not found in observed map");
-                    mutations.insert(key, Mutations::replace(value));
+                    mutations.insert(PathSegment::from_key(&key)?, Mutations::replace(value));
                 }
             }
         }
+        // The inverse of the keys is attached before the value mutations, so that the inverses of
+        // the values are applied first.
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
         for (key, mut ob) in inner {
             let value = self
                 .untracked_mut()
                 .get_mut(&key)
                 .expect("observer key not found in observed map");
             unsafe { O::relocate(&mut ob, value) }
-            mutations.insert(key, unsafe { O::flush(&mut ob) });
+            mutations.insert(PathSegment::from_key(&key)?, unsafe { O::flush(&mut ob) });
         }
-        mutations
+        Ok(mutations)
     }
 }
 
@@ -252,39 +373,35 @@ where
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
-    K: Serialize + Clone + Eq + Hash + Into<PathSegment> + 'static,
+    K: Serialize + Clone + Eq + Hash + 'static,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            // Fall back to replacing the whole map if a key cannot be used as a path segment.
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
+        let map = (*this.ptr).as_deref();
+        let mutations = this.state.attach(map, Mutations::replace(map));
         this.state.mutated = false;
         this.state.diff.clear();
+        this.state.moves.clear();
         this.state.inner.get_mut().clear();
-        Mutations::replace((*this).untracked_ref())
+        mutations
     }
 
     unsafe fn flat_flush(this: &mut Self) -> Mutations {
         if !this.state.mutated {
-            return unsafe { this.partial_flush() };
+            return unsafe { this.partial_flush() }.unwrap_or_else(|_| Mutations::replace((*this).untracked_ref()));
         }
-        this.state.mutated = false;
         this.state.inner.get_mut().clear();
         // After DerefMut, diff contains only Deleted entries representing original keys.
         // Emit Replace for each current key, Delete for original keys no longer present.
-        let mut diff = std::mem::take(&mut this.state.diff);
         let map = (*this.ptr).as_deref();
-        let mut mutations = Mutations::new().with_replace(true);
-        for (key, value) in map {
-            diff.swap_remove(key);
-            mutations.insert(key.clone(), Mutations::replace(value));
-        }
-        for (key, _) in diff {
-            #[cfg(feature = "delete")]
-            mutations.insert(key, MutationKind::Delete);
-            #[cfg(not(feature = "delete"))]
-            unreachable!("delete feature is not enabled");
-        }
+        let deleted = this.state.diff.keys().filter(|key| !map.contains_key(*key));
+        let mutations = flat_replace(map, deleted).unwrap_or_else(|_| Mutations::replace(map));
+        let mutations = this.state.attach(map, mutations);
+        this.state.mutated = false;
+        this.state.diff.clear();
         mutations
     }
 }
@@ -294,7 +411,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Clone + Eq + Hash,
+    V: Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     delegate_methods! { untracked_mut() as IndexMap =>
         pub fn reserve(&mut self, additional: usize);
@@ -306,18 +424,157 @@ where
     }
 
     delegate_methods! { tracked_mut() as IndexMap =>
-        pub fn sort_keys(&mut self) where K: Ord;
-        pub fn sort_by<F>(&mut self, cmp: F) where F: FnMut(&K, &V, &K, &V) -> Ordering;
-        #[cfg_version(indexmap = "2.11")]
-        pub fn sort_by_key<T, F>(&mut self, sort_key: F) where T: Ord, F: FnMut(&K, &V) -> T;
-        pub fn sort_unstable_keys(&mut self) where K: Ord;
-        pub fn sort_unstable_by<F>(&mut self, cmp: F) where F: FnMut(&K, &V, &K, &V) -> Ordering;
-        #[cfg_version(indexmap = "2.11")]
-        pub fn sort_unstable_by_key<T, F>(&mut self, sort_key: F) where T: Ord, F: FnMut(&K, &V) -> T;
-        pub fn sort_by_cached_key<T, F>(&mut self, sort_key: F) where T: Ord, F: FnMut(&K, &V) -> T;
         pub fn reverse(&mut self);
-        pub fn move_index(&mut self, from: usize, to: usize);
-        pub fn swap_indices(&mut self, a: usize, b: usize);
+    }
+
+    fn is_movable(&self) -> bool {
+        cfg!(feature = "move") && !self.state.mutated && self.state.diff.is_empty()
+    }
+
+    /// Rearranges the leading `len` entries by the permutation computed by `f`, recording the
+    /// rearrangement as moves.
+    ///
+    /// Returns `false` without touching the map if the rearrangement cannot be recorded.
+    fn permute_with(&mut self, len: usize, f: impl FnOnce(&IndexMap<K, V>, &mut [usize])) -> bool {
+        if !self.is_movable() {
+            return false;
+        }
+        let map = (*self.ptr).as_deref_mut();
+        let mut perm = (0..len).collect::<Vec<_>>();
+        f(map, &mut perm);
+        let limit = map.len().saturating_sub(self.state.moves.len());
+        let Some(moves) = permutation_moves(&perm, limit) else {
+            let map = self.tracked_mut();
+            let mut entries = std::mem::take(map).into_iter().collect::<Vec<_>>();
+            permute(&mut entries, &perm);
+            map.extend(entries);
+            return true;
+        };
+        for (from, to) in moves {
+            map.move_index(from, to);
+            self.state.moves.push((from, to));
+        }
+        true
+    }
+
+    fn sort_with(&mut self, mut cmp: impl FnMut(&K, &V, &K, &V) -> Ordering) -> bool {
+        let len = (*self).untracked_ref().len();
+        self.permute_with(len, |map, perm| {
+            perm.sort_by(|&i, &j| {
+                let (k1, v1) = map.get_index(i).unwrap();
+                let (k2, v2) = map.get_index(j).unwrap();
+                cmp(k1, v1, k2, v2)
+            })
+        })
+    }
+
+    fn sort_with_key<T: Ord>(&mut self, mut sort_key: impl FnMut(&K, &V) -> T, cached: bool) -> bool {
+        let len = (*self).untracked_ref().len();
+        self.permute_with(len, |map, perm| {
+            let mut key = |&i: &usize| {
+                let (k, v) = map.get_index(i).unwrap();
+                sort_key(k, v)
+            };
+            if cached {
+                perm.sort_by_cached_key(key);
+            } else {
+                perm.sort_by_key(&mut key);
+            }
+        })
+    }
+
+    /// See [`IndexMap::sort_keys`].
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        if !self.sort_with(|k1, _, k2, _| k1.cmp(k2)) {
+            self.tracked_mut().sort_keys();
+        }
+    }
+
+    /// See [`IndexMap::sort_by`].
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        if !self.sort_with(&mut cmp) {
+            self.tracked_mut().sort_by(cmp);
+        }
+    }
+
+    /// See [`IndexMap::sort_by_key`].
+    #[cfg_version(indexmap = "2.11")]
+    pub fn sort_by_key<T, F>(&mut self, mut sort_key: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        if !self.sort_with_key(&mut sort_key, false) {
+            self.tracked_mut().sort_by_key(sort_key);
+        }
+    }
+
+    /// See [`IndexMap::sort_unstable_keys`].
+    pub fn sort_unstable_keys(&mut self)
+    where
+        K: Ord,
+    {
+        if !self.sort_with(|k1, _, k2, _| k1.cmp(k2)) {
+            self.tracked_mut().sort_unstable_keys();
+        }
+    }
+
+    /// See [`IndexMap::sort_unstable_by`].
+    pub fn sort_unstable_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        if !self.sort_with(&mut cmp) {
+            self.tracked_mut().sort_unstable_by(cmp);
+        }
+    }
+
+    /// See [`IndexMap::sort_unstable_by_key`].
+    #[cfg_version(indexmap = "2.11")]
+    pub fn sort_unstable_by_key<T, F>(&mut self, mut sort_key: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        if !self.sort_with_key(&mut sort_key, false) {
+            self.tracked_mut().sort_unstable_by_key(sort_key);
+        }
+    }
+
+    /// See [`IndexMap::sort_by_cached_key`].
+    pub fn sort_by_cached_key<T, F>(&mut self, mut sort_key: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        if !self.sort_with_key(&mut sort_key, true) {
+            self.tracked_mut().sort_by_cached_key(sort_key);
+        }
+    }
+
+    /// See [`IndexMap::move_index`].
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        if !self.is_movable() {
+            return self.tracked_mut().move_index(from, to);
+        }
+        (*self.ptr).as_deref_mut().move_index(from, to);
+        if from != to {
+            self.state.moves.push((from, to));
+        }
+    }
+
+    /// See [`IndexMap::swap_indices`].
+    pub fn swap_indices(&mut self, a: usize, b: usize) {
+        let len = (*self).untracked_ref().len();
+        if a >= len || b >= len || !self.permute_with(a.max(b) + 1, |_, perm| perm.swap(a, b)) {
+            self.tracked_mut().swap_indices(a, b);
+        }
     }
 }
 
@@ -326,7 +583,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Clone + Eq + Hash,
+    V: Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     delegate_methods! { tracked_mut() as IndexMap =>
         #[cfg_version(indexmap = "2.2.4")]
@@ -349,8 +607,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     /// See [`IndexMap::get`].
     pub fn get<Q>(&self, key: &Q) -> Option<&O>
@@ -391,8 +649,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn replacing_mut(&mut self) -> &mut IndexMap<K, O::Head> {
         self.state.inner.get_mut().clear();
@@ -413,8 +671,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn __force_all(&mut self) -> &mut IndexMap<K, Box<O>> {
         let map = (*self.ptr).as_deref_mut();
@@ -450,8 +708,8 @@ where
             return;
         }
         let map = (*self.ptr).as_deref_mut();
-        for key in map.keys().skip(len).cloned() {
-            self.state.mark_deleted(key);
+        for (key, value) in map.iter().skip(len) {
+            self.state.mark_deleted(key.clone(), value);
         }
         map.truncate(len);
     }
@@ -476,17 +734,10 @@ where
             Bound::Excluded(&n) => n,
             Bound::Unbounded => map.len(),
         };
-        let keys: Vec<K> = map
-            .keys()
-            .skip(start)
-            .take(end.saturating_sub(start))
-            .cloned()
-            .collect();
-        let drain = map.drain(range);
-        for key in keys {
-            self.state.mark_deleted(key);
+        for (key, value) in map.iter().skip(start).take(end.saturating_sub(start)) {
+            self.state.mark_deleted(key.clone(), value);
         }
-        drain
+        map.drain(range)
     }
 
     /// See [`IndexMap::extract_if`].
@@ -511,8 +762,8 @@ where
             return self.tracked_mut().split_off(at);
         }
         let split = (*self.ptr).as_deref_mut().split_off(at);
-        for key in split.keys().cloned() {
-            self.state.mark_deleted(key);
+        for (key, value) in &split {
+            self.state.mark_deleted(key.clone(), value);
         }
         split
     }
@@ -529,7 +780,12 @@ where
         }
         let key_cloned = key.clone();
         let (index, old_value) = (*self.ptr).as_deref_mut().insert_full(key_cloned, value);
-        self.state.inner.get_mut().swap_remove(&key);
+        let ob = self.state.inner.get_mut().swap_remove(&key);
+        if let Some(old_value) = &old_value
+            && !self.state.diff.contains_key(&key)
+        {
+            self.state.capture(&key, old_value, ob.as_deref());
+        }
         match self.state.diff.entry(key) {
             Entry::Occupied(mut e) => {
                 if matches!(e.get(), ValueState::Deleted) {
@@ -568,12 +824,21 @@ where
 
         // Snapshot existing keys to distinguish Insert vs Replace for replacement keys.
         let keys_before: IndexMap<K, ()> = map.keys().cloned().map(|k| (k, ())).collect();
+        // Capture the values of existing replacement keys, which are overwritten in place.
+        for key in &new_keys {
+            if let Some(value) = map.get(key)
+                && !self.state.diff.contains_key(key)
+            {
+                let ob = self.state.inner.get_mut().swap_remove(key);
+                self.state.capture(key, value, ob.as_deref());
+            }
+        }
         let removed: Vec<_> = map.splice(range, replace_with).collect();
 
         // Mark removed keys that are no longer in the map
-        for (key, _) in &removed {
+        for (key, value) in &removed {
             if !map.contains_key(key) {
-                self.state.mark_deleted(key.clone());
+                self.state.mark_deleted(key.clone(), value);
             }
         }
 
@@ -664,7 +929,7 @@ where
             return self.tracked_mut().swap_remove_full(key);
         }
         let (index, key, old_value) = (*self.ptr).as_deref_mut().swap_remove_full(key)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((index, key, old_value))
     }
 
@@ -693,7 +958,7 @@ where
             return self.tracked_mut().shift_remove_full(key);
         }
         let (index, key, old_value) = (*self.ptr).as_deref_mut().shift_remove_full(key)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((index, key, old_value))
     }
 
@@ -703,7 +968,7 @@ where
             return self.tracked_mut().pop();
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().pop()?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -757,7 +1022,7 @@ where
             return self.tracked_mut().swap_remove_index(index);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().swap_remove_index(index)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 
@@ -767,17 +1032,18 @@ where
             return self.tracked_mut().shift_remove_index(index);
         }
         let (key, old_value) = (*self.ptr).as_deref_mut().shift_remove_index(index)?;
-        self.state.mark_deleted(key.clone());
+        self.state.mark_deleted(key.clone(), &old_value);
         Some((key, old_value))
     }
 }
 
 impl<K, V, O, S: ?Sized, D> Debug for IndexMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     IndexMap<K, V>: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -787,10 +1053,11 @@ where
 
 impl<K, V, O, S: ?Sized, D> PartialEq<IndexMap<K, V>> for IndexMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     IndexMap<K, V>: PartialEq,
 {
     fn eq(&self, other: &IndexMap<K, V>) -> bool {
@@ -801,14 +1068,16 @@ where
 impl<K1, K2, V1, V2, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<IndexMapObserver<K2, O2, S2, D2>>
     for IndexMapObserver<K1, O1, S1, D1>
 where
-    K1: Clone + Eq + Hash,
-    K2: Clone + Eq + Hash,
+    K1: Clone + Eq + Hash + Capture,
+    K2: Clone + Eq + Hash + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = IndexMap<K1, V1>>,
     S2: AsDeref<D2, Target = IndexMap<K2, V2>>,
     O1: Observer<InnerDepth = Zero, Head = V1>,
+    V1: Capture,
     O2: Observer<InnerDepth = Zero, Head = V2>,
+    V2: Capture,
     IndexMap<K1, V1>: PartialEq<IndexMap<K2, V2>>,
 {
     fn eq(&self, other: &IndexMapObserver<K2, O2, S2, D2>) -> bool {
@@ -818,10 +1087,11 @@ where
 
 impl<K, V, O, S: ?Sized, D> Eq for IndexMapObserver<K, O, S, D>
 where
-    K: Clone + Eq + Hash,
+    K: Clone + Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
+    V: Capture,
     IndexMap<K, V>: Eq,
 {
 }
@@ -831,7 +1101,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Clone + Eq + Hash,
+    V: Capture,
+    K: Clone + Eq + Hash + Capture,
     Q: Hash + Equivalent<K>,
 {
     type Output = O;
@@ -846,7 +1117,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, V>>,
     O: Observer<InnerDepth = Zero, Head = V>,
-    K: Clone + Eq + Hash,
+    V: Capture,
+    K: Clone + Eq + Hash + Capture,
     Q: Hash + Equivalent<K>,
 {
     fn index_mut(&mut self, index: &'q Q) -> &mut Self::Output {
@@ -859,8 +1131,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     type Output = O;
 
@@ -874,8 +1146,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.get_index_mut(index).expect("index out of bounds").1
@@ -889,8 +1161,8 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
-    K: Clone + Eq + Hash,
+    O::Head: Sized + Capture,
+    K: Clone + Eq + Hash + Capture,
 {
     fn extend<I: IntoIterator<Item = (K, O::Head)>>(&mut self, iter: I) {
         let iter = iter.into_iter();
@@ -911,15 +1183,15 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexMap<K, O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Copy,
-    K: Copy + Eq + Hash,
+    O::Head: Copy + Capture,
+    K: Copy + Eq + Hash + Capture,
 {
     fn extend<I: IntoIterator<Item = (&'a K, &'a O::Head)>>(&mut self, iter: I) {
         self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
     }
 }
 
-impl<K: Clone + Eq + Hash, V: Observe> Observe for IndexMap<K, V> {
+impl<K: Clone + Eq + Hash + Capture, V: Observe + Capture> Observe for IndexMap<K, V> {
     type Observer<'ob, S, D>
         = IndexMapObserver<K, V::Observer<'ob, V, Zero>, S, D>
     where
@@ -1490,4 +1762,111 @@ mod tests {
             Some(batch!(_, replace!(c, json!(30)), delete!(a), delete!(b)))
         );
     }
+
+    #[test]
+    fn sort_keys_triggers_move() {
+        let mut map = IndexMap::from([("c", "x".to_string()), ("a", "y".to_string()), ("b", "z".to_string())]);
+        let mut ob = map.__observe();
+        ob.get_mut("a").unwrap().push('!');
+        ob.sort_keys();
+        assert_eq!(ob.untracked_ref().keys().copied().collect::<Vec<_>>(), ["a", "b", "c"]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, mv!(_, 0, 2), append!(a, json!("!")))));
+
+        ob.sort_by(|_, v1, _, v2| v2.cmp(v1));
+        assert_eq!(ob.untracked_ref().keys().copied().collect::<Vec<_>>(), ["b", "a", "c"]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(mv!(_, 1, 0)));
+    }
+
+    #[test]
+    fn move_index_and_swap_indices() {
+        let mut map = IndexMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let mut ob = map.__observe();
+        ob.move_index(0, 2);
+        ob.swap_indices(0, 1);
+        assert_eq!(ob.untracked_ref().keys().copied().collect::<Vec<_>>(), ["c", "b", "a"]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, mv!(_, 0, 2), mv!(_, 1, 0))));
+    }
+
+    #[test]
+    fn sort_after_insert_triggers_replace() {
+        let mut map = IndexMap::from([("b", 1)]);
+        let mut ob = map.__observe();
+        ob.insert("a", 2);
+        ob.sort_keys();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!({"a": 2, "b": 1}))));
+    }
+
+    #[test]
+    fn too_many_moves_trigger_replace() {
+        let mut map = IndexMap::from([("d", 1), ("c", 2), ("b", 3), ("a", 4)]);
+        let mut ob = map.__observe();
+        ob.move_index(0, 1);
+        ob.move_index(0, 1);
+        ob.sort_keys();
+        assert_eq!(
+            ob.untracked_ref().keys().copied().collect::<Vec<_>>(),
+            ["a", "b", "c", "d"]
+        );
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!({"a": 4, "b": 3, "c": 2, "d": 1}))));
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(
+            &mut super::IndexMapObserver<String, crate::impls::StringObserver<'_, String>, IndexMap<String, String>>,
+        );
+        let cases: [Case; 5] = [
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.shift_remove("a");
+                ob.insert("c".into(), "z".into());
+            },
+            |ob| {
+                ob.get_mut("a").unwrap().push('!');
+                ob.insert("a".into(), "y".into());
+                ob.get_mut("a").unwrap().push('?');
+                ob.get_mut("b").unwrap().push('!');
+            },
+            |ob| {
+                ob.insert("c".into(), "z".into());
+                ob.truncate(1);
+                ob.pop();
+            },
+            |ob| {
+                ob.splice(1.., [("a".into(), "z".into()), ("c".into(), "z".into())]);
+            },
+            |ob| {
+                ob.swap_remove("a");
+                ob.insert("a".into(), "y".into());
+                *ob.tracked_mut() = IndexMap::from([("c".into(), "z".into())]);
+            },
+        ];
+        for mutate in cases {
+            let before = IndexMap::from([("a".to_string(), "x".to_string()), ("b".into(), "y".into())]);
+            let mut map = before.clone();
+            let mut ob = map.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(map), flushed);
+        }
+    }
+
+    #[cfg(all(feature = "inverse", feature = "move"))]
+    #[test]
+    fn inverse_after_move() {
+        let mut map = IndexMap::from([("b".to_string(), 1), ("a".into(), 2)]);
+        let mut ob = map.__observe();
+        ob.sort_keys();
+        ob.swap_remove("b");
+        let (_, inverse) = ob.flush_inverse::<Json>().unwrap();
+        assert_eq!(inverse, None);
+    }
 }
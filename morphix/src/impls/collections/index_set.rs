@@ -1,5 +1,7 @@
 //! Observer implementation for [`IndexSet<T>`].
 
+#[cfg(feature = "inverse")]
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::iter::FusedIterator;
@@ -11,8 +13,10 @@ use indexmap::{Equivalent, IndexSet, TryReserveError};
 use serde::Serialize;
 
 use crate::general::Snapshot;
+#[cfg(feature = "inverse")]
+use crate::helper::Captured;
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
 use crate::{Mutations, Observe};
 
@@ -22,6 +26,10 @@ struct IndexSetObserverState<T> {
     /// Starting index of appended elements. Elements before this index are unchanged
     /// from the last flush; elements from this index onward are "new".
     append_index: usize,
+    /// Captured original elements, keyed by their indices. Elements are captured right before the
+    /// first edit which may remove them.
+    #[cfg(feature = "inverse")]
+    captured: BTreeMap<usize, Captured>,
     phantom: PhantomData<T>,
 }
 
@@ -30,6 +38,8 @@ impl<T> Default for IndexSetObserverState<T> {
         Self {
             truncate_len: 0,
             append_index: 0,
+            #[cfg(feature = "inverse")]
+            captured: BTreeMap::new(),
             phantom: PhantomData,
         }
     }
@@ -38,12 +48,42 @@ impl<T> Default for IndexSetObserverState<T> {
 impl<T> IndexSetObserverState<T> {
     fn observe(set: &IndexSet<T>) -> Self {
         Self {
-            truncate_len: 0,
             append_index: set.len(),
-            phantom: PhantomData,
+            ..Default::default()
+        }
+    }
+
+    /// Captures the unchanged elements of `set` from `index` onward, before they are removed.
+    ///
+    /// Without the `inverse` feature, this is a no-op.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture_truncate(&mut self, set: &IndexSet<T>, index: usize)
+    where
+        T: Capture,
+    {
+        #[cfg(feature = "inverse")]
+        for (offset, value) in set.iter().enumerate().take(self.append_index).skip(index) {
+            self.captured.entry(offset).or_insert_with(|| Captured::new(value));
         }
     }
 
+    /// Returns the inverse of the recorded mutations, given the current length `len` of the set.
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, len: usize) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        let appended_len = len - self.append_index;
+        if appended_len > 0 {
+            mutations.extend(crate::MutationKind::Truncate(appended_len));
+        }
+        if self.truncate_len > 0 {
+            let truncated = (self.append_index..self.append_index + self.truncate_len)
+                .map(|index| self.captured.get(&index).cloned())
+                .collect::<Option<Vec<_>>>()?;
+            mutations.extend(Mutations::append_owned(truncated));
+        }
+        Some(mutations)
+    }
+
     /// Mark that all elements from `index` onward have changed.
     /// If `index` is already at or beyond `append_index`, this is a no-op.
     fn mark_truncate(&mut self, index: usize) {
@@ -55,10 +95,16 @@ impl<T> IndexSetObserverState<T> {
     }
 }
 
-impl<T> Invalidate<IndexSet<T>> for IndexSetObserverState<T> {
-    fn invalidate(&mut self, _set: &IndexSet<T>) {
+impl<T: Capture> Invalidate<IndexSet<T>> for IndexSetObserverState<T> {
+    fn invalidate(&mut self, set: &IndexSet<T>) {
+        self.capture_truncate(set, 0);
         self.mark_truncate(0);
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, set: &IndexSet<T>) -> Option<Mutations> {
+        self.inverse(set.len())
+    }
 }
 
 /// Observer implementation for [`IndexSet<T>`](IndexSet).
@@ -91,6 +137,7 @@ impl<'ob, T, S: ?Sized, D> DerefMut for IndexSetObserver<'ob, T, S, D> {
 
 impl<'ob, T, S: ?Sized, D> QuasiObserver for IndexSetObserver<'ob, T, S, D>
 where
+    T: Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexSet<T>>,
 {
@@ -101,10 +148,16 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<'ob, T, S: ?Sized, D> Observer for IndexSetObserver<'ob, T, S, D>
 where
+    T: Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexSet<T>>,
 {
@@ -131,10 +184,17 @@ where
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
         let set = (*this.ptr).as_deref();
+        #[cfg(feature = "inverse")]
+        let inverse = this.state.inverse(set.len());
+        #[cfg(feature = "inverse")]
+        this.state.captured.clear();
         let append_index = std::mem::replace(&mut this.state.append_index, set.len());
         let truncate_len = std::mem::replace(&mut this.state.truncate_len, 0);
 
         if append_index == 0 && truncate_len > 0 {
+            #[cfg(feature = "inverse")]
+            return Mutations::replace(set).with_inverse(inverse);
+            #[cfg(not(feature = "inverse"))]
             return Mutations::replace(set);
         }
 
@@ -155,40 +215,30 @@ where
             }
         }
 
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
         mutations
     }
 }
 
-/// Guard that calls [`mark_truncate`](IndexSetObserverState::mark_truncate) on drop
-/// with the current length of the inner set.
-struct TruncateGuard<'a, T> {
-    state: &'a mut IndexSetObserverState<T>,
-    inner: &'a mut IndexSet<T>,
-}
-
-impl<T> Drop for TruncateGuard<'_, T> {
-    fn drop(&mut self) {
-        self.state.mark_truncate(self.inner.len());
-    }
-}
-
-impl<T> Deref for TruncateGuard<'_, T> {
-    type Target = IndexSet<T>;
-
-    fn deref(&self) -> &Self::Target {
-        self.inner
-    }
-}
-
-impl<T> DerefMut for TruncateGuard<'_, T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.inner
+impl<'ob, T, S: ?Sized, D> IndexSetObserver<'ob, T, S, D>
+where
+    T: Capture,
+    D: Unsigned,
+    S: AsDerefMut<D, Target = IndexSet<T>>,
+{
+    /// Marks that all elements from `index` onward are about to change, capturing them first.
+    fn mark_truncate(&mut self, index: usize) {
+        self.state.capture_truncate((*self.ptr).as_deref(), index);
+        self.state.mark_truncate(index);
     }
 }
 
 impl<'ob, T, S: ?Sized, D> IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexSet<T>>,
 {
@@ -200,19 +250,14 @@ where
         }
     }
 
-    fn truncate_mut(&mut self) -> TruncateGuard<'_, T> {
-        TruncateGuard {
-            state: &mut self.state,
-            inner: (*self.ptr).as_deref_mut(),
-        }
-    }
-
     delegate_methods! { nonempty_mut() as IndexSet =>
         pub fn clear(&mut self);
     }
 
-    delegate_methods! { truncate_mut() as IndexSet =>
-        pub fn truncate(&mut self, len: usize);
+    /// See [`IndexSet::truncate`].
+    pub fn truncate(&mut self, len: usize) {
+        self.mark_truncate(len);
+        self.untracked_mut().truncate(len)
     }
 
     /// See [`IndexSet::drain`].
@@ -225,7 +270,7 @@ where
             Bound::Excluded(&n) => n + 1,
             Bound::Unbounded => 0,
         };
-        self.state.mark_truncate(start_index);
+        self.mark_truncate(start_index);
         self.untracked_mut().drain(range)
     }
 
@@ -241,6 +286,7 @@ where
             Bound::Excluded(&n) => n + 1,
             Bound::Unbounded => 0,
         };
+        self.state.capture_truncate((*self.ptr).as_deref(), index);
         let state = &mut self.state;
         let set = (*self.ptr).as_deref_mut();
         let inner = set.extract_if(range, move |v| {
@@ -256,7 +302,7 @@ where
 
     /// See [`IndexSet::split_off`].
     pub fn split_off(&mut self, at: usize) -> IndexSet<T> {
-        self.state.mark_truncate(at);
+        self.mark_truncate(at);
         self.untracked_mut().split_off(at)
     }
 
@@ -269,10 +315,20 @@ where
         pub fn shrink_to(&mut self, min_capacity: usize);
     }
 
-    delegate_methods! { truncate_mut() as IndexSet =>
-        pub fn pop(&mut self) -> Option<T>;
-        #[cfg_version(indexmap = "2.12")]
-        pub fn pop_if(&mut self, predicate: impl FnOnce(&T) -> bool) -> Option<T>;
+    /// See [`IndexSet::pop`].
+    pub fn pop(&mut self) -> Option<T> {
+        let len = (*self).untracked_ref().len();
+        self.mark_truncate(len.saturating_sub(1));
+        self.untracked_mut().pop()
+    }
+
+    /// See [`IndexSet::pop_if`].
+    #[cfg_version(indexmap = "2.12")]
+    pub fn pop_if(&mut self, predicate: impl FnOnce(&T) -> bool) -> Option<T> {
+        if !predicate((*self).untracked_ref().last()?) {
+            return None;
+        }
+        self.pop()
     }
 
     /// See [`IndexSet::retain`].
@@ -299,13 +355,13 @@ where
 
     /// See [`IndexSet::swap_remove_index`].
     pub fn swap_remove_index(&mut self, index: usize) -> Option<T> {
-        self.state.mark_truncate(index);
+        self.mark_truncate(index);
         self.untracked_mut().swap_remove_index(index)
     }
 
     /// See [`IndexSet::shift_remove_index`].
     pub fn shift_remove_index(&mut self, index: usize) -> Option<T> {
-        self.state.mark_truncate(index);
+        self.mark_truncate(index);
         self.untracked_mut().shift_remove_index(index)
     }
 
@@ -319,7 +375,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexSet<T>>,
 {
@@ -351,11 +407,9 @@ where
         if let Some(index) = set.get_index_of(&value) {
             return (index, false);
         }
-        let (index, inserted) = self.untracked_mut().insert_sorted(value);
-        if inserted {
-            self.state.mark_truncate(index);
-        }
-        (index, inserted)
+        let index = set.binary_search(&value).unwrap_or_else(|index| index);
+        self.mark_truncate(index);
+        self.untracked_mut().insert_sorted(value)
     }
 
     // TODO
@@ -384,41 +438,34 @@ where
     #[cfg_version(indexmap = "2.5")]
     pub fn insert_before(&mut self, index: usize, value: T) -> (usize, bool) {
         let set = (*self.ptr).as_deref();
-        let existed = set.contains(&value);
-        let old_index = if existed { set.get_index_of(&value) } else { None };
-        let (result_index, inserted) = self.untracked_mut().insert_before(index, value);
-        // Determine the first position that was disturbed.
-        let disturbed = if let Some(old_idx) = old_index {
-            // Existing element was moved: everything from min(old, new) is disturbed.
-            old_idx.min(result_index)
-        } else {
-            // New element inserted at result_index, shifting everything after.
-            result_index
+        // Determine the first position that is disturbed: an existing element is moved, so
+        // everything from min(old, new) is disturbed, while a new element is inserted at `index`,
+        // shifting everything after.
+        let disturbed = match set.get_index_of(&value) {
+            Some(old_index) => old_index.min(index),
+            None => index,
         };
-        self.state.mark_truncate(disturbed);
-        (result_index, inserted)
+        self.mark_truncate(disturbed);
+        self.untracked_mut().insert_before(index, value)
     }
 
     /// See [`IndexSet::shift_insert`].
     #[cfg_version(indexmap = "2.2.3")]
     pub fn shift_insert(&mut self, index: usize, value: T) -> bool {
         let set = (*self.ptr).as_deref();
-        let old_index = set.get_index_of(&value);
-        let inserted = self.untracked_mut().shift_insert(index, value);
-        let disturbed = if let Some(old_idx) = old_index {
-            old_idx.min(index)
-        } else {
-            index
+        let disturbed = match set.get_index_of(&value) {
+            Some(old_index) => old_index.min(index),
+            None => index,
         };
-        self.state.mark_truncate(disturbed);
-        inserted
+        self.mark_truncate(disturbed);
+        self.untracked_mut().shift_insert(index, value)
     }
 
     /// See [`IndexSet::replace`].
     pub fn replace(&mut self, value: T) -> Option<T> {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(&value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().replace(value)
     }
@@ -428,7 +475,7 @@ where
     pub fn replace_full(&mut self, value: T) -> (usize, Option<T>) {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(&value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().replace_full(value)
     }
@@ -436,7 +483,7 @@ where
     /// See [`IndexSet::replace_index`].
     #[cfg_version(indexmap = "2.11")]
     pub fn replace_index(&mut self, index: usize, value: T) -> Result<T, (usize, T)> {
-        self.state.mark_truncate(index);
+        self.mark_truncate(index);
         self.untracked_mut().replace_index(index, value)
     }
 
@@ -456,7 +503,7 @@ where
             Bound::Excluded(&n) => n + 1,
             Bound::Unbounded => 0,
         };
-        self.state.mark_truncate(start_index);
+        self.mark_truncate(start_index);
         self.untracked_mut().splice(range, replace_with).collect()
     }
 
@@ -472,6 +519,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> IndexSetObserver<'ob, T, S, D>
 where
+    T: Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexSet<T>>,
 {
@@ -482,7 +530,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
             self.untracked_mut().swap_remove(value);
             return true;
         }
@@ -496,7 +544,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
             self.untracked_mut().shift_remove(value);
             return true;
         }
@@ -510,7 +558,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().swap_take(value)
     }
@@ -522,7 +570,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().shift_take(value)
     }
@@ -534,7 +582,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().swap_remove_full(value)
     }
@@ -546,7 +594,7 @@ where
     {
         let set = (*self.ptr).as_deref();
         if let Some(index) = set.get_index_of(value) {
-            self.state.mark_truncate(index);
+            self.mark_truncate(index);
         }
         self.untracked_mut().shift_remove_full(value)
     }
@@ -554,7 +602,7 @@ where
 
 impl<'ob, T, S: ?Sized, D, U> Extend<U> for IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDerefMut<D, Target = IndexSet<T>>,
     IndexSet<T>: Extend<U>,
@@ -606,7 +654,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> Debug for IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexSet<T>>,
     IndexSet<T>: Debug,
@@ -618,7 +666,7 @@ where
 
 impl<'ob, T, S: ?Sized, D> PartialEq<IndexSet<T>> for IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexSet<T>>,
     IndexSet<T>: PartialEq,
@@ -631,8 +679,8 @@ where
 impl<'ob, T1, T2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<IndexSetObserver<'ob, T2, S2, D2>>
     for IndexSetObserver<'ob, T1, S1, D1>
 where
-    T1: Eq + Hash,
-    T2: Eq + Hash,
+    T1: Eq + Hash + Capture,
+    T2: Eq + Hash + Capture,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = IndexSet<T1>>,
@@ -646,14 +694,14 @@ where
 
 impl<'ob, T, S, D> Eq for IndexSetObserver<'ob, T, S, D>
 where
-    T: Eq + Hash,
+    T: Eq + Hash + Capture,
     D: Unsigned,
     S: AsDeref<D, Target = IndexSet<T>>,
     IndexSet<T>: Eq,
 {
 }
 
-impl<T: Clone + Eq + Hash> Observe for IndexSet<T> {
+impl<T: Clone + Eq + Hash + Capture> Observe for IndexSet<T> {
     type Observer<'ob, S, D>
         = IndexSetObserver<'ob, T, S, D>
     where
@@ -1030,4 +1078,42 @@ mod tests {
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, Some(batch!(_, truncate!(_, 2), append!(_, json!([20, 3])))));
     }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::IndexSetObserver<'_, i32, IndexSet<i32>>);
+        let cases: [Case; 5] = [
+            |ob| {
+                ob.insert(4);
+                ob.swap_remove(&2);
+            },
+            |ob| {
+                ob.pop();
+                ob.shift_insert(0, 5);
+            },
+            |ob| {
+                ob.retain(|value| value % 2 == 1);
+                ob.insert(2);
+            },
+            |ob| {
+                ob.truncate(1);
+                ob.extend([3, 2]);
+            },
+            |ob| {
+                ob.insert(4);
+                ob.sort_by(|a, b| b.cmp(a));
+            },
+        ];
+        for mutate in cases {
+            let before = IndexSet::from([1, 2, 3]);
+            let mut set = before.clone();
+            let mut ob = set.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(set), flushed);
+        }
+    }
 }
@@ -1,4 +1,16 @@
 //! Observer implementations for collection types in [`std::collections`].
+//!
+//! ## Map keys
+//!
+//! Map observers convert keys into path segments with [`PathSegment::from_key`], so any key that
+//! `serde_json` can use as an object key is supported, e.g. integers, UUIDs or unit enum variants.
+//! Adapters whose maps keep integer and boolean keys, i.e. the YAML, MessagePack and CBOR
+//! adapters, look such keys up by their string form.
+//!
+//! If a changed key cannot be converted, e.g. if it is a tuple or a struct, the whole map is
+//! replaced instead. A map flattened into its parent with `#[serde(flatten)]` is replaced by a
+//! [`Replace`](crate::MutationKind::Replace) with an empty path in this case, upon which the parent
+//! replaces itself as a whole, see [`Mutations::is_root_replace`].
 
 pub mod binary_heap;
 pub mod btree_map;
@@ -9,13 +21,68 @@ pub mod hash_set;
 pub mod index_map;
 #[cfg(feature = "indexmap")]
 pub mod index_set;
+mod set;
+
+use serde::Serialize;
+
+#[cfg(feature = "inverse")]
+use crate::Mutation;
+#[cfg(any(feature = "delete", feature = "inverse"))]
+use crate::MutationKind;
+use crate::{Mutations, PathError, PathSegment};
 
 pub use binary_heap::BinaryHeapObserver;
 pub use btree_map::BTreeMapObserver;
 pub use btree_set::BTreeSetObserver;
-pub use hash_map::HashMapObserver;
-pub use hash_set::HashSetObserver;
+pub use hash_map::{HashMapLike, HashMapObserver};
+pub use hash_set::{HashSetLike, HashSetObserver};
 #[cfg(feature = "indexmap")]
 pub use index_map::IndexMapObserver;
 #[cfg(feature = "indexmap")]
 pub use index_set::IndexSetObserver;
+pub use set::SetMember;
+
+/// Breaks the replacement of a map flattened into its parent apart into the replacements of its
+/// `entries` and the deletions of the `deleted` keys.
+///
+/// Returns an error if a key cannot be converted with [`PathSegment::from_key`].
+pub(crate) fn flat_replace<'a, K, V>(
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+    deleted: impl IntoIterator<Item = &'a K>,
+) -> Result<Mutations, PathError>
+where
+    K: Serialize + 'a,
+    V: Serialize + 'static,
+{
+    let mut mutations = Mutations::new().with_replace(true);
+    for (key, value) in entries {
+        mutations.insert(PathSegment::from_key(key)?, Mutations::replace(value));
+    }
+    #[cfg(feature = "delete")]
+    for key in deleted {
+        mutations.insert(PathSegment::from_key(key)?, MutationKind::Delete);
+    }
+    #[cfg(not(feature = "delete"))]
+    assert!(deleted.into_iter().next().is_none(), "delete feature is not enabled");
+    Ok(mutations)
+}
+
+/// Inserts the mutations which restore a removed map entry at `segment` into `mutations`.
+///
+/// The restoring mutations are inserted one by one rather than as a single batch at `segment`,
+/// since such a batch cannot be applied while the entry is absent.
+#[cfg(feature = "inverse")]
+pub(crate) fn insert_restore(mutations: &mut Mutations, segment: PathSegment, restore: Mutations) {
+    match restore.into_inner() {
+        Some(Mutation {
+            path,
+            kind: MutationKind::Batch(batch),
+        }) if path.is_empty() => {
+            for mutation in batch {
+                mutations.extend(mutation.with_prefix(segment.clone()));
+            }
+        }
+        Some(mutation) => mutations.extend(mutation.with_prefix(segment)),
+        None => {}
+    }
+}
@@ -0,0 +1,271 @@
+//! State shared by [`HashSetObserver`](super::HashSetObserver) and
+//! [`BTreeSetObserver`](super::BTreeSetObserver).
+
+#[cfg(feature = "set")]
+use std::collections::{BTreeSet, HashSet};
+#[cfg(feature = "set")]
+use std::hash::Hash;
+#[cfg(not(feature = "set"))]
+use std::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::Mutations;
+#[cfg(feature = "set")]
+use crate::helper::Captor;
+#[cfg(feature = "set")]
+use crate::helper::Capture;
+#[cfg(feature = "inverse")]
+use crate::helper::Captured;
+use crate::helper::Invalidate;
+
+/// Members of sets observed by [`HashSetObserver`](super::HashSetObserver) and
+/// [`BTreeSetObserver`](super::BTreeSetObserver).
+///
+/// With the `set` feature, the observers keep the inserted and removed members to emit
+/// [`Add`](crate::MutationKind::Add) / [`Remove`](crate::MutationKind::Remove) mutations, so members
+/// must be [`Clone`]. Without it, any change replaces the whole set and every type is a member.
+#[cfg(feature = "set")]
+pub trait SetMember: Clone {}
+
+#[cfg(feature = "set")]
+impl<T: Clone> SetMember for T {}
+
+/// Members of sets observed by [`HashSetObserver`](super::HashSetObserver) and
+/// [`BTreeSetObserver`](super::BTreeSetObserver).
+///
+/// With the `set` feature, the observers keep the inserted and removed members to emit
+/// `Add` / `Remove` mutations, so members must be [`Clone`]. Without it, any change replaces the
+/// whole set and every type is a member.
+#[cfg(not(feature = "set"))]
+pub trait SetMember {}
+
+#[cfg(not(feature = "set"))]
+impl<T> SetMember for T {}
+
+/// Sets in which [`SetObserverState`] records members.
+#[cfg(feature = "set")]
+pub(super) trait Members: Default {
+    type Member;
+
+    fn insert(&mut self, member: Self::Member) -> bool;
+
+    fn remove(&mut self, member: &Self::Member) -> bool;
+
+    fn replace(&mut self, member: Self::Member) -> Option<Self::Member>;
+
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool;
+}
+
+#[cfg(feature = "set")]
+impl<T: Eq + Hash> Members for HashSet<T> {
+    type Member = T;
+
+    fn insert(&mut self, member: T) -> bool {
+        HashSet::insert(self, member)
+    }
+
+    fn remove(&mut self, member: &T) -> bool {
+        HashSet::remove(self, member)
+    }
+
+    fn replace(&mut self, member: T) -> Option<T> {
+        HashSet::replace(self, member)
+    }
+
+    fn clear(&mut self) {
+        HashSet::clear(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        HashSet::is_empty(self)
+    }
+}
+
+#[cfg(feature = "set")]
+impl<T: Ord> Members for BTreeSet<T> {
+    type Member = T;
+
+    fn insert(&mut self, member: T) -> bool {
+        BTreeSet::insert(self, member)
+    }
+
+    fn remove(&mut self, member: &T) -> bool {
+        BTreeSet::remove(self, member)
+    }
+
+    fn replace(&mut self, member: T) -> Option<T> {
+        BTreeSet::replace(self, member)
+    }
+
+    fn clear(&mut self) {
+        BTreeSet::clear(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        BTreeSet::is_empty(self)
+    }
+}
+
+/// Observer state of a set, which records the members inserted and removed since the last flush in
+/// sets of type `C`.
+pub(super) struct SetObserverState<C> {
+    pub(super) mutated: bool,
+    /// Members absent from the original set which have been inserted since the last flush.
+    #[cfg(feature = "set")]
+    added: C,
+    /// Members of the original set which have been removed since the last flush.
+    #[cfg(feature = "set")]
+    removed: C,
+    /// The set before it has been invalidated, together with the inverse of the members recorded
+    /// until then.
+    #[cfg(feature = "set")]
+    captor: Captor,
+    #[cfg(not(feature = "set"))]
+    phantom: PhantomData<C>,
+}
+
+impl<C: Default> Default for SetObserverState<C> {
+    fn default() -> Self {
+        Self {
+            mutated: false,
+            #[cfg(feature = "set")]
+            added: Default::default(),
+            #[cfg(feature = "set")]
+            removed: Default::default(),
+            #[cfg(feature = "set")]
+            captor: Default::default(),
+            #[cfg(not(feature = "set"))]
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "set")]
+impl<C: Members + Capture, S: Capture + ?Sized> Invalidate<S> for SetObserverState<C> {
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn invalidate(&mut self, set: &S) {
+        #[cfg(feature = "inverse")]
+        {
+            let changes = self.inverse_members();
+            self.captor.capture_with(set, || Some(changes));
+        }
+        self.mutated = true;
+        self.added.clear();
+        self.removed.clear();
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, _: &S) -> Option<Mutations> {
+        if self.mutated {
+            self.captor.inverse()
+        } else {
+            Some(self.inverse_members())
+        }
+    }
+}
+
+#[cfg(not(feature = "set"))]
+impl<C, S: ?Sized> Invalidate<S> for SetObserverState<C> {
+    fn invalidate(&mut self, _: &S) {
+        self.mutated = true;
+    }
+}
+
+#[cfg(feature = "inverse")]
+impl<C: Members + Capture> SetObserverState<C> {
+    /// Returns the mutations which revert the members inserted and removed since the last flush.
+    fn inverse_members(&self) -> Mutations {
+        let mut mutations = Mutations::new();
+        if !self.added.is_empty() {
+            mutations.extend(Mutations::remove(Captured::new(&self.added)));
+        }
+        if !self.removed.is_empty() {
+            mutations.extend(Mutations::add(Captured::new(&self.removed)));
+        }
+        mutations
+    }
+}
+
+#[cfg(feature = "set")]
+impl<C: Members> SetObserverState<C>
+where
+    C::Member: SetMember,
+{
+    /// Records that `member`, which was absent from the set, has been inserted.
+    pub(super) fn mark_added(&mut self, member: &C::Member) {
+        if !self.removed.remove(member) {
+            self.added.insert(member.clone());
+        }
+    }
+
+    /// Records that `member`, which was present in the set, has been removed.
+    pub(super) fn mark_removed(&mut self, member: &C::Member) {
+        if !self.added.remove(member) {
+            self.removed.insert(member.clone());
+        }
+    }
+
+    /// Records that `old_member` has been replaced with the equal `member`.
+    pub(super) fn mark_replaced(&mut self, old_member: &C::Member, member: &C::Member) {
+        // The replaced member may be distinguishable from its replacement when serialized, so it
+        // is removed and re-added rather than cancelled out.
+        self.mark_removed(old_member);
+        self.added.replace(member.clone());
+    }
+
+    /// Flushes the recorded changes of `set`, replacing it as a whole if it has been invalidated.
+    pub(super) fn flush<S: Serialize + ?Sized + 'static>(&mut self, set: &S) -> Mutations
+    where
+        C: Serialize + 'static,
+    {
+        if std::mem::take(&mut self.mutated) {
+            return self.captor.attach(Mutations::replace(set));
+        }
+        #[cfg(feature = "inverse")]
+        let inverse = self.inverse_members();
+        let added = std::mem::take(&mut self.added);
+        let removed = std::mem::take(&mut self.removed);
+        let mut mutations = Mutations::new();
+        if !removed.is_empty() {
+            mutations.extend(Mutations::remove(removed));
+        }
+        if !added.is_empty() {
+            mutations.extend(Mutations::add(added));
+        }
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
+        mutations
+    }
+}
+
+#[cfg(not(feature = "set"))]
+impl<C> SetObserverState<C> {
+    /// Records that a member, which was absent from the set, has been inserted.
+    pub(super) fn mark_added<T>(&mut self, _: &T) {
+        self.mutated = true;
+    }
+
+    /// Records that a member, which was present in the set, has been removed.
+    pub(super) fn mark_removed<T>(&mut self, _: &T) {
+        self.mutated = true;
+    }
+
+    /// Records that a member has been replaced with an equal one.
+    pub(super) fn mark_replaced<T>(&mut self, _: &T, _: &T) {
+        self.mutated = true;
+    }
+
+    /// Flushes the recorded changes of `set`, replacing it as a whole if it has been changed.
+    pub(super) fn flush<S: Serialize + ?Sized + 'static>(&mut self, set: &S) -> Mutations {
+        if std::mem::take(&mut self.mutated) {
+            Mutations::replace(set)
+        } else {
+            Mutations::new()
+        }
+    }
+}
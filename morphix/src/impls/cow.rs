@@ -46,6 +46,15 @@ where
         this.mutated = true;
         B::invalidate(&mut this.inner);
     }
+
+    /// Returns [`None`] while the changes are tracked by the observer of the owned value.
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        match this.owned {
+            Some(_) if !this.mutated => None,
+            _ => B::inverse(&this.inner, head),
+        }
+    }
 }
 
 impl<'a, B, O, D, T> Observer for CowObserver<B, O>
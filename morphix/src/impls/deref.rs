@@ -1,18 +1,100 @@
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
 
 use crate::general::Snapshot;
-use crate::helper::{AsDeref, AsDerefMut, QuasiObserver, Succ, Unsigned};
+use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{Observer, RefObserve, RefObserver, SerializeObserver};
 use crate::{Mutations, Observe};
 
+/// Shared pointers whose value can be made unique for [`DerefObserver::make_mut`].
+pub trait MakeMut {
+    /// The pointed-to value.
+    type Value;
+
+    /// Returns a mutable reference to the value, cloning it first if it is shared.
+    fn make_mut(this: &mut Self) -> &mut Self::Value;
+}
+
+impl<T: Clone> MakeMut for std::rc::Rc<T> {
+    type Value = T;
+
+    fn make_mut(this: &mut Self) -> &mut T {
+        std::rc::Rc::make_mut(this)
+    }
+}
+
+impl<T: Clone> MakeMut for std::sync::Arc<T> {
+    type Value = T;
+
+    fn make_mut(this: &mut Self) -> &mut T {
+        std::sync::Arc::make_mut(this)
+    }
+}
+
 /// Observer implementation for shared-access pointer types such as [`&T`](reference),
 /// [`Rc<T>`](std::rc::Rc), and [`Arc<T>`](std::sync::Arc).
 ///
 /// This observer wraps the inner type's observer and forwards all operations to it, maintaining
 /// proper dereference chains for pointer types.
+///
+/// ## Copy-on-Write
+///
+/// For [`Rc<T>`](std::rc::Rc) and [`Arc<T>`](std::sync::Arc), [`make_mut`](Self::make_mut) gives
+/// mutable access to the pointed-to value through its default observer, so edits made in the
+/// copy-on-write style are tracked granularly. Once it has been called, the next flush reports the
+/// mutations recorded by that observer instead of a replace caused by the pointer change.
 pub struct DerefObserver<O> {
     inner: O,
+    owned: OwnedObserver,
+}
+
+/// Type-erased default observer of the value behind [`DerefObserver::make_mut`].
+///
+/// The observer type depends on the pointed-to value, which [`DerefObserver`] cannot name for
+/// every shared pointer, so it is boxed and driven through function pointers instead.
+struct OwnedObserver(Option<ErasedObserver>);
+
+struct ErasedObserver {
+    ptr: NonNull<()>,
+    flush: unsafe fn(NonNull<()>) -> Mutations,
+    flat_flush: unsafe fn(NonNull<()>) -> Mutations,
+    drop: unsafe fn(NonNull<()>),
+}
+
+impl ErasedObserver {
+    fn new<M: SerializeObserver>(ob: M) -> Self {
+        unsafe fn flush<M: SerializeObserver>(ptr: NonNull<()>) -> Mutations {
+            unsafe { M::flush(ptr.cast::<M>().as_mut()) }
+        }
+
+        unsafe fn flat_flush<M: SerializeObserver>(ptr: NonNull<()>) -> Mutations {
+            unsafe { M::flat_flush(ptr.cast::<M>().as_mut()) }
+        }
+
+        unsafe fn drop<M>(ptr: NonNull<()>) {
+            std::mem::drop(unsafe { Box::from_raw(ptr.cast::<M>().as_ptr()) });
+        }
+
+        Self {
+            ptr: NonNull::from(Box::leak(Box::new(ob))).cast(),
+            flush: flush::<M>,
+            flat_flush: flat_flush::<M>,
+            drop: drop::<M>,
+        }
+    }
+}
+
+impl Drop for ErasedObserver {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.ptr) }
+    }
+}
+
+impl<T: ?Sized> Invalidate<T> for OwnedObserver {
+    fn invalidate(&mut self, _: &T) {
+        self.0 = None;
+    }
 }
 
 /// Observer implementation for pointer types such as [`Box<T>`] and `&mut T`.
@@ -51,6 +133,41 @@ impl<O> DerefMut for DerefMutObserver<O> {
     }
 }
 
+impl<O, D, P, T> DerefObserver<O>
+where
+    O: RefObserver<InnerDepth = Succ<D>>,
+    O::Head: AsDerefMut<D, Target = P>,
+    D: Unsigned,
+    P: MakeMut<Value = T>,
+    T: Observe,
+{
+    /// Makes the pointed-to value unique and returns its default observer.
+    ///
+    /// The value is cloned first if the pointer is shared, see
+    /// [`Rc::make_mut`](std::rc::Rc::make_mut) and [`Arc::make_mut`](std::sync::Arc::make_mut).
+    /// The returned observer keeps its state across calls, and its mutations take the place of
+    /// the replace that the pointer change would otherwise cause on the next flush.
+    pub fn make_mut<'a>(&'a mut self) -> &'a mut T::Observer<'a, T, Zero>
+    where
+        T: 'a,
+        T::Observer<'a, T, Zero>: SerializeObserver,
+    {
+        let head = unsafe { Pointer::as_mut(self.inner.as_deref_coinductive()) };
+        let value = MakeMut::make_mut(AsDerefMut::<D>::as_deref_mut(head));
+        match &mut self.owned.0 {
+            Some(ob) => {
+                let ob = unsafe { ob.ptr.cast::<T::Observer<'a, T, Zero>>().as_mut() };
+                unsafe { Observer::relocate(ob, value) };
+                ob
+            }
+            None => {
+                let ob = self.owned.0.insert(ErasedObserver::new(T::Observer::observe(value)));
+                unsafe { ob.ptr.cast().as_mut() }
+            }
+        }
+    }
+}
+
 impl<O, D> QuasiObserver for DerefObserver<O>
 where
     D: Unsigned,
@@ -62,8 +179,19 @@ where
     type InnerDepth = D;
 
     fn invalidate(this: &mut Self) {
+        this.owned.0 = None;
         O::invalidate(&mut this.inner);
     }
+
+    /// Returns [`None`] once [`make_mut`](DerefObserver::make_mut) has been called, as the
+    /// mutations are then recorded by the type-erased observer of the pointed-to value.
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        match this.owned.0 {
+            Some(_) => None,
+            None => O::inverse(&this.inner, head),
+        }
+    }
 }
 
 impl<O, D> QuasiObserver for DerefMutObserver<O>
@@ -79,6 +207,11 @@ where
     fn invalidate(this: &mut Self) {
         O::invalidate(&mut this.inner);
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        O::inverse(&this.inner, head)
+    }
 }
 
 impl<O, D> Observer for DerefObserver<O>
@@ -88,9 +221,12 @@ where
     D: Unsigned,
 {
     fn observe(head: &mut Self::Head) -> Self {
-        Self {
+        let this = Self {
             inner: O::observe(head),
-        }
+            owned: OwnedObserver(None),
+        };
+        Pointer::register_state::<_, Zero>(this.inner.as_deref_coinductive(), &this.owned);
+        this
     }
 
     unsafe fn relocate(this: &mut Self, head: &mut Self::Head) {
@@ -105,9 +241,12 @@ where
     D: Unsigned,
 {
     fn observe(head: &Self::Head) -> Self {
-        Self {
+        let this = Self {
             inner: O::observe(head),
-        }
+            owned: OwnedObserver(None),
+        };
+        Pointer::register_state::<_, Zero>(this.inner.as_deref_coinductive(), &this.owned);
+        this
     }
 
     unsafe fn relocate(this: &mut Self, head: &Self::Head) {
@@ -139,11 +278,19 @@ where
     D: Unsigned,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
-        unsafe { O::flush(&mut this.inner) }
+        let mutations = unsafe { O::flush(&mut this.inner) };
+        match &this.owned.0 {
+            Some(ob) => unsafe { (ob.flush)(ob.ptr) },
+            None => mutations,
+        }
     }
 
     unsafe fn flat_flush(this: &mut Self) -> Mutations {
-        unsafe { O::flat_flush(&mut this.inner) }
+        let mutations = unsafe { O::flat_flush(&mut this.inner) };
+        match &this.owned.0 {
+            Some(ob) => unsafe { (ob.flat_flush)(ob.ptr) },
+            None => mutations,
+        }
     }
 }
 
@@ -413,6 +560,9 @@ generic_impl_cmp! {
 
 #[cfg(test)]
 mod test {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
     use morphix_test_utils::*;
     use serde_json::json;
 
@@ -452,4 +602,39 @@ mod test {
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, Some(replace!(_, json!("42"))));
     }
+
+    #[test]
+    fn test_make_mut() {
+        let mut value: Rc<Vec<i32>> = Rc::new(vec![1]);
+        let mut ob = value.__observe();
+        ob.make_mut().push(2);
+        ob.make_mut().push(3);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!([2, 3]))));
+
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+
+        *ob.tracked_mut() = Rc::new(vec![4]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([4]))));
+    }
+
+    #[test]
+    fn test_make_mut_shared() {
+        let mut value: Arc<String> = Arc::new(String::from("foo"));
+        let other = value.clone();
+        let mut ob = value.__observe();
+        ob.make_mut().push_str("bar");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!("bar"))));
+        assert_eq!(*other, "foo");
+
+        let other = Arc::clone(&ob);
+        ob.make_mut().push('!');
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(_, json!("!"))));
+        assert_eq!(*other, "foobar");
+        assert_eq!(ob.untracked_ref().as_str(), "foobar!");
+    }
 }
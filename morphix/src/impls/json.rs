@@ -0,0 +1,1007 @@
+//! Observer implementations for [`serde_json::Value`] documents.
+
+use std::cell::UnsafeCell;
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+
+use serde_json::{Map, Value};
+
+use crate::general::Snapshot;
+use crate::helper::macros::default_impl_ref_observe;
+use crate::helper::{AsDeref, AsDerefMut, Captor, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+#[cfg(feature = "inverse")]
+use crate::impls::collections::insert_restore;
+use crate::impls::{StringObserver, VecObserver};
+use crate::observe::{DefaultSpec, Observer, SerializeObserver};
+use crate::{MutationKind, Mutations, Observe};
+
+enum EntryState {
+    /// Key existed in the original object and was overwritten via
+    /// [`insert`](ObjectObserver::insert).
+    Replaced,
+    /// Key is new (did not exist in the original object), added via
+    /// [`insert`](ObjectObserver::insert).
+    Inserted,
+    /// Key existed in the original object and was removed.
+    Deleted,
+}
+
+#[derive(Default)]
+struct ObjectObserverState<'ob> {
+    mutated: bool,
+    diff: BTreeMap<String, EntryState>,
+    /// Values of the original object displaced since the last flush, together with the inverses of
+    /// their observers.
+    #[cfg(feature = "inverse")]
+    captured: BTreeMap<String, Captor>,
+    /// Boxed so that observers handed out by [`ObjectObserver::get`] stay in place while other
+    /// entries are inserted through the shared reference.
+    inner: UnsafeCell<BTreeMap<String, Box<ValueObserver<'ob, Value>>>>,
+}
+
+impl Invalidate<Map<String, Value>> for ObjectObserverState<'_> {
+    fn invalidate(&mut self, map: &Map<String, Value>) {
+        if !self.mutated {
+            self.mutated = true;
+            for (key, value) in map {
+                self.mark_deleted(key.clone(), value);
+            }
+        }
+        self.inner.get_mut().clear();
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, map: &Map<String, Value>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if !self.mutated {
+            for (key, ob) in unsafe { &*self.inner.get() } {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(key.clone(), QuasiObserver::inverse(&**ob, map.get(key)?)?);
+                }
+            }
+        }
+        mutations.extend(self.inverse_structure(map)?);
+        Some(mutations)
+    }
+}
+
+impl<'ob> ObjectObserverState<'ob> {
+    /// Captures the original `value` of `key` with the inverse of its observer `ob`, unless it has
+    /// been captured since the last flush.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture(&mut self, key: &str, value: &Value, ob: Option<&ValueObserver<'ob, Value>>) {
+        #[cfg(feature = "inverse")]
+        self.captured
+            .entry(key.to_owned())
+            .or_default()
+            .capture_with(value, || match ob {
+                Some(ob) => QuasiObserver::inverse(ob, value),
+                None => Some(Mutations::new()),
+            });
+    }
+
+    /// Returns the mutations which restore the keys of the original object from the current `map`,
+    /// leaving the inverses of the value observers aside.
+    #[cfg(feature = "inverse")]
+    fn inverse_structure(&self, map: &Map<String, Value>) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if self.mutated {
+            for key in map.keys() {
+                if !self.diff.contains_key(key) {
+                    mutations.insert(key.clone(), MutationKind::Delete);
+                }
+            }
+        }
+        for (key, entry_state) in &self.diff {
+            match entry_state {
+                EntryState::Inserted => mutations.insert(key.clone(), MutationKind::Delete),
+                EntryState::Replaced | EntryState::Deleted => {
+                    insert_restore(&mut mutations, key.clone().into(), self.captured.get(key)?.inverse()?);
+                }
+            }
+        }
+        Some(mutations)
+    }
+
+    /// Attaches the inverse of the changes to the keys since the last flush to `mutations`, given
+    /// the current `map`, and resets the captured values.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn attach(&mut self, map: &Map<String, Value>, mutations: Mutations) -> Mutations {
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(self.inverse_structure(map));
+        #[cfg(feature = "inverse")]
+        self.captured.clear();
+        mutations
+    }
+
+    fn mark_deleted(&mut self, key: String, value: &Value) {
+        let ob = self.inner.get_mut().remove(&key);
+        if !self.diff.contains_key(&key) {
+            self.capture(&key, value, ob.as_deref());
+        }
+        match self.diff.entry(key) {
+            Entry::Occupied(mut e) => {
+                if matches!(e.get(), EntryState::Inserted) {
+                    e.remove();
+                } else {
+                    e.insert(EntryState::Deleted);
+                }
+            }
+            Entry::Vacant(e) => {
+                e.insert(EntryState::Deleted);
+            }
+        }
+    }
+}
+
+/// Observer implementation for [`Map<String, Value>`](Map), the object type of
+/// [`serde_json::Value`].
+///
+/// It tracks keys the same way as [`HashMapObserver`](super::HashMapObserver): inserted and
+/// overwritten keys are replaced, removed keys are deleted, and values reached through
+/// [`get_mut`](Self::get_mut) or indexing are observed by [`ValueObserver`].
+pub struct ObjectObserver<'ob, S: ?Sized, D = Zero> {
+    ptr: Pointer<S>,
+    state: ObjectObserverState<'ob>,
+    phantom: PhantomData<D>,
+}
+
+impl<'ob, S: ?Sized, D> Deref for ObjectObserver<'ob, S, D> {
+    type Target = Pointer<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+impl<'ob, S: ?Sized, D> DerefMut for ObjectObserver<'ob, S, D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        std::ptr::from_mut(self).expose_provenance();
+        Pointer::invalidate(&mut self.ptr);
+        &mut self.ptr
+    }
+}
+
+impl<'ob, S: ?Sized, D> QuasiObserver for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Map<String, Value>>,
+{
+    type Head = S;
+    type OuterDepth = Succ<Zero>;
+    type InnerDepth = D;
+
+    fn invalidate(this: &mut Self) {
+        Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
+}
+
+impl<'ob, S: ?Sized, D> Observer for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Map<String, Value>>,
+{
+    fn observe(head: &mut Self::Head) -> Self {
+        let this = Self {
+            ptr: Pointer::new(head),
+            state: Default::default(),
+            phantom: PhantomData,
+        };
+        Pointer::register_state::<_, D>(&this.ptr, &this.state);
+        this
+    }
+
+    unsafe fn relocate(this: &mut Self, head: &mut Self::Head) {
+        Pointer::set(this, head);
+    }
+}
+
+impl<'ob, S: ?Sized, D> ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    unsafe fn partial_flush(&mut self) -> Mutations {
+        #[cfg(feature = "inverse")]
+        let inverse = self.state.inverse_structure((*self.ptr).as_deref());
+        #[cfg(feature = "inverse")]
+        self.state.captured.clear();
+        let diff = std::mem::take(&mut self.state.diff);
+        let mut inner = std::mem::take(self.state.inner.get_mut());
+        let mut mutations = Mutations::new();
+        for (key, entry_state) in diff {
+            match entry_state {
+                EntryState::Deleted => {
+                    #[cfg(feature = "delete")]
+                    mutations.insert(key, MutationKind::Delete);
+                    #[cfg(not(feature = "delete"))]
+                    return Mutations::replace((*self).untracked_ref());
+                }
+                EntryState::Replaced | EntryState::Inserted => {
+                    inner.remove(&key);
+                    let value = (*self)
+                        .untracked_ref()
+                        .get(&key)
+                        .expect("replaced key not found in observed object");
+                    mutations.insert(key, Mutations::replace(value));
+                }
+            }
+        }
+        // The inverse of the keys is attached before the value mutations, so that the inverses of
+        // the values are applied first.
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+        }
+        for (key, mut ob) in inner {
+            let value = self
+                .untracked_mut()
+                .get_mut(&key)
+                .expect("observer key not found in observed object");
+            unsafe { Observer::relocate(&mut *ob, value) }
+            mutations.insert(key, unsafe { SerializeObserver::flush(&mut *ob) });
+        }
+        mutations
+    }
+}
+
+impl<'ob, S: ?Sized, D> SerializeObserver for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    unsafe fn flush(this: &mut Self) -> Mutations {
+        if !this.state.mutated {
+            return unsafe { this.partial_flush() };
+        }
+        let map = (*this.ptr).as_deref();
+        let mutations = this.state.attach(map, Mutations::replace(map));
+        this.state.mutated = false;
+        this.state.diff.clear();
+        this.state.inner.get_mut().clear();
+        mutations
+    }
+
+    unsafe fn flat_flush(this: &mut Self) -> Mutations {
+        if !this.state.mutated {
+            return unsafe { this.partial_flush() };
+        }
+        this.state.inner.get_mut().clear();
+        // After DerefMut, diff contains only Deleted entries representing original keys.
+        // Emit Replace for each current key, Delete for original keys no longer present.
+        let map = (*this.ptr).as_deref();
+        let mut mutations = Mutations::new().with_replace(true);
+        for (key, value) in map {
+            mutations.insert(key.clone(), Mutations::replace(value));
+        }
+        for key in this.state.diff.keys() {
+            if !map.contains_key(key) {
+                #[cfg(feature = "delete")]
+                mutations.insert(key.clone(), MutationKind::Delete);
+                #[cfg(not(feature = "delete"))]
+                unreachable!("delete feature is not enabled");
+            }
+        }
+        let mutations = this.state.attach(map, mutations);
+        this.state.mutated = false;
+        this.state.diff.clear();
+        mutations
+    }
+}
+
+impl<'ob, S: ?Sized, D> ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    /// See [`Map::get`].
+    pub fn get(&self, key: &str) -> Option<&ValueObserver<'ob, Value>> {
+        let value = unsafe { Pointer::as_mut(&self.ptr) }.as_deref_mut().get_mut(key)?;
+        match unsafe { (*self.state.inner.get()).entry(key.to_owned()) } {
+            Entry::Occupied(occupied) => {
+                let ob = occupied.into_mut().as_mut();
+                unsafe { Observer::relocate(ob, value) }
+                Some(ob)
+            }
+            Entry::Vacant(vacant) => Some(vacant.insert(Box::new(Observer::observe(value)))),
+        }
+    }
+
+    /// See [`Map::get_mut`].
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut ValueObserver<'ob, Value>> {
+        let value = (*self.ptr).as_deref_mut().get_mut(key)?;
+        match self.state.inner.get_mut().entry(key.to_owned()) {
+            Entry::Occupied(occupied) => {
+                let ob = occupied.into_mut().as_mut();
+                unsafe { Observer::relocate(ob, value) }
+                Some(ob)
+            }
+            Entry::Vacant(vacant) => Some(vacant.insert(Box::new(Observer::observe(value)))),
+        }
+    }
+
+    fn __force_all(&mut self) -> &mut BTreeMap<String, Box<ValueObserver<'ob, Value>>> {
+        let map = (*self.ptr).as_deref_mut();
+        let inner = self.state.inner.get_mut();
+        for (key, value) in map.iter_mut() {
+            match inner.entry(key.clone()) {
+                Entry::Occupied(occupied) => {
+                    let observer = occupied.into_mut().as_mut();
+                    unsafe { Observer::relocate(observer, value) }
+                }
+                Entry::Vacant(vacant) => {
+                    vacant.insert(Box::new(Observer::observe(value)));
+                }
+            }
+        }
+        inner
+    }
+
+    /// See [`Map::clear`].
+    pub fn clear(&mut self) {
+        if (*self).untracked_ref().is_empty() {
+            self.state.inner.get_mut().clear();
+            self.untracked_mut().clear()
+        } else {
+            self.tracked_mut().clear()
+        }
+    }
+
+    /// See [`Map::insert`].
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        if self.state.mutated {
+            return self.tracked_mut().insert(key, value);
+        }
+        let old_value = (*self.ptr).as_deref_mut().insert(key.clone(), value);
+        let ob = self.state.inner.get_mut().remove(&key);
+        if let Some(old_value) = &old_value
+            && !self.state.diff.contains_key(&key)
+        {
+            self.state.capture(&key, old_value, ob.as_deref());
+        }
+        match self.state.diff.entry(key) {
+            Entry::Occupied(mut e) => {
+                if matches!(e.get(), EntryState::Deleted) {
+                    e.insert(EntryState::Replaced);
+                }
+            }
+            Entry::Vacant(e) => {
+                if old_value.is_some() {
+                    e.insert(EntryState::Replaced);
+                } else {
+                    e.insert(EntryState::Inserted);
+                }
+            }
+        }
+        old_value
+    }
+
+    /// See [`Map::remove`].
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// See [`Map::remove_entry`].
+    pub fn remove_entry(&mut self, key: &str) -> Option<(String, Value)> {
+        if self.state.mutated {
+            return self.tracked_mut().remove_entry(key);
+        }
+        let (key, old_value) = (*self.ptr).as_deref_mut().remove_entry(key)?;
+        self.state.mark_deleted(key.clone(), &old_value);
+        Some((key, old_value))
+    }
+
+    /// See [`Map::retain`].
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&String, &mut Value) -> bool,
+    {
+        if self.state.mutated {
+            return self.tracked_mut().retain(f);
+        }
+        let state = &mut self.state;
+        (*self.ptr).as_deref_mut().retain(|key, value| {
+            let keep = f(key, value);
+            if !keep {
+                state.mark_deleted(key.clone(), value);
+            }
+            keep
+        });
+    }
+
+    /// See [`Map::iter_mut`].
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut ValueObserver<'ob, Value>)> + '_ {
+        self.__force_all().iter_mut().map(|(k, v)| (k, v.as_mut()))
+    }
+
+    /// See [`Map::values_mut`].
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut ValueObserver<'ob, Value>> + '_ {
+        self.__force_all().values_mut().map(|v| v.as_mut())
+    }
+}
+
+impl<'ob, S: ?Sized, D> Debug for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Map<String, Value>>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ObjectObserver").field(&self.untracked_ref()).finish()
+    }
+}
+
+impl<'ob, S: ?Sized, D> PartialEq<Map<String, Value>> for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Map<String, Value>>,
+{
+    fn eq(&self, other: &Map<String, Value>) -> bool {
+        self.untracked_ref().eq(other)
+    }
+}
+
+impl<'ob, S: ?Sized, D> Index<&str> for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    type Output = ValueObserver<'ob, Value>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<'ob, S: ?Sized, D> IndexMut<&str> for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        self.get_mut(key).expect("no entry found for key")
+    }
+}
+
+impl<'ob, S: ?Sized, D> Extend<(String, Value)> for ObjectObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Map<String, Value>>,
+{
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// Observer of the payload of a [`Value`], created for the variant it currently holds.
+enum ValueObserverInner<'ob> {
+    Other,
+    String(StringObserver<'ob, String>),
+    Array(VecObserver<ValueObserver<'ob, Value>, Vec<Value>>),
+    Object(ObjectObserver<'ob, Map<String, Value>>),
+}
+
+struct ValueObserverState<'ob> {
+    mutated: bool,
+    inner: UnsafeCell<ValueObserverInner<'ob>>,
+    captor: Captor,
+}
+
+impl Invalidate<Value> for ValueObserverState<'_> {
+    fn invalidate(&mut self, value: &Value) {
+        if !self.mutated {
+            let changes = self.inverse_inner(value);
+            self.captor.capture_with(value, || changes);
+        }
+        self.mutated = true;
+        *self.inner.get_mut() = ValueObserverInner::Other;
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &Value) -> Option<Mutations> {
+        match self.mutated {
+            true => self.captor.inverse(),
+            false => self.inverse_inner(value),
+        }
+    }
+}
+
+impl ValueObserverState<'_> {
+    /// Returns the inverse of the changes recorded by the observer of the payload.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn inverse_inner(&self, value: &Value) -> Option<Mutations> {
+        #[cfg(feature = "inverse")]
+        match (value, unsafe { &*self.inner.get() }) {
+            (Value::String(value), ValueObserverInner::String(ob)) => return QuasiObserver::inverse(ob, value),
+            (Value::Array(value), ValueObserverInner::Array(ob)) => return QuasiObserver::inverse(ob, value),
+            (Value::Object(value), ValueObserverInner::Object(ob)) => return QuasiObserver::inverse(ob, value),
+            _ => {}
+        }
+        Some(Mutations::new())
+    }
+}
+
+/// Observer implementation for [`serde_json::Value`].
+///
+/// The observer follows the variant the value currently holds. Objects are observed like a
+/// [`HashMapObserver`](super::HashMapObserver) (see [`ObjectObserver`]), arrays by a
+/// [`VecObserver`] and strings by a [`StringObserver`], reached through
+/// [`as_object_mut`](Self::as_object_mut), [`as_array_mut`](Self::as_array_mut) and
+/// [`as_string_mut`](Self::as_string_mut), or by indexing with a key or a position. Other variants
+/// are replaced as a whole.
+///
+/// ## Example
+///
+/// ```
+/// use morphix::adapter::Json;
+/// use morphix::{Mutation, MutationKind, Observe, observe};
+/// use serde::Serialize;
+/// use serde_json::{Value, json};
+///
+/// #[derive(Serialize, Observe)]
+/// struct User {
+///     data: Value,
+/// }
+///
+/// let mut user = User { data: json!({ "a": { "b": 0 }, "list": [1] }) };
+/// let Json(mutation) = observe!(user => {
+///     user.data["a"]["b"] = json!(1);
+///     user.data["list"].as_array_mut().unwrap().push(json!(2));
+/// }).unwrap();
+///
+/// let mutations = mutation.unwrap().flatten();
+/// assert_eq!(mutations.len(), 2);
+/// assert_eq!(mutations[0].0.to_string(), ".data.a.b");
+/// assert_eq!(mutations[0].1, MutationKind::Replace(json!(1)));
+/// assert_eq!(mutations[1].0.to_string(), ".data.list");
+/// assert_eq!(mutations[1].1, MutationKind::Append(json!([2])));
+/// ```
+///
+/// ## Indexing
+///
+/// Indexing follows [`Value`]: mutably indexing [`Value::Null`] with a key turns it into an
+/// object, and a missing key is inserted as [`Value::Null`]. Unlike [`Value`], shared indexing
+/// panics on a missing key or position instead of returning [`Value::Null`], since there is no
+/// observer to return.
+pub struct ValueObserver<'ob, S: ?Sized, D = Zero> {
+    ptr: Pointer<S>,
+    state: ValueObserverState<'ob>,
+    phantom: PhantomData<D>,
+}
+
+impl<'ob, S: ?Sized, D> Deref for ValueObserver<'ob, S, D> {
+    type Target = Pointer<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+impl<'ob, S: ?Sized, D> DerefMut for ValueObserver<'ob, S, D> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        std::ptr::from_mut(self).expose_provenance();
+        Pointer::invalidate(&mut self.ptr);
+        &mut self.ptr
+    }
+}
+
+impl<'ob, S: ?Sized, D> QuasiObserver for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Value>,
+{
+    type Head = S;
+    type OuterDepth = Succ<Zero>;
+    type InnerDepth = D;
+
+    fn invalidate(this: &mut Self) {
+        Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
+    }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
+}
+
+impl<'ob, S: ?Sized, D> Observer for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Value>,
+{
+    fn observe(head: &mut Self::Head) -> Self {
+        let this = Self {
+            ptr: Pointer::new(head),
+            state: ValueObserverState {
+                mutated: false,
+                inner: UnsafeCell::new(ValueObserverInner::Other),
+                captor: Captor::default(),
+            },
+            phantom: PhantomData,
+        };
+        Pointer::register_state::<_, D>(&this.ptr, &this.state);
+        this
+    }
+
+    unsafe fn relocate(this: &mut Self, head: &mut Self::Head) {
+        Pointer::set(this, head);
+    }
+}
+
+impl<'ob, S: ?Sized, D> SerializeObserver for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    unsafe fn flush(this: &mut Self) -> Mutations {
+        if this.state.mutated {
+            this.state.mutated = false;
+            *this.state.inner.get_mut() = ValueObserverInner::Other;
+            return this.state.captor.attach(Mutations::replace((*this).untracked_ref()));
+        }
+        let value = (*this.ptr).as_deref_mut();
+        match (value, this.state.inner.get_mut()) {
+            (Value::String(value), ValueObserverInner::String(ob)) => unsafe {
+                Observer::relocate(ob, value);
+                SerializeObserver::flush(ob)
+            },
+            (Value::Array(value), ValueObserverInner::Array(ob)) => unsafe {
+                Observer::relocate(ob, value);
+                SerializeObserver::flush(ob)
+            },
+            (Value::Object(value), ValueObserverInner::Object(ob)) => unsafe {
+                Observer::relocate(ob, value);
+                SerializeObserver::flush(ob)
+            },
+            _ => Mutations::new(),
+        }
+    }
+}
+
+impl<'ob, S: ?Sized, D> ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    /// Returns the observer of the current payload, creating it if the variant has changed.
+    fn force(&self) -> &ValueObserverInner<'ob> {
+        let value = unsafe { Pointer::as_mut(&self.ptr) }.as_deref_mut();
+        let inner = unsafe { &mut *self.state.inner.get() };
+        match (value, &mut *inner) {
+            (Value::String(value), ValueObserverInner::String(ob)) => unsafe { Observer::relocate(ob, value) },
+            (Value::Array(value), ValueObserverInner::Array(ob)) => unsafe { Observer::relocate(ob, value) },
+            (Value::Object(value), ValueObserverInner::Object(ob)) => unsafe { Observer::relocate(ob, value) },
+            (Value::String(value), _) => *inner = ValueObserverInner::String(Observer::observe(value)),
+            (Value::Array(value), _) => *inner = ValueObserverInner::Array(Observer::observe(value)),
+            (Value::Object(value), _) => *inner = ValueObserverInner::Object(Observer::observe(value)),
+            _ => *inner = ValueObserverInner::Other,
+        }
+        inner
+    }
+
+    fn force_mut(&mut self) -> &mut ValueObserverInner<'ob> {
+        self.force();
+        self.state.inner.get_mut()
+    }
+
+    /// See [`Value::as_str`]. Returns the observer of the string if the value is a string.
+    ///
+    /// [`Value`] has no counterpart, since a [`String`] can only be reached mutably through
+    /// pattern matching.
+    pub fn as_string_mut(&mut self) -> Option<&mut StringObserver<'ob, String>> {
+        match self.force_mut() {
+            ValueObserverInner::String(ob) => Some(ob),
+            _ => None,
+        }
+    }
+
+    /// See [`Value::as_array_mut`].
+    pub fn as_array_mut(&mut self) -> Option<&mut VecObserver<ValueObserver<'ob, Value>, Vec<Value>>> {
+        match self.force_mut() {
+            ValueObserverInner::Array(ob) => Some(ob),
+            _ => None,
+        }
+    }
+
+    /// See [`Value::as_object_mut`].
+    pub fn as_object_mut(&mut self) -> Option<&mut ObjectObserver<'ob, Map<String, Value>>> {
+        match self.force_mut() {
+            ValueObserverInner::Object(ob) => Some(ob),
+            _ => None,
+        }
+    }
+
+    /// See [`Value::take`].
+    pub fn take(&mut self) -> Value {
+        if (*self).untracked_ref().is_null() {
+            return Value::Null;
+        }
+        std::mem::take(self.tracked_mut())
+    }
+}
+
+impl<'ob, S: ?Sized, D> Debug for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Value>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ValueObserver").field(&self.untracked_ref()).finish()
+    }
+}
+
+impl<'ob, S: ?Sized, D, U> PartialEq<U> for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDeref<D, Target = Value>,
+    Value: PartialEq<U>,
+{
+    fn eq(&self, other: &U) -> bool {
+        self.untracked_ref().eq(other)
+    }
+}
+
+impl<'ob, S: ?Sized, D> Index<&str> for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    type Output = ValueObserver<'ob, Value>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self.force() {
+            ValueObserverInner::Object(ob) => &ob[key],
+            _ => panic!("cannot access key {key:?} in a non-object JSON value"),
+        }
+    }
+}
+
+impl<'ob, S: ?Sized, D> IndexMut<&str> for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        if (*self).untracked_ref().is_null() {
+            *self.tracked_mut() = Value::Object(Map::new());
+        }
+        match self.force_mut() {
+            ValueObserverInner::Object(ob) => {
+                if !(*ob).untracked_ref().contains_key(key) {
+                    ob.insert(key.to_owned(), Value::Null);
+                }
+                &mut ob[key]
+            }
+            _ => panic!("cannot access key {key:?} in a non-object JSON value"),
+        }
+    }
+}
+
+impl<'ob, S: ?Sized, D> Index<usize> for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    type Output = ValueObserver<'ob, Value>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.force() {
+            ValueObserverInner::Array(ob) => &ob[index],
+            _ => panic!("cannot access index {index} of a non-array JSON value"),
+        }
+    }
+}
+
+impl<'ob, S: ?Sized, D> IndexMut<usize> for ValueObserver<'ob, S, D>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = Value>,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match self.force_mut() {
+            ValueObserverInner::Array(ob) => &mut ob[index],
+            _ => panic!("cannot access index {index} of a non-array JSON value"),
+        }
+    }
+}
+
+impl Observe for Value {
+    type Observer<'ob, S, D>
+        = ValueObserver<'ob, S, D>
+    where
+        Self: 'ob,
+        D: Unsigned,
+        S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+    type Spec = DefaultSpec;
+}
+
+impl Observe for Map<String, Value> {
+    type Observer<'ob, S, D>
+        = ObjectObserver<'ob, S, D>
+    where
+        Self: 'ob,
+        D: Unsigned,
+        S: AsDerefMut<D, Target = Self> + ?Sized + 'ob;
+
+    type Spec = DefaultSpec;
+}
+
+default_impl_ref_observe! {
+    impl RefObserve for Value;
+    impl RefObserve for Map<String, Value>;
+}
+
+impl Snapshot for Value {
+    type Snapshot = Self;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        self == snapshot
+    }
+}
+
+impl Snapshot for Map<String, Value> {
+    type Snapshot = Self;
+
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+
+    fn eq_snapshot(&self, snapshot: &Self::Snapshot) -> bool {
+        self == snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::json;
+
+    use crate::adapter::Json;
+    use crate::helper::QuasiObserver;
+    use crate::observe::{ObserveExt, SerializeObserverExt};
+
+    #[test]
+    fn no_change() {
+        let mut value = json!({ "a": [1, "x"] });
+        let mut ob = value.__observe();
+        assert_eq!(ob["a"][1], json!("x"));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn nested_replace() {
+        let mut value = json!({ "a": { "b": 0 } });
+        let mut ob = value.__observe();
+        *ob["a"]["b"].tracked_mut() = json!(1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(a.b, json!(1))));
+        *ob["a"]["c"].tracked_mut() = json!(2);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(a.c, json!(2))));
+        assert_eq!(ob.untracked_ref(), &json!({ "a": { "b": 1, "c": 2 } }));
+    }
+
+    #[test]
+    fn array_append() {
+        let mut value = json!({ "list": [1] });
+        let mut ob = value.__observe();
+        ob["list"].as_array_mut().unwrap().push(json!(2));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(list, json!([2]))));
+        *ob["list"][0].tracked_mut() = json!(3);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(list.-2, json!(3))));
+    }
+
+    #[test]
+    fn string_append() {
+        let mut value = json!(["foo"]);
+        let mut ob = value.__observe();
+        ob[0].as_string_mut().unwrap().push_str("bar");
+        assert!(ob[0].as_array_mut().is_none());
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(append!(-1, json!("bar"))));
+    }
+
+    #[test]
+    fn object_insert_remove() {
+        let mut value = json!({ "a": 1, "b": 2 });
+        let mut ob = value.__observe();
+        let object = ob.as_object_mut().unwrap();
+        assert_eq!(object.insert("c".into(), json!(3)), None);
+        assert_eq!(object.remove("a"), Some(json!(1)));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, delete!(a), replace!(c, json!(3)))));
+    }
+
+    #[test]
+    fn variant_change() {
+        let mut value = json!({ "a": "x" });
+        let mut ob = value.__observe();
+        *ob["a"].tracked_mut() = json!([1]);
+        ob["a"].as_array_mut().unwrap().push(json!(2));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(a, json!([1, 2]))));
+        assert_eq!(ob.take(), json!({ "a": [1, 2] }));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!(null))));
+    }
+
+    #[test]
+    fn index_null() {
+        let mut value = json!(null);
+        let mut ob = value.__observe();
+        *ob["a"]["b"].tracked_mut() = json!(1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!({ "a": { "b": 1 } }))));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot access key \"a\" in a non-object JSON value")]
+    fn index_non_object() {
+        let mut value = json!(1);
+        let mut ob = value.__observe();
+        *ob["a"].tracked_mut() = json!(1);
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use serde_json::Value;
+
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::ValueObserver<'_, Value>);
+        let cases: [Case; 5] = [
+            |ob| {
+                *ob["a"]["b"].tracked_mut() = json!(1);
+                let object = ob.as_object_mut().unwrap();
+                object.remove("a");
+                object.insert("c".into(), json!(3));
+            },
+            |ob| {
+                ob["list"][0].as_string_mut().unwrap().push('!');
+                ob["list"].as_array_mut().unwrap().push(json!(2));
+            },
+            |ob| {
+                ob["a"]["b"].as_string_mut().unwrap().push('!');
+                *ob["a"].tracked_mut() = json!([1]);
+                ob["a"].as_array_mut().unwrap().push(json!(2));
+            },
+            |ob| {
+                ob["a"]["b"].as_string_mut().unwrap().push('!');
+                let object = ob.as_object_mut().unwrap();
+                object.clear();
+                object.insert("list".into(), json!(null));
+            },
+            |ob| {
+                ob["a"]["b"].as_string_mut().unwrap().push('!');
+                ob.as_object_mut().unwrap().retain(|key, _| key != "a");
+                *ob.tracked_mut() = json!(null);
+            },
+        ];
+        for mutate in cases {
+            let before = json!({ "a": { "b": "x" }, "list": ["y"] });
+            let mut value = before.clone();
+            let mut ob = value.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(before, value, flushed);
+        }
+    }
+}
@@ -17,9 +17,12 @@
 //! stable.
 
 mod atomic;
+mod cell;
 mod collections;
 mod cow;
 mod deref;
+#[cfg(feature = "json")]
+mod json;
 mod newtype;
 mod option;
 mod range;
@@ -28,9 +31,12 @@ mod strings;
 mod tuple;
 mod weak;
 
+pub use cell::{LockObserver, LockObserverGuard, MutexObserver, RefCellObserver, RwLockObserver};
 pub use collections::*;
 pub use cow::CowObserver;
-pub use deref::{DerefMutObserver, DerefObserver};
+pub use deref::{DerefMutObserver, DerefObserver, MakeMut};
+#[cfg(feature = "json")]
+pub use json::{ObjectObserver, ValueObserver};
 pub use newtype::NewtypeObserver;
 pub use option::OptionObserver;
 pub use slices::*;
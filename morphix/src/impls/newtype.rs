@@ -78,7 +78,7 @@ impl<O, S: ?Sized, D> QuasiObserver for NewtypeObserver<O, S, D>
 where
     O: QuasiObserver,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
 {
     type Head = S;
     type OuterDepth = Succ<Zero>;
@@ -87,6 +87,11 @@ where
     fn invalidate(this: &mut Self) {
         O::invalidate(&mut this.0);
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        O::inverse(&this.0, head.as_deref().as_inner())
+    }
 }
 
 impl<O, S: ?Sized, D> Observer for NewtypeObserver<O, S, D>
@@ -150,9 +155,9 @@ where
 
 impl<O, S: ?Sized, D> Debug for NewtypeObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
     S::Target: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -162,12 +167,12 @@ where
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<NewtypeObserver<O2, S2, D2>> for NewtypeObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<Target: Deref<Target: AsDeref<O1::InnerDepth>>>,
-    O2: QuasiObserver<Target: Deref<Target: AsDeref<O2::InnerDepth>>>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D1: Unsigned,
     D2: Unsigned,
-    S1: AsDeref<D1>,
-    S2: AsDeref<D2>,
+    S1: AsDeref<D1, Target: Newtype<Inner = O1::Head>>,
+    S2: AsDeref<D2, Target: Newtype<Inner = O2::Head>>,
     S1::Target: PartialEq<S2::Target>,
 {
     fn eq(&self, other: &NewtypeObserver<O2, S2, D2>) -> bool {
@@ -177,21 +182,21 @@ where
 
 impl<O, S: ?Sized, D> Eq for NewtypeObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
     S::Target: Eq,
 {
 }
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<NewtypeObserver<O2, S2, D2>> for NewtypeObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<Target: Deref<Target: AsDeref<O1::InnerDepth>>>,
-    O2: QuasiObserver<Target: Deref<Target: AsDeref<O2::InnerDepth>>>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D1: Unsigned,
     D2: Unsigned,
-    S1: AsDeref<D1>,
-    S2: AsDeref<D2>,
+    S1: AsDeref<D1, Target: Newtype<Inner = O1::Head>>,
+    S2: AsDeref<D2, Target: Newtype<Inner = O2::Head>>,
     S1::Target: PartialOrd<S2::Target>,
 {
     fn partial_cmp(&self, other: &NewtypeObserver<O2, S2, D2>) -> Option<std::cmp::Ordering> {
@@ -201,9 +206,9 @@ where
 
 impl<O, S: ?Sized, D> Ord for NewtypeObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
     S::Target: Ord,
 {
     fn cmp(&self, other: &NewtypeObserver<O, S, D>) -> std::cmp::Ordering {
@@ -216,9 +221,9 @@ macro_rules! impl_fmt {
         $(
             impl<O, S: ?Sized, D> std::fmt::$trait for NewtypeObserver<O, S, D>
             where
-                O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+                O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
                 D: Unsigned,
-                S: AsDeref<D>,
+                S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
                 S::Target: std::fmt::$trait,
             {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -244,8 +249,8 @@ macro_rules! impl_ops_assign {
         $(
             impl<O, S: ?Sized, D, U> std::ops::$trait<U> for NewtypeObserver<O, S, D>
             where
-                S: AsDerefMut<D>,
-                O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+                S: AsDerefMut<D, Target: Newtype<Inner = O::Head>>,
+                O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
                 D: Unsigned,
                 S::Target: std::ops::$trait<U>,
             {
@@ -275,8 +280,8 @@ macro_rules! impl_ops_copy {
         $(
             impl<O, S: ?Sized, D, U> std::ops::$trait<U> for NewtypeObserver<O, S, D>
             where
-                O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
-                S: AsDeref<D>,
+                O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+                S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
                 D: Unsigned,
                 S::Target: std::ops::$trait<U> + Copy,
             {
@@ -308,8 +313,8 @@ macro_rules! impl_ops_copy_unary {
         $(
             impl<O, S: ?Sized, D> std::ops::$trait for NewtypeObserver<O, S, D>
             where
-                O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
-                S: AsDeref<D>,
+                O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+                S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
                 D: Unsigned,
                 S::Target: std::ops::$trait + Copy,
             {
@@ -347,9 +352,9 @@ macro_rules! impl_newtype {
 
         impl<O, S: ?Sized, D, U> PartialEq<$wrapper<U>> for NewtypeObserver<O, S, D>
         where
-            O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+            O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
             S::Target: PartialEq<$wrapper<U>>,
         {
             fn eq(&self, other: &$wrapper<U>) -> bool {
@@ -359,9 +364,9 @@ macro_rules! impl_newtype {
 
         impl<O, S: ?Sized, D, U> PartialOrd<$wrapper<U>> for NewtypeObserver<O, S, D>
         where
-            O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+            O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target: Newtype<Inner = O::Head>>,
             S::Target: PartialOrd<$wrapper<U>>,
         {
             fn partial_cmp(&self, other: &$wrapper<U>) -> Option<std::cmp::Ordering> {
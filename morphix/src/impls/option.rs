@@ -7,23 +7,51 @@ use serde::Serialize;
 use crate::Mutations;
 use crate::general::Snapshot;
 use crate::helper::macros::{spec_impl_observe, spec_impl_ref_observe};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::{AsDeref, AsDerefMut, Captor, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::observe::{Observer, RefObserver, SerializeObserver};
 
 struct OptionObserverState<O> {
     initial: bool,
     mutated: bool,
     inner: Option<O>,
+    captor: Captor,
 }
 
 impl<O> Invalidate<Option<O::Head>> for OptionObserverState<O>
 where
-    O: QuasiObserver<Head: Sized>,
+    O: QuasiObserver<Head: Sized + Capture>,
 {
-    fn invalidate(&mut self, _value: &Option<O::Head>) {
+    fn invalidate(&mut self, value: &Option<O::Head>) {
+        if !self.mutated {
+            let changes = self.inverse_inner(value);
+            self.captor.capture_with(value, || changes);
+        }
         self.mutated = true;
         self.inner = None;
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &Option<O::Head>) -> Option<Mutations> {
+        match self.mutated {
+            true => self.captor.inverse(),
+            false => self.inverse_inner(value),
+        }
+    }
+}
+
+impl<O> OptionObserverState<O>
+where
+    O: QuasiObserver<Head: Sized>,
+{
+    /// Returns the inverse of the changes recorded by the inner observer.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn inverse_inner(&self, value: &Option<O::Head>) -> Option<Mutations> {
+        #[cfg(feature = "inverse")]
+        if let (Some(inner), Some(value)) = (&self.inner, value) {
+            return QuasiObserver::inverse(inner, value);
+        }
+        Some(Mutations::new())
+    }
 }
 
 /// Observer implementation for [`Option<T>`].
@@ -51,7 +79,7 @@ impl<O, S: ?Sized, D> DerefMut for OptionObserver<O, S, D> {
 
 impl<O, S: ?Sized, D> QuasiObserver for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
 {
@@ -62,6 +90,11 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<O, S: ?Sized, D> Observer for OptionObserver<O, S, D>
@@ -69,7 +102,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Option<O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
+    O::Head: Sized + Capture,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let this = Self {
@@ -77,6 +110,7 @@ where
                 initial: head.as_deref_mut().is_some(),
                 mutated: false,
                 inner: head.as_deref_mut().as_mut().map(O::observe),
+                captor: Captor::default(),
             },
             ptr: Pointer::new(head),
             phantom: PhantomData,
@@ -98,7 +132,7 @@ where
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
     O: RefObserver<InnerDepth = Zero>,
-    O::Head: Sized,
+    O::Head: Sized + Capture,
 {
     fn observe(head: &Self::Head) -> Self {
         let this = Self {
@@ -107,6 +141,7 @@ where
                 initial: head.as_deref().is_some(),
                 mutated: false,
                 inner: head.as_deref().as_ref().map(O::observe),
+                captor: Captor::default(),
             },
             phantom: PhantomData,
         };
@@ -138,11 +173,12 @@ where
             return unsafe { O::flush(this.state.inner.as_mut().unwrap()) };
         }
         this.state.inner = None;
-        if initial || option.is_some() {
+        let mutations = if initial || option.is_some() {
             Mutations::replace(option)
         } else {
             Mutations::new()
-        }
+        };
+        this.state.captor.attach(mutations)
     }
 }
 
@@ -151,7 +187,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Option<O::Head>>,
     O: Observer<InnerDepth = Zero>,
-    O::Head: Sized,
+    O::Head: Sized + Capture,
 {
     /// See [`Option::as_mut`].
     pub fn as_mut(&mut self) -> Option<&mut O> {
@@ -197,7 +233,7 @@ where
 
 impl<O, S: ?Sized, D> Debug for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Debug>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture + Debug>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
 {
@@ -208,7 +244,7 @@ where
 
 impl<O, S: ?Sized, D, U> PartialEq<Option<U>> for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
     Option<O::Head>: PartialEq<Option<U>>,
@@ -220,8 +256,8 @@ where
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<OptionObserver<O2, S2, D2>> for OptionObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
-    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = Option<O1::Head>>,
@@ -235,7 +271,7 @@ where
 
 impl<O, S: ?Sized, D> Eq for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Eq>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture + Eq>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
 {
@@ -243,7 +279,7 @@ where
 
 impl<O, S: ?Sized, D, U> PartialOrd<Option<U>> for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
     Option<O::Head>: PartialOrd<Option<U>>,
@@ -255,8 +291,8 @@ where
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<OptionObserver<O2, S2, D2>> for OptionObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
-    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture>,
     D1: Unsigned,
     D2: Unsigned,
     S1: AsDeref<D1, Target = Option<O1::Head>>,
@@ -270,7 +306,7 @@ where
 
 impl<O, S: ?Sized, D> Ord for OptionObserver<O, S, D>
 where
-    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Ord>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized + Capture + Ord>,
     D: Unsigned,
     S: AsDeref<D, Target = Option<O::Head>>,
 {
@@ -417,6 +453,39 @@ mod tests {
         assert_eq!(format!("{ob:?}"), r#"DerefObserver(OptionObserver(Some("")))"#);
     }
 
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut OptionObserver<crate::impls::StringObserver<'_, String>, Option<String>>);
+        let cases: [(Option<&str>, Case); 4] = [
+            (Some("foo"), |ob| {
+                *ob.as_mut().unwrap() += "bar";
+            }),
+            (Some("foo"), |ob| {
+                *ob.as_mut().unwrap() += "bar";
+                *ob.tracked_mut() = None;
+            }),
+            (None, |ob| {
+                *ob.insert("x".into()) += "y";
+            }),
+            (Some("foo"), |ob| {
+                ob.as_mut().unwrap().truncate(1);
+                *ob.tracked_mut() = Some("bar".into());
+                *ob.as_mut().unwrap() += "baz";
+            }),
+        ];
+        for (before, mutate) in cases {
+            let before = before.map(String::from);
+            let mut opt = before.clone();
+            let mut ob = opt.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(opt), flushed);
+        }
+    }
+
     #[test]
     fn relocate() {
         let mut vec = vec![None::<i32>];
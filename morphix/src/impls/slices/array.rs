@@ -29,6 +29,15 @@ where
             O::invalidate(ob);
         }
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &[O::Head; N]) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        for (index, (ob, head)) in self.iter().zip(value).enumerate() {
+            mutations.insert(index, O::inverse(ob, head)?);
+        }
+        Some(mutations)
+    }
 }
 
 impl<O, const N: usize> SliceObserverState for [O; N]
@@ -85,7 +94,7 @@ where
             mutations.insert(index, inner_mutations);
         }
         if is_replace {
-            return Mutations::replace(slice.as_ref());
+            return Mutations::replace(slice.as_ref()).with_inverses_of(mutations);
         };
         mutations
     }
@@ -141,6 +150,11 @@ where
     fn invalidate(this: &mut Self) {
         SliceObserver::invalidate(&mut this.inner);
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        QuasiObserver::inverse(&this.inner, head)
+    }
 }
 
 impl<const N: usize, O, S: ?Sized, D, T> Observer for ArrayObserver<N, O, S, D>
@@ -11,7 +11,8 @@ use std::slice::{
 
 use crate::general::{Unsize, UnsizeObserver};
 use crate::helper::macros::delegate_methods;
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::reorder::permute;
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::impls::slices::helper::GetDisjointMutIndexImpl;
 use crate::impls::vec::VecObserverState;
 use crate::observe::{DefaultSpec, Observer, RefObserve, RefObserver, SerializeObserver};
@@ -47,6 +48,27 @@ pub trait SliceObserverState: Invalidate<Self::Target> + Sized {
     /// alive when this method is called, as the implementation may create mutable references to
     /// the same storage through interior mutability.
     unsafe fn relocate(&self, slice: &mut Self::Target);
+
+    /// Returns the number of leading elements whose rearrangement can be recorded with
+    /// [`mark_permute`](Self::mark_permute).
+    ///
+    /// Defaults to `0`, in which case rearranged elements are tracked as replaced.
+    fn movable_len(&self) -> usize {
+        0
+    }
+
+    /// Records that the leading elements were rearranged, where `perm[i]` is the previous index of
+    /// the element now at index `i`, and rearranges the element observers accordingly.
+    ///
+    /// Returns `false` without recording anything if the rearrangement would take as many moves as
+    /// there are elements, in which case the slice should be tracked as replaced instead.
+    ///
+    /// This is only called right after [`relocate`](Self::relocate), with `perm.len()` not
+    /// exceeding [`movable_len`](Self::movable_len).
+    fn mark_permute(&mut self, perm: &[usize]) -> bool {
+        let _ = perm;
+        false
+    }
 }
 
 /// Shared-reference counterpart to [`SliceObserverState`] for element [`RefObserver`] management.
@@ -113,6 +135,11 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.state, (*this.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.state, head.as_deref())
+    }
 }
 
 impl<V, S: ?Sized, D, T> Observer for SliceObserver<V, S, D>
@@ -193,6 +220,29 @@ where
         }
     }
 
+    /// Rearranges the leading `len` elements by the permutation computed by `f`, recording the
+    /// rearrangement as moves.
+    ///
+    /// Returns `false` without touching the slice if the rearrangement cannot be recorded.
+    fn permute_with(&mut self, len: usize, f: impl FnOnce(&[T], &mut [usize])) -> bool {
+        if len > self.state.movable_len() {
+            return false;
+        }
+        self.force_mut();
+        let mut perm = (0..len).collect::<Vec<_>>();
+        f((*self).untracked_ref().as_ref(), &mut perm);
+        if self.state.mark_permute(&perm) {
+            permute(self.untracked_mut().as_mut(), &perm);
+        } else {
+            permute(self.tracked_mut().as_mut(), &perm);
+        }
+        true
+    }
+
+    fn untracked_len(&self) -> usize {
+        (*self).untracked_ref().as_ref().len()
+    }
+
     delegate_methods! { force_mut() as slice =>
         pub fn first_mut(&mut self) -> Option<&mut V::Item>;
         pub fn last_mut(&mut self) -> Option<&mut V::Item>;
@@ -210,6 +260,10 @@ where
 
     /// See [`slice::swap`].
     pub fn swap(&mut self, a: usize, b: usize) {
+        let len = self.untracked_len();
+        if a < len && b < len && self.permute_with(a.max(b) + 1, |_, perm| perm.swap(a, b)) {
+            return;
+        }
         QuasiObserver::invalidate(&mut self[a]);
         QuasiObserver::invalidate(&mut self[b]);
         self.untracked_mut().as_mut().swap(a, b);
@@ -239,15 +293,61 @@ where
         pub fn rsplitn_mut<F>(&mut self, n: usize, pred: F) -> RSplitNMut<'_, V::Item, F> where F: FnMut(&V::Item) -> bool;
     }
 
+    /// See [`slice::rotate_left`].
+    pub fn rotate_left(&mut self, mid: usize) {
+        let len = self.untracked_len();
+        if !self.permute_with(len, |_, perm| perm.rotate_left(mid)) {
+            self.nonempty_mut().rotate_left(mid);
+        }
+    }
+
+    /// See [`slice::rotate_right`].
+    pub fn rotate_right(&mut self, k: usize) {
+        let len = self.untracked_len();
+        if !self.permute_with(len, |_, perm| perm.rotate_right(k)) {
+            self.nonempty_mut().rotate_right(k);
+        }
+    }
+
+    /// See [`slice::sort_unstable`].
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| {
+            perm.sort_by(|&i, &j| slice[i].cmp(&slice[j]))
+        }) {
+            self.nonempty_mut().sort_unstable();
+        }
+    }
+
+    /// See [`slice::sort_unstable_by`].
+    pub fn sort_unstable_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| {
+            perm.sort_by(|&i, &j| compare(&slice[i], &slice[j]))
+        }) {
+            self.nonempty_mut().sort_unstable_by(compare);
+        }
+    }
+
+    /// See [`slice::sort_unstable_by_key`].
+    pub fn sort_unstable_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| perm.sort_by_key(|&i| f(&slice[i]))) {
+            self.nonempty_mut().sort_unstable_by_key(f);
+        }
+    }
+
     delegate_methods! { nonempty_mut() as slice =>
-        pub fn sort_unstable(&mut self) where T: Ord;
-        pub fn sort_unstable_by<F>(&mut self, compare: F) where F: FnMut(&T, &T) -> Ordering;
-        pub fn sort_unstable_by_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: Ord;
         pub fn select_nth_unstable(&mut self, index: usize) -> (&mut [T], &mut T, &mut [T]) where T: Ord;
         pub fn select_nth_unstable_by<F>(&mut self, index: usize, compare: F) -> (&mut [T], &mut T, &mut [T]) where F: FnMut(&T, &T) -> Ordering;
         pub fn select_nth_unstable_by_key<K, F>(&mut self, index: usize, f: F) -> (&mut [T], &mut T, &mut [T]) where F: FnMut(&T) -> K, K: Ord;
-        pub fn rotate_left(&mut self, mid: usize);
-        pub fn rotate_right(&mut self, k: usize);
         pub fn fill(&mut self, value: T) where T: Clone;
         pub fn fill_with<F>(&mut self, f: F) where F: FnMut() -> T;
         pub fn clone_from_slice(&mut self, src: &[T]) where T: Clone;
@@ -280,11 +380,52 @@ where
         GetDisjointMutIndexImpl::get_disjoint_mut(self.force_mut(), indices)
     }
 
-    delegate_methods! { nonempty_mut() as slice =>
-        pub fn sort(&mut self) where T: Ord;
-        pub fn sort_by<F>(&mut self, compare: F) where F: FnMut(&T, &T) -> Ordering;
-        pub fn sort_by_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: Ord;
-        pub fn sort_by_cached_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: Ord;
+    /// See [`slice::sort`].
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| {
+            perm.sort_by(|&i, &j| slice[i].cmp(&slice[j]))
+        }) {
+            self.nonempty_mut().sort();
+        }
+    }
+
+    /// See [`slice::sort_by`].
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| {
+            perm.sort_by(|&i, &j| compare(&slice[i], &slice[j]))
+        }) {
+            self.nonempty_mut().sort_by(compare);
+        }
+    }
+
+    /// See [`slice::sort_by_key`].
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| perm.sort_by_key(|&i| f(&slice[i]))) {
+            self.nonempty_mut().sort_by_key(f);
+        }
+    }
+
+    /// See [`slice::sort_by_cached_key`].
+    pub fn sort_by_cached_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        if !self.permute_with(self.untracked_len(), |slice, perm| {
+            perm.sort_by_cached_key(|&i| f(&slice[i]))
+        }) {
+            self.nonempty_mut().sort_by_cached_key(f);
+        }
     }
 }
 
@@ -418,7 +559,7 @@ where
     }
 }
 
-impl<T: Observe> Observe for [T] {
+impl<T: Observe + Capture> Observe for [T] {
     type Observer<'ob, S, D>
         = SliceObserver<VecObserverState<T::Observer<'ob, T, Zero>>, S, D>
     where
@@ -441,7 +582,7 @@ impl<T> Unsize for [T] {
     }
 }
 
-impl<T> RefObserve for [T] {
+impl<T: Capture> RefObserve for [T] {
     type Observer<'ob, S, D>
         = UnsizeObserver<'ob, S, D>
     where
@@ -494,9 +635,60 @@ mod tests {
         ob.swap(0, 1);
         assert_eq!(*ob.untracked_ref(), [1, 0, 2]);
         let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(mv!(_, 1, 0)));
+    }
+
+    #[test]
+    fn swap_distant() {
+        let slice: &mut [u32] = &mut [0, 1, 2, 3];
+        let mut ob = slice.__observe();
+        ob.swap(3, 0);
+        assert_eq!(*ob.untracked_ref(), [3, 1, 2, 0]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, mv!(_, 3, 0), mv!(_, 1, 3))));
+    }
+
+    #[test]
+    fn rotate_triggers_move() {
+        let slice: &mut [u32] = &mut [0, 1, 2, 3, 4];
+        let mut ob = slice.__observe();
+        ob.rotate_left(1);
+        assert_eq!(*ob.untracked_ref(), [1, 2, 3, 4, 0]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(mv!(_, 0, 4)));
+
+        ob.rotate_right(2);
+        assert_eq!(*ob.untracked_ref(), [4, 0, 1, 2, 3]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, mv!(_, 3, 0), mv!(_, 4, 1))));
+    }
+
+    #[test]
+    fn sort_triggers_move() {
+        let slice: &mut [u32] = &mut [3, 0, 1, 2];
+        let mut ob = slice.__observe();
+        ob.sort();
+        assert_eq!(*ob.untracked_ref(), [0, 1, 2, 3]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(mv!(_, 0, 3)));
+
+        ob.sort_by_key(|x| *x);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn move_preserves_element_observers() {
+        let slice: &mut [u32] = &mut [2, 0, 1];
+        let mut ob = slice.__observe();
+        *ob[0].tracked_mut() = 3;
+        ob.sort_unstable();
+        *ob[0].tracked_mut() = 4;
+        assert_eq!(*ob.untracked_ref(), [4, 1, 3]);
+        let Json(mutation) = ob.flush().unwrap();
         assert_eq!(
             mutation,
-            Some(batch!(_, replace!(-2, json!(0)), replace!(-3, json!(1))))
+            Some(batch!(_, mv!(_, 0, 2), replace!(-1, json!(3)), replace!(-3, json!(4))))
         );
     }
 
@@ -504,10 +696,10 @@ mod tests {
     fn boxed_slice_deref_mut_triggers_replace() {
         let mut boxed: Box<[u32]> = vec![1, 2, 3].into_boxed_slice();
         let mut ob = boxed.__observe();
-        // Mutate through the slice observer's DerefMut (e.g. via sort).
-        ob.sort();
+        // Mutate through the slice observer's DerefMut (e.g. via copy_from_slice).
+        ob.copy_from_slice(&[1, 2, 3]);
         let Json(mutation) = ob.flush().unwrap();
-        // Even though sort is a no-op here (already sorted), DerefMut was triggered
+        // Even though copy_from_slice is a no-op here (same values), DerefMut was triggered
         // so a Replace should be emitted. With diff type `()`, this bug causes None.
         assert!(mutation.is_some(), "DerefMut on Box<[T]> should trigger Replace");
     }
@@ -1,6 +1,8 @@
 //! Observer implementation for [`Vec<T>`].
 
 use std::cell::UnsafeCell;
+#[cfg(feature = "inverse")]
+use std::collections::BTreeMap;
 use std::collections::TryReserveError;
 use std::fmt::Debug;
 use std::mem::MaybeUninit;
@@ -11,31 +13,58 @@ use std::vec::{Drain, ExtractIf, Splice};
 use serde::Serialize;
 
 use crate::general::Snapshot;
+#[cfg(feature = "inverse")]
+use crate::helper::Captured;
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::reorder::{permutation_moves, permute};
+use crate::helper::{AsDeref, AsDerefMut, Capture, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
 use crate::impls::slice::{SliceObserver, SliceObserverState, SliceSerializeObserverState};
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
 use crate::{MutationKind, Mutations, Observe, PathSegment};
 
 /// Observer state for dynamically-sized slices ([`Vec<T>`], [`Box<[T]>`](Box)), tracking
-/// [`Append`](MutationKind::Append) and [`Truncate`](MutationKind::Truncate) boundaries.
+/// [`Append`](MutationKind::Append), [`Truncate`](MutationKind::Truncate) and
+/// [`Splice`](MutationKind::Splice) boundaries, as well as [`Move`](MutationKind::Move)s among
+/// existing elements.
 ///
 /// The `append_index` divides the observed slice into two regions: elements before it are
 /// "existing" (may have individual observer state), and elements from `append_index` onward are
 /// "appended" (new since the last flush).
 ///
+/// Within the existing region, a single splice window `[splice_index, splice_index +
+/// splice_insert_len)` records elements which replaced `splice_delete_len` original elements. Edits
+/// touching the existing region widen the window instead of truncating everything after them.
+///
+/// Rearrangements of the existing region are recorded as moves, which are emitted before any other
+/// mutation. Therefore, they can only be recorded while there is no splice window.
+///
 /// ## Replace Semantics
 ///
 /// During [`flush`](SliceSerializeObserverState::flush), if all existing elements' inner observers
 /// report [`Replace`](MutationKind::Replace) and there was at least some tracked content
 /// (`append_index > 0` or `truncate_len > 0`), the granular mutations are collapsed into a single
 /// whole-slice [`Replace`](MutationKind::Replace).
+///
+/// ## Inverse
+///
+/// With the `inverse` feature, each original element is captured right before the first edit which
+/// removes it, by the splice window or the truncation, together with the inverse of its observer.
+/// The inverse reinserts the removed elements and reverts them, while the remaining existing
+/// elements are reverted by their own observers.
 pub struct VecObserverState<O> {
     /// Number of elements truncated from the end since the last flush.
     truncate_len: usize,
     /// Starting index of appended elements. Elements before this index are "existing" and have
     /// their inner observers flushed individually.
     append_index: usize,
+    /// Starting index of the splice window.
+    splice_index: usize,
+    /// Number of original elements removed by the splice window.
+    splice_delete_len: usize,
+    /// Number of elements currently inside the splice window.
+    splice_insert_len: usize,
+    /// Moves among existing elements, as `(from, to)` pairs in order.
+    moves: Vec<(usize, usize)>,
     /// Lazily-initialized element observer storage.
     ///
     /// Unlike map observers which use [`Box<O>`] for pointer stability across rehashing /
@@ -48,35 +77,322 @@ pub struct VecObserverState<O> {
     /// and subsequent calls within the same `&self` borrow lifetime never trigger reallocation,
     /// keeping all previously returned references valid.
     inner: UnsafeCell<Vec<O>>,
+    /// Observers of the elements following the splice window, detached from `inner` so that they
+    /// keep their state while the window is resized.
+    ///
+    /// When non-empty, `inner` holds exactly `splice_index` observers.
+    /// [`relocate`](SliceObserverState::relocate) fills the window with fresh observers and
+    /// reattaches these.
+    detached: UnsafeCell<Vec<O>>,
+    /// Captured original elements with the inverse of their observers, keyed by their indices after
+    /// the recorded moves.
+    #[cfg(feature = "inverse")]
+    captured: BTreeMap<usize, (Captured, Option<Mutations<Captured>>)>,
+    /// All moves since the last flush, including those superseded by a truncation.
+    #[cfg(feature = "inverse")]
+    moved: Vec<(usize, usize)>,
 }
 
 impl<O> VecObserverState<O> {
-    fn mark_truncate(&mut self, index: usize) {
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            truncate_len: 0,
+            append_index: len,
+            splice_index: 0,
+            splice_delete_len: 0,
+            splice_insert_len: 0,
+            moves: Vec::new(),
+            inner: UnsafeCell::new(Vec::new()),
+            detached: UnsafeCell::new(Vec::new()),
+            #[cfg(feature = "inverse")]
+            captured: BTreeMap::new(),
+            #[cfg(feature = "inverse")]
+            moved: Vec::new(),
+        }
+    }
+
+    fn has_splice(&self) -> bool {
+        self.splice_delete_len + self.splice_insert_len > 0
+    }
+
+    /// Folds the splice window into the truncated tail, so that everything from `splice_index`
+    /// onward is re-sent as appended elements.
+    fn merge_splice(&mut self) {
+        self.truncate_len += self.append_index + self.splice_delete_len - self.splice_index - self.splice_insert_len;
+        self.append_index = self.splice_index;
+        self.splice_delete_len = 0;
+        self.splice_insert_len = 0;
+        self.inner.get_mut().truncate(self.splice_index);
+        self.detached.get_mut().clear();
+    }
+
+    pub(super) fn mark_truncate(&mut self, index: usize) {
         if self.append_index <= index {
             return;
         }
+        if self.has_splice() && index <= self.splice_index + self.splice_insert_len {
+            self.merge_splice();
+            if self.append_index <= index {
+                return;
+            }
+        }
         self.truncate_len += self.append_index - index;
         self.append_index = index;
+        if index == 0 {
+            self.moves.clear();
+        }
+    }
+
+    /// Records that `delete_len` elements starting at `index` were replaced by `insert_len` new
+    /// elements. Indices refer to the slice as it was before the edit.
+    pub(super) fn mark_splice(&mut self, index: usize, delete_len: usize, insert_len: usize) {
+        if cfg!(not(feature = "splice")) || index + delete_len >= self.append_index {
+            self.mark_truncate(index);
+            return;
+        }
+        if delete_len == 0 && insert_len == 0 {
+            return;
+        }
+        if !self.has_splice() {
+            self.splice_index = index;
+        }
+        let splice_end = self.splice_index + self.splice_insert_len;
+        let start = index.min(self.splice_index);
+        let end = (index + delete_len).max(splice_end);
+
+        // Observers inside `[start, end)` are discarded, as the window covers these elements.
+        let inner = self.inner.get_mut();
+        let detached = self.detached.get_mut();
+        if detached.is_empty() && inner.len() > splice_end {
+            *detached = inner.split_off(splice_end);
+        }
+        detached.drain(..(end - splice_end).min(detached.len()));
+        inner.truncate(start);
+
+        self.splice_delete_len += (self.splice_index - start) + (end - splice_end);
+        self.splice_insert_len = end - start - delete_len + insert_len;
+        self.splice_index = start;
+        self.append_index = self.append_index - delete_len + insert_len;
+        if self.splice_delete_len + self.splice_insert_len == 0 {
+            self.inner.get_mut().append(detached);
+        }
+    }
+
+    /// Drops observers at and beyond `index` in the appended region, as those slots hold shifted
+    /// elements after an insertion.
+    pub(super) fn truncate_appended(&mut self, index: usize) {
+        if index < self.append_index {
+            return;
+        }
+        let detached = self.detached.get_mut();
+        if detached.is_empty() {
+            self.inner.get_mut().truncate(index);
+        } else {
+            detached.truncate(index - self.splice_index - self.splice_insert_len);
+        }
     }
 
-    fn mark_replace(&mut self) {
+    pub(super) fn mark_replace(&mut self) {
         self.inner.get_mut().clear();
+        self.detached.get_mut().clear();
         self.mark_truncate(0);
     }
 }
 
+impl<O> VecObserverState<O>
+where
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
+{
+    /// Captures the original elements which [`mark_splice`](Self::mark_splice) would remove, before
+    /// the edit is applied to `values`.
+    ///
+    /// Without the `inverse` feature, this is a no-op.
+    pub(super) fn capture_splice<V>(&mut self, values: &V, index: usize, delete_len: usize)
+    where
+        V: Index<usize, Output = O::Head> + ?Sized,
+    {
+        if cfg!(not(feature = "splice")) || index + delete_len >= self.append_index {
+            return self.capture_truncate(values, index);
+        }
+        let (splice_index, splice_end) = match self.has_splice() {
+            true => (self.splice_index, self.splice_index + self.splice_insert_len),
+            false => (index, index),
+        };
+        self.capture_range(values, index.min(splice_index), splice_index);
+        self.capture_range(values, splice_end, (index + delete_len).max(splice_end));
+    }
+
+    /// Captures the original elements which [`mark_truncate`](Self::mark_truncate) would remove,
+    /// before the edit is applied to `values`.
+    ///
+    /// Without the `inverse` feature, this is a no-op.
+    pub(super) fn capture_truncate<V>(&mut self, values: &V, index: usize)
+    where
+        V: Index<usize, Output = O::Head> + ?Sized,
+    {
+        if self.has_splice() && index <= self.splice_index + self.splice_insert_len {
+            // The splice window is merged into the truncated tail.
+            self.capture_range(values, self.splice_index + self.splice_insert_len, self.append_index);
+            self.capture_range(values, index, self.splice_index);
+        } else {
+            self.capture_range(values, index, self.append_index);
+        }
+    }
+
+    /// Captures the existing elements of `values` in `start..end` outside the splice window.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_variables))]
+    fn capture_range<V>(&mut self, values: &V, start: usize, end: usize)
+    where
+        V: Index<usize, Output = O::Head> + ?Sized,
+    {
+        #[cfg(feature = "inverse")]
+        {
+            let has_splice = self.has_splice();
+            let splice_end = self.splice_index + self.splice_insert_len;
+            let inner = self.inner.get_mut();
+            let detached = self.detached.get_mut();
+            for index in start..end {
+                let (key, ob) = if has_splice && index >= splice_end {
+                    let key = index + self.splice_delete_len - self.splice_insert_len;
+                    match detached.is_empty() {
+                        true => (key, inner.get(index)),
+                        false => (key, detached.get(index - splice_end)),
+                    }
+                } else {
+                    (index, inner.get(index))
+                };
+                self.captured.entry(key).or_insert_with(|| {
+                    let value = &values[index];
+                    let inverse = match ob {
+                        Some(ob) => QuasiObserver::inverse(ob, value).map(Captured::mutations),
+                        None => Some(Mutations::new()),
+                    };
+                    (Captured::new(value), inverse)
+                });
+            }
+        }
+    }
+
+    /// Returns the inverse of the recorded mutations, given the current `values` of length `len`.
+    #[cfg(feature = "inverse")]
+    pub(super) fn inverse_in<V>(&self, values: &V, len: usize) -> Option<Mutations>
+    where
+        V: Index<usize, Output = O::Head> + ?Sized,
+    {
+        let mut mutations = Mutations::new();
+        for (index, ob) in self.existing() {
+            mutations.insert(
+                PathSegment::Negative(len - index),
+                QuasiObserver::inverse(ob, &values[index])?,
+            );
+        }
+        mutations.extend(self.inverse_structure(len)?);
+        Some(mutations)
+    }
+
+    /// Returns the observers of the existing elements outside the splice window, with their
+    /// indices.
+    #[cfg(feature = "inverse")]
+    fn existing(&self) -> impl Iterator<Item = (usize, &O)> {
+        let inner = unsafe { &*self.inner.get() };
+        let detached = unsafe { &*self.detached.get() };
+        let splice_end = self.splice_index + self.splice_insert_len;
+        let window = if detached.is_empty() {
+            self.splice_index..splice_end
+        } else {
+            0..0
+        };
+        inner
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| !window.contains(index))
+            .chain(
+                detached
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, ob)| (splice_end + index, ob)),
+            )
+            .take_while(|(index, _)| *index < self.append_index)
+    }
+
+    /// Returns the mutations which restore the original elements from the current `len` elements,
+    /// leaving the inverses of the existing elements aside.
+    #[cfg(feature = "inverse")]
+    fn inverse_structure(&self, len: usize) -> Option<Mutations> {
+        let mut mutations = Mutations::new();
+        if len > self.append_index {
+            mutations.extend(MutationKind::Truncate(len - self.append_index));
+        }
+        if self.truncate_len > 0 {
+            let start = self.append_index + self.splice_delete_len - self.splice_insert_len;
+            mutations.extend(self.restore(start, self.truncate_len, self.append_index, |values| {
+                Mutations::append_owned(values)
+            })?);
+        }
+        #[cfg(feature = "splice")]
+        if self.has_splice() {
+            let (index, insert_len) = (self.splice_index, self.splice_insert_len);
+            mutations.extend(self.restore(index, self.splice_delete_len, index, |values| {
+                Mutations::splice_owned(index, insert_len, values)
+            })?);
+        }
+        #[cfg(feature = "move")]
+        for &(from, to) in self.moved.iter().rev() {
+            mutations.extend(MutationKind::Move { from: to, to: from });
+        }
+        Some(mutations)
+    }
+
+    /// Reinserts `len` captured elements from `key` onward at `index` with `insert`, and reverts
+    /// them with the inverses of their observers.
+    #[cfg(feature = "inverse")]
+    fn restore(
+        &self,
+        key: usize,
+        len: usize,
+        index: usize,
+        insert: impl FnOnce(Vec<Captured>) -> Mutations,
+    ) -> Option<Mutations> {
+        let mut values = Vec::with_capacity(len);
+        let mut inverses = Mutations::new();
+        for offset in 0..len {
+            let (value, inverse) = self.captured.get(&(key + offset))?;
+            values.push(value.clone());
+            inverses.insert(
+                PathSegment::Positive(index + offset),
+                Captured::release(inverse.clone()?),
+            );
+        }
+        let mut mutations = insert(values);
+        mutations.extend(inverses);
+        Some(mutations)
+    }
+
+    #[cfg(feature = "inverse")]
+    fn reset_captured(&mut self) {
+        self.captured.clear();
+        self.moved.clear();
+    }
+}
+
 impl<O> Invalidate<[O::Head]> for VecObserverState<O>
 where
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
 {
-    fn invalidate(&mut self, _: &[O::Head]) {
+    fn invalidate(&mut self, value: &[O::Head]) {
+        self.capture_truncate(value, 0);
         self.mark_replace();
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &[O::Head]) -> Option<Mutations> {
+        self.inverse_in(value, value.len())
+    }
 }
 
 impl<O> SliceObserverState for VecObserverState<O>
 where
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
 {
     type Target = [O::Head];
     type Item = O;
@@ -90,15 +406,20 @@ where
     }
 
     fn observe(slice: &mut Self::Target) -> Self {
-        Self {
-            truncate_len: 0,
-            append_index: slice.as_ref().len(),
-            inner: UnsafeCell::new(Vec::new()),
-        }
+        Self::new(slice.len())
     }
 
     unsafe fn relocate(&self, slice: &mut Self::Target) {
         let inner = unsafe { &mut *self.inner.get() };
+        let detached = unsafe { &mut *self.detached.get() };
+        if !detached.is_empty() {
+            let splice_end = self.splice_index + self.splice_insert_len;
+            inner.reserve(splice_end - inner.len() + detached.len());
+            for value in slice[inner.len()..splice_end].iter_mut() {
+                inner.push(O::observe(value));
+            }
+            inner.append(detached);
+        }
         if inner.len() < slice.len() {
             inner.reserve(slice.len() - inner.len());
             for value in slice[inner.len()..].iter_mut() {
@@ -110,57 +431,129 @@ where
             unsafe { Observer::relocate(ob, value) }
         }
     }
+
+    fn movable_len(&self) -> usize {
+        if cfg!(feature = "move") && !self.has_splice() {
+            self.append_index
+        } else {
+            0
+        }
+    }
+
+    fn mark_permute(&mut self, perm: &[usize]) -> bool {
+        let inner = self.inner.get_mut();
+        let Some(moves) = permutation_moves(perm, inner.len().saturating_sub(self.moves.len())) else {
+            return false;
+        };
+        permute(inner, perm);
+        #[cfg(feature = "inverse")]
+        {
+            // Captured elements which have not been removed may be rearranged, so they are
+            // captured again when removed.
+            self.captured = self.captured.split_off(&perm.len());
+            self.moved.extend(&moves);
+        }
+        self.moves.extend(moves);
+        true
+    }
 }
 
-impl<O, S, D> SliceSerializeObserverState<S, D> for VecObserverState<O>
+impl<O> VecObserverState<O>
 where
-    D: Unsigned,
-    S: AsDerefMut<D, Target = [O::Head]> + ?Sized,
     O: Observer<InnerDepth = Zero> + SerializeObserver,
     O::Head: Serialize + Sized + 'static,
 {
-    type Target = [O::Head];
-    fn flush(&mut self, ptr: &mut Pointer<S>) -> Mutations {
-        // Drop stale inner observers beyond `append_index`. Elements in this region were popped
-        // since the last flush; their observers carry outdated state. Truncating here lets
-        // `relocate` create fresh observers for the newly appended elements at these indices.
-        self.inner.get_mut().truncate(self.append_index);
+    pub(super) fn flush_slice(&mut self, slice: &mut [O::Head]) -> Mutations {
+        // Drop stale inner observers beyond `append_index` and inside the splice window. Elements
+        // in these regions were replaced since the last flush; their observers carry outdated
+        // state. Truncating here lets `relocate` create fresh observers at these indices.
+        let splice_index = self.splice_index;
+        let splice_end = splice_index + self.splice_insert_len;
+        if self.has_splice() {
+            let inner = self.inner.get_mut();
+            let detached = self.detached.get_mut();
+            if detached.is_empty() && inner.len() > splice_end {
+                *detached = inner.split_off(splice_end);
+            }
+            inner.truncate(splice_index);
+            detached.truncate(self.append_index - splice_end);
+        } else {
+            self.inner.get_mut().truncate(self.append_index);
+        }
 
         // `relocate` must precede `Mutations::append`: `relocate` takes `&mut slice` (Unique
         // function-entry retag over the full slice), which would invalidate a `SerializeRef`'s
         // SRO tag if the append mutation were created first.
-        let slice = (**ptr).as_deref_mut();
         unsafe { self.relocate(slice) }
 
+        #[cfg(feature = "inverse")]
+        let inverse = self.inverse_structure(slice.len());
         let append_index = core::mem::replace(&mut self.append_index, slice.len());
         let truncate_len = core::mem::replace(&mut self.truncate_len, 0);
+        let has_splice = self.has_splice();
+        let must_replace = (has_splice && cfg!(not(feature = "splice")))
+            || (truncate_len > 0 && cfg!(not(feature = "truncate")))
+            || (slice.len() > append_index && cfg!(not(feature = "append")));
         let mut mutations = Mutations::new();
+        #[cfg(feature = "move")]
+        for (from, to) in self.moves.drain(..) {
+            mutations.extend(MutationKind::Move { from, to });
+        }
+        #[cfg(feature = "splice")]
+        if has_splice {
+            mutations.extend(Mutations::splice(
+                splice_index,
+                self.splice_delete_len,
+                &slice[splice_index..splice_end],
+            ));
+        }
+        self.splice_delete_len = 0;
+        self.splice_insert_len = 0;
+        #[cfg(feature = "truncate")]
         if truncate_len > 0 {
-            #[cfg(feature = "truncate")]
             mutations.extend(MutationKind::Truncate(truncate_len));
-            #[cfg(not(feature = "truncate"))]
-            return Mutations::replace(slice);
         }
+        #[cfg(feature = "append")]
         if slice.len() > append_index {
-            #[cfg(feature = "append")]
             mutations.extend(Mutations::append(&slice[append_index..]));
-            #[cfg(not(feature = "append"))]
-            return Mutations::replace(slice);
+        }
+        // The structural inverse is attached before the element mutations, so that the inverses of
+        // the elements are applied first.
+        #[cfg(feature = "inverse")]
+        {
+            mutations = mutations.with_inverse(inverse);
+            self.reset_captured();
         }
 
         let mut is_replace = true;
         for (index, ob) in self.inner.get_mut().iter_mut().take(append_index).enumerate().rev() {
+            if (splice_index..splice_end).contains(&index) {
+                continue;
+            }
             let mutations_i = unsafe { SerializeObserver::flush(ob) };
             is_replace &= mutations_i.is_replace();
             mutations.insert(PathSegment::Negative(slice.len() - index), mutations_i);
         }
-        if is_replace && !mutations.is_empty() {
-            return Mutations::replace(slice);
+        if must_replace || (is_replace && !mutations.is_empty()) {
+            return Mutations::replace(slice).with_inverses_of(mutations);
         };
         mutations
     }
 }
 
+impl<O, S, D> SliceSerializeObserverState<S, D> for VecObserverState<O>
+where
+    D: Unsigned,
+    S: AsDerefMut<D, Target = [O::Head]> + ?Sized,
+    O: Observer<InnerDepth = Zero> + SerializeObserver,
+    O::Head: Serialize + Sized + 'static,
+{
+    type Target = [O::Head];
+    fn flush(&mut self, ptr: &mut Pointer<S>) -> Mutations {
+        self.flush_slice((**ptr).as_deref_mut())
+    }
+}
+
 /// Observer implementation for [`Vec<T>`].
 pub struct VecObserver<O, S: ?Sized, D = Zero> {
     inner: SliceObserver<VecObserverState<O>, S, Succ<D>>,
@@ -184,7 +577,7 @@ impl<O, S: ?Sized, D> QuasiObserver for VecObserver<O, S, D>
 where
     D: Unsigned,
     S: AsDeref<D, Target = Vec<O::Head>>,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
 {
     type Head = S;
     type OuterDepth = Succ<Succ<Zero>>;
@@ -194,6 +587,11 @@ where
         // SliceObserver::invalidate(&mut this.inner);
         Invalidate::invalidate(&mut this.inner.state, (*this.inner.ptr).as_deref());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        QuasiObserver::inverse(&this.inner, head)
+    }
 }
 
 impl<O, S: ?Sized, D, T> Observer for VecObserver<O, S, D>
@@ -201,6 +599,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
 {
     fn observe(head: &mut Self::Head) -> Self {
         Self {
@@ -255,6 +654,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
 {
     fn nonempty_mut(&mut self) -> &mut Vec<T> {
         if (*self).untracked_ref().is_empty() {
@@ -264,6 +664,20 @@ where
         }
     }
 
+    fn range_bounds(&self, range: &impl RangeBounds<usize>) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => (*self).untracked_ref().len(),
+        };
+        (start, end)
+    }
+
     fn truncate_mut(&mut self) -> TruncateGuard<'_, O, T> {
         TruncateGuard {
             state: &mut self.inner.state,
@@ -271,6 +685,18 @@ where
         }
     }
 
+    /// Captures the elements which [`mark_splice`](VecObserverState::mark_splice) would remove.
+    fn capture_splice(&mut self, index: usize, delete_len: usize) {
+        let vec = (*self.inner.ptr).as_deref();
+        self.inner.state.capture_splice(vec, index, delete_len);
+    }
+
+    /// Captures the elements which [`mark_truncate`](VecObserverState::mark_truncate) would remove.
+    fn capture_truncate(&mut self, index: usize) {
+        let vec = (*self.inner.ptr).as_deref();
+        self.inner.state.capture_truncate(vec, index);
+    }
+
     delegate_methods! { untracked_mut() as Vec =>
         pub fn push(&mut self, value: T);
     }
@@ -291,8 +717,10 @@ where
         pub fn shrink_to(&mut self, min_capacity: usize);
     }
 
-    delegate_methods! { truncate_mut() as Vec =>
-        pub fn truncate(&mut self, len: usize);
+    /// See [`Vec::truncate`].
+    pub fn truncate(&mut self, len: usize) {
+        self.capture_truncate(len);
+        self.truncate_mut().truncate(len)
     }
 
     /// See [`Vec::as_mut_slice`].
@@ -304,12 +732,19 @@ where
         pub fn as_mut_ptr(&mut self) -> *mut T;
     }
 
-    delegate_methods! { truncate_mut() as Vec =>
-        pub unsafe fn set_len(&mut self, new_len: usize);
+    /// See [`Vec::set_len`].
+    ///
+    /// # Safety
+    ///
+    /// See [`Vec::set_len`].
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        self.capture_truncate(new_len);
+        unsafe { self.truncate_mut().set_len(new_len) }
     }
 
     /// See [`Vec::swap_remove`].
     pub fn swap_remove(&mut self, index: usize) -> T {
+        self.capture_truncate(index);
         let value = self.untracked_mut().swap_remove(index);
         self.state.mark_truncate(index);
         value
@@ -317,24 +752,27 @@ where
 
     /// See [`Vec::insert`].
     pub fn insert(&mut self, index: usize, element: T) {
+        self.capture_splice(index, 0);
         self.untracked_mut().insert(index, element);
-        self.state.mark_truncate(index);
+        self.state.mark_splice(index, 0, 1);
     }
 
     /// See [`Vec::insert_mut`].
     #[rustversion::since(1.95)]
     pub fn insert_mut(&mut self, index: usize, element: T) -> &mut O {
-        self.state.mark_truncate(index);
+        self.capture_splice(index, 0);
+        self.state.mark_splice(index, 0, 1);
         self.untracked_mut().insert(index, element);
         // Drop stale observers at and beyond `index` — those slots now hold shifted elements.
-        self.state.inner.get_mut().truncate(index);
+        self.state.truncate_appended(index);
         &mut self.force_mut()[index]
     }
 
     /// See [`Vec::remove`].
     pub fn remove(&mut self, index: usize) -> T {
+        self.capture_splice(index, 1);
         let value = self.untracked_mut().remove(index);
-        self.state.mark_truncate(index);
+        self.state.mark_splice(index, 1, 0);
         value
     }
 
@@ -351,13 +789,16 @@ where
     where
         F: FnMut(&mut T) -> bool,
     {
+        self.capture_truncate(0);
         let mut index = 0;
         (*self.inner.ptr).as_deref_mut().retain_mut(|v| {
+            // `index` counts the retained elements, which is the current position of `v`.
             let is_retained = f(v);
-            if !is_retained {
-                self.inner.state.mark_truncate(index);
+            if is_retained {
+                index += 1;
+            } else {
+                self.inner.state.mark_splice(index, 1, 0);
             }
-            index += 1;
             is_retained
         });
     }
@@ -367,9 +808,18 @@ where
         pub fn dedup_by<F>(&mut self, same_bucket: F) where F: FnMut(&mut T, &mut T) -> bool;
     }
 
-    delegate_methods! { truncate_mut() as Vec =>
-        pub fn pop(&mut self) -> Option<T>;
-        pub fn pop_if(&mut self, predicate: impl FnOnce(&mut T) -> bool) -> Option<T>;
+    /// See [`Vec::pop`].
+    pub fn pop(&mut self) -> Option<T> {
+        let len = (*self).untracked_ref().len();
+        self.capture_truncate(len.saturating_sub(1));
+        self.truncate_mut().pop()
+    }
+
+    /// See [`Vec::pop_if`].
+    pub fn pop_if(&mut self, predicate: impl FnOnce(&mut T) -> bool) -> Option<T> {
+        let len = (*self).untracked_ref().len();
+        self.capture_truncate(len.saturating_sub(1));
+        self.truncate_mut().pop_if(predicate)
     }
 
     delegate_methods! { untracked_mut() as Vec =>
@@ -381,12 +831,9 @@ where
     where
         R: RangeBounds<usize>,
     {
-        let start_index = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
-        self.state.mark_truncate(start_index);
+        let (start, end) = self.range_bounds(&range);
+        self.capture_splice(start, end.saturating_sub(start));
+        self.state.mark_splice(start, end.saturating_sub(start), 0);
         self.untracked_mut().drain(range)
     }
 
@@ -394,9 +841,19 @@ where
         pub fn clear(&mut self);
     }
 
-    delegate_methods! { truncate_mut() as Vec =>
-        pub fn split_off(&mut self, at: usize) -> Vec<T>;
-        pub fn resize_with<F>(&mut self, new_len: usize, f: F) where F: FnMut() -> T;
+    /// See [`Vec::split_off`].
+    pub fn split_off(&mut self, at: usize) -> Vec<T> {
+        self.capture_truncate(at);
+        self.truncate_mut().split_off(at)
+    }
+
+    /// See [`Vec::resize_with`].
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F)
+    where
+        F: FnMut() -> T,
+    {
+        self.capture_truncate(new_len);
+        self.truncate_mut().resize_with(new_len, f)
     }
 
     delegate_methods! { untracked_mut() as Vec =>
@@ -404,17 +861,21 @@ where
     }
 
     /// See [`Vec::splice`].
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter>
+    ///
+    /// Unlike [`Vec::splice`], the returned [`Splice`] is generic over
+    /// [`vec::IntoIter<T>`](std::vec::IntoIter) instead of `I::IntoIter`. The number of inserted
+    /// elements must be known to track the edit, so `replace_with` is collected into a [`Vec`]
+    /// before any element is removed, rather than lazily as the [`Splice`] is dropped.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, std::vec::IntoIter<T>>
     where
         R: RangeBounds<usize>,
         I: IntoIterator<Item = T>,
     {
-        let start_index = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
-        self.state.mark_truncate(start_index);
+        let (start, end) = self.range_bounds(&range);
+        self.capture_splice(start, end.saturating_sub(start));
+        let replace_with = replace_with.into_iter().collect::<Vec<_>>();
+        self.state
+            .mark_splice(start, end.saturating_sub(start), replace_with.len());
         self.untracked_mut().splice(range, replace_with)
     }
 
@@ -424,19 +885,17 @@ where
         F: FnMut(&mut T) -> bool,
         R: RangeBounds<usize>,
     {
-        let mut index = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
+        let (mut index, end) = self.range_bounds(&range);
+        self.capture_splice(index, end.saturating_sub(index));
         let state = &mut self.inner.state;
         let vec = (*self.inner.ptr).as_deref_mut();
         vec.extract_if(range, move |v| {
             let is_extracted = filter(v);
             if is_extracted {
-                state.mark_truncate(index);
+                state.mark_splice(index, 1, 0);
+            } else {
+                index += 1;
             }
-            index += 1;
             is_extracted
         })
     }
@@ -447,10 +906,13 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
     T: Clone,
 {
-    delegate_methods! { truncate_mut() as Vec =>
-        pub fn resize(&mut self, new_len: usize, value: T);
+    /// See [`Vec::resize`].
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        self.capture_truncate(new_len);
+        self.truncate_mut().resize(new_len, value)
     }
 
     delegate_methods! { untracked_mut() as Vec =>
@@ -464,6 +926,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
     T: PartialEq,
 {
     delegate_methods! { nonempty_mut() as Vec =>
@@ -476,6 +939,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
     Vec<T>: Extend<U>,
 {
     fn extend<I: IntoIterator<Item = U>>(&mut self, other: I) {
@@ -501,7 +965,7 @@ macro_rules! generic_impl_partial_eq {
             where
                 D: Unsigned,
                 S: AsDeref<D, Target = Vec<O::Head>>,
-                O: Observer<InnerDepth = Zero, Head: Sized>,
+                O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
                 Vec<O::Head>: PartialEq<$ty>,
             {
                 fn eq(&self, other: &$ty) -> bool {
@@ -523,8 +987,8 @@ impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<VecObserver<O2, S2, D2>>
 where
     D1: Unsigned,
     D2: Unsigned,
-    O1: Observer<InnerDepth = Zero, Head: Sized>,
-    O2: Observer<InnerDepth = Zero, Head: Sized>,
+    O1: Observer<InnerDepth = Zero, Head: Sized + Capture>,
+    O2: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S1: AsDeref<D1, Target = Vec<O1::Head>>,
     S2: AsDeref<D2, Target = Vec<O2::Head>>,
     Vec<O1::Head>: PartialEq<Vec<O2::Head>>,
@@ -538,7 +1002,7 @@ impl<O, S: ?Sized, D> Eq for VecObserver<O, S, D>
 where
     D: Unsigned,
     S: AsDeref<D, Target = Vec<O::Head>>,
-    O: Observer<InnerDepth = Zero, Head: Sized + Eq>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture + Eq>,
 {
 }
 
@@ -546,7 +1010,7 @@ impl<O, S: ?Sized, D, U> PartialOrd<Vec<U>> for VecObserver<O, S, D>
 where
     D: Unsigned,
     S: AsDeref<D, Target = Vec<O::Head>>,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     Vec<O::Head>: PartialOrd<Vec<U>>,
 {
     fn partial_cmp(&self, other: &Vec<U>) -> Option<std::cmp::Ordering> {
@@ -558,8 +1022,8 @@ impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<VecObserver<O2, S2, D2>>
 where
     D1: Unsigned,
     D2: Unsigned,
-    O1: Observer<InnerDepth = Zero, Head: Sized>,
-    O2: Observer<InnerDepth = Zero, Head: Sized>,
+    O1: Observer<InnerDepth = Zero, Head: Sized + Capture>,
+    O2: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S1: AsDeref<D1, Target = Vec<O1::Head>>,
     S2: AsDeref<D2, Target = Vec<O2::Head>>,
     Vec<O1::Head>: PartialOrd<Vec<O2::Head>>,
@@ -573,7 +1037,7 @@ impl<O, S: ?Sized, D> Ord for VecObserver<O, S, D>
 where
     D: Unsigned,
     S: AsDeref<D, Target = Vec<O::Head>>,
-    O: Observer<InnerDepth = Zero, Head: Sized + Ord>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture + Ord>,
 {
     fn cmp(&self, other: &VecObserver<O, S, D>) -> std::cmp::Ordering {
         self.untracked_ref().cmp(other.untracked_ref())
@@ -585,6 +1049,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
     I: SliceIndex<[O]>,
 {
     type Output = I::Output;
@@ -599,6 +1064,7 @@ where
     D: Unsigned,
     S: AsDerefMut<D, Target = Vec<T>>,
     O: Observer<InnerDepth = Zero, Head = T>,
+    T: Capture,
     I: SliceIndex<[O]>,
 {
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
@@ -606,7 +1072,7 @@ where
     }
 }
 
-impl<T: Observe> Observe for Vec<T> {
+impl<T: Observe + Capture> Observe for Vec<T> {
     type Observer<'ob, S, D>
         = VecObserver<T::Observer<'ob, T, Zero>, S, D>
     where
@@ -796,27 +1262,24 @@ mod tests {
     }
 
     #[test]
-    fn insert_triggers_truncate() {
+    fn insert_triggers_splice() {
         let mut vec = vec![1, 2, 3];
         let mut ob = vec.__observe();
         ob.insert(1, 99);
         assert_eq!(ob, vec![1, 99, 2, 3]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(
-            mutation,
-            Some(batch!(_, truncate!(_, 2), append!(_, json!([99, 2, 3]))))
-        );
+        assert_eq!(mutation, Some(splice!(_, 1, 0, json!([99]))));
     }
 
     #[test]
-    fn remove_triggers_truncate() {
+    fn remove_triggers_splice() {
         let mut vec = vec![1, 2, 3, 4];
         let mut ob = vec.__observe();
         let removed = ob.remove(1);
         assert_eq!(removed, 2);
         assert_eq!(ob, vec![1, 3, 4]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 3), append!(_, json!([3, 4])))));
+        assert_eq!(mutation, Some(splice!(_, 1, 1, json!([]))));
     }
 
     #[test]
@@ -826,7 +1289,7 @@ mod tests {
         ob.retain(|x| x % 2 == 1);
         assert_eq!(ob, vec![1, 3]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 3), append!(_, json!([3])))));
+        assert_eq!(mutation, Some(batch!(_, splice!(_, 1, 1, json!([])), truncate!(_, 1))));
     }
 
     #[test]
@@ -836,7 +1299,128 @@ mod tests {
         ob.retain_mut(|x| *x % 2 == 1);
         assert_eq!(ob, vec![1, 3, 5]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 4), append!(_, json!([3, 5])))));
+        assert_eq!(mutation, Some(splice!(_, 1, 3, json!([3]))));
+    }
+
+    #[test]
+    fn merge_adjacent_splices() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        let mut ob = vec.__observe();
+        ob.remove(1);
+        ob.insert(1, 6);
+        ob.insert(0, 7);
+        assert_eq!(ob, vec![7, 1, 6, 3, 4, 5]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(splice!(_, 0, 2, json!([7, 1, 6]))));
+    }
+
+    #[test]
+    fn splice_then_truncate() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        let mut ob = vec.__observe();
+        ob.insert(1, 6);
+        ob.truncate(4);
+        ob.push(7);
+        assert_eq!(ob, vec![1, 6, 2, 3, 7]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(
+                _,
+                splice!(_, 1, 0, json!([6])),
+                truncate!(_, 2),
+                append!(_, json!([7]))
+            ))
+        );
+    }
+
+    #[test]
+    fn truncate_into_splice() {
+        let mut vec = vec![1, 2, 3, 4, 5];
+        let mut ob = vec.__observe();
+        ob.insert(2, 6);
+        ob.truncate(2);
+        assert_eq!(ob, vec![1, 2]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(truncate!(_, 3)));
+    }
+
+    #[test]
+    fn splice_preserves_element_observers() {
+        let mut vec = vec!["a".to_string(), "b".into(), "c".into(), "d".into()];
+        let mut ob = vec.__observe();
+        ob[0].push('!');
+        ob[3].push('?');
+        ob.remove(1);
+        ob[2].push('?');
+        ob.insert(1, "e".into());
+        assert_eq!(ob, vec!["a!".to_string(), "e".into(), "c".into(), "d??".into()]);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(
+                _,
+                splice!(_, 1, 1, json!(["e"])),
+                append!(-1, json!("??")),
+                append!(-4, json!("!")),
+            ))
+        );
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn move_then_splice() {
+        let mut vec = vec!["a".to_string(), "b".into(), "c".into()];
+        let mut ob = vec.__observe();
+        ob[0].push('!');
+        ob.swap(0, 2);
+        ob.insert(1, "d".into());
+        ob.push("e".into());
+        ob[0].push('?');
+        assert_eq!(
+            ob,
+            vec!["c?".to_string(), "d".into(), "b".into(), "a!".into(), "e".into()]
+        );
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(
+                _,
+                mv!(_, 2, 0),
+                mv!(_, 2, 1),
+                splice!(_, 1, 0, json!(["d"])),
+                append!(_, json!(["e"])),
+                append!(-2, json!("!")),
+                append!(-5, json!("?")),
+            ))
+        );
+    }
+
+    #[test]
+    fn sort_after_splice_triggers_replace() {
+        let mut vec = vec![3, 1, 2];
+        let mut ob = vec.__observe();
+        ob.remove(1);
+        ob.sort();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([2, 3]))));
+    }
+
+    #[test]
+    fn too_many_moves_trigger_replace() {
+        let mut vec = vec![1, 2, 3, 4];
+        let mut ob = vec.__observe();
+        ob.swap(0, 3);
+        ob.swap(1, 2);
+        ob.swap(0, 1);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([3, 4, 2, 1]))));
+
+        let mut ob = vec.__observe();
+        ob.reverse();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([1, 2, 4, 3]))));
     }
 
     #[test]
@@ -849,14 +1433,14 @@ mod tests {
     }
 
     #[test]
-    fn drain_range_triggers_truncate() {
+    fn drain_range_triggers_splice() {
         let mut vec = vec![1, 2, 3, 4];
         let mut ob = vec.__observe();
         let drained: Vec<_> = ob.drain(1..3).collect();
         assert_eq!(drained, vec![2, 3]);
         assert_eq!(ob, vec![1, 4]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 3), append!(_, json!([4])))));
+        assert_eq!(mutation, Some(splice!(_, 1, 2, json!([]))));
     }
 
     #[test]
@@ -870,17 +1454,14 @@ mod tests {
     }
 
     #[test]
-    fn splice_triggers_truncate() {
+    fn splice_triggers_splice() {
         let mut vec = vec![1, 2, 3, 4];
         let mut ob = vec.__observe();
         let removed: Vec<_> = ob.splice(1..3, [10, 20, 30]).collect();
         assert_eq!(removed, vec![2, 3]);
         assert_eq!(ob, vec![1, 10, 20, 30, 4]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(
-            mutation,
-            Some(batch!(_, truncate!(_, 3), append!(_, json!([10, 20, 30, 4]))))
-        );
+        assert_eq!(mutation, Some(splice!(_, 1, 2, json!([10, 20, 30]))));
     }
 
     #[test]
@@ -910,12 +1491,12 @@ mod tests {
     fn extract_if_before_append_index() {
         let mut vec = vec![1, 2, 3, 4];
         let mut ob = vec.__observe();
-        // First extracted element is at index 1, so mark_truncate(1).
+        // The first extracted element becomes a splice, the last one touches the end.
         let extracted: Vec<_> = ob.extract_if(.., |x| *x % 2 == 0).collect();
         assert_eq!(extracted, vec![2, 4]);
         assert_eq!(ob, vec![1, 3]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 3), append!(_, json!([3])))));
+        assert_eq!(mutation, Some(batch!(_, splice!(_, 1, 1, json!([])), truncate!(_, 1))));
     }
 
     #[test]
@@ -937,12 +1518,15 @@ mod tests {
         ob.push(4);
         ob.push(5);
         // Range 1.. straddles: start_index=1 < append_index=3.
-        // First extraction triggers mark_truncate(1).
+        // Extracting from the existing region splices, extracting appended elements is untracked.
         let extracted: Vec<_> = ob.extract_if(1.., |x| *x % 2 == 0).collect();
         assert_eq!(extracted, vec![2, 4]);
         assert_eq!(ob, vec![1, 3, 5]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 2), append!(_, json!([3, 5])),)));
+        assert_eq!(
+            mutation,
+            Some(batch!(_, splice!(_, 1, 1, json!([])), append!(_, json!([5]))))
+        );
     }
 
     #[rustversion::since(1.95)]
@@ -976,10 +1560,7 @@ mod tests {
         inserted.push_str("Y");
         assert_eq!(ob, vec!["a".to_string(), "XY".into(), "b".into(), "c".into()]);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(
-            mutation,
-            Some(batch!(_, truncate!(_, 2), append!(_, json!(["XY", "b", "c"]))))
-        );
+        assert_eq!(mutation, Some(splice!(_, 1, 0, json!(["XY"]))));
     }
 
     #[rustversion::since(1.95)]
@@ -992,4 +1573,58 @@ mod tests {
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, Some(append!(_, json!(["d!"]))));
     }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::VecObserver<crate::impls::StringObserver<'_, String>, Vec<String>>);
+        let cases: [Case; 7] = [
+            |ob| {
+                ob[1].push('!');
+                ob.remove(1);
+                ob.push("z".into());
+            },
+            |ob| {
+                ob.insert(0, "x".into());
+                ob[2].push('!');
+                ob.pop();
+                ob.truncate(2);
+            },
+            |ob| {
+                ob.swap(0, 2);
+                ob.pop();
+                ob[0].push('!');
+            },
+            |ob| {
+                ob[3].push('!');
+                ob.drain(1..2);
+                ob.splice(2..3, ["y".into(), "z".into()]);
+                ob[0].tracked_mut().clear();
+            },
+            |ob| {
+                ob.retain(|s| s != "b");
+                ob.remove(0);
+            },
+            |ob| {
+                ob[0].push('!');
+                ob.clear();
+                ob.push("x".into());
+            },
+            |ob| {
+                ob.remove(3);
+                ob.insert(1, "x".into());
+                ob.truncate(1);
+            },
+        ];
+        for mutate in cases {
+            let before = vec!["a".to_string(), "b".into(), "c".into(), "d".into()];
+            let mut vec = before.clone();
+            let mut ob = vec.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(vec), flushed);
+        }
+    }
 }
@@ -1,6 +1,5 @@
 //! Observer implementation for [`VecDeque<T>`].
 
-use std::cell::UnsafeCell;
 use std::collections::vec_deque::Drain;
 use std::collections::{TryReserveError, VecDeque};
 use std::fmt::Debug;
@@ -10,68 +9,29 @@ use std::ops::{Bound, Deref, DerefMut, Index, IndexMut, RangeBounds};
 use serde::Serialize;
 
 use crate::helper::macros::{default_impl_ref_observe, delegate_methods};
-use crate::helper::{AsDeref, AsDerefMut, Invalidate, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::helper::reorder::permute;
+use crate::helper::{AsDeref, AsDerefMut, Capture, Pointer, QuasiObserver, Succ, Unsigned, Zero};
+use crate::impls::slice::SliceObserverState;
+use crate::impls::vec::VecObserverState;
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
-use crate::{MutationKind, Mutations, Observe, PathSegment};
-
-/// Observer state for [`VecDeque<T>`], tracking back-end
-/// [`Append`](MutationKind::Append) / [`Truncate`](MutationKind::Truncate) boundaries.
-///
-/// Front-end mutations (`push_front`, `pop_front`) trigger a full
-/// [`Replace`](MutationKind::Replace) because the current [`MutationKind`] set has no `Prepend`
-/// variant.
-struct VecDequeObserverState<O> {
-    /// Number of elements truncated from the back since the last flush.
-    back_truncate_len: usize,
-    /// Logical index dividing "existing" elements from "appended" elements at the back.
-    /// Elements at indices `[0, back_append_index)` are existing; `[back_append_index, len)` are
-    /// appended.
-    back_append_index: usize,
-    /// Whether a front-end mutation (push_front / pop_front) occurred, forcing full Replace.
-    front_mutated: bool,
-    /// Lazily-initialized element observer storage.
-    ///
-    /// Mirrors the logical layout of the observed `VecDeque<T>`. Observers are created lazily
-    /// via [`relocate`] on first mutable access.
-    inner: UnsafeCell<VecDeque<O>>,
-}
-
-impl<O> VecDequeObserverState<O> {
-    fn mark_back_truncate(&mut self, new_len: usize) {
-        self.back_truncate_len += self.back_append_index - new_len;
-        self.back_append_index = new_len;
-    }
-
-    /// Full invalidation: all existing content is lost, emit Replace on next flush.
-    /// Does NOT set front_mutated — that's only for explicit front-end operations.
-    fn mark_replace(&mut self) {
-        self.inner.get_mut().clear();
-        self.back_truncate_len += self.back_append_index;
-        self.back_append_index = 0;
-    }
-}
-
-impl<O> Invalidate<VecDeque<O::Head>> for VecDequeObserverState<O>
-where
-    O: Observer<InnerDepth = Zero, Head: Sized>,
-{
-    fn invalidate(&mut self, _: &VecDeque<O::Head>) {
-        self.mark_replace();
-    }
-}
+use crate::{Mutations, Observe};
 
 /// Observer implementation for [`VecDeque<T>`].
 ///
-/// Precisely tracks back-end `push_back` / `pop_back` as [`Append`](MutationKind::Append) /
-/// [`Truncate`](MutationKind::Truncate). Front-end mutations and arbitrary modifications
-/// fall back to [`Replace`](MutationKind::Replace).
+/// Shares its state with [`VecObserver`](super::VecObserver): back-end `push_back` / `pop_back`
+/// are tracked as [`Append`](crate::MutationKind::Append) /
+/// [`Truncate`](crate::MutationKind::Truncate), while front-end and middle edits (`push_front`,
+/// `pop_front`, `insert`, `remove`, etc.) are tracked as [`Splice`](crate::MutationKind::Splice),
+/// and `swap` / `rotate_left` / `rotate_right` are tracked as [`Move`](crate::MutationKind::Move).
+/// Arbitrary modifications fall back to [`Replace`](crate::MutationKind::Replace).
 ///
-/// Inner element observers are stored in a parallel `VecDeque<O>`, enabling fine-grained
-/// mutation tracking for individual elements (e.g., modifying a field of a struct inside the
-/// deque produces a path like `[-2].field` instead of a whole-deque Replace).
+/// Inner element observers are stored in a parallel [`Vec<O>`] mirroring the logical order of the
+/// deque, enabling fine-grained mutation tracking for individual elements (e.g., modifying a field
+/// of a struct inside the deque produces a path like `[-2].field` instead of a whole-deque
+/// Replace).
 pub struct VecDequeObserver<'ob, O, S: ?Sized, D = Zero> {
     ptr: Pointer<S>,
-    state: VecDequeObserverState<O>,
+    state: VecObserverState<O>,
     phantom: PhantomData<&'ob mut D>,
 }
 
@@ -94,7 +54,7 @@ impl<'ob, O, S: ?Sized, D> DerefMut for VecDequeObserver<'ob, O, S, D> {
 impl<'ob, O, S: ?Sized, D> QuasiObserver for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDeref<D, Target = VecDeque<O::Head>>,
 {
     type Head = S;
@@ -102,25 +62,27 @@ where
     type InnerDepth = D;
 
     fn invalidate(this: &mut Self) {
+        this.state.capture_truncate((*this.ptr).as_deref(), 0);
         this.state.mark_replace();
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        let deque = head.as_deref();
+        this.state.inverse_in(deque, deque.len())
+    }
 }
 
 impl<'ob, O, S: ?Sized, D> Observer for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     fn observe(head: &mut Self::Head) -> Self {
         let len = head.as_deref_mut().len();
         Self {
-            state: VecDequeObserverState {
-                back_truncate_len: 0,
-                back_append_index: len,
-                front_mutated: false,
-                inner: UnsafeCell::new(VecDeque::new()),
-            },
+            state: VecObserverState::new(len),
             ptr: Pointer::new(head),
             phantom: PhantomData,
         }
@@ -134,97 +96,21 @@ where
 impl<'ob, O, S: ?Sized, D> SerializeObserver for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized> + SerializeObserver,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture> + SerializeObserver,
     O::Head: Serialize + 'static,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     unsafe fn flush(this: &mut Self) -> Mutations {
-        let deque = (*this.ptr).as_deref_mut();
-        let len = deque.len();
-        let back_append_index = core::mem::replace(&mut this.state.back_append_index, len);
-        let back_truncate_len = core::mem::replace(&mut this.state.back_truncate_len, 0);
-        let front_mutated = core::mem::replace(&mut this.state.front_mutated, false);
-
         // Make contiguous so we can take slices for serialization.
-        let slice = deque.make_contiguous();
-
-        // If front was mutated, or if all existing content was replaced, emit whole Replace.
-        if front_mutated || (back_append_index == 0 && back_truncate_len > 0) {
-            if len > 0 || back_truncate_len > 0 {
-                // Clear stale inner observers.
-                this.state.inner.get_mut().clear();
-                return Mutations::replace(slice);
-            }
-            return Mutations::new();
-        }
-
-        let mut mutations = Mutations::new();
-        #[cfg(feature = "truncate")]
-        if back_truncate_len > 0 {
-            mutations.extend(MutationKind::Truncate(back_truncate_len));
-        }
-
-        // Ensure inner observers exist for existing elements so we can flush them.
-        unsafe { relocate(&this.state.inner, &mut slice[..back_append_index]) };
-
-        #[cfg(feature = "append")]
-        if len > back_append_index {
-            mutations.extend(Mutations::append(&slice[back_append_index..]));
-        }
-
-        // Flush inner observers for existing elements.
-        let inner = this.state.inner.get_mut();
-        // inner might be shorter than back_append_index if not all elements were accessed;
-        // relocate above ensures it's at least back_append_index long.
-        let existing_len = back_append_index.min(inner.len());
-        let existing = inner.make_contiguous();
-        let mut is_replace = true;
-        for (index, ob) in existing[..existing_len].iter_mut().enumerate().rev() {
-            let mutations_i = unsafe { SerializeObserver::flush(ob) };
-            is_replace &= mutations_i.is_replace();
-            mutations.insert(PathSegment::Negative(len - index), mutations_i);
-        }
-
-        // Clear stale observers beyond the existing region.
-        inner.truncate(existing_len);
-
-        if is_replace && (back_append_index > 0 || back_truncate_len > 0) {
-            return Mutations::replace(slice);
-        }
-
-        mutations
-    }
-}
-
-/// Ensures element observers exist for all elements and updates their pointers.
-///
-/// Creates observers for any new elements via [`Observer::observe`] and calls
-/// [`Observer::relocate`] on existing observers to update their pointers.
-///
-/// # Safety
-///
-/// The caller must ensure no references from previous `relocate` calls are alive.
-unsafe fn relocate<O>(inner: &UnsafeCell<VecDeque<O>>, deque_slice: &mut [O::Head])
-where
-    O: Observer<InnerDepth = Zero, Head: Sized>,
-{
-    let observers = unsafe { &mut *inner.get() };
-    if observers.len() < deque_slice.len() {
-        for value in deque_slice[observers.len()..].iter_mut() {
-            observers.push_back(O::observe(value));
-        }
-    }
-    observers.truncate(deque_slice.len());
-    let ob_contiguous = observers.make_contiguous();
-    for (ob, value) in ob_contiguous.iter_mut().zip(deque_slice.iter_mut()) {
-        unsafe { Observer::relocate(ob, value) };
+        let deque = (*this.ptr).as_deref_mut();
+        this.state.flush_slice(deque.make_contiguous())
     }
 }
 
 impl<'ob, O, S: ?Sized, D> VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     delegate_methods! { untracked_mut() as VecDeque =>
@@ -241,79 +127,80 @@ where
     /// This only rearranges internal memory layout without changing logical order.
     /// Returns a mutable slice of inner element observers.
     pub fn make_contiguous(&mut self) -> &mut [O] {
-        let deque = (*self.ptr).as_deref_mut();
-        let deque_slice = deque.make_contiguous();
-        unsafe { relocate(&self.state.inner, deque_slice) };
-        self.state.inner.get_mut().make_contiguous()
+        self.force_all()
     }
 
-    /// Ensures element observers exist and returns a mutable reference at `index`.
-    #[expect(clippy::mut_from_ref)]
-    fn force_index(&self, index: usize) -> Option<&mut O> {
+    /// Ensures element observers exist and returns a reference at `index`.
+    fn force_index(&self, index: usize) -> Option<&O> {
         let deque = unsafe { Pointer::as_mut(&self.ptr).as_deref_mut() };
-        let len = deque.len();
-        if index >= len {
-            return None;
-        }
         // Make contiguous so relocate can work with a slice.
         let slice = deque.make_contiguous();
-        unsafe { relocate(&self.state.inner, slice) };
-        let observers = unsafe { &mut *self.state.inner.get() };
-        observers.get_mut(index)
+        unsafe { self.state.relocate(slice) };
+        self.state.as_slice().get(index)
     }
 
     /// Ensures element observers exist and returns mutable references to all.
-    fn force_all(&mut self) -> &mut VecDeque<O> {
+    fn force_all(&mut self) -> &mut [O] {
         let deque = (*self.ptr).as_deref_mut();
         let slice = deque.make_contiguous();
-        unsafe { relocate(&self.state.inner, slice) };
-        self.state.inner.get_mut()
+        unsafe { self.state.relocate(slice) };
+        self.state.as_mut_slice()
+    }
+
+    /// Captures the elements which [`mark_splice`](VecObserverState::mark_splice) would remove.
+    fn capture_splice(&mut self, index: usize, delete_len: usize) {
+        self.state.capture_splice((*self.ptr).as_deref(), index, delete_len);
+    }
+
+    /// Captures the elements which [`mark_truncate`](VecObserverState::mark_truncate) would remove.
+    #[cfg(any(feature = "append", feature = "truncate"))]
+    fn capture_truncate(&mut self, index: usize) {
+        self.state.capture_truncate((*self.ptr).as_deref(), index);
+    }
+
+    #[cfg(any(feature = "append", feature = "truncate"))]
+    fn range_bounds(&self, range: &impl RangeBounds<usize>) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => (*self).untracked_ref().len(),
+        };
+        (start, end)
     }
 
     /// See [`VecDeque::get_mut`].
     pub fn get_mut(&mut self, index: usize) -> Option<&mut O> {
-        let deque = (*self.ptr).as_deref_mut();
-        let len = deque.len();
-        if index >= len {
-            return None;
-        }
-        let slice = deque.make_contiguous();
-        unsafe { relocate(&self.state.inner, slice) };
-        self.state.inner.get_mut().get_mut(index)
+        self.force_all().get_mut(index)
     }
 
     /// See [`VecDeque::front_mut`].
     pub fn front_mut(&mut self) -> Option<&mut O> {
-        if (*self).untracked_ref().is_empty() {
-            return None;
-        }
-        self.get_mut(0)
+        self.force_all().first_mut()
     }
 
     /// See [`VecDeque::back_mut`].
     pub fn back_mut(&mut self) -> Option<&mut O> {
-        let len = (*self).untracked_ref().len();
-        if len == 0 {
-            return None;
-        }
-        self.get_mut(len - 1)
+        self.force_all().last_mut()
     }
 
     /// See [`VecDeque::iter_mut`].
     ///
     /// Returns an iterator over mutable references to inner element observers.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut O> {
-        let observers = self.force_all();
-        observers.iter_mut()
+        self.force_all().iter_mut()
     }
 
     /// See [`VecDeque::as_mut_slices`].
     ///
-    /// Returns observer slices. Since `force_all` makes the observer deque contiguous,
-    /// the second slice will be empty.
+    /// Returns observer slices. Since `force_all` makes the deque contiguous, the second slice will
+    /// be empty.
     pub fn as_mut_slices(&mut self) -> (&mut [O], &mut [O]) {
-        self.force_all();
-        self.state.inner.get_mut().as_mut_slices()
+        (self.force_all(), &mut [])
     }
 
     /// See [`VecDeque::range_mut`].
@@ -321,78 +208,92 @@ where
     where
         R: RangeBounds<usize> + Clone,
     {
-        let deque = (*self.ptr).as_deref_mut();
-        let slice = deque.make_contiguous();
-        unsafe { relocate(&self.state.inner, slice) };
-        self.state.inner.get_mut().range_mut(range)
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        self.force_all()[bounds].iter_mut()
+    }
+
+    /// Rearranges the leading `len` elements by the permutation computed by `f`, recording the
+    /// rearrangement as moves.
+    ///
+    /// Returns `false` without touching the deque if the rearrangement cannot be recorded.
+    fn permute_with(&mut self, len: usize, f: impl FnOnce(&mut [usize])) -> bool {
+        if len > self.state.movable_len() {
+            return false;
+        }
+        self.force_all();
+        let mut perm = (0..len).collect::<Vec<_>>();
+        f(&mut perm);
+        if self.state.mark_permute(&perm) {
+            permute(self.untracked_mut().make_contiguous(), &perm);
+        } else {
+            permute(self.tracked_mut().make_contiguous(), &perm);
+        }
+        true
     }
 
     /// See [`VecDeque::swap`].
     pub fn swap(&mut self, i: usize, j: usize) {
-        if i != j {
+        let len = (*self).untracked_ref().len();
+        if i != j && !(i < len && j < len && self.permute_with(i.max(j) + 1, |perm| perm.swap(i, j))) {
             // Invalidate observers for swapped elements.
-            let observers = self.state.inner.get_mut();
-            if let Some(ob) = observers.get_mut(i) {
-                QuasiObserver::invalidate(ob);
-            }
-            if let Some(ob) = observers.get_mut(j) {
-                QuasiObserver::invalidate(ob);
-            }
+            let observers = self.force_all();
+            QuasiObserver::invalidate(&mut observers[i]);
+            QuasiObserver::invalidate(&mut observers[j]);
             self.untracked_mut().swap(i, j);
         }
     }
 
     /// See [`VecDeque::rotate_left`].
     pub fn rotate_left(&mut self, n: usize) {
-        if n != 0 && (*self).untracked_ref().len() > 1 {
+        let len = (*self).untracked_ref().len();
+        if n != 0 && len > 1 && !self.permute_with(len, |perm| perm.rotate_left(n)) {
             self.tracked_mut().rotate_left(n);
         }
     }
 
     /// See [`VecDeque::rotate_right`].
     pub fn rotate_right(&mut self, n: usize) {
-        if n != 0 && (*self).untracked_ref().len() > 1 {
+        let len = (*self).untracked_ref().len();
+        if n != 0 && len > 1 && !self.permute_with(len, |perm| perm.rotate_right(n)) {
             self.tracked_mut().rotate_right(n);
         }
     }
 
     /// See [`VecDeque::push_front`].
     pub fn push_front(&mut self, value: O::Head) {
-        self.state.front_mutated = true;
-        self.state.inner.get_mut().clear();
+        self.capture_splice(0, 0);
         self.untracked_mut().push_front(value);
+        self.state.mark_splice(0, 0, 1);
     }
 
     /// See [`VecDeque::push_front_mut`].
     #[rustversion::since(1.95)]
     pub fn push_front_mut(&mut self, value: O::Head) -> &mut O {
-        self.state.front_mutated = true;
-        self.state.inner.get_mut().clear();
-        self.untracked_mut().push_front(value);
-        self.force_all().front_mut().unwrap()
+        self.push_front(value);
+        self.force_all().first_mut().unwrap()
     }
 
     /// See [`VecDeque::pop_front`].
     pub fn pop_front(&mut self) -> Option<O::Head> {
+        self.capture_splice(0, 1);
         let value = self.untracked_mut().pop_front()?;
-        self.state.front_mutated = true;
-        self.state.inner.get_mut().clear();
+        self.state.mark_splice(0, 1, 0);
         Some(value)
     }
 
     /// See [`VecDeque::pop_front_if`].
     #[rustversion::since(1.93)]
     pub fn pop_front_if(&mut self, predicate: impl FnOnce(&mut O::Head) -> bool) -> Option<O::Head> {
-        // We need to check predicate without committing to front_mutated.
         let front = self.untracked_mut().front_mut()?;
         if predicate(front) { self.pop_front() } else { None }
     }
 
     /// See [`VecDeque::swap_remove_front`].
     pub fn swap_remove_front(&mut self, index: usize) -> Option<O::Head> {
+        self.capture_splice(0, index + 1);
         let value = self.untracked_mut().swap_remove_front(index)?;
-        self.state.front_mutated = true;
-        self.state.inner.get_mut().clear();
+        // The first `index + 1` elements are replaced by `index` shifted elements.
+        self.state.mark_splice(0, index + 1, index);
         Some(value)
     }
 }
@@ -401,7 +302,7 @@ where
 impl<'ob, O, S: ?Sized, D> VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     /// See [`VecDeque::push_back`].
@@ -413,7 +314,7 @@ where
     #[rustversion::since(1.95)]
     pub fn push_back_mut(&mut self, value: O::Head) -> &mut O {
         self.untracked_mut().push_back(value);
-        self.force_all().back_mut().unwrap()
+        self.force_all().last_mut().unwrap()
     }
 
     /// See [`VecDeque::append`].
@@ -423,26 +324,20 @@ where
 
     /// See [`VecDeque::insert`].
     pub fn insert(&mut self, index: usize, value: O::Head) {
-        if index >= self.state.back_append_index {
-            self.untracked_mut().insert(index, value);
-        } else {
-            self.state.inner.get_mut().clear();
-            self.tracked_mut().insert(index, value);
-        }
+        self.capture_splice(index, 0);
+        self.untracked_mut().insert(index, value);
+        self.state.mark_splice(index, 0, 1);
     }
 
     /// See [`VecDeque::insert_mut`].
     #[rustversion::since(1.95)]
     pub fn insert_mut(&mut self, index: usize, value: O::Head) -> &mut O {
-        if index >= self.state.back_append_index {
-            self.untracked_mut().insert(index, value);
-            // Drop stale observers at and beyond `index` — those slots now hold shifted elements.
-            self.state.inner.get_mut().truncate(index);
-        } else {
-            self.state.inner.get_mut().clear();
-            self.tracked_mut().insert(index, value);
-        }
-        self.force_all().get_mut(index).unwrap()
+        self.capture_splice(index, 0);
+        self.state.mark_splice(index, 0, 1);
+        self.untracked_mut().insert(index, value);
+        // Drop stale observers at and beyond `index` — those slots now hold shifted elements.
+        self.state.truncate_appended(index);
+        &mut self.force_all()[index]
     }
 }
 
@@ -450,20 +345,20 @@ where
 impl<'ob, O, S: ?Sized, D> VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
+    fn mark_truncate(&mut self) {
+        let len = (*self).untracked_ref().len();
+        self.state.mark_truncate(len);
+    }
+
     /// See [`VecDeque::pop_back`].
     pub fn pop_back(&mut self) -> Option<O::Head> {
-        let value = self.untracked_mut().pop_back()?;
         let len = (*self).untracked_ref().len();
-        if len >= self.state.back_append_index {
-            // popped from appended region, no-op
-        } else if cfg!(feature = "truncate") && len + 1 == self.state.back_append_index {
-            self.state.mark_back_truncate(len);
-        } else {
-            self.state.mark_replace();
-        }
+        self.capture_truncate(len.saturating_sub(1));
+        let value = self.untracked_mut().pop_back()?;
+        self.mark_truncate();
         Some(value)
     }
 
@@ -476,101 +371,74 @@ where
 
     /// See [`VecDeque::clear`].
     pub fn clear(&mut self) {
-        if self.state.back_append_index == 0 && !self.state.front_mutated {
-            self.untracked_mut().clear();
-        } else {
-            self.state.inner.get_mut().clear();
-            self.tracked_mut().clear();
-        }
+        self.capture_truncate(0);
+        self.untracked_mut().clear();
+        self.mark_truncate();
     }
 
     /// See [`VecDeque::truncate`].
     pub fn truncate(&mut self, len: usize) {
-        let bai = self.state.back_append_index;
-        if len >= bai {
-            self.untracked_mut().truncate(len);
-            return;
-        }
-        if cfg!(not(feature = "truncate")) || len == 0 {
-            self.state.inner.get_mut().clear();
-            self.tracked_mut().truncate(len);
-            return;
-        }
-        self.state.mark_back_truncate(len);
+        self.capture_truncate(len);
         self.untracked_mut().truncate(len);
+        self.mark_truncate();
     }
 
     /// See [`VecDeque::remove`].
     pub fn remove(&mut self, index: usize) -> Option<O::Head> {
-        let bai = self.state.back_append_index;
+        self.capture_splice(index, 1);
         let value = self.untracked_mut().remove(index)?;
-        if index >= bai {
-            // removed from appended region, no-op
-        } else if cfg!(feature = "truncate") && index + 1 == bai {
-            self.state.mark_back_truncate(index);
-        } else {
-            self.state.mark_replace();
-        }
+        self.state.mark_splice(index, 1, 0);
         Some(value)
     }
 
     /// See [`VecDeque::swap_remove_back`].
     pub fn swap_remove_back(&mut self, index: usize) -> Option<O::Head> {
-        let bai = self.state.back_append_index;
+        self.capture_truncate(index);
         let value = self.untracked_mut().swap_remove_back(index)?;
-        if index >= bai {
-            // removed from appended region
-        } else if cfg!(feature = "truncate") && index + 1 == bai {
-            self.state.mark_back_truncate(index);
-        } else {
-            self.state.mark_replace();
-        }
+        self.state.mark_truncate(index);
         Some(value)
     }
 
     /// See [`VecDeque::split_off`].
     pub fn split_off(&mut self, at: usize) -> VecDeque<O::Head> {
-        let bai = self.state.back_append_index;
+        self.capture_truncate(at);
         let split = self.untracked_mut().split_off(at);
-        if at >= bai {
-            // split from appended region, no-op
-        } else if cfg!(feature = "truncate") && at > 0 {
-            self.state.mark_back_truncate(at);
-        } else {
-            self.state.mark_replace();
-        }
+        self.mark_truncate();
         split
     }
 
     /// See [`VecDeque::retain`].
-    pub fn retain<F>(&mut self, f: F)
+    pub fn retain<F>(&mut self, mut f: F)
     where
         F: FnMut(&O::Head) -> bool,
     {
-        self.state.inner.get_mut().clear();
-        self.tracked_mut().retain(f);
+        self.retain_mut(|v| f(v));
     }
 
     /// See [`VecDeque::retain_mut`].
-    pub fn retain_mut<F>(&mut self, f: F)
+    pub fn retain_mut<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut O::Head) -> bool,
     {
-        self.state.inner.get_mut().clear();
-        self.tracked_mut().retain_mut(f);
+        self.capture_truncate(0);
+        let mut index = 0;
+        (*self.ptr).as_deref_mut().retain_mut(|v| {
+            // `index` counts the retained elements, which is the current position of `v`.
+            let is_retained = f(v);
+            if is_retained {
+                index += 1;
+            } else {
+                self.state.mark_splice(index, 1, 0);
+            }
+            is_retained
+        });
     }
 
     /// See [`VecDeque::resize_with`].
     pub fn resize_with(&mut self, new_len: usize, generator: impl FnMut() -> O::Head) {
-        let bai = self.state.back_append_index;
+        self.capture_truncate(new_len);
         self.untracked_mut().resize_with(new_len, generator);
-        if new_len >= bai {
-            // grew or stayed same, no-op
-        } else if cfg!(feature = "truncate") && new_len > 0 {
-            self.state.mark_back_truncate(new_len);
-        } else {
-            self.state.mark_replace();
-        }
+        self.mark_truncate();
     }
 
     /// See [`VecDeque::drain`].
@@ -578,31 +446,10 @@ where
     where
         R: RangeBounds<usize>,
     {
-        let bai = self.state.back_append_index;
-        let start = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
-        if start >= bai {
-            return self.untracked_mut().drain(range);
-        }
-        if cfg!(not(feature = "truncate")) || start == 0 {
-            self.state.inner.get_mut().clear();
-            return self.tracked_mut().drain(range);
-        }
-        let end = match range.end_bound() {
-            Bound::Included(&n) => n + 1,
-            Bound::Excluded(&n) => n,
-            Bound::Unbounded => (*self).untracked_ref().len(),
-        };
-        if end < bai {
-            self.state.inner.get_mut().clear();
-            return self.tracked_mut().drain(range);
-        }
-        self.state.mark_back_truncate(start);
-        self.state.inner.get_mut().clear();
-        self.tracked_mut().drain(range)
+        let (start, end) = self.range_bounds(&range);
+        self.capture_splice(start, end.saturating_sub(start));
+        self.state.mark_splice(start, end.saturating_sub(start), 0);
+        self.untracked_mut().drain(range)
     }
 }
 
@@ -610,21 +457,15 @@ where
 impl<'ob, O, S: ?Sized, D> VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     O::Head: Clone,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     /// See [`VecDeque::resize`].
     pub fn resize(&mut self, new_len: usize, value: O::Head) {
-        let bai = self.state.back_append_index;
+        self.capture_truncate(new_len);
         self.untracked_mut().resize(new_len, value);
-        if new_len >= bai {
-            // grew or stayed same
-        } else if cfg!(feature = "truncate") && new_len > 0 {
-            self.state.mark_back_truncate(new_len);
-        } else {
-            self.state.mark_replace();
-        }
+        self.mark_truncate();
     }
 }
 
@@ -632,7 +473,7 @@ where
 impl<'ob, O, S: ?Sized, D, U> Extend<U> for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
     VecDeque<O::Head>: Extend<U>,
 {
@@ -644,7 +485,7 @@ where
 impl<'ob, O, S: ?Sized, D> Index<usize> for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     type Output = O;
@@ -657,7 +498,7 @@ where
 impl<'ob, O, S: ?Sized, D> IndexMut<usize> for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDerefMut<D, Target = VecDeque<O::Head>>,
 {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
@@ -668,7 +509,7 @@ where
 impl<'ob, O, S: ?Sized, D> Debug for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     O::Head: Debug,
     S: AsDeref<D, Target = VecDeque<O::Head>>,
 {
@@ -680,7 +521,7 @@ where
 impl<'ob, O, S: ?Sized, D, U> PartialEq<VecDeque<U>> for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S: AsDeref<D, Target = VecDeque<O::Head>>,
     VecDeque<O::Head>: PartialEq<VecDeque<U>>,
 {
@@ -694,8 +535,8 @@ impl<'ob, O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<VecDequeObserver<'ob
 where
     D1: Unsigned,
     D2: Unsigned,
-    O1: Observer<InnerDepth = Zero, Head: Sized>,
-    O2: Observer<InnerDepth = Zero, Head: Sized>,
+    O1: Observer<InnerDepth = Zero, Head: Sized + Capture>,
+    O2: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     S1: AsDeref<D1, Target = VecDeque<O1::Head>>,
     S2: AsDeref<D2, Target = VecDeque<O2::Head>>,
     VecDeque<O1::Head>: PartialEq<VecDeque<O2::Head>>,
@@ -708,13 +549,13 @@ where
 impl<'ob, O, S: ?Sized, D> Eq for VecDequeObserver<'ob, O, S, D>
 where
     D: Unsigned,
-    O: Observer<InnerDepth = Zero, Head: Sized>,
+    O: Observer<InnerDepth = Zero, Head: Sized + Capture>,
     O::Head: Eq,
     S: AsDeref<D, Target = VecDeque<O::Head>>,
 {
 }
 
-impl<T: Observe> Observe for VecDeque<T> {
+impl<T: Observe + Capture> Observe for VecDeque<T> {
     type Observer<'ob, S, D>
         = VecDequeObserver<'ob, T::Observer<'ob, T, Zero>, S, D>
     where
@@ -840,32 +681,32 @@ mod tests {
     }
 
     #[test]
-    fn push_front_triggers_replace() {
+    fn push_front_triggers_splice() {
         let mut deque = VecDeque::from([1, 2]);
         let mut ob = deque.__observe();
         ob.push_front(0);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([0, 1, 2]))));
+        assert_eq!(mutation, Some(splice!(_, 0, 0, json!([0]))));
     }
 
     #[test]
-    fn pop_front_triggers_replace() {
+    fn pop_front_triggers_splice() {
         let mut deque = VecDeque::from([1, 2, 3]);
         let mut ob = deque.__observe();
         ob.pop_front();
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([2, 3]))));
+        assert_eq!(mutation, Some(splice!(_, 0, 1, json!([]))));
     }
 
     #[rustversion::since(1.93)]
     #[test]
-    fn pop_front_if_true_triggers_replace() {
+    fn pop_front_if_true_triggers_splice() {
         let mut deque = VecDeque::from([1, 2, 3]);
         let mut ob = deque.__observe();
         let result = ob.pop_front_if(|x| *x == 1);
         assert_eq!(result, Some(1));
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([2, 3]))));
+        assert_eq!(mutation, Some(splice!(_, 0, 1, json!([]))));
     }
 
     #[rustversion::since(1.93)]
@@ -880,20 +721,51 @@ mod tests {
     }
 
     #[test]
-    fn push_front_overrides_back_append() {
+    fn push_front_with_back_append() {
         let mut deque = VecDeque::from([1]);
         let mut ob = deque.__observe();
         ob.push_back(2);
-        ob.push_front(0); // front mutation overrides back tracking
+        ob.push_front(0);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(_, splice!(_, 0, 0, json!([0])), append!(_, json!([2]))))
+        );
+    }
+
+    #[test]
+    fn pop_front_preserves_element_observers() {
+        let mut deque = VecDeque::from(["a".to_string(), "b".into(), "c".into()]);
+        let mut ob = deque.__observe();
+        ob[2].push('!');
+        ob.pop_front();
+        ob[0].push('?');
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(
+                _,
+                splice!(_, 0, 1, json!([])),
+                append!(-1, json!("!")),
+                append!(-2, json!("?")),
+            ))
+        );
+    }
+
+    #[test]
+    fn retain_triggers_splice() {
+        let mut deque = VecDeque::from([1, 2, 3, 4]);
+        let mut ob = deque.__observe();
+        ob.retain(|x| *x != 2);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([0, 1, 2]))));
+        assert_eq!(mutation, Some(splice!(_, 1, 1, json!([]))));
     }
 
     #[test]
     fn deref_mut_triggers_replace() {
         let mut deque = VecDeque::from([1, 2]);
         let mut ob = deque.__observe();
-        ob.retain(|x| *x > 1);
+        ob.tracked_mut().retain(|x| *x > 1);
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, Some(replace!(_, json!([2]))));
     }
@@ -912,7 +784,7 @@ mod tests {
         let mut ob = deque.__observe();
         ob.push_back(1);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(append!(_, json!([1]))));
+        assert_eq!(mutation, Some(replace!(_, json!([1]))));
     }
 
     #[test]
@@ -975,13 +847,13 @@ mod tests {
     }
 
     #[test]
-    fn remove_from_middle_triggers_replace() {
+    fn remove_from_middle_triggers_splice() {
         let mut deque = VecDeque::from([1, 2, 3, 4]);
         let mut ob = deque.__observe();
         let val = ob.remove(1);
         assert_eq!(val, Some(2));
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([1, 3, 4]))));
+        assert_eq!(mutation, Some(splice!(_, 1, 1, json!([]))));
     }
 
     #[test]
@@ -1000,17 +872,17 @@ mod tests {
         let mut ob = deque.__observe();
         ob.insert(1, 99);
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([1, 99, 2, 3]))));
+        assert_eq!(mutation, Some(splice!(_, 1, 0, json!([99]))));
     }
 
     #[test]
-    fn swap_remove_front_triggers_replace() {
+    fn swap_remove_front_triggers_splice() {
         let mut deque = VecDeque::from([1, 2, 3]);
         let mut ob = deque.__observe();
         let val = ob.swap_remove_front(1);
         assert_eq!(val, Some(2));
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!([1, 3]))));
+        assert_eq!(mutation, Some(splice!(_, 0, 2, json!([1]))));
     }
 
     #[test]
@@ -1089,8 +961,8 @@ mod tests {
         let drained: Vec<_> = ob.drain(1..).collect();
         assert_eq!(drained, vec![2, 3, 4]);
         let Json(mutation) = ob.flush().unwrap();
-        // Drain straddles the append boundary, so granular tracking is lost → Replace.
-        assert_eq!(mutation, Some(replace!(_, json!([1]))));
+        // Drain straddles the append boundary, so the existing tail is truncated.
+        assert_eq!(mutation, Some(truncate!(_, 2)));
     }
 
     #[test]
@@ -1282,7 +1154,7 @@ mod tests {
         let pushed = ob.push_front_mut("x".into());
         pushed.push_str("y");
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!(["xy", "a", "b"]))));
+        assert_eq!(mutation, Some(splice!(_, 0, 0, json!(["xy"]))));
     }
 
     #[rustversion::since(1.95)]
@@ -1308,6 +1180,75 @@ mod tests {
             VecDeque::from(["a".to_string(), "XY".into(), "b".into(), "c".into()])
         );
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(replace!(_, json!(["a", "XY", "b", "c"]))));
+        assert_eq!(mutation, Some(splice!(_, 1, 0, json!(["XY"]))));
+    }
+
+    #[test]
+    fn swap_triggers_move() {
+        let mut deque = VecDeque::from(["a".to_string(), "b".into(), "c".into()]);
+        let mut ob = deque.__observe();
+        ob[2].push('!');
+        ob.swap(0, 2);
+        assert_eq!(ob, VecDeque::from(["c!".to_string(), "b".into(), "a".into()]));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(_, mv!(_, 2, 0), mv!(_, 2, 1), append!(-3, json!("!"))))
+        );
+    }
+
+    #[test]
+    fn rotate_triggers_move() {
+        let mut deque = VecDeque::from([1, 2, 3, 4]);
+        deque.push_front(0);
+        let mut ob = deque.__observe();
+        ob.rotate_right(1);
+        assert_eq!(ob, VecDeque::from([4, 0, 1, 2, 3]));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(mv!(_, 4, 0)));
+
+        ob.push_back(5);
+        ob.rotate_left(1);
+        assert_eq!(ob, VecDeque::from([0, 1, 2, 3, 5, 4]));
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(replace!(_, json!([0, 1, 2, 3, 5, 4]))));
+    }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::VecDequeObserver<'_, crate::impls::StringObserver<'_, String>, VecDeque<String>>);
+        let cases: [Case; 4] = [
+            |ob| {
+                ob[0].push('!');
+                ob.pop_front();
+                ob.push_front("x".into());
+                ob.pop_back();
+            },
+            |ob| {
+                ob.rotate_left(1);
+                ob.pop_back();
+                ob[1].push('!');
+            },
+            |ob| {
+                ob[2].push('!');
+                ob.remove(1);
+                ob.swap_remove_front(1);
+            },
+            |ob| {
+                ob.retain(|s| s != "c");
+                ob.push_back("x".into());
+            },
+        ];
+        for mutate in cases {
+            let before = VecDeque::from(["a".to_string(), "b".into(), "c".into(), "d".into()]);
+            let mut deque = before.clone();
+            let mut ob = deque.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(before), json!(deque), flushed);
+        }
     }
 }
@@ -14,26 +14,127 @@ use crate::impls::strings::str::{StrObserver, StrObserverState, StrSerializeObse
 use crate::observe::{DefaultSpec, Observer, SerializeObserver};
 use crate::{MutationKind, Mutations, Observe};
 
+/// Observer state for [`String`], tracking [`Append`](MutationKind::Append),
+/// [`Truncate`](MutationKind::Truncate) and [`Splice`](MutationKind::Splice) boundaries.
+///
+/// Byte indices refer to the current value, while lengths of removed text are counted in chars, so
+/// that the emitted mutations share the char semantics of [`Adapter::len`](crate::Adapter::len).
+/// Like [`Vec`], a single splice window `[splice_index, splice_index + splice_insert_len)` before
+/// `append_index` records text which replaced `splice_delete_len` original chars.
+///
+/// With the `inverse` feature, the original text removed by the splice window and by the truncation
+/// is kept, so that the original value can be restored.
 pub struct StringObserverState {
-    pub append_index: usize, // byte index
-    pub truncate_len: usize, // char count
+    pub append_index: usize,      // byte index
+    pub truncate_len: usize,      // char count
+    pub splice_index: usize,      // byte index
+    pub splice_delete_len: usize, // char count
+    pub splice_insert_len: usize, // byte count
+    #[cfg(feature = "inverse")]
+    deleted: String,
+    #[cfg(feature = "inverse")]
+    truncated: String,
+    #[cfg(feature = "inverse")]
+    blind: bool,
 }
 
 impl StringObserverState {
+    fn has_splice(&self) -> bool {
+        self.splice_delete_len + self.splice_insert_len > 0
+    }
+
+    /// Folds the splice window into the truncated tail, so that everything from `splice_index`
+    /// onward is re-sent as appended text.
+    fn merge_splice(&mut self, value: &str) {
+        let splice_end = self.splice_index + self.splice_insert_len;
+        #[cfg(feature = "inverse")]
+        {
+            let mut truncated = std::mem::take(&mut self.deleted);
+            truncated.push_str(&value[splice_end..self.append_index]);
+            truncated.push_str(&self.truncated);
+            self.truncated = truncated;
+        }
+        self.truncate_len += self.splice_delete_len + value[splice_end..self.append_index].chars().count();
+        self.append_index = self.splice_index;
+        self.splice_delete_len = 0;
+        self.splice_insert_len = 0;
+    }
+
     fn mark_truncate(&mut self, value: &str, index: usize) {
         if self.append_index <= index {
             return;
         }
+        if self.has_splice() && index <= self.splice_index + self.splice_insert_len {
+            self.merge_splice(value);
+            if self.append_index <= index {
+                return;
+            }
+        }
         let count = value[index..self.append_index].chars().count();
+        #[cfg(feature = "inverse")]
+        self.truncated.insert_str(0, &value[index..self.append_index]);
         self.truncate_len += count;
         self.append_index = index;
     }
+
+    /// Records that the bytes `[index, delete_end)` of `value` are about to be replaced by
+    /// `insert_len` bytes of new text.
+    fn mark_splice(&mut self, value: &str, index: usize, delete_end: usize, insert_len: usize) {
+        if cfg!(not(feature = "splice")) || delete_end >= self.append_index {
+            self.mark_truncate(value, index);
+            return;
+        }
+        if index == delete_end && insert_len == 0 {
+            return;
+        }
+        if !self.has_splice() {
+            self.splice_index = index;
+        }
+        let splice_end = self.splice_index + self.splice_insert_len;
+        let start = index.min(self.splice_index);
+        let end = delete_end.max(splice_end);
+        // Text outside of the window but inside `[start, end)` is original text being removed.
+        self.splice_delete_len += value[start..self.splice_index].chars().count();
+        self.splice_delete_len += value[splice_end..end].chars().count();
+        #[cfg(feature = "inverse")]
+        {
+            self.deleted.insert_str(0, &value[start..self.splice_index]);
+            self.deleted.push_str(&value[splice_end..end]);
+        }
+        self.splice_insert_len = end - start - (delete_end - index) + insert_len;
+        self.splice_index = start;
+        self.append_index = self.append_index - (delete_end - index) + insert_len;
+    }
 }
 
 impl Invalidate<str> for StringObserverState {
     fn invalidate(&mut self, value: &str) {
         self.mark_truncate(value, 0);
     }
+
+    /// Returns [`None`] if the original text has been lost by a blind invalidation.
+    #[cfg(feature = "inverse")]
+    fn inverse(&self, value: &str) -> Option<Mutations> {
+        if self.blind {
+            return None;
+        }
+        let mut mutations = Mutations::new();
+        if value.len() > self.append_index {
+            mutations.extend(MutationKind::Truncate(value[self.append_index..].chars().count()));
+        }
+        if !self.truncated.is_empty() {
+            mutations.extend(Mutations::append_owned(self.truncated.clone()));
+        }
+        if self.has_splice() {
+            let splice_end = self.splice_index + self.splice_insert_len;
+            mutations.extend(Mutations::splice_owned(
+                value[..self.splice_index].chars().count(),
+                value[self.splice_index..splice_end].chars().count(),
+                self.deleted.clone(),
+            ));
+        }
+        Some(mutations)
+    }
 }
 
 impl Invalidate<()> for StringObserverState {
@@ -46,6 +147,12 @@ impl Invalidate<()> for StringObserverState {
         // value left by a prior `mark_truncate`.
         self.append_index = 0;
         self.truncate_len = self.truncate_len.max(1);
+        self.splice_delete_len = 0;
+        self.splice_insert_len = 0;
+        #[cfg(feature = "inverse")]
+        {
+            self.blind = true;
+        }
     }
 }
 
@@ -54,6 +161,15 @@ impl StrObserverState for StringObserverState {
         Self {
             append_index: value.len(),
             truncate_len: 0,
+            splice_index: 0,
+            splice_delete_len: 0,
+            splice_insert_len: 0,
+            #[cfg(feature = "inverse")]
+            deleted: String::new(),
+            #[cfg(feature = "inverse")]
+            truncated: String::new(),
+            #[cfg(feature = "inverse")]
+            blind: false,
         }
     }
 }
@@ -65,13 +181,42 @@ where
 {
     fn flush(&mut self, ptr: &mut Pointer<S>) -> Mutations {
         let value = (**ptr).as_deref();
+        #[cfg(feature = "inverse")]
+        let inverse = Invalidate::<str>::inverse(self, value);
+        #[cfg(feature = "inverse")]
+        {
+            self.deleted.clear();
+            self.truncated.clear();
+            self.blind = false;
+        }
+        let mutations = self.flush_forward(value);
+        #[cfg(feature = "inverse")]
+        let mutations = mutations.with_inverse(inverse);
+        mutations
+    }
+}
+
+impl StringObserverState {
+    fn flush_forward(&mut self, value: &str) -> Mutations {
         let len = value.len();
         let append_index = std::mem::replace(&mut self.append_index, len);
         let truncate_len = std::mem::replace(&mut self.truncate_len, 0);
+        let splice_delete_len = std::mem::replace(&mut self.splice_delete_len, 0);
+        let splice_insert_len = std::mem::replace(&mut self.splice_insert_len, 0);
         if append_index == 0 && truncate_len > 0 {
             return Mutations::replace(value);
         }
         let mut mutations = Mutations::new();
+        if splice_delete_len + splice_insert_len > 0 {
+            #[cfg(feature = "splice")]
+            mutations.extend(Mutations::splice(
+                value[..self.splice_index].chars().count(),
+                splice_delete_len,
+                &value[self.splice_index..self.splice_index + splice_insert_len],
+            ));
+            #[cfg(not(feature = "splice"))]
+            return Mutations::replace(value);
+        }
         if truncate_len > 0 {
             #[cfg(feature = "truncate")]
             mutations.extend(MutationKind::Truncate(truncate_len));
@@ -119,6 +264,11 @@ where
     fn invalidate(this: &mut Self) {
         Invalidate::invalidate(&mut this.inner.state, (*this.inner.ptr).as_deref().as_str());
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Invalidate::inverse(&this.inner.state, head.as_deref().as_str())
+    }
 }
 
 impl<'ob, S: ?Sized, D> Observer for StringObserver<'ob, S, D>
@@ -181,19 +331,20 @@ where
     pub fn pop(&mut self) -> Option<char> {
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        let ch = value.pop()?;
-        if value.len() < state.append_index {
-            state.truncate_len += 1;
-            state.append_index = value.len();
-        }
-        Some(ch)
+        let ch = value.chars().next_back()?;
+        state.mark_truncate(value.as_str(), value.len() - ch.len_utf8());
+        value.pop()
     }
 
     /// See [`String::remove`].
     pub fn remove(&mut self, idx: usize) -> char {
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        state.mark_truncate(value.as_str(), idx);
+        let ch = value[idx..]
+            .chars()
+            .next()
+            .expect("cannot remove a char from the end of a string");
+        state.mark_splice(value.as_str(), idx, idx + ch.len_utf8(), 0);
         value.remove(idx)
     }
 
@@ -204,10 +355,15 @@ where
     {
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
+        if state.has_splice() {
+            state.merge_splice(value.as_str());
+        }
         let append_index = state.append_index;
         let mut byte_offset = 0;
         let mut first_removed: Option<usize> = None;
         let mut chars_in_range = 0usize;
+        #[cfg(feature = "inverse")]
+        let mut truncated = String::new();
         value.retain(|ch| {
             let kept = f(ch);
             if byte_offset < append_index {
@@ -216,6 +372,8 @@ where
                 }
                 if first_removed.is_some() {
                     chars_in_range += 1;
+                    #[cfg(feature = "inverse")]
+                    truncated.push(ch);
                 }
             }
             byte_offset += ch.len_utf8();
@@ -224,6 +382,8 @@ where
         if let Some(idx) = first_removed {
             state.truncate_len += chars_in_range;
             state.append_index = idx;
+            #[cfg(feature = "inverse")]
+            state.truncated.insert_str(0, &truncated);
         }
     }
 
@@ -231,7 +391,7 @@ where
     pub fn insert(&mut self, idx: usize, ch: char) {
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        state.mark_truncate(value.as_str(), idx);
+        state.mark_splice(value.as_str(), idx, idx, ch.len_utf8());
         value.insert(idx, ch);
     }
 
@@ -239,7 +399,7 @@ where
     pub fn insert_str(&mut self, idx: usize, string: &str) {
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        state.mark_truncate(value.as_str(), idx);
+        state.mark_splice(value.as_str(), idx, idx, string.len());
         value.insert_str(idx, string);
     }
 
@@ -274,14 +434,10 @@ where
     where
         R: RangeBounds<usize>,
     {
-        let start_index = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        state.mark_truncate(value.as_str(), start_index);
+        let (start_index, end_index) = byte_range(value, &range);
+        state.mark_splice(value.as_str(), start_index, end_index, 0);
         value.drain(range)
     }
 
@@ -290,18 +446,30 @@ where
     where
         R: RangeBounds<usize>,
     {
-        let start_index = match range.start_bound() {
-            Bound::Included(&n) => n,
-            Bound::Excluded(&n) => n + 1,
-            Bound::Unbounded => 0,
-        };
         let state = &mut self.inner.state;
         let value = (*self.inner.ptr).as_deref_mut();
-        state.mark_truncate(value.as_str(), start_index);
+        let (start_index, end_index) = byte_range(value, &range);
+        state.mark_splice(value.as_str(), start_index, end_index, replace_with.len());
         value.replace_range(range, replace_with);
     }
 }
 
+/// Resolves `range` against `value`, panicking on invalid ranges like the methods of [`String`].
+fn byte_range(value: &str, range: &impl RangeBounds<usize>) -> (usize, usize) {
+    let start_index = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n + 1,
+        Bound::Unbounded => 0,
+    };
+    let end_index = match range.end_bound() {
+        Bound::Included(&n) => n + 1,
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => value.len(),
+    };
+    assert!(value.get(start_index..end_index).is_some(), "invalid range for string");
+    (start_index, end_index)
+}
+
 impl<'ob, S: ?Sized, D> AddAssign<&str> for StringObserver<'ob, S, D>
 where
     D: Unsigned,
@@ -595,7 +763,7 @@ mod tests {
         let mut ob = s.__observe();
         assert_eq!(ob.remove("你好".len()), '，');
         let Json(mutation) = ob.flush().unwrap();
-        assert_eq!(mutation, Some(batch!(_, truncate!(_, 4), append!(_, json!("世界！")))));
+        assert_eq!(mutation, Some(splice!(_, 2, 1, json!(""))));
     }
 
     #[test]
@@ -608,6 +776,80 @@ mod tests {
         assert_eq!(mutation, Some(truncate!(_, 2)));
     }
 
+    #[test]
+    fn insert_as_splice() {
+        let mut s = String::from("你好世界");
+        let mut ob = s.__observe();
+        ob.insert("你好".len(), '，');
+        ob.insert_str("你好，世".len(), "！！");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(splice!(_, 2, 1, json!("，世！！"))));
+        assert_eq!(s, "你好，世！！界");
+    }
+
+    #[test]
+    fn insert_at_append_index() {
+        let mut s = String::from("ab");
+        let mut ob = s.__observe();
+        ob.insert(2, 'c');
+        ob.insert(0, 'x');
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(
+            mutation,
+            Some(batch!(_, splice!(_, 0, 0, json!("x")), append!(_, json!("c"))))
+        );
+    }
+
+    #[test]
+    fn insert_then_remove() {
+        let mut s = String::from("abc");
+        let mut ob = s.__observe();
+        ob.insert(1, 'x');
+        assert_eq!(ob.remove(1), 'x');
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, None);
+    }
+
+    #[test]
+    fn replace_range_as_splice() {
+        let mut s = String::from("Hello, World!");
+        let mut ob = s.__observe();
+        ob.replace_range(7..12, "世界");
+        ob.replace_range(0..=4, "Hi");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(splice!(_, 0, 12, json!("Hi, 世界"))));
+        assert_eq!(s, "Hi, 世界!");
+    }
+
+    #[test]
+    fn drain_as_splice() {
+        let mut s = String::from("你好，世界！");
+        let mut ob = s.__observe();
+        assert_eq!(ob.drain("你".len().."你好，".len()).as_str(), "好，");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(splice!(_, 1, 2, json!(""))));
+
+        let mut ob = s.__observe();
+        assert_eq!(ob.drain("你".len()..).as_str(), "世界！");
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(truncate!(_, 3)));
+    }
+
+    #[test]
+    fn truncate_into_splice() {
+        let mut s = String::from("abcdef");
+        let mut ob = s.__observe();
+        ob.insert(4, 'x');
+        ob.pop();
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, splice!(_, 4, 0, json!("x")), truncate!(_, 1))));
+
+        ob.insert(2, 'y');
+        ob.truncate(3);
+        let Json(mutation) = ob.flush().unwrap();
+        assert_eq!(mutation, Some(batch!(_, truncate!(_, 4), append!(_, json!("y")))));
+    }
+
     #[test]
     fn retain_no_removal() {
         let mut s = String::from("hello");
@@ -664,4 +906,41 @@ mod tests {
         let Json(mutation) = ob.flush().unwrap();
         assert_eq!(mutation, Some(append!(_, json!("bar42"))));
     }
+
+    #[cfg(feature = "inverse")]
+    #[test]
+    fn inverse() {
+        use crate::helper::assert_inverse;
+
+        type Case = fn(&mut super::StringObserver<'_, String>);
+        let cases: [(&str, Case); 4] = [
+            ("héllo wörld", |ob| {
+                ob.replace_range(1..3, "EE");
+                ob.insert(0, 'ö');
+                ob.truncate(9);
+                ob.push_str("!?");
+            }),
+            ("héllo wörld", |ob| {
+                ob.remove(7);
+                ob.retain(|ch| ch != 'l');
+                ob.push('x');
+            }),
+            ("héllo", |ob| {
+                ob.clear();
+                ob.push_str("world");
+            }),
+            ("héllo", |ob| {
+                ob.pop();
+                ob.push('!');
+                ob.drain(..1);
+            }),
+        ];
+        for (value, mutate) in cases {
+            let mut s = String::from(value);
+            let mut ob = s.__observe();
+            mutate(&mut ob);
+            let flushed = ob.flush_inverse::<Json>().unwrap();
+            assert_inverse(json!(value), json!(s), flushed);
+        }
+    }
 }
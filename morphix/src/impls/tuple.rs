@@ -31,9 +31,9 @@ impl<O, S: ?Sized, D> DerefMut for TupleObserver<O, S, D> {
 
 impl<O, S: ?Sized, D> QuasiObserver for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
 {
     type Head = S;
     type OuterDepth = Succ<Zero>;
@@ -42,6 +42,11 @@ where
     fn invalidate(this: &mut Self) {
         O::invalidate(&mut this.0);
     }
+
+    #[cfg(feature = "inverse")]
+    fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+        Some(O::inverse(&this.0, &head.as_deref().0)?.with_prefix(0))
+    }
 }
 
 impl<O, S: ?Sized, D> Observer for TupleObserver<O, S, D>
@@ -98,7 +103,7 @@ where
     unsafe fn flush(this: &mut Self) -> Mutations {
         let mutations_0 = unsafe { SerializeObserver::flush(&mut this.0) };
         if mutations_0.is_replace() {
-            Mutations::replace((*this).untracked_ref())
+            Mutations::replace((*this).untracked_ref()).with_inverses_of(mutations_0.with_prefix(0))
         } else {
             mutations_0.with_prefix(0)
         }
@@ -107,9 +112,9 @@ where
 
 impl<O, S: ?Sized, D> Debug for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
     S::Target: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -119,9 +124,9 @@ where
 
 impl<O, S: ?Sized, D, U> PartialEq<(U,)> for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
     S::Target: PartialEq<(U,)>,
 {
     fn eq(&self, other: &(U,)) -> bool {
@@ -131,12 +136,12 @@ where
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<TupleObserver<O2, S2, D2>> for TupleObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<Target: Deref<Target: AsDeref<O1::InnerDepth>>>,
-    O2: QuasiObserver<Target: Deref<Target: AsDeref<O2::InnerDepth>>>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D1: Unsigned,
     D2: Unsigned,
-    S1: AsDeref<D1>,
-    S2: AsDeref<D2>,
+    S1: AsDeref<D1, Target = (O1::Head,)>,
+    S2: AsDeref<D2, Target = (O2::Head,)>,
     S1::Target: PartialEq<S2::Target>,
 {
     fn eq(&self, other: &TupleObserver<O2, S2, D2>) -> bool {
@@ -146,18 +151,18 @@ where
 
 impl<O, S: ?Sized, D> Eq for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
     S::Target: Eq,
 {
 }
 
 impl<O, S: ?Sized, D, U> PartialOrd<(U,)> for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
     S::Target: PartialOrd<(U,)>,
 {
     fn partial_cmp(&self, other: &(U,)) -> Option<std::cmp::Ordering> {
@@ -167,12 +172,12 @@ where
 
 impl<O1, O2, S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<TupleObserver<O2, S2, D2>> for TupleObserver<O1, S1, D1>
 where
-    O1: QuasiObserver<Target: Deref<Target: AsDeref<O1::InnerDepth>>>,
-    O2: QuasiObserver<Target: Deref<Target: AsDeref<O2::InnerDepth>>>,
+    O1: QuasiObserver<InnerDepth = Zero, Head: Sized>,
+    O2: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D1: Unsigned,
     D2: Unsigned,
-    S1: AsDeref<D1>,
-    S2: AsDeref<D2>,
+    S1: AsDeref<D1, Target = (O1::Head,)>,
+    S2: AsDeref<D2, Target = (O2::Head,)>,
     S1::Target: PartialOrd<S2::Target>,
 {
     fn partial_cmp(&self, other: &TupleObserver<O2, S2, D2>) -> Option<std::cmp::Ordering> {
@@ -182,9 +187,9 @@ where
 
 impl<O, S: ?Sized, D> Ord for TupleObserver<O, S, D>
 where
-    O: QuasiObserver<Target: Deref<Target: AsDeref<O::InnerDepth>>>,
+    O: QuasiObserver<InnerDepth = Zero, Head: Sized>,
     D: Unsigned,
-    S: AsDeref<D>,
+    S: AsDeref<D, Target = (O::Head,)>,
     S::Target: Ord,
 {
     fn cmp(&self, other: &TupleObserver<O, S, D>) -> std::cmp::Ordering {
@@ -248,9 +253,9 @@ macro_rules! tuple_observer {
 
         impl<$($o,)* S: ?Sized, D> QuasiObserver for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
         {
             type Head = S;
             type OuterDepth = Succ<Zero>;
@@ -259,6 +264,14 @@ macro_rules! tuple_observer {
             fn invalidate(this: &mut Self) {
                 $($o::invalidate(&mut this.$n);)*
             }
+
+            #[cfg(feature = "inverse")]
+            fn inverse(this: &Self, head: &Self::Head) -> Option<Mutations> {
+                let tuple = head.as_deref();
+                let mut mutations = Mutations::new();
+                $(mutations.insert($n, $o::inverse(&this.$n, &tuple.$n)?);)*
+                Some(mutations)
+            }
         }
 
         impl<$($o,)* S: ?Sized, D> Observer for $ty<$($o,)* S, D>
@@ -322,22 +335,22 @@ macro_rules! tuple_observer {
             unsafe fn flush(this: &mut Self) -> Mutations {
                 let mutations_tuple = ($(unsafe { SerializeObserver::flush(&mut this.$n).with_prefix($n) },)*);
                 let capacity = 0 $(+ mutations_tuple.$n.len())*;
-                if capacity == $ptr {
-                    return Mutations::replace((*this).untracked_ref());
-                }
                 let mut mutations = Mutations::new().with_capacity(capacity);
                 $(
                     mutations.extend(mutations_tuple.$n);
                 )*
+                if capacity == $ptr {
+                    return Mutations::replace((*this).untracked_ref()).with_inverses_of(mutations);
+                }
                 mutations
             }
         }
 
         impl<$($o,)* S: ?Sized, D> Debug for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
             S::Target: Debug,
         {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -347,9 +360,9 @@ macro_rules! tuple_observer {
 
         impl<$($o,)* S: ?Sized, D, U> PartialEq<(U,)> for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
             S::Target: PartialEq<(U,)>,
         {
             fn eq(&self, other: &(U,)) -> bool {
@@ -360,12 +373,12 @@ macro_rules! tuple_observer {
         impl<$($o,)* $($p,)* S1: ?Sized, S2: ?Sized, D1, D2> PartialEq<$ty<$($p,)* S2, D2>>
             for $ty<$($o,)* S1, D1>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
-            $($p: QuasiObserver<Target: Deref<Target: AsDeref<$p::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
+            $($p: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D1: Unsigned,
             D2: Unsigned,
-            S1: AsDeref<D1>,
-            S2: AsDeref<D2>,
+            S1: AsDeref<D1, Target = ($($o::Head,)*)>,
+            S2: AsDeref<D2, Target = ($($p::Head,)*)>,
             S1::Target: PartialEq<S2::Target>,
         {
             fn eq(&self, other: &$ty<$($p,)* S2, D2>) -> bool {
@@ -375,18 +388,18 @@ macro_rules! tuple_observer {
 
         impl<$($o,)* S: ?Sized, D> Eq for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
             S::Target: Eq,
         {
         }
 
         impl<$($o,)* S: ?Sized, D, U> PartialOrd<(U,)> for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
             S::Target: PartialOrd<(U,)>,
         {
             fn partial_cmp(&self, other: &(U,)) -> Option<std::cmp::Ordering> {
@@ -397,12 +410,12 @@ macro_rules! tuple_observer {
         impl<$($o,)* $($p,)* S1: ?Sized, S2: ?Sized, D1, D2> PartialOrd<$ty<$($p,)* S2, D2>>
             for $ty<$($o,)* S1, D1>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
-            $($p: QuasiObserver<Target: Deref<Target: AsDeref<$p::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
+            $($p: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D1: Unsigned,
             D2: Unsigned,
-            S1: AsDeref<D1>,
-            S2: AsDeref<D2>,
+            S1: AsDeref<D1, Target = ($($o::Head,)*)>,
+            S2: AsDeref<D2, Target = ($($p::Head,)*)>,
             S1::Target: PartialOrd<S2::Target>,
         {
             fn partial_cmp(&self, other: &$ty<$($p,)* S2, D2>) -> Option<std::cmp::Ordering> {
@@ -412,9 +425,9 @@ macro_rules! tuple_observer {
 
         impl<$($o,)* S: ?Sized, D> Ord for $ty<$($o,)* S, D>
         where
-            $($o: QuasiObserver<Target: Deref<Target: AsDeref<$o::InnerDepth>>>,)*
+            $($o: QuasiObserver<InnerDepth = Zero, Head: Sized>,)*
             D: Unsigned,
-            S: AsDeref<D>,
+            S: AsDeref<D, Target = ($($o::Head,)*)>,
             S::Target: Ord,
         {
             fn cmp(&self, other: &$ty<$($o,)* S, D>) -> std::cmp::Ordering {
@@ -19,12 +19,13 @@ pub mod impls;
 mod mutation;
 pub mod observe;
 mod path;
+pub mod wire;
 
 pub use adapter::Adapter;
 pub use batch::BatchTree;
-pub use error::MutationError;
+pub use error::{MutationError, PathError};
 #[cfg(feature = "derive")]
 pub use morphix_derive::{Observe, observe};
 pub use mutation::{Mutation, MutationKind, Mutations};
 pub use observe::Observe;
-pub use path::{Path, PathSegment};
+pub use path::{Path, PathPattern, PathSegment, PatternSegment};
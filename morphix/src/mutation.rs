@@ -1,8 +1,11 @@
+use std::convert::Infallible;
 use std::fmt::Debug;
 
 use erased_serde::Serialize;
 
-use crate::{Path, PathSegment};
+#[cfg(all(feature = "transform", feature = "move"))]
+use crate::helper::reorder::permutation_moves;
+use crate::{Adapter, BatchTree, MutationError, Path, PathPattern, PathSegment};
 
 /// The kind of mutation that occurred.
 ///
@@ -14,6 +17,11 @@ use crate::{Path, PathSegment};
 /// - [`Replace`](MutationKind::Replace): Complete replacement of a value
 /// - [`Append`](MutationKind::Append): Append operation for strings and vectors
 /// - [`Truncate`](MutationKind::Truncate): Truncate operation for strings and vectors
+/// - [`Splice`](MutationKind::Splice): Removal and insertion in the middle of strings and vectors
+/// - [`Move`](MutationKind::Move): Rearrangement of vectors and ordered maps
+/// - [`Increment`](MutationKind::Increment): Arithmetic increment of counters
+/// - [`Add`](MutationKind::Add): Insertion of members into sets
+/// - [`Remove`](MutationKind::Remove): Removal of members from sets
 /// - [`Delete`](MutationKind::Delete): Deletion of a value from a map or conditional skip
 /// - [`Batch`](MutationKind::Batch): Multiple mutations combined
 ///
@@ -123,6 +131,82 @@ pub enum MutationKind<T> {
     #[cfg(feature = "truncate")]
     Truncate(usize),
 
+    /// [`Splice`](MutationKind::Splice) represents removing `delete_len` elements starting at
+    /// `index` of a string or vector and inserting the elements of `insert` in their place. This is
+    /// more efficient than a [`Truncate`](MutationKind::Truncate) /
+    /// [`Append`](MutationKind::Append) pair because the elements after the edited range do not
+    /// need to be transmitted.
+    ///
+    /// Unlike element paths, which use [`Negative`](crate::PathSegment::Negative) indices, `index`
+    /// is counted from the start of the vector. For strings, both `index` and `delete_len` are
+    /// counted in chars.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   vec: Vec<i32>,
+    /// # }
+    /// let mut foo = Foo {
+    ///     vec: vec![1, 2, 3, 4, 5],
+    /// };
+    /// foo.vec.remove(1);                  // Splice at 1, deleting 1 element from .vec
+    /// foo.vec.insert(2, 42);              // Splice at 2, inserting [42] into .vec
+    /// foo.vec.splice(0..2, [6, 7, 8]);    // Splice at 0, replacing 2 elements of .vec
+    /// ```
+    ///
+    /// ```
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   text: String,
+    /// # }
+    /// let mut foo = Foo {
+    ///     text: "Hello, World!".to_string(),
+    /// };
+    /// foo.text.insert(5, '!');                // Splice at 5, inserting "!" into .text
+    /// foo.text.replace_range(0..5, "Hi");     // Splice at 0, replacing 5 chars of .text
+    /// ```
+    #[cfg(feature = "splice")]
+    Splice {
+        /// The index of the first removed element.
+        index: usize,
+        /// The number of elements removed.
+        delete_len: usize,
+        /// The elements inserted at `index`.
+        insert: T,
+    },
+
+    /// [`Move`](MutationKind::Move) represents moving the element at index `from` of a vector or
+    /// ordered map to index `to`, shifting the elements in between. This is more efficient than
+    /// [`Replace`](MutationKind::Replace) because no element needs to be transmitted when the
+    /// elements are only rearranged.
+    ///
+    /// The element is removed from `from` before being inserted at `to`, so both indices are
+    /// counted from the start of the container and `to` refers to the position after removal.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   vec: Vec<i32>,
+    /// # }
+    /// let mut foo = Foo {
+    ///     vec: vec![1, 2, 3, 4, 5],
+    /// };
+    /// foo.vec.swap(0, 1);         // Move 1 to 0 in .vec
+    /// foo.vec.rotate_left(1);     // Move 0 to 4 in .vec
+    /// foo.vec.sort();             // A sequence of moves restoring the order of .vec
+    /// ```
+    #[cfg(feature = "move")]
+    Move {
+        /// The index of the moved element before the move.
+        from: usize,
+        /// The index of the moved element after the move.
+        to: usize,
+    },
+
     /// [`Delete`](MutationKind::Delete) represents the removal of a value entirely.
     ///
     /// This mutation kind is used in two scenarios:
@@ -152,6 +236,71 @@ pub enum MutationKind<T> {
     #[cfg(feature = "delete")]
     Delete,
 
+    /// [`Increment`](MutationKind::Increment) represents adding a (possibly negative) delta to a
+    /// numeric value or a duration. Unlike [`Replace`](MutationKind::Replace), concurrent increments
+    /// of the same counter commute, so no update is lost when they are applied in any order.
+    ///
+    /// The delta of a duration is serialized as `{"$duration": [secs, nanos]}`, and only such
+    /// deltas are added as durations. Any other delta must be a number.
+    ///
+    /// Increments are only produced for fields marked with `#[morphix(counter)]`, see
+    /// [`CounterObserver`](crate::general::CounterObserver).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   count: u32,
+    /// #   elapsed: Duration,
+    /// # }
+    /// # let mut foo = Foo::default();
+    /// foo.count += 2;                             // Increment .count by 2
+    /// foo.count -= 1;                             // Increment .count by 1 in total
+    /// foo.elapsed += Duration::from_secs(1);      // Increment .elapsed by 1 second
+    /// ```
+    #[cfg(feature = "increment")]
+    Increment(T),
+
+    /// [`Add`](MutationKind::Add) represents inserting members into a set. The value is a sequence
+    /// of the inserted members, and members which are already present are ignored.
+    ///
+    /// Sets are serialized as sequences, so their members cannot be addressed by path. Unlike
+    /// [`Replace`](MutationKind::Replace), only the inserted members need to be transmitted, and
+    /// the order of members in the target is not significant.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   tags: HashSet<String>,
+    /// # }
+    /// # let mut foo = Foo::default();
+    /// foo.tags.insert("new".to_string());     // Add ["new"] to .tags
+    /// ```
+    #[cfg(feature = "set")]
+    Add(T),
+
+    /// [`Remove`](MutationKind::Remove) represents removing members from a set. The value is a
+    /// sequence of the removed members, and members which are not present are ignored.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # #[derive(Default)]
+    /// # struct Foo {
+    /// #   tags: HashSet<String>,
+    /// # }
+    /// # let mut foo = Foo::default();
+    /// foo.tags.remove("old");     // Remove ["old"] from .tags
+    /// ```
+    #[cfg(feature = "set")]
+    Remove(T),
+
     /// [`Batch`](MutationKind::Batch) combines multiple mutations that occurred during a single
     /// observation period. This is automatically created when multiple independent changes are
     /// detected.
@@ -166,18 +315,48 @@ pub enum MutationKind<T> {
 }
 
 impl<T> MutationKind<T> {
-    #[cfg(any(feature = "json", feature = "yaml"))]
-    pub(crate) fn try_map<U, E>(self, f: &mut impl FnMut(T) -> Result<U, E>) -> Result<MutationKind<U>, E> {
+    /// Maps the values of the mutation kind, including the values of nested mutations.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> MutationKind<U> {
+        match self.try_map_with(&mut |value| Ok::<_, Infallible>(f(value))) {
+            Ok(kind) => kind,
+        }
+    }
+
+    /// Maps the values of the mutation kind with a fallible function, including the values of
+    /// nested mutations. Stops at the first error.
+    pub fn try_map<U, E>(self, mut f: impl FnMut(T) -> Result<U, E>) -> Result<MutationKind<U>, E> {
+        self.try_map_with(&mut f)
+    }
+
+    fn try_map_with<U, E>(self, f: &mut impl FnMut(T) -> Result<U, E>) -> Result<MutationKind<U>, E> {
         Ok(match self {
             MutationKind::Replace(value) => MutationKind::Replace(f(value)?),
             #[cfg(feature = "append")]
             MutationKind::Append(value) => MutationKind::Append(f(value)?),
             #[cfg(feature = "truncate")]
             MutationKind::Truncate(len) => MutationKind::Truncate(len),
+            #[cfg(feature = "splice")]
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            } => MutationKind::Splice {
+                index,
+                delete_len,
+                insert: f(insert)?,
+            },
+            #[cfg(feature = "move")]
+            MutationKind::Move { from, to } => MutationKind::Move { from, to },
             #[cfg(feature = "delete")]
             MutationKind::Delete => MutationKind::Delete,
+            #[cfg(feature = "increment")]
+            MutationKind::Increment(value) => MutationKind::Increment(f(value)?),
+            #[cfg(feature = "set")]
+            MutationKind::Add(value) => MutationKind::Add(f(value)?),
+            #[cfg(feature = "set")]
+            MutationKind::Remove(value) => MutationKind::Remove(f(value)?),
             MutationKind::Batch(batch) => {
-                MutationKind::Batch(batch.into_iter().map(|m| m.try_map(f)).collect::<Result<_, E>>()?)
+                MutationKind::Batch(batch.into_iter().map(|m| m.try_map_with(f)).collect::<Result<_, E>>()?)
             }
         })
     }
@@ -193,6 +372,40 @@ impl<T> MutationKind<T> {
 ///
 /// The path is stored in *reverse order* for efficiency during collection.
 /// For example, a change at `foo.bar.baz` would have `path = ["baz", "bar", "foo"]`.
+///
+/// ## Serialization
+///
+/// [`Mutation`] and [`MutationKind`] implement [`Serialize`](serde::Serialize) and
+/// [`Deserialize`](serde::Deserialize) when their values do, following the [wire
+/// format](crate::wire). In human-readable formats, a mutation is a struct with the following
+/// fields, in order:
+///
+/// - `path`: a sequence of segments in *natural order*, where a
+///   [`String`](PathSegment::String) segment is a string, a [`Positive`](PathSegment::Positive)
+///   index is a non-negative integer `n`, and a [`Negative`](PathSegment::Negative) index is a
+///   negative integer `-n`.
+/// - `kind`: the name of the kind in lowercase, e.g. `"replace"`.
+/// - The fields of the kind:
+///
+/// | Kind | Tag | Fields |
+/// | --- | --- | --- |
+/// | [`Replace`](MutationKind::Replace) | 0 | `value` |
+/// | [`Append`](MutationKind::Append) | 1 | `value` |
+/// | [`Truncate`](MutationKind::Truncate) | 2 | `len` |
+/// | [`Splice`](MutationKind::Splice) | 3 | `index`, `delete_len`, `value` |
+/// | [`Move`](MutationKind::Move) | 4 | `from`, `to` |
+/// | [`Delete`](MutationKind::Delete) | 5 | |
+/// | [`Increment`](MutationKind::Increment) | 6 | `value` |
+/// | [`Add`](MutationKind::Add) | 7 | `value` |
+/// | [`Remove`](MutationKind::Remove) | 8 | `value` |
+/// | [`Batch`](MutationKind::Batch) | 9 | `mutations`, a sequence of mutations |
+///
+/// In binary formats, a mutation is instead a compact sequence `[path, tag, fields...]`, where
+/// `tag` is the number of the kind in the table above, e.g. `[["a"], 2, 3]` for truncating 3
+/// elements at `a`. Deserialization of both forms requires a self-describing format such as JSON,
+/// MessagePack or CBOR; formats like bincode are not supported.
+///
+/// A [`MutationKind`] alone is serialized as the same struct or sequence without `path`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Mutation<V> {
     /// The path to the mutated value, stored in *reverse order*.
@@ -205,14 +418,198 @@ pub struct Mutation<V> {
 }
 
 impl<V> Mutation<V> {
-    #[cfg(any(feature = "json", feature = "yaml"))]
-    pub(crate) fn try_map<U, E>(self, f: &mut impl FnMut(V) -> Result<U, E>) -> Result<Mutation<U>, E> {
+    /// Maps the values of the mutation, including the values of nested mutations.
+    ///
+    /// ```
+    /// use morphix::{Mutation, MutationKind};
+    ///
+    /// let mutation = Mutation {
+    ///     path: vec!["a".into()].into(),
+    ///     kind: MutationKind::Replace(1),
+    /// };
+    /// assert_eq!(mutation.map(|value| value.to_string()).kind, MutationKind::Replace("1".to_string()));
+    /// ```
+    pub fn map<U>(self, f: impl FnMut(V) -> U) -> Mutation<U> {
+        Mutation {
+            path: self.path,
+            kind: self.kind.map(f),
+        }
+    }
+
+    /// Maps the values of the mutation with a fallible function, including the values of nested
+    /// mutations. Stops at the first error.
+    pub fn try_map<U, E>(self, f: impl FnMut(V) -> Result<U, E>) -> Result<Mutation<U>, E> {
         Ok(Mutation {
             path: self.path,
             kind: self.kind.try_map(f)?,
         })
     }
 
+    fn try_map_with<U, E>(self, f: &mut impl FnMut(V) -> Result<U, E>) -> Result<Mutation<U>, E> {
+        Ok(Mutation {
+            path: self.path,
+            kind: self.kind.try_map_with(f)?,
+        })
+    }
+
+    /// Prepends a path segment to the mutation.
+    ///
+    /// The segment is pushed onto the reverse-order path, see also
+    /// [`Mutations::with_prefix`].
+    pub fn with_prefix(mut self, segment: impl Into<PathSegment>) -> Self {
+        self.path.push(segment.into());
+        self
+    }
+
+    /// Rebases the mutation onto the subtree at `prefix`.
+    ///
+    /// Returns the part of the mutation at or below `prefix`, with paths relative to `prefix`.
+    /// [`Batch`](MutationKind::Batch) mutations are descended into and rebuilt with the remaining
+    /// elements. Mutations outside the subtree are dropped, including mutations at an ancestor of
+    /// `prefix` which cannot be expressed relative to it; use [`filter`](Mutation::filter) to find
+    /// them. Returns [`None`] if nothing is left.
+    ///
+    /// ```
+    /// use morphix::{Mutation, MutationKind, Path};
+    ///
+    /// let mutation = Mutation {
+    ///     path: vec!["a".into(), "b".into(), "c".into()].into(),
+    ///     kind: MutationKind::Replace(1),
+    /// };
+    /// let prefix: Path<false> = "a.b".parse().unwrap();
+    /// let rebased = mutation.strip_prefix(&prefix).unwrap();
+    /// assert_eq!(rebased.path.to_string(), ".c");
+    /// ```
+    pub fn strip_prefix<const REV: bool>(self, prefix: &Path<REV>) -> Option<Self> {
+        let prefix: Vec<&PathSegment> = match REV {
+            true => prefix.iter().rev().collect(),
+            false => prefix.iter().collect(),
+        };
+        self.strip_segments(&prefix)
+    }
+
+    fn strip_segments(mut self, prefix: &[&PathSegment]) -> Option<Self> {
+        let mut prefix = prefix.iter();
+        loop {
+            let Some(expected) = prefix.clone().next() else {
+                return Some(self);
+            };
+            match self.path.last() {
+                Some(segment) if segment == *expected => {
+                    self.path.pop();
+                    prefix.next();
+                }
+                Some(_) => return None,
+                None => break,
+            }
+        }
+        let MutationKind::Batch(mutations) = self.kind else {
+            return None;
+        };
+        let rest: Vec<_> = prefix.copied().collect();
+        let mut batch = vec![];
+        for mutation in mutations
+            .into_iter()
+            .filter_map(|mutation| mutation.strip_segments(&rest))
+        {
+            match mutation.kind {
+                MutationKind::Batch(mutations) if mutation.path.is_empty() => batch.extend(mutations),
+                _ => batch.push(mutation),
+            }
+        }
+        match batch.len() {
+            0 => None,
+            1 => batch.pop(),
+            _ => Some(Mutation {
+                path: Path::new(),
+                kind: MutationKind::Batch(batch),
+            }),
+        }
+    }
+
+    /// Flattens the mutation into a list of full paths and non-batch kinds, in order.
+    ///
+    /// ```
+    /// use morphix::{Mutation, MutationKind};
+    ///
+    /// let mutation = Mutation {
+    ///     path: vec!["a".into()].into(),
+    ///     kind: MutationKind::Batch(vec![
+    ///         Mutation {
+    ///             path: vec!["b".into()].into(),
+    ///             kind: MutationKind::Replace(1),
+    ///         },
+    ///         Mutation {
+    ///             path: vec!["c".into()].into(),
+    ///             kind: MutationKind::Replace(2),
+    ///         },
+    ///     ]),
+    /// };
+    /// let flat: Vec<_> = mutation
+    ///     .flatten()
+    ///     .into_iter()
+    ///     .map(|(path, kind)| (path.to_string(), kind))
+    ///     .collect();
+    /// assert_eq!(flat, [(".a.b".to_string(), MutationKind::Replace(1)), (".a.c".to_string(), MutationKind::Replace(2))]);
+    /// ```
+    pub fn flatten(self) -> Vec<(Path<false>, MutationKind<V>)> {
+        let mut output = vec![];
+        self.flatten_into(&mut vec![], &mut output);
+        output.into_iter().map(|(path, kind)| (path.into(), kind)).collect()
+    }
+
+    fn flatten_into(self, prefix: &mut Vec<PathSegment>, output: &mut Vec<FlatMutation<V>>) {
+        let len = prefix.len();
+        prefix.extend(self.path.iter().rev().cloned());
+        match self.kind {
+            MutationKind::Batch(mutations) => {
+                for mutation in mutations {
+                    mutation.flatten_into(prefix, output);
+                }
+            }
+            kind => output.push((prefix.clone(), kind)),
+        }
+        prefix.truncate(len);
+    }
+
+    /// Returns an iterator over the full paths and non-batch kinds of the mutation, in order.
+    ///
+    /// This is the borrowing counterpart of [`flatten`](Mutation::flatten).
+    pub fn leaves(&self) -> impl Iterator<Item = (Path<false>, &MutationKind<V>)> {
+        Leaves {
+            stack: vec![(Vec::new(), self)],
+        }
+    }
+
+    /// Merges two mutations into one, as if `b` was made after `a`.
+    ///
+    /// Both mutations are loaded into a [`BatchTree`](crate::BatchTree), which combines them
+    /// with the same rules as observers, e.g. a replace overrides earlier mutations below it and
+    /// consecutive appends are concatenated. Returns [`None`] if the mutations cancel out.
+    ///
+    /// ```
+    /// use morphix::adapter::Json;
+    /// use morphix::{Mutation, MutationKind};
+    /// use serde_json::json;
+    ///
+    /// let a = Mutation {
+    ///     path: vec!["a".into(), "b".into()].into(),
+    ///     kind: MutationKind::Replace(json!(1)),
+    /// };
+    /// let b = Mutation {
+    ///     path: vec!["a".into()].into(),
+    ///     kind: MutationKind::Replace(json!({})),
+    /// };
+    /// let merged = Mutation::merge::<Json>(a, b).unwrap().unwrap();
+    /// assert_eq!(merged.path.to_string(), ".a");
+    /// ```
+    pub fn merge<A: Adapter<Value = V>>(a: Self, b: Self) -> Result<Option<Self>, MutationError> {
+        let mut tree = BatchTree::<A>::new();
+        tree.load(a)?;
+        tree.load(b)?;
+        Ok(tree.dump().into_inner())
+    }
+
     fn make_batch(&mut self, capacity: usize) -> &mut Vec<Self> {
         if self.path.is_empty()
             && let MutationKind::Batch(ref mut batch) = self.kind
@@ -234,6 +631,587 @@ impl<V> Mutation<V> {
     }
 }
 
+impl<V: Clone> Mutation<V> {
+    /// Returns the part of the mutation that may affect values matched by a pattern.
+    ///
+    /// A non-batch mutation is kept if the pattern matches its path, one of its ancestors (the
+    /// mutation changes a matched value), or one of its descendants (the mutation replaces or
+    /// resizes a container of matched values). [`Batch`](MutationKind::Batch) mutations are
+    /// filtered element by element, and rebuilt with only the kept elements. A batch with a single
+    /// kept element is collapsed into that element. Returns [`None`] if nothing is kept.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use morphix::{Mutation, MutationKind, PathPattern};
+    /// use serde_json::json;
+    ///
+    /// let mutation = Mutation {
+    ///     path: vec!["users".into()].into(),
+    ///     kind: MutationKind::Batch(vec![
+    ///         Mutation {
+    ///             path: vec!["alice".into(), "name".into()].into(),
+    ///             kind: MutationKind::Replace(json!("Alice")),
+    ///         },
+    ///         Mutation {
+    ///             path: vec!["bob".into(), "age".into()].into(),
+    ///             kind: MutationKind::Replace(json!(42)),
+    ///         },
+    ///     ]),
+    /// };
+    /// let pattern: PathPattern = "users.*.name".parse().unwrap();
+    /// let filtered = mutation.filter(&pattern).unwrap();
+    /// assert_eq!(filtered.path.to_string(), ".users.alice.name");
+    /// assert_eq!(filtered.kind, MutationKind::Replace(json!("Alice")));
+    /// ```
+    pub fn filter(&self, pattern: &PathPattern) -> Option<Self> {
+        self.filter_with_prefix(&mut vec![], pattern)
+    }
+
+    /// Filters the mutation by each pattern, as by [`filter`](Mutation::filter).
+    ///
+    /// Returns one result per pattern, in order. A mutation matched by several patterns is
+    /// included in each of their results.
+    pub fn split_by(&self, patterns: &[PathPattern]) -> Vec<Option<Self>> {
+        patterns.iter().map(|pattern| self.filter(pattern)).collect()
+    }
+
+    fn filter_with_prefix(&self, prefix: &mut Vec<PathSegment>, pattern: &PathPattern) -> Option<Self> {
+        let len = prefix.len();
+        prefix.extend(self.path.iter().rev().cloned());
+        let result = match &self.kind {
+            MutationKind::Batch(mutations) => {
+                let mut batch: Vec<_> = mutations
+                    .iter()
+                    .filter_map(|mutation| mutation.filter_with_prefix(prefix, pattern))
+                    .collect();
+                match batch.len() {
+                    0 => None,
+                    1 => {
+                        let mut mutation = batch.pop().unwrap();
+                        mutation.path.extend(self.path.iter().cloned());
+                        Some(mutation)
+                    }
+                    _ => Some(Mutation {
+                        path: self.path.clone(),
+                        kind: MutationKind::Batch(batch),
+                    }),
+                }
+            }
+            _ => pattern.overlaps(prefix).then(|| self.clone()),
+        };
+        prefix.truncate(len);
+        result
+    }
+}
+
+/// A mutation with its path in natural order and a non-batch kind.
+type FlatMutation<V> = (Vec<PathSegment>, MutationKind<V>);
+
+/// Iterator returned by [`Mutation::leaves`].
+struct Leaves<'a, V> {
+    stack: Vec<(Vec<PathSegment>, &'a Mutation<V>)>,
+}
+
+impl<'a, V> Iterator for Leaves<'a, V> {
+    type Item = (Path<false>, &'a MutationKind<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (mut path, mutation) = self.stack.pop()?;
+            path.extend(mutation.path.iter().rev().cloned());
+            match &mutation.kind {
+                MutationKind::Batch(mutations) => {
+                    self.stack
+                        .extend(mutations.iter().rev().map(|mutation| (path.clone(), mutation)));
+                }
+                kind => return Some((path.into(), kind)),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "transform")]
+impl<V: Clone> Mutation<V> {
+    /// Transforms two concurrent mutations against each other.
+    ///
+    /// Given two mutations `a` and `b` which were made against the same value, returns `(a', b')`
+    /// such that applying `a` then `b'` yields the same value as applying `b` then `a'`. A
+    /// transformed mutation is [`None`] if it has no effect left. `a` takes priority over `b` in
+    /// case of ties.
+    ///
+    /// ## Convergence Rules
+    ///
+    /// - Mutations at unrelated paths are left untouched.
+    /// - [`Replace`](MutationKind::Replace) and [`Delete`](MutationKind::Delete) override any
+    ///   concurrent mutation at the same path or below it. A delete wins over a replace at the
+    ///   same path. If both mutations are replaces at the same path, `a` wins.
+    /// - Element paths below a sequence are shifted by concurrent [`Append`](MutationKind::Append),
+    ///   [`Truncate`](MutationKind::Truncate), [`Splice`](MutationKind::Splice) and
+    ///   [`Move`](MutationKind::Move) mutations of the sequence, and dropped if the element was
+    ///   removed.
+    /// - Concurrent appends are both kept, with the elements of `a` first.
+    /// - Concurrent truncates remove the longer of the two tails.
+    /// - Overlapping splices remove the union of both ranges, and insert the elements of `a`
+    ///   followed by the elements of `b`.
+    /// - Concurrent moves of the same element move it to the destination of `a`. Concurrent moves
+    ///   of different elements are both kept, with the element of `a` placed at its destination
+    ///   last.
+    /// - A move is shifted by a concurrent splice of the sequence if it stays on one side of the
+    ///   spliced range, and dropped if it stays within the removed range.
+    /// - Concurrent [`Increment`](MutationKind::Increment) mutations are both kept.
+    /// - For concurrent [`Add`](MutationKind::Add) and [`Remove`](MutationKind::Remove) of the
+    ///   same member, `a` wins.
+    /// - [`Batch`](MutationKind::Batch) mutations are transformed element by element. The
+    ///   transformed mutations may be regrouped into batches at different paths.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ConflictError`](MutationError::ConflictError) if the outcome depends on
+    /// information not carried by the mutations, such as the length of a sequence addressed from
+    /// the start by one mutation and from the end by the other (e.g., a
+    /// [`Truncate`](MutationKind::Truncate) and a [`Splice`](MutationKind::Splice) or
+    /// [`Move`](MutationKind::Move) of the same sequence), if a move crosses the boundary of a
+    /// concurrently spliced range, or if the mutations are incompatible with each other (e.g., an
+    /// [`Increment`](MutationKind::Increment) and an [`Append`](MutationKind::Append) at the same
+    /// path).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use morphix::adapter::Json;
+    /// use morphix::{Mutation, MutationKind, PathSegment};
+    /// use serde_json::json;
+    ///
+    /// // Client A appends an element while client B edits the last element.
+    /// let a = Mutation {
+    ///     path: vec!["items".into()].into(),
+    ///     kind: MutationKind::Append(json!([4])),
+    /// };
+    /// let b = Mutation {
+    ///     path: vec!["items".into(), PathSegment::Negative(1)].into(),
+    ///     kind: MutationKind::Replace(json!(30)),
+    /// };
+    /// let (a, b) = Mutation::transform::<Json>(a, b).unwrap();
+    /// assert_eq!(a.unwrap().path.to_string(), ".items");
+    /// assert_eq!(b.unwrap().path.to_string(), ".items[-2]");
+    /// ```
+    pub fn transform<A: Adapter<Value = V>>(a: Self, b: Self) -> Result<(Option<Self>, Option<Self>), MutationError> {
+        let (mut flat_a, mut flat_b) = (vec![], vec![]);
+        a.flatten_into(&mut vec![], &mut flat_a);
+        b.flatten_into(&mut vec![], &mut flat_b);
+        let (flat_a, flat_b) = transform_flat::<A>(flat_a, flat_b)?;
+        Ok((Self::unflatten(flat_a), Self::unflatten(flat_b)))
+    }
+
+    fn unflatten(mut mutations: Vec<FlatMutation<V>>) -> Option<Self> {
+        if mutations.len() <= 1 {
+            return mutations.pop().map(|(path, kind)| Mutation {
+                path: path.into(),
+                kind,
+            });
+        }
+        let first = &mutations[0].0;
+        let common = mutations[1..].iter().fold(first.len(), |len, (path, _)| {
+            path.iter()
+                .zip(&first[..len])
+                .take_while(|(lhs, rhs)| lhs == rhs)
+                .count()
+        });
+        let path = first[..common].to_vec().into();
+        let batch = mutations
+            .into_iter()
+            .map(|(path, kind)| Mutation {
+                path: path[common..].to_vec().into(),
+                kind,
+            })
+            .collect();
+        Some(Mutation {
+            path,
+            kind: MutationKind::Batch(batch),
+        })
+    }
+}
+
+#[cfg(feature = "transform")]
+type Transformed<V> = Result<(Vec<FlatMutation<V>>, Vec<FlatMutation<V>>), MutationError>;
+
+#[cfg(feature = "transform")]
+fn conflict(path: &[PathSegment]) -> MutationError {
+    MutationError::ConflictError {
+        path: path.to_vec().into(),
+    }
+}
+
+/// Transforms two sequences of mutations against each other, one pair at a time.
+#[cfg(feature = "transform")]
+fn transform_flat<A: Adapter>(
+    mut a: Vec<FlatMutation<A::Value>>,
+    mut b: Vec<FlatMutation<A::Value>>,
+) -> Transformed<A::Value>
+where
+    A::Value: Clone,
+{
+    if a.len() > 1 {
+        let rest = a.split_off(1);
+        let (mut a, b) = transform_flat::<A>(a, b)?;
+        let (rest, b) = transform_flat::<A>(rest, b)?;
+        a.extend(rest);
+        return Ok((a, b));
+    }
+    if b.len() > 1 {
+        let rest = b.split_off(1);
+        let (a, mut b) = transform_flat::<A>(a, b)?;
+        let (a, rest) = transform_flat::<A>(a, rest)?;
+        b.extend(rest);
+        return Ok((a, b));
+    }
+    match (a.pop(), b.pop()) {
+        (Some(a), Some(b)) => transform_pair::<A>(a, b),
+        (a, b) => Ok((a.into_iter().collect(), b.into_iter().collect())),
+    }
+}
+
+#[cfg(feature = "transform")]
+fn transform_pair<A: Adapter>(a: FlatMutation<A::Value>, b: FlatMutation<A::Value>) -> Transformed<A::Value>
+where
+    A::Value: Clone,
+{
+    let depth = a.0.iter().zip(&b.0).take_while(|(lhs, rhs)| lhs == rhs).count();
+    if depth < a.0.len() && depth < b.0.len() {
+        return match (&a.0[depth], &b.0[depth]) {
+            (PathSegment::Positive(_), PathSegment::Negative(_))
+            | (PathSegment::Negative(_), PathSegment::Positive(_)) => Err(conflict(&a.0[..depth])),
+            _ => Ok((vec![a], vec![b])),
+        };
+    }
+    if depth < b.0.len() {
+        let b = transform_descendant::<A>(&a, b)?;
+        return Ok((vec![a], b.into_iter().collect()));
+    }
+    if depth < a.0.len() {
+        let a = transform_descendant::<A>(&b, a)?;
+        return Ok((a.into_iter().collect(), vec![b]));
+    }
+    transform_same::<A>(a, b)
+}
+
+/// Transforms a mutation below the path of `ancestor` against `ancestor`, which itself is not
+/// affected by the transformation.
+#[cfg(feature = "transform")]
+fn transform_descendant<A: Adapter>(
+    ancestor: &FlatMutation<A::Value>,
+    mut descendant: FlatMutation<A::Value>,
+) -> Result<Option<FlatMutation<A::Value>>, MutationError> {
+    let depth = ancestor.0.len();
+    let segment = &mut descendant.0[depth];
+    match (&ancestor.1, segment) {
+        (MutationKind::Replace(_), _) => return Ok(None),
+        #[cfg(feature = "delete")]
+        (MutationKind::Delete, _) => return Ok(None),
+        (MutationKind::Append(value), PathSegment::Negative(index)) => {
+            let Some(len) = A::len(value) else {
+                return Err(conflict(&ancestor.0));
+            };
+            *index += len;
+        }
+        (MutationKind::Truncate(truncate_len), PathSegment::Negative(index)) => {
+            if *index <= *truncate_len {
+                return Ok(None);
+            }
+            *index -= truncate_len;
+        }
+        #[cfg(feature = "splice")]
+        (
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            },
+            PathSegment::Positive(element),
+        ) => {
+            if *element >= *index {
+                if *element - index < *delete_len {
+                    return Ok(None);
+                }
+                let Some(insert_len) = A::len(insert) else {
+                    return Err(conflict(&ancestor.0));
+                };
+                *element = *element - delete_len + insert_len;
+            }
+        }
+        #[cfg(feature = "move")]
+        (MutationKind::Move { from, to }, PathSegment::Positive(element)) => {
+            if *element == *from {
+                *element = *to;
+            } else {
+                if *element > *from {
+                    *element -= 1;
+                }
+                if *element >= *to {
+                    *element += 1;
+                }
+            }
+        }
+        (MutationKind::Append(_), PathSegment::Positive(_) | PathSegment::String(_)) => {}
+        _ => return Err(conflict(&ancestor.0)),
+    }
+    Ok(Some(descendant))
+}
+
+/// Transforms two mutations at the same path.
+#[cfg(feature = "transform")]
+fn transform_same<A: Adapter>(a: FlatMutation<A::Value>, b: FlatMutation<A::Value>) -> Transformed<A::Value>
+where
+    A::Value: Clone,
+{
+    let path = a.0;
+    let with_path = |kinds: Vec<MutationKind<A::Value>>| -> Vec<FlatMutation<A::Value>> {
+        kinds.into_iter().map(|kind| (path.clone(), kind)).collect()
+    };
+    let len_of = |value: &A::Value| A::len(value).ok_or_else(|| conflict(&path));
+    let (a, b) = match (a.1, b.1) {
+        #[cfg(feature = "delete")]
+        (MutationKind::Delete, MutationKind::Delete) => (vec![], vec![]),
+        #[cfg(feature = "delete")]
+        (a @ MutationKind::Delete, _) => (vec![a], vec![]),
+        #[cfg(feature = "delete")]
+        (_, b @ MutationKind::Delete) => (vec![], vec![b]),
+        (a @ MutationKind::Replace(_), _) => (vec![a], vec![]),
+        (_, b @ MutationKind::Replace(_)) => (vec![], vec![b]),
+        // An append cannot be inserted before another one, so the other one is truncated and
+        // appended again.
+        (MutationKind::Append(a), MutationKind::Append(b)) => (
+            vec![
+                MutationKind::Truncate(len_of(&b)?),
+                MutationKind::Append(a),
+                MutationKind::Append(b.clone()),
+            ],
+            vec![MutationKind::Append(b)],
+        ),
+        (MutationKind::Append(a), MutationKind::Truncate(b)) => (
+            vec![MutationKind::Append(a.clone())],
+            vec![MutationKind::Truncate(b + len_of(&a)?), MutationKind::Append(a)],
+        ),
+        (MutationKind::Truncate(a), MutationKind::Append(b)) => (
+            vec![MutationKind::Truncate(a + len_of(&b)?), MutationKind::Append(b.clone())],
+            vec![MutationKind::Append(b)],
+        ),
+        (MutationKind::Truncate(a), MutationKind::Truncate(b)) => {
+            let len = a.max(b);
+            (
+                (len > b)
+                    .then_some(MutationKind::Truncate(len - b))
+                    .into_iter()
+                    .collect(),
+                (len > a)
+                    .then_some(MutationKind::Truncate(len - a))
+                    .into_iter()
+                    .collect(),
+            )
+        }
+        #[cfg(feature = "splice")]
+        (a @ MutationKind::Append(_), b @ MutationKind::Splice { .. })
+        | (a @ MutationKind::Splice { .. }, b @ MutationKind::Append(_)) => (vec![a], vec![b]),
+        #[cfg(feature = "splice")]
+        (
+            MutationKind::Splice {
+                index: i,
+                delete_len: d,
+                insert: x,
+            },
+            MutationKind::Splice {
+                index: j,
+                delete_len: e,
+                insert: y,
+            },
+        ) => {
+            let (m, p) = (len_of(&x)?, len_of(&y)?);
+            if i + d <= j {
+                (
+                    vec![MutationKind::Splice {
+                        index: i,
+                        delete_len: d,
+                        insert: x,
+                    }],
+                    vec![MutationKind::Splice {
+                        index: j - d + m,
+                        delete_len: e,
+                        insert: y,
+                    }],
+                )
+            } else if j + e <= i {
+                (
+                    vec![MutationKind::Splice {
+                        index: i - e + p,
+                        delete_len: d,
+                        insert: x,
+                    }],
+                    vec![MutationKind::Splice {
+                        index: j,
+                        delete_len: e,
+                        insert: y,
+                    }],
+                )
+            } else {
+                // Both mutations replace the union of the two ranges with `x` followed by `y`.
+                let start = i.min(j);
+                let end = (i + d).max(j + e);
+                let mut insert = x;
+                if A::append(&mut insert, y).is_none() {
+                    return Err(conflict(&path));
+                }
+                (
+                    vec![MutationKind::Splice {
+                        index: start,
+                        delete_len: end - start - e + p,
+                        insert: insert.clone(),
+                    }],
+                    vec![MutationKind::Splice {
+                        index: start,
+                        delete_len: end - start - d + m,
+                        insert,
+                    }],
+                )
+            }
+        }
+        #[cfg(feature = "move")]
+        (a @ MutationKind::Append(_), b @ MutationKind::Move { .. })
+        | (a @ MutationKind::Move { .. }, b @ MutationKind::Append(_)) => (vec![a], vec![b]),
+        #[cfg(feature = "move")]
+        (
+            MutationKind::Move { from, to },
+            MutationKind::Move {
+                from: other_from,
+                to: other_to,
+            },
+        ) if from == other_from => (
+            (to != other_to)
+                .then_some(MutationKind::Move { from: other_to, to })
+                .into_iter()
+                .collect(),
+            vec![],
+        ),
+        #[cfg(feature = "move")]
+        (MutationKind::Move { from: i, to: j }, MutationKind::Move { from: k, to: l }) => {
+            // Both sides converge to `b` followed by moving the element of `a` to its destination,
+            // so only the leading elements up to the largest index are rearranged.
+            let len = i.max(j).max(k).max(l) + 1;
+            let moved = |from, to| {
+                let mut order = (0..len).collect::<Vec<_>>();
+                let element = order.remove(from);
+                order.insert(to, element);
+                order
+            };
+            let (after_a, mut merged) = (moved(i, j), moved(k, l));
+            let index = merged.iter().position(|&element| element == i).unwrap();
+            let element = merged.remove(index);
+            merged.insert(j, element);
+            let perm = merged
+                .iter()
+                .map(|element| after_a.iter().position(|other| other == element).unwrap())
+                .collect::<Vec<_>>();
+            let b = permutation_moves(&perm, usize::MAX).unwrap();
+            (
+                (index != j)
+                    .then_some(MutationKind::Move { from: index, to: j })
+                    .into_iter()
+                    .collect(),
+                b.into_iter()
+                    .map(|(from, to)| MutationKind::Move { from, to })
+                    .collect(),
+            )
+        }
+        #[cfg(all(feature = "move", feature = "splice"))]
+        (
+            MutationKind::Move { from, to },
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            },
+        ) => {
+            let insert_len = len_of(&insert)?;
+            let a = transform_move_splice(from, to, index, delete_len, insert_len).ok_or_else(|| conflict(&path))?;
+            let b = MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            };
+            (a.into_iter().collect(), vec![b])
+        }
+        #[cfg(all(feature = "move", feature = "splice"))]
+        (
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            },
+            MutationKind::Move { from, to },
+        ) => {
+            let insert_len = len_of(&insert)?;
+            let b = transform_move_splice(from, to, index, delete_len, insert_len).ok_or_else(|| conflict(&path))?;
+            let a = MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            };
+            (vec![a], b.into_iter().collect())
+        }
+        #[cfg(feature = "increment")]
+        (a @ MutationKind::Increment(_), b @ MutationKind::Increment(_)) => (vec![a], vec![b]),
+        #[cfg(feature = "set")]
+        (a @ MutationKind::Add(_), b @ MutationKind::Add(_))
+        | (a @ MutationKind::Remove(_), b @ MutationKind::Remove(_)) => (vec![a], vec![b]),
+        #[cfg(feature = "set")]
+        (MutationKind::Add(a), MutationKind::Remove(mut b)) => {
+            if A::remove_members(&mut b, &a).is_none() {
+                return Err(conflict(&path));
+            }
+            let b = (len_of(&b)? > 0).then_some(MutationKind::Remove(b));
+            (vec![MutationKind::Add(a)], b.into_iter().collect())
+        }
+        #[cfg(feature = "set")]
+        (MutationKind::Remove(a), MutationKind::Add(mut b)) => {
+            if A::remove_members(&mut b, &a).is_none() {
+                return Err(conflict(&path));
+            }
+            let b = (len_of(&b)? > 0).then_some(MutationKind::Add(b));
+            (vec![MutationKind::Remove(a)], b.into_iter().collect())
+        }
+        _ => return Err(conflict(&path)),
+    };
+    Ok((with_path(a), with_path(b)))
+}
+
+/// Transforms a [`Move`](MutationKind::Move) against a concurrent
+/// [`Splice`](MutationKind::Splice) of the same sequence, which itself is not affected.
+///
+/// Returns [`None`] if the move crosses the boundary of the spliced range or the range overflows,
+/// and `Some(None)` if the moved element is removed by the splice.
+#[cfg(all(feature = "transform", feature = "move", feature = "splice"))]
+fn transform_move_splice<V>(
+    from: usize,
+    to: usize,
+    index: usize,
+    delete_len: usize,
+    insert_len: usize,
+) -> Option<Option<MutationKind<V>>> {
+    let end = index.checked_add(delete_len)?;
+    if from < index && to < index {
+        Some(Some(MutationKind::Move { from, to }))
+    } else if from >= end && to >= end {
+        Some(Some(MutationKind::Move {
+            from: from - delete_len + insert_len,
+            to: to - delete_len + insert_len,
+        }))
+    } else if (index..end).contains(&from) && (index..end).contains(&to) {
+        Some(None)
+    } else {
+        None
+    }
+}
+
 /// A collection of mutations collected during observation.
 ///
 /// It is the return type for [`flush`](crate::observe::SerializeObserver::flush) and
@@ -269,6 +1247,10 @@ pub struct Mutations<V = Box<dyn Serialize>> {
     inner: Option<Mutation<V>>,
     capacity: usize,
     is_replace: bool,
+    /// Inverses of the collected mutations in the order they were collected, or [`None`] if some
+    /// of them are unknown.
+    #[cfg(feature = "inverse")]
+    inverses: Option<Vec<Mutation<V>>>,
 }
 
 impl<V> Default for Mutations<V> {
@@ -279,13 +1261,22 @@ impl<V> Default for Mutations<V> {
 
 impl<V> From<MutationKind<V>> for Mutations<V> {
     fn from(kind: MutationKind<V>) -> Self {
+        Mutation {
+            path: Default::default(),
+            kind,
+        }
+        .into()
+    }
+}
+
+impl<V> From<Mutation<V>> for Mutations<V> {
+    fn from(mutation: Mutation<V>) -> Self {
         Self {
-            is_replace: matches!(kind, MutationKind::Replace(_)),
-            inner: Some(Mutation {
-                path: Default::default(),
-                kind,
-            }),
+            is_replace: mutation.path.is_empty() && matches!(mutation.kind, MutationKind::Replace(_)),
+            inner: Some(mutation),
             capacity: 2,
+            #[cfg(feature = "inverse")]
+            inverses: None,
         }
     }
 }
@@ -303,6 +1294,8 @@ impl<V> Mutations<V> {
             is_replace: false,
             inner: None,
             capacity: 2,
+            #[cfg(feature = "inverse")]
+            inverses: Some(vec![]),
         }
     }
 
@@ -330,12 +1323,95 @@ impl<V> Mutations<V> {
     /// If the collection is empty, this is a no-op. Otherwise, the segment is pushed onto the
     /// mutation's reverse-order path.
     pub fn with_prefix(mut self, segment: impl Into<PathSegment>) -> Self {
+        let segment = segment.into();
+        #[cfg(feature = "inverse")]
+        for inverse in self.inverses.iter_mut().flatten() {
+            inverse.path.push(segment.clone());
+        }
         if let Some(mutation) = &mut self.inner {
-            mutation.path.push(segment.into());
+            mutation.path.push(segment);
+        }
+        self
+    }
+
+    /// Attaches the inverse of the whole collection, which restores the state before the collected
+    /// mutations when applied after them. [`None`] marks the inverse as unknown.
+    ///
+    /// Without the `inverse` feature, this is a no-op.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_mut))]
+    pub fn with_inverse(mut self, inverse: impl Into<Option<Self>>) -> Self {
+        #[cfg(feature = "inverse")]
+        {
+            self.inverses = inverse.into().map(|inverse| inverse.inner.into_iter().collect());
+        }
+        #[cfg(not(feature = "inverse"))]
+        drop(inverse);
+        self
+    }
+
+    /// Attaches the inverses recorded by `other`, whose mutations are superseded by this
+    /// collection.
+    ///
+    /// This is used by composite observers which collapse the mutations of their children into a
+    /// single [`Replace`](MutationKind::Replace). Without the `inverse` feature, this is a no-op.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_mut))]
+    pub fn with_inverses_of(mut self, other: Self) -> Self {
+        #[cfg(feature = "inverse")]
+        {
+            self.inverses = other.inverses;
         }
+        #[cfg(not(feature = "inverse"))]
+        drop(other);
         self
     }
 
+    /// Marks the inverse of this collection as unknown.
+    ///
+    /// This is used by observers which cannot capture the values displaced by their mutations.
+    /// Without the `inverse` feature, this is a no-op.
+    #[cfg_attr(not(feature = "inverse"), expect(unused_mut))]
+    pub fn without_inverse(mut self) -> Self {
+        #[cfg(feature = "inverse")]
+        {
+            self.inverses = None;
+        }
+        self
+    }
+
+    /// Splits this collection into its mutations and their inverse.
+    ///
+    /// Applying the inverse after the mutations restores the state before them. The inverse is
+    /// [`None`] if some of the mutations were collected without their inverse.
+    #[cfg(feature = "inverse")]
+    pub fn split_inverse(self) -> (Self, Option<Self>) {
+        let inverse = self.inverses.map(|inverses| {
+            let mut mutations = Mutations::new().with_capacity(inverses.len());
+            for inverse in inverses.into_iter().rev() {
+                mutations.extend(inverse);
+            }
+            mutations
+        });
+        let forward = Self { inverses: None, ..self };
+        (forward, inverse)
+    }
+
+    /// Maps every value in this collection with `f`, keeping the [`is_replace`](Self::is_replace)
+    /// flag.
+    ///
+    /// See [`Mutation::map`].
+    #[cfg_attr(not(feature = "inverse"), expect(unused_mut))]
+    pub fn map<U>(self, mut f: impl FnMut(V) -> U) -> Mutations<U> {
+        Mutations {
+            #[cfg(feature = "inverse")]
+            inverses: self
+                .inverses
+                .map(|inverses| inverses.into_iter().map(|inverse| inverse.map(&mut f)).collect()),
+            inner: self.inner.map(|mutation| mutation.map(f)),
+            capacity: self.capacity,
+            is_replace: self.is_replace,
+        }
+    }
+
     /// Consumes the batch and returns the collected mutation.
     pub fn into_inner(self) -> Option<Mutation<V>> {
         self.inner
@@ -352,12 +1428,29 @@ impl<V> Mutations<V> {
         self.is_replace
     }
 
+    /// Returns `true` if this collection is a single [`Replace`](MutationKind::Replace) with an
+    /// empty path.
+    ///
+    /// [`flat_flush`](crate::observe::SerializeObserver::flat_flush) returns such a collection if
+    /// the content cannot be broken apart into field mutations, e.g. if a key of a flattened map
+    /// cannot be converted into a path segment. The parent observer then replaces itself as a
+    /// whole.
+    pub fn is_root_replace(&self) -> bool {
+        matches!(&self.inner, Some(Mutation { path, kind: MutationKind::Replace(_) }) if path.is_empty())
+    }
+
     /// Merges another collection of mutations into this one.
     ///
     /// If the incoming collection contains a [`Batch`](MutationKind::Batch) with an empty path, its
     /// inner mutations are flattened into this collection rather than being nested.
     pub fn extend(&mut self, mutations: impl Into<Self>) {
-        let Some(incoming) = mutations.into().into_inner() else {
+        let mutations = mutations.into();
+        #[cfg(feature = "inverse")]
+        match (&mut self.inverses, mutations.inverses) {
+            (Some(existing), Some(incoming)) => existing.extend(incoming),
+            (inverses, _) => *inverses = None,
+        }
+        let Some(incoming) = mutations.inner else {
             return;
         };
         let Some(existing) = &mut self.inner else {
@@ -422,8 +1515,11 @@ impl<V> Mutations<V> {
     /// mutation is converted individually, preserving the per-field paths. This is used by
     /// [`flat_flush`](crate::observe::SerializeObserver::flat_flush) when the parent needs to emit
     /// deletions for all fields of a flattened struct or map.
+    ///
+    /// The inverse of the converted collection is unknown.
     #[cfg(feature = "delete")]
     pub fn into_delete(mut self) -> Self {
+        self = self.without_inverse();
         if let Some(mutation) = &mut self.inner {
             match &mut mutation.kind {
                 MutationKind::Batch(batch) => {
@@ -438,6 +1534,36 @@ impl<V> Mutations<V> {
     }
 }
 
+impl<V: serde::Serialize> Mutations<V> {
+    /// Serializes the collected mutation straight into `serializer`.
+    ///
+    /// The mutation is written as an optional value in the layout documented on [`Mutation`], and
+    /// the values it carries are serialized in place. Unlike going through an [`Adapter`], no
+    /// intermediate value tree is built, which saves an allocation of every replaced value.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use morphix::{MutationKind, Mutations};
+    ///
+    /// let mut mutations = Mutations::new();
+    /// mutations.insert("a", MutationKind::Replace(42));
+    /// mutations.insert("b", MutationKind::Truncate(1));
+    ///
+    /// let mut buffer = vec![];
+    /// mutations.serialize_into(&mut serde_json::Serializer::new(&mut buffer)).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(buffer).unwrap(),
+    ///     r#"{"path":[],"kind":"batch","mutations":[{"path":["a"],"kind":"replace","value":42},{"path":["b"],"kind":"truncate","len":1}]}"#,
+    /// );
+    /// ```
+    ///
+    /// [`Adapter`]: crate::Adapter
+    pub fn serialize_into<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&self.inner, serializer)
+    }
+}
+
 /// A raw-pointer wrapper that implements [`Serialize`](serde::Serialize) for `?Sized` types.
 ///
 /// This type enables creating [`Box<dyn Serialize>`](erased_serde::Serialize) from references to
@@ -502,4 +1628,408 @@ impl Mutations {
     pub fn append<T: serde::Serialize + ?Sized + 'static>(value: &T) -> Self {
         Self::append_owned(SerializeRef(value))
     }
+
+    /// Creates a [`Mutations`] containing a single [`Splice`](MutationKind::Splice) mutation,
+    /// taking ownership of the inserted value.
+    ///
+    /// Unlike [`splice`](Self::splice), which accepts `&T` (including unsized types) and wraps it
+    /// in [`SerializeRef`], this method takes `T` by value and boxes it directly.
+    #[cfg(feature = "splice")]
+    pub fn splice_owned<T: serde::Serialize + 'static>(index: usize, delete_len: usize, insert: T) -> Self {
+        MutationKind::Splice {
+            index,
+            delete_len,
+            insert: Box::new(insert) as Box<dyn Serialize>,
+        }
+        .into()
+    }
+
+    /// Creates a [`Mutations`] containing a single [`Splice`](MutationKind::Splice) mutation
+    /// with the given inserted value.
+    ///
+    /// The value is wrapped in a [`Box<dyn Serialize>`](erased_serde::Serialize) via
+    /// [`SerializeRef`], allowing unsized types like `str` and `[T]` to be used.
+    #[cfg(feature = "splice")]
+    pub fn splice<T: serde::Serialize + ?Sized + 'static>(index: usize, delete_len: usize, insert: &T) -> Self {
+        Self::splice_owned(index, delete_len, SerializeRef(insert))
+    }
+
+    /// Creates a [`Mutations`] containing a single [`Increment`](MutationKind::Increment) mutation
+    /// with the given delta.
+    #[cfg(feature = "increment")]
+    pub fn increment<T: serde::Serialize + 'static>(delta: T) -> Self {
+        MutationKind::Increment(Box::new(delta) as Box<dyn Serialize>).into()
+    }
+
+    /// Creates a [`Mutations`] containing a single [`Add`](MutationKind::Add) mutation with the
+    /// given members.
+    #[cfg(feature = "set")]
+    pub fn add<T: serde::Serialize + 'static>(members: T) -> Self {
+        MutationKind::Add(Box::new(members) as Box<dyn Serialize>).into()
+    }
+
+    /// Creates a [`Mutations`] containing a single [`Remove`](MutationKind::Remove) mutation with
+    /// the given members.
+    #[cfg(feature = "set")]
+    pub fn remove<T: serde::Serialize + 'static>(members: T) -> Self {
+        MutationKind::Remove(Box::new(members) as Box<dyn Serialize>).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::adapter::Json;
+
+    fn apply(value: &mut Value, mutation: Option<Mutation<Value>>) {
+        if let Some(mutation) = mutation {
+            Json::mutate(value, mutation, &mut Default::default()).unwrap();
+        }
+    }
+
+    #[test]
+    fn strip_prefix() {
+        let mutation: Mutation<Value> = batch!(
+            a,
+            replace!(b.c, json!(1)),
+            batch!(b, append!(d, json!("x")), delete!(e)),
+            replace!(f, json!(2)),
+        );
+        let prefix: Path<false> = "a.b".parse().unwrap();
+        assert_eq!(
+            mutation.clone().strip_prefix(&prefix),
+            Some(batch!(_, replace!(c, json!(1)), append!(d, json!("x")), delete!(e))),
+        );
+        let prefix: Path<true> = "a.f".parse().unwrap();
+        assert_eq!(mutation.clone().strip_prefix(&prefix), Some(replace!(_, json!(2))));
+        assert_eq!(
+            mutation.clone().strip_prefix(&"a.g".parse::<Path<false>>().unwrap()),
+            None
+        );
+        assert_eq!(mutation.clone().strip_prefix(&Path::<false>::new()), Some(mutation));
+        let mutation: Mutation<Value> = replace!(a, json!({}));
+        assert_eq!(mutation.strip_prefix(&"a.b".parse::<Path<false>>().unwrap()), None);
+        let mutation: Mutation<Value> = replace!(_, json!(1));
+        assert_eq!(mutation.with_prefix(0).with_prefix("a"), replace!(a.0, json!(1)),);
+    }
+
+    #[test]
+    fn flatten() {
+        let mutation: Mutation<Value> =
+            batch!(a, replace!(b, json!(1)), batch!(c, append!(-1, json!("x")), delete!(_)),);
+        let expected = vec![
+            ("a.b".parse().unwrap(), MutationKind::Replace(json!(1))),
+            ("a.c[-1]".parse().unwrap(), MutationKind::Append(json!("x"))),
+            ("a.c".parse().unwrap(), MutationKind::Delete),
+        ];
+        let leaves: Vec<_> = mutation.leaves().map(|(path, kind)| (path, kind.clone())).collect();
+        assert_eq!(leaves, expected);
+        assert_eq!(mutation.flatten(), expected);
+    }
+
+    #[test]
+    fn map() {
+        let mutation: Mutation<i32> = Mutation {
+            path: vec!["a".into()].into(),
+            kind: MutationKind::Batch(vec![
+                Mutation {
+                    path: Path::new(),
+                    kind: MutationKind::Replace(1),
+                },
+                Mutation {
+                    path: vec!["b".into()].into(),
+                    kind: MutationKind::Append(2),
+                },
+            ]),
+        };
+        assert_eq!(
+            mutation.clone().map(|value| json!(value * 10)),
+            batch!(a, replace!(_, json!(10)), append!(b, json!(20))),
+        );
+        assert_eq!(
+            mutation.try_map(|value| if value > 1 { Err(value) } else { Ok(value) }),
+            Err(2),
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let merged = Mutation::merge::<Json>(append!(a, json!("x")), append!(a, json!("y"))).unwrap();
+        assert_eq!(merged, Some(append!(a, json!("xy"))));
+        let merged = Mutation::merge::<Json>(replace!(a.b, json!(1)), replace!(c, json!(2))).unwrap();
+        assert_eq!(merged, Some(batch!(_, replace!(a.b, json!(1)), replace!(c, json!(2)))));
+    }
+
+    #[test]
+    fn filter() {
+        let mutation: Mutation<Value> = batch!(
+            users,
+            replace!(alice.name, json!("Alice")),
+            append!(bob.name, json!("!")),
+            batch!(carol, replace!(name, json!("Carol")), replace!(age, json!(42))),
+            replace!(dave.age, json!(7)),
+        );
+        let pattern: PathPattern = "users.*.name".parse().unwrap();
+        assert_eq!(
+            mutation.filter(&pattern),
+            Some(batch!(
+                users,
+                replace!(alice.name, json!("Alice")),
+                append!(bob.name, json!("!")),
+                replace!(carol.name, json!("Carol")),
+            )),
+        );
+        assert_eq!(
+            mutation.filter(&"users.carol.**".parse().unwrap()),
+            Some(batch!(
+                users.carol,
+                replace!(name, json!("Carol")),
+                replace!(age, json!(42))
+            )),
+        );
+        assert_eq!(mutation.filter(&"users.eve".parse().unwrap()), None);
+        assert_eq!(
+            mutation.filter(&"users.dave.age.value".parse().unwrap()),
+            Some(replace!(users.dave.age, json!(7))),
+        );
+
+        let mutation: Mutation<Value> = replace!(users, json!({}));
+        assert_eq!(
+            mutation.split_by(&[pattern, "items".parse().unwrap(), "**".parse().unwrap()]),
+            vec![Some(mutation.clone()), None, Some(mutation)],
+        );
+    }
+
+    fn transform(
+        value: &Value,
+        a: Mutation<Value>,
+        b: Mutation<Value>,
+    ) -> (Option<Mutation<Value>>, Option<Mutation<Value>>) {
+        let (a2, b2) = Mutation::transform::<Json>(a.clone(), b.clone()).unwrap();
+        let mut lhs = value.clone();
+        apply(&mut lhs, Some(a));
+        apply(&mut lhs, b2.clone());
+        let mut rhs = value.clone();
+        apply(&mut rhs, Some(b));
+        apply(&mut rhs, a2.clone());
+        assert_eq!(lhs, rhs);
+        (a2, b2)
+    }
+
+    #[test]
+    fn transform_unrelated() {
+        let value = json!({"a": 1, "b": [1, 2]});
+        let (a, b) = transform(&value, replace!(a, json!(2)), append!(b, json!([3])));
+        assert_eq!(a, Some(replace!(a, json!(2))));
+        assert_eq!(b, Some(append!(b, json!([3]))));
+    }
+
+    #[test]
+    fn transform_replace_delete() {
+        let value = json!({"a": {"b": 1}, "c": 1});
+        let (a, b) = transform(&value, replace!(a, json!(2)), replace!(a, json!(3)));
+        assert_eq!((a, b), (Some(replace!(a, json!(2))), None));
+
+        let (a, b) = transform(&value, replace!(a.b, json!(2)), replace!(a, json!(3)));
+        assert_eq!((a, b), (None, Some(replace!(a, json!(3)))));
+
+        let (a, b) = transform(&value, delete!(a), replace!(a.b, json!(2)));
+        assert_eq!((a, b), (Some(delete!(a)), None));
+
+        let (a, b) = transform(&value, delete!(c), delete!(c));
+        assert_eq!((a, b), (None, None));
+
+        let (a, b) = transform(&value, delete!(c), replace!(c, json!(2)));
+        assert_eq!((a, b), (Some(delete!(c)), None));
+
+        let (a, b) = transform(&value, replace!(c, json!(2)), delete!(c));
+        assert_eq!((a, b), (None, Some(delete!(c))));
+
+        let (a, b) = transform(&value, replace!(a, json!(2)), delete!(a));
+        assert_eq!((a, b), (None, Some(delete!(a))));
+
+        let (a, b) = transform(&value, delete!(a), replace!(a, json!(2)));
+        assert_eq!((a, b), (Some(delete!(a)), None));
+    }
+
+    #[test]
+    fn transform_append_truncate() {
+        let value = json!([1, 2, 3]);
+        let (a, b) = transform(&value, append!(_, json!([4])), replace!(-1, json!(30)));
+        assert_eq!(a, Some(append!(_, json!([4]))));
+        assert_eq!(b, Some(replace!(-2, json!(30))));
+
+        let (a, b) = transform(&value, truncate!(_, 1), replace!(-2, json!(20)));
+        assert_eq!(a, Some(truncate!(_, 1)));
+        assert_eq!(b, Some(replace!(-1, json!(20))));
+
+        let (a, b) = transform(&value, truncate!(_, 2), replace!(-2, json!(20)));
+        assert_eq!((a, b), (Some(truncate!(_, 2)), None));
+
+        let (a, b) = transform(&value, append!(_, json!([4])), append!(_, json!([5, 6])));
+        assert_eq!(
+            a,
+            Some(batch!(
+                _,
+                truncate!(_, 2),
+                append!(_, json!([4])),
+                append!(_, json!([5, 6]))
+            ))
+        );
+        assert_eq!(b, Some(append!(_, json!([5, 6]))));
+
+        let (a, b) = transform(&value, truncate!(_, 1), append!(_, json!([4])));
+        assert_eq!(a, Some(batch!(_, truncate!(_, 2), append!(_, json!([4])))));
+        assert_eq!(b, Some(append!(_, json!([4]))));
+
+        let (a, b) = transform(&value, truncate!(_, 1), truncate!(_, 2));
+        assert_eq!((a, b), (None, Some(truncate!(_, 1))));
+    }
+
+    #[test]
+    fn transform_splice() {
+        let value = json!([1, 2, 3, 4, 5]);
+        let (a, b) = transform(&value, splice!(_, 0, 1, json!([6, 7])), replace!(3, json!(40)));
+        assert_eq!(b, Some(replace!(4, json!(40))));
+        assert_eq!(a, Some(splice!(_, 0, 1, json!([6, 7]))));
+
+        let (_, b) = transform(&value, splice!(_, 2, 2, json!([])), replace!(3, json!(40)));
+        assert_eq!(b, None);
+
+        let (a, b) = transform(&value, splice!(_, 1, 2, json!(["a"])), splice!(_, 2, 2, json!(["b"])));
+        assert_eq!(a, Some(splice!(_, 1, 2, json!(["a", "b"]))));
+        assert_eq!(b, Some(splice!(_, 1, 2, json!(["a", "b"]))));
+
+        let value = json!("hello");
+        let (a, b) = transform(&value, splice!(_, 0, 1, json!("H")), splice!(_, 4, 1, json!("O")));
+        assert_eq!(a, Some(splice!(_, 0, 1, json!("H"))));
+        assert_eq!(b, Some(splice!(_, 4, 1, json!("O"))));
+    }
+
+    #[test]
+    fn transform_splice_exhaustive() {
+        let value = json!([0, 1, 2, 3]);
+        for i in 0..=4 {
+            for j in 0..=4 {
+                for d in 0..=4 - i {
+                    for e in 0..=4 - j {
+                        for (x, y) in [(json!([]), json!([])), (json!(["x"]), json!(["y", "z"]))] {
+                            transform(&value, splice!(_, i, d, x.clone()), splice!(_, j, e, y.clone()));
+                            transform(&value, splice!(_, i, d, x), append!(_, y));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transform_move() {
+        let value = json!([1, 2, 3, 4]);
+        let (a, b) = transform(&value, mv!(_, 0, 2), replace!(0, json!(10)));
+        assert_eq!(a, Some(mv!(_, 0, 2)));
+        assert_eq!(b, Some(replace!(2, json!(10))));
+
+        let (a, b) = transform(&value, mv!(_, 1, 3), mv!(_, 1, 0));
+        assert_eq!((a, b), (Some(mv!(_, 0, 3)), None));
+
+        for from in 0..4 {
+            for to in 0..4 {
+                for index in 0..4 {
+                    let b = Mutation {
+                        path: vec![PathSegment::Positive(index)].into(),
+                        kind: MutationKind::Replace(json!(0)),
+                    };
+                    transform(&value, mv!(_, from, to), b);
+                    transform(&value, mv!(_, from, to), mv!(_, from, index));
+                }
+            }
+        }
+
+        let (a, b) = transform(&value, mv!(_, 0, 2), mv!(_, 3, 1));
+        assert_eq!(a, Some(mv!(_, 0, 2)));
+        assert_eq!(b, Some(batch!(_, mv!(_, 3, 0), mv!(_, 3, 2))));
+
+        for (i, j, k, l) in (0..256).map(|n| (n & 3, n >> 2 & 3, n >> 4 & 3, n >> 6)) {
+            let (a, _) = transform(&value, mv!(_, i, j), mv!(_, k, l));
+            let mut result = value.clone();
+            apply(&mut result, Some(mv!(_, k, l)));
+            apply(&mut result, a);
+            assert_eq!(result[j], value[i]);
+        }
+    }
+
+    #[test]
+    fn transform_move_splice() {
+        let value = json!([1, 2, 3, 4, 5, 6]);
+        let (a, b) = transform(&value, mv!(_, 4, 5), splice!(_, 1, 2, json!([7])));
+        assert_eq!(a, Some(mv!(_, 3, 4)));
+        assert_eq!(b, Some(splice!(_, 1, 2, json!([7]))));
+
+        let (a, b) = transform(&value, splice!(_, 2, 3, json!([])), mv!(_, 4, 2));
+        assert_eq!((a, b), (Some(splice!(_, 2, 3, json!([]))), None));
+
+        let (a, b) = transform(&value, splice!(_, 3, 1, json!([7, 8])), mv!(_, 0, 2));
+        assert_eq!(a, Some(splice!(_, 3, 1, json!([7, 8]))));
+        assert_eq!(b, Some(mv!(_, 0, 2)));
+
+        let error = Mutation::transform::<Json>(mv!(_, 0, 3), splice!(_, 2, 1, json!([]))).unwrap_err();
+        assert_eq!(error, MutationError::ConflictError { path: vec![].into() });
+
+        let value = json!([0, 1, 2, 3]);
+        for (from, to, index, delete_len) in (0..256).map(|n| (n & 3, n >> 2 & 3, n >> 4 & 3, n >> 6)) {
+            if index + delete_len > 4 {
+                continue;
+            }
+            let splice = splice!(_, index, delete_len, json!(["x", "y"]));
+            if Mutation::transform::<Json>(mv!(_, from, to), splice.clone()).is_ok() {
+                transform(&value, mv!(_, from, to), splice.clone());
+                transform(&value, splice, mv!(_, from, to));
+            }
+        }
+    }
+
+    #[test]
+    fn transform_increment_set() {
+        let value = json!({"n": 1, "s": ["a", "b"]});
+        let (a, b) = transform(&value, increment!(n, json!(2)), increment!(n, json!(3)));
+        assert_eq!(a, Some(increment!(n, json!(2))));
+        assert_eq!(b, Some(increment!(n, json!(3))));
+
+        let (a, b) = transform(&value, add!(s, json!(["c", "d"])), remove!(s, json!(["a", "c"])));
+        assert_eq!(a, Some(add!(s, json!(["c", "d"]))));
+        assert_eq!(b, Some(remove!(s, json!(["a"]))));
+
+        let (a, b) = transform(&value, remove!(s, json!(["c"])), add!(s, json!(["c"])));
+        assert_eq!((a, b), (Some(remove!(s, json!(["c"]))), None));
+    }
+
+    #[test]
+    fn transform_batch() {
+        let value = json!({"a": [1, 2], "b": {"c": 1}});
+        let (a, b) = transform(
+            &value,
+            batch!(_, append!(a, json!([3])), delete!(b)),
+            batch!(_, replace!(a.-1, json!(20)), replace!(b.c, json!(2))),
+        );
+        assert_eq!(a, Some(batch!(_, append!(a, json!([3])), delete!(b))));
+        assert_eq!(b, Some(replace!(a.-2, json!(20))));
+    }
+
+    #[test]
+    fn transform_conflict() {
+        let error = Mutation::transform::<Json>(splice!(a, 0, 1, json!([])), replace!(a.-1, json!(1))).unwrap_err();
+        assert_eq!(
+            error,
+            MutationError::ConflictError {
+                path: vec!["a".into()].into()
+            }
+        );
+
+        let error = Mutation::transform::<Json>(truncate!(_, 1), splice!(_, 0, 1, json!([]))).unwrap_err();
+        assert_eq!(error, MutationError::ConflictError { path: vec![].into() });
+    }
 }
@@ -252,6 +252,10 @@ pub trait SerializeObserver: QuasiObserver<Target = Pointer<<Self as QuasiObserv
     /// of its fields (including this flattened one) were replaced, and if so, collapses
     /// everything into a single whole-struct [`Replace`](crate::MutationKind::Replace).
     ///
+    /// If the content cannot be broken apart, e.g. if a key of a map cannot be converted into a
+    /// path segment, a single root-level [`Replace`](crate::MutationKind::Replace) is returned
+    /// instead, and the parent replaces itself as a whole (see [`Mutations::is_root_replace`]).
+    ///
     /// The default implementation panics. Only struct observers (generated by the derive macro),
     /// map observers, and wrapper observers that delegate to an inner observer (e.g.,
     /// [`DerefObserver`](crate::impls::DerefObserver),
@@ -284,6 +288,80 @@ pub trait SerializeObserverExt: SerializeObserver {
     fn flat_flush<A: Adapter>(&mut self) -> Result<A, A::Error> {
         A::from_mutations(unsafe { SerializeObserver::flat_flush(self) })
     }
+
+    /// Collects mutations and serializes them straight into `serializer`.
+    ///
+    /// The mutation is written in the layout documented on [`Mutation`](crate::Mutation), without
+    /// building the intermediate value tree of an [`Adapter`]. See
+    /// [`Mutations::serialize_into`] for details.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use morphix::Observe;
+    /// use morphix::observe::{ObserveExt, SerializeObserverExt};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize, Observe)]
+    /// struct Doc {
+    ///     title: String,
+    ///     views: u32,
+    /// }
+    ///
+    /// let mut doc = Doc { title: "Hello".to_string(), views: 0 };
+    /// let mut ob = doc.__observe();
+    /// ob.title.push_str(", world");
+    ///
+    /// let mut writer = vec![];
+    /// ob.flush_into(&mut serde_json::Serializer::new(&mut writer)).unwrap();
+    /// assert_eq!(writer, br#"{"path":["title"],"kind":"append","value":", world"}"#);
+    /// ```
+    fn flush_into<S: serde::Serializer>(&mut self, serializer: S) -> Result<S::Ok, S::Error> {
+        unsafe { SerializeObserver::flush(self) }.serialize_into(serializer)
+    }
+
+    /// Collects mutations using the specified adapter, together with their inverse.
+    ///
+    /// Observers capture the values displaced by each mutation (e.g., the old value of a
+    /// [`Replace`](crate::MutationKind::Replace) or the removed tail of a
+    /// [`Truncate`](crate::MutationKind::Truncate)) as it happens, so the inverse is built from
+    /// the changed parts only. Applying the inverse after the forward mutation restores the state
+    /// as of the previous flush.
+    ///
+    /// The inverse is [`None`] if some observer could not capture the displaced values, e.g.,
+    /// observers of interior mutability or fields emptied by `skip_serializing_if`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use morphix::adapter::Json;
+    /// use morphix::observe::{ObserveExt, SerializeObserverExt};
+    /// use morphix::{Adapter, Observe};
+    /// use serde::Serialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Serialize, Observe)]
+    /// struct Doc {
+    ///     title: String,
+    /// }
+    ///
+    /// let mut doc = Doc { title: "Hello".to_string() };
+    /// let mut ob = doc.__observe();
+    /// ob.title.push_str(", world");
+    /// let (Json(forward), inverse) = ob.flush_inverse::<Json>().unwrap();
+    /// let Some(Json(inverse)) = inverse else { unreachable!() };
+    ///
+    /// let mut replica = json!({"title": "Hello"});
+    /// Json::mutate(&mut replica, forward.unwrap(), &mut Default::default()).unwrap();
+    /// assert_eq!(replica, json!({"title": "Hello, world"}));
+    /// Json::mutate(&mut replica, inverse.unwrap(), &mut Default::default()).unwrap();
+    /// assert_eq!(replica, json!({"title": "Hello"}));
+    /// ```
+    #[cfg(feature = "inverse")]
+    fn flush_inverse<A: Adapter>(&mut self) -> Result<(A, Option<A>), A::Error> {
+        let (forward, inverse) = unsafe { SerializeObserver::flush(self) }.split_inverse();
+        Ok((A::from_mutations(forward)?, inverse.map(A::from_mutations).transpose()?))
+    }
 }
 
 impl<T: SerializeObserver> SerializeObserverExt for T {}
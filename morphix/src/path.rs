@@ -1,6 +1,12 @@
 use std::borrow::Cow;
 use std::fmt::{Debug, Display};
 use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use serde::ser::Impossible;
+use serde::{Serialize, Serializer};
+
+use crate::PathError;
 
 /// A segment of a mutation path.
 ///
@@ -42,9 +48,61 @@ impl From<Cow<'static, str>> for PathSegment {
     }
 }
 
+impl PathSegment {
+    /// Converts a map key into a [`String`](PathSegment::String) segment through its [`Serialize`]
+    /// implementation.
+    ///
+    /// Keys are stringified the way `serde_json` stringifies map keys: strings and chars are kept
+    /// as is, numbers and booleans are formatted, unit variants become their names, and newtype
+    /// structs are unwrapped. Other keys, such as tuples or structs, are rejected with
+    /// [`PathError::InvalidKey`].
+    ///
+    /// ```
+    /// use morphix::PathSegment;
+    ///
+    /// assert_eq!(PathSegment::from_key(&42_u64).unwrap(), "42".into());
+    /// assert_eq!(PathSegment::from_key("a").unwrap(), "a".into());
+    /// assert!(PathSegment::from_key(&(1, 2)).is_err());
+    /// ```
+    pub fn from_key<K: Serialize + ?Sized>(key: &K) -> Result<Self, PathError> {
+        key.serialize(KeySerializer)
+            .map_err(|KeyError(message)| PathError::InvalidKey { message })
+    }
+
+    /// Returns the key addressed by the segment in a string-keyed object.
+    ///
+    /// [`Positive`](PathSegment::Positive) indices address the keys made of their digits, as paths
+    /// parsed from JSON pointers cannot tell array indices from such keys.
+    #[cfg(any(
+        feature = "bson",
+        feature = "cbor",
+        feature = "json",
+        feature = "msgpack",
+        feature = "toml",
+        feature = "yaml"
+    ))]
+    pub(crate) fn as_key(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Self::String(key) => Some(Cow::Borrowed(key)),
+            Self::Positive(index) => Some(Cow::Owned(index.to_string())),
+            Self::Negative(_) => None,
+        }
+    }
+}
+
 impl Display for PathSegment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            PathSegment::String(s) if s.is_empty() || s.contains(['.', '[']) || s == "*" || s == "**" => {
+                f.write_str("[\"")?;
+                for c in s.chars() {
+                    if matches!(c, '"' | '\\') {
+                        f.write_str("\\")?;
+                    }
+                    write!(f, "{c}")?;
+                }
+                f.write_str("\"]")
+            }
             PathSegment::String(s) => write!(f, ".{s}"),
             PathSegment::Positive(n) => write!(f, "[{n}]"),
             PathSegment::Negative(n) => write!(f, "[-{n}]"),
@@ -59,6 +117,26 @@ impl Display for PathSegment {
 /// - `Path<false>`: Segments stored in natural order (root to leaf)
 /// - `Path<true>`: Segments stored in reverse order (leaf to root), optimized for efficient `push`
 ///   and `pop` operations during mutation collection
+///
+/// ## String Formats
+///
+/// A path is displayed as a sequence of `.key`, `[index]` and `[-index]` segments, e.g.
+/// `.items[0].name`, and the root path is displayed as an empty string. Keys that are empty, contain
+/// `.` or `[`, or are the wildcards `*` and `**` of [`PathPattern`] are quoted as `["key"]`, with
+/// `"` and `\` escaped by a backslash. The same syntax is accepted by [`FromStr`], where the
+/// leading `.` may be omitted:
+///
+/// ```
+/// use morphix::{Path, PathSegment};
+///
+/// let path: Path<false> = "items[-1][\"a.b\"]".parse().unwrap();
+/// assert_eq!(path[..], ["items".into(), PathSegment::Negative(1), "a.b".into()]);
+/// assert_eq!(path.to_string(), ".items[-1][\"a.b\"]");
+/// ```
+///
+/// Paths can also be converted from and to [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901)
+/// JSON pointers with [`from_json_pointer`](Path::from_json_pointer) and
+/// [`to_json_pointer`](Path::to_json_pointer).
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct Path<const REV: bool>(Vec<PathSegment>);
 
@@ -67,6 +145,90 @@ impl<const REV: bool> Path<REV> {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Parses an [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer.
+    ///
+    /// Reference tokens are unescaped (`~1` to `/` and `~0` to `~`). Tokens that are valid array
+    /// indices, such as `0` or `42`, become [`Positive`](PathSegment::Positive) segments, the token
+    /// `-` becomes `Negative(0)`, i.e. the position past the last element, and other tokens become
+    /// [`String`](PathSegment::String) segments. String-keyed adapters resolve
+    /// [`Positive`](PathSegment::Positive) segments on objects as keys, so that a pointer such as
+    /// `/42` still reaches the key `"42"`.
+    ///
+    /// ```
+    /// use morphix::{Path, PathSegment};
+    ///
+    /// let path = Path::<false>::from_json_pointer("/a~1b/0/-").unwrap();
+    /// assert_eq!(path[..], ["a/b".into(), PathSegment::Positive(0), PathSegment::Negative(0)]);
+    /// ```
+    pub fn from_json_pointer(pointer: &str) -> Result<Self, PathError> {
+        let error = || PathError::InvalidPointer {
+            pointer: pointer.to_string(),
+        };
+        if pointer.is_empty() {
+            return Ok(Self::new());
+        }
+        let rest = pointer.strip_prefix('/').ok_or_else(error)?;
+        rest.split('/')
+            .map(|token| {
+                if token == "-" {
+                    return Ok(PathSegment::Negative(0));
+                }
+                let is_index = token == "0" || !token.starts_with('0') && token.bytes().all(|b| b.is_ascii_digit());
+                if let Some(index) = token.parse().ok().filter(|_| is_index) {
+                    return Ok(PathSegment::Positive(index));
+                }
+                let mut key = String::with_capacity(token.len());
+                let mut chars = token.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '~' => match chars.next() {
+                            Some('0') => key.push('~'),
+                            Some('1') => key.push('/'),
+                            _ => return Err(error()),
+                        },
+                        c => key.push(c),
+                    }
+                }
+                Ok(PathSegment::String(key.into()))
+            })
+            .collect()
+    }
+
+    /// Formats the path as an [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON
+    /// pointer.
+    ///
+    /// Keys are escaped (`~` to `~0` and `/` to `~1`), and `Negative(0)` is formatted as `-`. Fails
+    /// with [`PathError::NegativeIndex`] if the path contains any other
+    /// [`Negative`](PathSegment::Negative) index, which JSON pointers cannot express.
+    ///
+    /// ```
+    /// use morphix::{Path, PathSegment};
+    ///
+    /// let path: Path<true> = vec!["a/b".into(), PathSegment::Positive(0)].into();
+    /// assert_eq!(path.to_json_pointer().unwrap(), "/a~1b/0");
+    /// ```
+    pub fn to_json_pointer(&self) -> Result<String, PathError> {
+        let segments: Vec<&PathSegment> = match REV {
+            true => self.0.iter().rev().collect(),
+            false => self.0.iter().collect(),
+        };
+        let mut pointer = String::new();
+        for segment in &segments {
+            pointer.push('/');
+            match segment {
+                PathSegment::String(key) => pointer.push_str(&key.replace('~', "~0").replace('/', "~1")),
+                PathSegment::Positive(index) => pointer.push_str(&index.to_string()),
+                PathSegment::Negative(0) => pointer.push('-'),
+                PathSegment::Negative(_) => {
+                    return Err(PathError::NegativeIndex {
+                        path: segments.into_iter().cloned().collect(),
+                    });
+                }
+            }
+        }
+        Ok(pointer)
+    }
 }
 
 impl<const REV: bool> From<Vec<PathSegment>> for Path<REV> {
@@ -93,6 +255,103 @@ impl<const REV: bool> Display for Path<REV> {
     }
 }
 
+/// A segment of a path string, before it is interpreted.
+enum RawSegment<'a> {
+    /// A `.key` segment, or a leading `key` segment.
+    Key(&'a str),
+    /// A `["key"]` segment, unescaped.
+    Quoted(String),
+    /// The content of a `[...]` segment.
+    Bracket(&'a str),
+}
+
+/// Splits a path string into segments, interpreted by `f`.
+///
+/// `f` returns [`None`] if a segment is invalid.
+fn parse_segments<T>(s: &str, mut f: impl FnMut(RawSegment<'_>) -> Option<T>) -> Result<Vec<T>, PathError> {
+    let error = |position| PathError::SyntaxError {
+        input: s.to_string(),
+        position,
+    };
+    let key_end = |start: usize| s[start..].find(['.', '[']).map_or(s.len(), |offset| start + offset);
+    let mut segments = vec![];
+    let mut pos = 0;
+    if !s.is_empty() && !s.starts_with(['.', '[']) {
+        pos = key_end(0);
+        segments.push(f(RawSegment::Key(&s[..pos])).ok_or_else(|| error(0))?);
+    }
+    while pos < s.len() {
+        if s[pos..].starts_with('.') {
+            let end = key_end(pos + 1);
+            if end == pos + 1 {
+                return Err(error(end));
+            }
+            segments.push(f(RawSegment::Key(&s[pos + 1..end])).ok_or_else(|| error(pos + 1))?);
+            pos = end;
+        } else if s[pos..].starts_with("[\"") {
+            let mut key = String::new();
+            let mut chars = s[pos + 2..].char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((offset, '"')) => break pos + 2 + offset + 1,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, c)) => key.push(c),
+                        None => return Err(error(s.len())),
+                    },
+                    Some((_, c)) => key.push(c),
+                    None => return Err(error(s.len())),
+                }
+            };
+            if !s[end..].starts_with(']') {
+                return Err(error(end));
+            }
+            segments.push(f(RawSegment::Quoted(key)).ok_or_else(|| error(pos + 1))?);
+            pos = end + 1;
+        } else if s[pos..].starts_with('[') {
+            let end = s[pos..]
+                .find(']')
+                .map(|offset| pos + offset)
+                .ok_or_else(|| error(s.len()))?;
+            segments.push(f(RawSegment::Bracket(&s[pos + 1..end])).ok_or_else(|| error(pos + 1))?);
+            pos = end + 1;
+        } else {
+            return Err(error(pos));
+        }
+    }
+    Ok(segments)
+}
+
+impl<'a> RawSegment<'a> {
+    fn into_path_segment(self) -> Option<PathSegment> {
+        match self {
+            RawSegment::Key(key) => Some(PathSegment::String(key.to_string().into())),
+            RawSegment::Quoted(key) => Some(PathSegment::String(key.into())),
+            RawSegment::Bracket(content) => {
+                let (digits, negative) = match content.strip_prefix('-') {
+                    Some(digits) => (digits, true),
+                    None => (content, false),
+                };
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                let index = digits.parse().ok()?;
+                Some(match negative {
+                    true => PathSegment::Negative(index),
+                    false => PathSegment::Positive(index),
+                })
+            }
+        }
+    }
+}
+
+impl<const REV: bool> FromStr for Path<REV> {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_segments(s, |segment| segment.into_path_segment()).map(Path::from)
+    }
+}
+
 impl<const REV: bool> Debug for Path<REV> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Path").field(&self.to_string()).finish()
@@ -122,3 +381,407 @@ impl<const REV: bool> FromIterator<PathSegment> for Path<REV> {
         Self(segments)
     }
 }
+
+/// A segment of a [`PathPattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternSegment {
+    /// Matches a segment equal to the given one.
+    Exact(PathSegment),
+    /// Matches any single segment, written `*` or `[*]`.
+    Any,
+    /// Matches any sequence of segments, including an empty one, written `**`.
+    AnyDeep,
+}
+
+/// A pattern matching paths, with wildcards.
+///
+/// A pattern is written in the [string format](Path#string-formats) of paths, where a `*` or
+/// `[*]` segment matches any single segment and a `**` segment matches any number of segments.
+/// Other segments are compared literally, so `[0]` does not match `[-1]` even if both address the
+/// same element.
+///
+/// ```
+/// use morphix::{Path, PathPattern};
+///
+/// let pattern: PathPattern = "users.*.profile.**".parse().unwrap();
+/// assert!(pattern.matches(&".users.alice.profile".parse::<Path<false>>().unwrap()));
+/// assert!(pattern.matches(&".users[0].profile.name".parse::<Path<false>>().unwrap()));
+/// assert!(!pattern.matches(&".users.alice".parse::<Path<false>>().unwrap()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PathPattern(Vec<PatternSegment>);
+
+impl PathPattern {
+    /// Returns `true` if the pattern matches the whole path.
+    pub fn matches<const REV: bool>(&self, path: &Path<REV>) -> bool {
+        let segments: Vec<&PathSegment> = match REV {
+            true => path.iter().rev().collect(),
+            false => path.iter().collect(),
+        };
+        Self::matches_segments(&self.0, &segments)
+    }
+
+    /// Returns `true` if the pattern matches the given path, one of its ancestors, or one of its
+    /// descendants, i.e., if a mutation at the path may affect a value matched by the pattern.
+    pub(crate) fn overlaps(&self, path: &[PathSegment]) -> bool {
+        let mut path = path.iter();
+        for segment in &self.0 {
+            let Some(next) = path.next() else {
+                return true;
+            };
+            match segment {
+                PatternSegment::Exact(expected) if expected != next => return false,
+                PatternSegment::Exact(_) | PatternSegment::Any => {}
+                PatternSegment::AnyDeep => return true,
+            }
+        }
+        true
+    }
+
+    fn matches_segments(pattern: &[PatternSegment], path: &[&PathSegment]) -> bool {
+        match (pattern.split_first(), path.split_first()) {
+            (None, rest) => rest.is_none(),
+            (Some((PatternSegment::AnyDeep, tail)), _) => {
+                (0..=path.len()).any(|skip| Self::matches_segments(tail, &path[skip..]))
+            }
+            (Some(_), None) => false,
+            (Some((PatternSegment::Any, tail)), Some((_, rest))) => Self::matches_segments(tail, rest),
+            (Some((PatternSegment::Exact(expected), tail)), Some((segment, rest))) => {
+                expected == *segment && Self::matches_segments(tail, rest)
+            }
+        }
+    }
+}
+
+impl From<Vec<PatternSegment>> for PathPattern {
+    fn from(segments: Vec<PatternSegment>) -> Self {
+        Self(segments)
+    }
+}
+
+impl FromIterator<PatternSegment> for PathPattern {
+    fn from_iter<T: IntoIterator<Item = PatternSegment>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Deref for PathPattern {
+    type Target = [PatternSegment];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_segments(s, |segment| match segment {
+            RawSegment::Key("*") | RawSegment::Bracket("*") => Some(PatternSegment::Any),
+            RawSegment::Key("**") => Some(PatternSegment::AnyDeep),
+            segment => segment.into_path_segment().map(PatternSegment::Exact),
+        })
+        .map(PathPattern)
+    }
+}
+
+impl Display for PathPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for segment in &self.0 {
+            match segment {
+                PatternSegment::Exact(segment) => write!(f, "{segment}")?,
+                PatternSegment::Any => f.write_str(".*")?,
+                PatternSegment::AnyDeep => f.write_str(".**")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes a map key into a [`PathSegment`], see [`PathSegment::from_key`].
+struct KeySerializer;
+
+#[derive(Debug)]
+struct KeyError(String);
+
+impl Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+impl serde::ser::Error for KeyError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+fn unsupported_key(kind: &str) -> KeyError {
+    KeyError(format!("{kind} cannot be used as a path segment"))
+}
+
+macro_rules! serialize_display {
+    ($($method:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+                Ok(PathSegment::String(value.to_string().into()))
+            }
+        )*
+    };
+}
+
+impl Serializer for KeySerializer {
+    type Ok = PathSegment;
+    type Error = KeyError;
+    type SerializeSeq = Impossible<PathSegment, KeyError>;
+    type SerializeTuple = Impossible<PathSegment, KeyError>;
+    type SerializeTupleStruct = Impossible<PathSegment, KeyError>;
+    type SerializeTupleVariant = Impossible<PathSegment, KeyError>;
+    type SerializeMap = Impossible<PathSegment, KeyError>;
+    type SerializeStruct = Impossible<PathSegment, KeyError>;
+    type SerializeStructVariant = Impossible<PathSegment, KeyError>;
+
+    serialize_display! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_i128: i128,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_u128: u128,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key("a byte array"))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key("an option"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key("an option"))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key("a unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key(&format!("unit struct `{name}`")))
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(PathSegment::String(Cow::Borrowed(variant)))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(unsupported_key(&format!("newtype variant `{name}::{variant}`")))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(unsupported_key("a sequence"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(unsupported_key("a tuple"))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(unsupported_key(&format!("tuple struct `{name}`")))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(unsupported_key(&format!("tuple variant `{name}::{variant}`")))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(unsupported_key("a map"))
+    }
+
+    fn serialize_struct(self, name: &'static str, _: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(unsupported_key(&format!("struct `{name}`")))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(unsupported_key(&format!("struct variant `{name}::{variant}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let path: Path<true> = ".a[0][-1]".parse().unwrap();
+        assert_eq!(path, Path::from(vec!["a".into(), 0.into(), PathSegment::Negative(1)]));
+        assert_eq!("a.b".parse::<Path<false>>().unwrap()[..], ["a".into(), "b".into()]);
+        assert_eq!("".parse::<Path<false>>().unwrap(), Path::new());
+        assert_eq!("[1]".parse::<Path<false>>().unwrap()[..], [1.into()]);
+
+        for key in ["", "a.b", "[0]", "\"\\", "x]"] {
+            let path: Path<false> = vec![key.into(), "c".into()].into();
+            assert_eq!(path.to_string().parse::<Path<false>>().unwrap(), path);
+        }
+        assert_eq!(Path::<false>::from(vec!["a.b".into()]).to_string(), r#"["a.b"]"#);
+
+        let error = |input: &str, position| {
+            assert_eq!(
+                input.parse::<Path<false>>(),
+                Err(PathError::SyntaxError {
+                    input: input.to_string(),
+                    position,
+                }),
+            );
+        };
+        error(".a.", 3);
+        error("a[", 2);
+        error("a[x]", 2);
+        error("a[-]", 2);
+        error("a[+1]", 2);
+        error(r#"a["b"#, 4);
+        error(r#"a["b"c"#, 5);
+        error("a]b[0]c", 6);
+    }
+
+    #[test]
+    fn pattern() {
+        let pattern: PathPattern = "users.*.profile.name".parse().unwrap();
+        let matches = |path: &str| pattern.matches(&path.parse::<Path<true>>().unwrap());
+        assert!(matches(".users.alice.profile.name"));
+        assert!(matches(".users[-1].profile.name"));
+        assert!(!matches(".users.alice.profile"));
+        assert!(!matches(".users.alice.profile.name.first"));
+
+        let pattern: PathPattern = "items[*].**".parse().unwrap();
+        let matches = |path: &str| pattern.matches(&path.parse::<Path<false>>().unwrap());
+        assert!(matches(".items[0]"));
+        assert!(matches(".items.a.b.c"));
+        assert!(!matches(".items"));
+        assert!(!matches(".other[0]"));
+
+        let pattern: PathPattern = "**.id".parse().unwrap();
+        assert!(pattern.matches(&"id".parse::<Path<false>>().unwrap()));
+        assert!(pattern.matches(&"a[0].id".parse::<Path<false>>().unwrap()));
+        assert!(!pattern.matches(&"a.idx".parse::<Path<false>>().unwrap()));
+
+        let pattern: PathPattern = r#"a["*"][0]"#.parse().unwrap();
+        assert_eq!(
+            pattern[..],
+            [
+                PatternSegment::Exact("a".into()),
+                PatternSegment::Exact("*".into()),
+                PatternSegment::Exact(0.into()),
+            ],
+        );
+        assert_eq!(pattern.to_string(), r#".a["*"][0]"#);
+        assert_eq!("*[*].**".parse::<PathPattern>().unwrap().to_string(), ".*.*.**");
+        assert_eq!(
+            "a[**]".parse::<PathPattern>(),
+            Err(PathError::SyntaxError {
+                input: "a[**]".to_string(),
+                position: 2,
+            }),
+        );
+    }
+
+    #[test]
+    fn from_key() {
+        #[derive(Serialize)]
+        struct Id(u32);
+
+        #[derive(Serialize)]
+        enum Color {
+            Red,
+            Custom(u32),
+        }
+
+        assert_eq!(PathSegment::from_key(&-1_i8).unwrap(), "-1".into());
+        assert_eq!(PathSegment::from_key(&true).unwrap(), "true".into());
+        assert_eq!(PathSegment::from_key(&'x').unwrap(), "x".into());
+        assert_eq!(PathSegment::from_key(&1.5).unwrap(), "1.5".into());
+        assert_eq!(PathSegment::from_key(&Id(7)).unwrap(), "7".into());
+        assert_eq!(PathSegment::from_key(&Color::Red).unwrap(), "Red".into());
+        assert_eq!(
+            PathSegment::from_key(&Color::Custom(0)).unwrap_err().to_string(),
+            "invalid map key: newtype variant `Color::Custom` cannot be used as a path segment",
+        );
+        assert_eq!(
+            PathSegment::from_key(&(1, 2)).unwrap_err().to_string(),
+            "invalid map key: a tuple cannot be used as a path segment",
+        );
+        assert!(PathSegment::from_key(&Some(1)).is_err());
+    }
+
+    #[test]
+    fn json_pointer() {
+        let path = Path::<true>::from_json_pointer("/a~1b~0c/0/01/-/").unwrap();
+        assert_eq!(
+            path,
+            Path::from(vec![
+                "a/b~c".into(),
+                0.into(),
+                "01".into(),
+                PathSegment::Negative(0),
+                "".into(),
+            ]),
+        );
+        assert_eq!(path.to_json_pointer().unwrap(), "/a~1b~0c/0/01/-/");
+        assert_eq!(Path::<false>::from_json_pointer("").unwrap(), Path::new());
+        assert_eq!(Path::<false>::new().to_json_pointer().unwrap(), "");
+
+        for pointer in ["a", "/~2", "/a~"] {
+            assert_eq!(
+                Path::<false>::from_json_pointer(pointer),
+                Err(PathError::InvalidPointer {
+                    pointer: pointer.to_string(),
+                }),
+            );
+        }
+        let path: Path<true> = vec!["a".into(), PathSegment::Negative(1)].into();
+        assert_eq!(
+            path.to_json_pointer(),
+            Err(PathError::NegativeIndex {
+                path: vec!["a".into(), PathSegment::Negative(1)].into(),
+            }),
+        );
+    }
+}
@@ -0,0 +1,837 @@
+//! Wire format of mutations and paths.
+//!
+//! [`Mutation`], [`MutationKind`], [`Path`] and [`PathSegment`] implement [`Serialize`] and
+//! [`Deserialize`] with a stable representation, whose current version is [`VERSION`]:
+//!
+//! - A [`PathSegment`] is a string for a [`String`](PathSegment::String) key, a non-negative
+//!   integer `n` for a [`Positive`](PathSegment::Positive) index, and a negative integer `-n` for a
+//!   [`Negative`](PathSegment::Negative) index.
+//! - A [`Path`] is a sequence of segments in natural order (root first), whatever its storage
+//!   order, so a [`Path<true>`] and a [`Path<false>`] to the same value are serialized alike.
+//! - A [`Mutation`] is serialized as described in its [documentation](Mutation#serialization):
+//!   a struct in human-readable formats such as JSON, and a compact sequence with numeric kind tags
+//!   in binary formats such as MessagePack.
+//!
+//! In human-readable formats, a [`Path`] can also be deserialized from a string in its [display
+//! format](Path#string-formats), and the [`path_string`] module serializes it as such a string.
+//!
+//! Path segments are told apart by their type, so deserialization requires a self-describing
+//! format. Any change to the representation bumps [`VERSION`]; wrap messages in [`Versioned`] to
+//! let peers detect a mismatch.
+//!
+//! ## Example
+//!
+//! ```
+//! use morphix::wire::Versioned;
+//! use morphix::{Mutation, MutationKind, PathSegment};
+//! use serde_json::{Value, json};
+//!
+//! let mutation = Mutation {
+//!     path: vec!["items".into(), PathSegment::Negative(1)].into(),
+//!     kind: MutationKind::Replace(json!(42)),
+//! };
+//! let message = serde_json::to_value(Versioned(&mutation)).unwrap();
+//! assert_eq!(
+//!     message,
+//!     json!({"version": 1, "data": {"path": ["items", -1], "kind": "replace", "value": 42}}),
+//! );
+//!
+//! let Versioned(decoded) = serde_json::from_value::<Versioned<Mutation<Value>>>(message).unwrap();
+//! assert_eq!(decoded, mutation);
+//! ```
+
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+use serde::de::{self, Expected, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Mutation, MutationKind, Path, PathSegment};
+
+/// The current version of the wire format.
+pub const VERSION: u32 = 1;
+
+/// The names of the mutation kinds, indexed by their numeric tags.
+const KINDS: [&str; 10] = [
+    "replace",
+    "append",
+    "truncate",
+    "splice",
+    "move",
+    "delete",
+    "increment",
+    "add",
+    "remove",
+    "batch",
+];
+
+const TAG_REPLACE: u8 = 0;
+#[cfg(feature = "append")]
+const TAG_APPEND: u8 = 1;
+#[cfg(feature = "truncate")]
+const TAG_TRUNCATE: u8 = 2;
+#[cfg(feature = "splice")]
+const TAG_SPLICE: u8 = 3;
+#[cfg(feature = "move")]
+const TAG_MOVE: u8 = 4;
+#[cfg(feature = "delete")]
+const TAG_DELETE: u8 = 5;
+#[cfg(feature = "increment")]
+const TAG_INCREMENT: u8 = 6;
+#[cfg(feature = "set")]
+const TAG_ADD: u8 = 7;
+#[cfg(feature = "set")]
+const TAG_REMOVE: u8 = 8;
+const TAG_BATCH: u8 = 9;
+
+/// A value tagged with the version of the wire format.
+///
+/// [`Versioned`] is serialized as a struct with a `version` field set to [`VERSION`] and a `data`
+/// field holding the value, or as a sequence `[version, data]` in binary formats. Deserialization
+/// fails if the version differs from [`VERSION`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Versioned<T>(pub T);
+
+impl<T: Serialize> Serialize for Versioned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let mut state = serializer.serialize_struct("Versioned", 2)?;
+            state.serialize_field("version", &VERSION)?;
+            state.serialize_field("data", &self.0)?;
+            state.end()
+        } else {
+            let mut seq = serializer.serialize_seq(Some(2))?;
+            seq.serialize_element(&VERSION)?;
+            seq.serialize_element(&self.0)?;
+            seq.end()
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Versioned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VersionedVisitor<T>(PhantomData<T>);
+
+        fn check_version<E: de::Error>(version: u32) -> Result<(), E> {
+            if version != VERSION {
+                return Err(E::custom(format_args!("unsupported wire format version {version}")));
+            }
+            Ok(())
+        }
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for VersionedVisitor<T> {
+            type Value = Versioned<T>;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a versioned value")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut index = 0;
+                check_version(next_element(&mut seq, &mut index, &self)?)?;
+                let data = next_element(&mut seq, &mut index, &self)?;
+                if seq.next_element::<IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(index + 1, &self));
+                }
+                Ok(Versioned(data))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut version = None;
+                let mut data = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "version" => {
+                            let value = map.next_value()?;
+                            check_version(value)?;
+                            set_field(&mut version, value, "version")?;
+                        }
+                        "data" => set_field(&mut data, map.next_value()?, "data")?,
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                required(version, "version")?;
+                Ok(Versioned(required(data, "data")?))
+            }
+        }
+
+        let visitor = VersionedVisitor(PhantomData);
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_struct("Versioned", &["version", "data"], visitor)
+        } else {
+            deserializer.deserialize_seq(visitor)
+        }
+    }
+}
+
+impl Serialize for PathSegment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PathSegment::String(key) => serializer.serialize_str(key),
+            PathSegment::Positive(index) => serializer.serialize_u64(*index as u64),
+            PathSegment::Negative(index) => {
+                let index = i64::try_from(*index).map_err(|_| serde::ser::Error::custom("negative index overflow"))?;
+                serializer.serialize_i64(-index)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PathSegment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SegmentVisitor;
+
+        impl Visitor<'_> for SegmentVisitor {
+            type Value = PathSegment;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a string or an integer")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(PathSegment::String(value.to_string().into()))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(PathSegment::String(value.into()))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                let index = usize::try_from(value).map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))?;
+                Ok(PathSegment::Positive(index))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                if value >= 0 {
+                    return self.visit_u64(value as u64);
+                }
+                let index = usize::try_from(value.unsigned_abs())
+                    .map_err(|_| E::invalid_value(Unexpected::Signed(value), &self))?;
+                Ok(PathSegment::Negative(index))
+            }
+        }
+
+        deserializer.deserialize_any(SegmentVisitor)
+    }
+}
+
+impl<const REV: bool> Serialize for Path<REV> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        if REV {
+            for segment in self.iter().rev() {
+                seq.serialize_element(segment)?;
+            }
+        } else {
+            for segment in self.iter() {
+                seq.serialize_element(segment)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<'de, const REV: bool> Deserialize<'de> for Path<REV> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PathVisitor<const REV: bool>;
+
+        impl<'de, const REV: bool> Visitor<'de> for PathVisitor<REV> {
+            type Value = Path<REV>;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a sequence of path segments or a path string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut segments = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(segment) = seq.next_element()? {
+                    segments.push(segment);
+                }
+                Ok(segments.into())
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(PathVisitor)
+        } else {
+            deserializer.deserialize_seq(PathVisitor)
+        }
+    }
+}
+
+/// Serializes a [`Path`] as a string in its [display format](Path#string-formats).
+///
+/// Use this module with `#[serde(with = "morphix::wire::path_string")]` to store a path as a
+/// string, e.g. in a configuration file. [`Path`] itself is serialized as a sequence, but also
+/// accepts a path string when deserialized from a human-readable format.
+///
+/// ```
+/// use morphix::{Path, PathSegment};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Route {
+///     #[serde(with = "morphix::wire::path_string")]
+///     path: Path<false>,
+/// }
+///
+/// let route: Route = serde_json::from_str(r#"{"path": ".items[-1].name"}"#).unwrap();
+/// assert_eq!(route.path[..], ["items".into(), PathSegment::Negative(1), "name".into()]);
+/// assert_eq!(serde_json::to_string(&route).unwrap(), r#"{"path":".items[-1].name"}"#);
+/// ```
+pub mod path_string {
+    use std::borrow::Cow;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::Path;
+
+    /// Serializes a path as a string.
+    pub fn serialize<S: Serializer, const REV: bool>(path: &Path<REV>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(path)
+    }
+
+    /// Deserializes a path from a string.
+    pub fn deserialize<'de, D: Deserializer<'de>, const REV: bool>(deserializer: D) -> Result<Path<REV>, D::Error> {
+        Cow::<str>::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl<T> MutationKind<T> {
+    /// Returns the numeric tag and the number of fields of the kind in the wire format.
+    fn wire_header(&self) -> (u8, usize) {
+        match self {
+            MutationKind::Replace(_) => (TAG_REPLACE, 1),
+            #[cfg(feature = "append")]
+            MutationKind::Append(_) => (TAG_APPEND, 1),
+            #[cfg(feature = "truncate")]
+            MutationKind::Truncate(_) => (TAG_TRUNCATE, 1),
+            #[cfg(feature = "splice")]
+            MutationKind::Splice { .. } => (TAG_SPLICE, 3),
+            #[cfg(feature = "move")]
+            MutationKind::Move { .. } => (TAG_MOVE, 2),
+            #[cfg(feature = "delete")]
+            MutationKind::Delete => (TAG_DELETE, 0),
+            #[cfg(feature = "increment")]
+            MutationKind::Increment(_) => (TAG_INCREMENT, 1),
+            #[cfg(feature = "set")]
+            MutationKind::Add(_) => (TAG_ADD, 1),
+            #[cfg(feature = "set")]
+            MutationKind::Remove(_) => (TAG_REMOVE, 1),
+            MutationKind::Batch(_) => (TAG_BATCH, 1),
+        }
+    }
+}
+
+impl<T: Serialize> MutationKind<T> {
+    /// Serializes the `kind` field and the fields specific to the kind.
+    fn serialize_fields<S: SerializeStruct>(&self, state: &mut S) -> Result<(), S::Error> {
+        state.serialize_field("kind", KINDS[self.wire_header().0 as usize])?;
+        match self {
+            MutationKind::Replace(value) => state.serialize_field("value", value),
+            #[cfg(feature = "append")]
+            MutationKind::Append(value) => state.serialize_field("value", value),
+            #[cfg(feature = "truncate")]
+            MutationKind::Truncate(len) => state.serialize_field("len", len),
+            #[cfg(feature = "splice")]
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            } => {
+                state.serialize_field("index", index)?;
+                state.serialize_field("delete_len", delete_len)?;
+                state.serialize_field("value", insert)
+            }
+            #[cfg(feature = "move")]
+            MutationKind::Move { from, to } => {
+                state.serialize_field("from", from)?;
+                state.serialize_field("to", to)
+            }
+            #[cfg(feature = "delete")]
+            MutationKind::Delete => Ok(()),
+            #[cfg(feature = "increment")]
+            MutationKind::Increment(value) => state.serialize_field("value", value),
+            #[cfg(feature = "set")]
+            MutationKind::Add(value) => state.serialize_field("value", value),
+            #[cfg(feature = "set")]
+            MutationKind::Remove(value) => state.serialize_field("value", value),
+            MutationKind::Batch(mutations) => state.serialize_field("mutations", mutations),
+        }
+    }
+
+    /// Serializes the numeric tag and the fields specific to the kind.
+    fn serialize_elements<S: SerializeSeq>(&self, seq: &mut S) -> Result<(), S::Error> {
+        seq.serialize_element(&self.wire_header().0)?;
+        match self {
+            MutationKind::Replace(value) => seq.serialize_element(value),
+            #[cfg(feature = "append")]
+            MutationKind::Append(value) => seq.serialize_element(value),
+            #[cfg(feature = "truncate")]
+            MutationKind::Truncate(len) => seq.serialize_element(len),
+            #[cfg(feature = "splice")]
+            MutationKind::Splice {
+                index,
+                delete_len,
+                insert,
+            } => {
+                seq.serialize_element(index)?;
+                seq.serialize_element(delete_len)?;
+                seq.serialize_element(insert)
+            }
+            #[cfg(feature = "move")]
+            MutationKind::Move { from, to } => {
+                seq.serialize_element(from)?;
+                seq.serialize_element(to)
+            }
+            #[cfg(feature = "delete")]
+            MutationKind::Delete => Ok(()),
+            #[cfg(feature = "increment")]
+            MutationKind::Increment(value) => seq.serialize_element(value),
+            #[cfg(feature = "set")]
+            MutationKind::Add(value) => seq.serialize_element(value),
+            #[cfg(feature = "set")]
+            MutationKind::Remove(value) => seq.serialize_element(value),
+            MutationKind::Batch(mutations) => seq.serialize_element(mutations),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for MutationKind<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = 1 + self.wire_header().1;
+        if serializer.is_human_readable() {
+            let mut state = serializer.serialize_struct("MutationKind", len)?;
+            self.serialize_fields(&mut state)?;
+            state.end()
+        } else {
+            let mut seq = serializer.serialize_seq(Some(len))?;
+            self.serialize_elements(&mut seq)?;
+            seq.end()
+        }
+    }
+}
+
+impl<V: Serialize> Serialize for Mutation<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = 2 + self.kind.wire_header().1;
+        if serializer.is_human_readable() {
+            let mut state = serializer.serialize_struct("Mutation", len)?;
+            state.serialize_field("path", &self.path)?;
+            self.kind.serialize_fields(&mut state)?;
+            state.end()
+        } else {
+            let mut seq = serializer.serialize_seq(Some(len))?;
+            seq.serialize_element(&self.path)?;
+            self.kind.serialize_elements(&mut seq)?;
+            seq.end()
+        }
+    }
+}
+
+/// A field of the struct form of a mutation.
+enum Field {
+    Path,
+    Kind,
+    Value,
+    #[cfg(feature = "truncate")]
+    Len,
+    #[cfg(feature = "splice")]
+    Index,
+    #[cfg(feature = "splice")]
+    DeleteLen,
+    #[cfg(feature = "move")]
+    From,
+    #[cfg(feature = "move")]
+    To,
+    Mutations,
+    Unknown,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a field name")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(match value {
+                    "path" => Field::Path,
+                    "kind" => Field::Kind,
+                    "value" => Field::Value,
+                    #[cfg(feature = "truncate")]
+                    "len" => Field::Len,
+                    #[cfg(feature = "splice")]
+                    "index" => Field::Index,
+                    #[cfg(feature = "splice")]
+                    "delete_len" => Field::DeleteLen,
+                    #[cfg(feature = "move")]
+                    "from" => Field::From,
+                    #[cfg(feature = "move")]
+                    "to" => Field::To,
+                    "mutations" => Field::Mutations,
+                    _ => Field::Unknown,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// The numeric tag of a mutation kind, deserialized from its name.
+struct KindName(u8);
+
+impl<'de> Deserialize<'de> for KindName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KindNameVisitor;
+
+        impl Visitor<'_> for KindNameVisitor {
+            type Value = KindName;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a mutation kind")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match KINDS.iter().position(|name| *name == value) {
+                    Some(tag) => Ok(KindName(tag as u8)),
+                    None => Err(E::unknown_variant(value, &KINDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(KindNameVisitor)
+    }
+}
+
+fn unsupported_kind<E: de::Error>(tag: u8) -> E {
+    match KINDS.get(tag as usize) {
+        Some(name) => E::custom(format_args!("mutation kind `{name}` is not enabled")),
+        None => E::invalid_value(Unexpected::Unsigned(tag.into()), &"a mutation kind tag"),
+    }
+}
+
+/// Stores a field of the struct form, failing if it is already set.
+fn set_field<T, E: de::Error>(slot: &mut Option<T>, value: T, name: &'static str) -> Result<(), E> {
+    if slot.replace(value).is_some() {
+        return Err(E::duplicate_field(name));
+    }
+    Ok(())
+}
+
+/// Takes a field of the struct form, failing if it is missing.
+fn required<T, E: de::Error>(slot: Option<T>, name: &'static str) -> Result<T, E> {
+    slot.ok_or_else(|| E::missing_field(name))
+}
+
+/// Reads the next element of the compact form, failing if the sequence ends.
+fn next_element<'de, A, T>(seq: &mut A, index: &mut usize, expected: &dyn Expected) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    let element = seq
+        .next_element()?
+        .ok_or_else(|| de::Error::invalid_length(*index, expected))?;
+    *index += 1;
+    Ok(element)
+}
+
+/// Deserializes a mutation, or a mutation kind without path, from either form.
+struct MutationVisitor<V> {
+    with_path: bool,
+    marker: PhantomData<V>,
+}
+
+impl<V> MutationVisitor<V> {
+    fn new(with_path: bool) -> Self {
+        Self {
+            with_path,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for MutationVisitor<V> {
+    type Value = (Path<true>, MutationKind<V>);
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        f.write_str(if self.with_path {
+            "a mutation"
+        } else {
+            "a mutation kind"
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut index = 0;
+        let path = match self.with_path {
+            true => next_element(&mut seq, &mut index, &self)?,
+            false => Path::new(),
+        };
+        let kind = match next_element(&mut seq, &mut index, &self)? {
+            TAG_REPLACE => MutationKind::Replace(next_element(&mut seq, &mut index, &self)?),
+            #[cfg(feature = "append")]
+            TAG_APPEND => MutationKind::Append(next_element(&mut seq, &mut index, &self)?),
+            #[cfg(feature = "truncate")]
+            TAG_TRUNCATE => MutationKind::Truncate(next_element(&mut seq, &mut index, &self)?),
+            #[cfg(feature = "splice")]
+            TAG_SPLICE => MutationKind::Splice {
+                index: next_element(&mut seq, &mut index, &self)?,
+                delete_len: next_element(&mut seq, &mut index, &self)?,
+                insert: next_element(&mut seq, &mut index, &self)?,
+            },
+            #[cfg(feature = "move")]
+            TAG_MOVE => MutationKind::Move {
+                from: next_element(&mut seq, &mut index, &self)?,
+                to: next_element(&mut seq, &mut index, &self)?,
+            },
+            #[cfg(feature = "delete")]
+            TAG_DELETE => MutationKind::Delete,
+            #[cfg(feature = "increment")]
+            TAG_INCREMENT => MutationKind::Increment(next_element(&mut seq, &mut index, &self)?),
+            #[cfg(feature = "set")]
+            TAG_ADD => MutationKind::Add(next_element(&mut seq, &mut index, &self)?),
+            #[cfg(feature = "set")]
+            TAG_REMOVE => MutationKind::Remove(next_element(&mut seq, &mut index, &self)?),
+            TAG_BATCH => MutationKind::Batch(next_element(&mut seq, &mut index, &self)?),
+            tag => return Err(unsupported_kind(tag)),
+        };
+        if seq.next_element::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(index + 1, &self));
+        }
+        Ok((path, kind))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut path = None;
+        let mut tag = None;
+        let mut value = None;
+        #[cfg(feature = "truncate")]
+        let mut len = None;
+        #[cfg(feature = "splice")]
+        let mut index = None;
+        #[cfg(feature = "splice")]
+        let mut delete_len = None;
+        #[cfg(feature = "move")]
+        let mut from = None;
+        #[cfg(feature = "move")]
+        let mut to = None;
+        let mut mutations = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Path if self.with_path => set_field(&mut path, map.next_value()?, "path")?,
+                Field::Kind => set_field(&mut tag, map.next_value::<KindName>()?.0, "kind")?,
+                Field::Value => set_field(&mut value, map.next_value()?, "value")?,
+                #[cfg(feature = "truncate")]
+                Field::Len => set_field(&mut len, map.next_value()?, "len")?,
+                #[cfg(feature = "splice")]
+                Field::Index => set_field(&mut index, map.next_value()?, "index")?,
+                #[cfg(feature = "splice")]
+                Field::DeleteLen => set_field(&mut delete_len, map.next_value()?, "delete_len")?,
+                #[cfg(feature = "move")]
+                Field::From => set_field(&mut from, map.next_value()?, "from")?,
+                #[cfg(feature = "move")]
+                Field::To => set_field(&mut to, map.next_value()?, "to")?,
+                Field::Mutations => set_field(&mut mutations, map.next_value()?, "mutations")?,
+                Field::Path | Field::Unknown => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let path = match self.with_path {
+            true => required(path, "path")?,
+            false => Path::new(),
+        };
+        let kind = match required(tag, "kind")? {
+            TAG_REPLACE => MutationKind::Replace(required(value, "value")?),
+            #[cfg(feature = "append")]
+            TAG_APPEND => MutationKind::Append(required(value, "value")?),
+            #[cfg(feature = "truncate")]
+            TAG_TRUNCATE => MutationKind::Truncate(required(len, "len")?),
+            #[cfg(feature = "splice")]
+            TAG_SPLICE => MutationKind::Splice {
+                index: required(index, "index")?,
+                delete_len: required(delete_len, "delete_len")?,
+                insert: required(value, "value")?,
+            },
+            #[cfg(feature = "move")]
+            TAG_MOVE => MutationKind::Move {
+                from: required(from, "from")?,
+                to: required(to, "to")?,
+            },
+            #[cfg(feature = "delete")]
+            TAG_DELETE => MutationKind::Delete,
+            #[cfg(feature = "increment")]
+            TAG_INCREMENT => MutationKind::Increment(required(value, "value")?),
+            #[cfg(feature = "set")]
+            TAG_ADD => MutationKind::Add(required(value, "value")?),
+            #[cfg(feature = "set")]
+            TAG_REMOVE => MutationKind::Remove(required(value, "value")?),
+            TAG_BATCH => MutationKind::Batch(required(mutations, "mutations")?),
+            tag => return Err(unsupported_kind(tag)),
+        };
+        Ok((path, kind))
+    }
+}
+
+const FIELDS: &[&str] = &[
+    "path",
+    "kind",
+    "value",
+    "len",
+    "index",
+    "delete_len",
+    "from",
+    "to",
+    "mutations",
+];
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for MutationKind<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = MutationVisitor::new(false);
+        let (_, kind) = if deserializer.is_human_readable() {
+            deserializer.deserialize_struct("MutationKind", &FIELDS[1..], visitor)?
+        } else {
+            deserializer.deserialize_seq(visitor)?
+        };
+        Ok(kind)
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Mutation<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = MutationVisitor::new(true);
+        let (path, kind) = if deserializer.is_human_readable() {
+            deserializer.deserialize_struct("Mutation", FIELDS, visitor)?
+        } else {
+            deserializer.deserialize_seq(visitor)?
+        };
+        Ok(Mutation { path, kind })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use morphix_test_utils::*;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::observe::{ObserveExt, SerializeObserverExt};
+
+    #[test]
+    fn serialize_kinds() {
+        let mutation = Mutation {
+            path: vec!["a".into(), PathSegment::Positive(0), PathSegment::Negative(2)].into(),
+            kind: MutationKind::Batch(vec![
+                splice!(_, 1, 2, json!("x")),
+                delete!(b),
+                mv!(_, 0, 1),
+                truncate!(_, 3),
+                increment!(c, json!(1)),
+            ]),
+        };
+        let value = json!({
+            "path": ["a", 0, -2],
+            "kind": "batch",
+            "mutations": [
+                {"path": [], "kind": "splice", "index": 1, "delete_len": 2, "value": "x"},
+                {"path": ["b"], "kind": "delete"},
+                {"path": [], "kind": "move", "from": 0, "to": 1},
+                {"path": [], "kind": "truncate", "len": 3},
+                {"path": ["c"], "kind": "increment", "value": 1},
+            ],
+        });
+        assert_eq!(serde_json::to_value(&mutation).unwrap(), value);
+        assert_eq!(serde_json::from_value::<Mutation<Value>>(value).unwrap(), mutation);
+
+        let kind = MutationKind::Add(json!([1]));
+        let value = json!({"kind": "add", "value": [1]});
+        assert_eq!(serde_json::to_value(&kind).unwrap(), value);
+        assert_eq!(serde_json::from_value::<MutationKind<Value>>(value).unwrap(), kind);
+    }
+
+    #[test]
+    fn deserialize_errors() {
+        let value = json!({"kind": "append", "value": "x", "path": ["a"], "extra": null});
+        assert_eq!(
+            serde_json::from_value::<Mutation<Value>>(value).unwrap(),
+            append!(a, json!("x")),
+        );
+
+        let error = serde_json::from_value::<Mutation<Value>>(json!({"path": [], "kind": "truncate"})).unwrap_err();
+        assert_eq!(error.to_string(), "missing field `len`");
+        let error = serde_json::from_value::<Mutation<Value>>(json!({"kind": "delete"})).unwrap_err();
+        assert_eq!(error.to_string(), "missing field `path`");
+        let error = serde_json::from_value::<Mutation<Value>>(json!({"path": [], "kind": "clear"})).unwrap_err();
+        assert!(error.to_string().starts_with("unknown variant `clear`"));
+        let error = serde_json::from_value::<Mutation<Value>>(json!({"path": [true], "kind": "delete"})).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid type: boolean `true`, expected a string or an integer",
+        );
+        let error = serde_json::from_value::<Versioned<Path<false>>>(json!({"version": 2, "data": []})).unwrap_err();
+        assert_eq!(error.to_string(), "unsupported wire format version 2");
+    }
+
+    #[test]
+    fn paths() {
+        let path: Path<true> = vec!["a".into(), PathSegment::Positive(1), PathSegment::Negative(1)].into();
+        let value = json!(["a", 1, -1]);
+        assert_eq!(serde_json::to_value(&path).unwrap(), value);
+        assert_eq!(serde_json::from_value::<Path<true>>(value.clone()).unwrap(), path);
+
+        let path: Path<false> = vec!["a".into(), PathSegment::Positive(1), PathSegment::Negative(1)].into();
+        assert_eq!(serde_json::to_value(&path).unwrap(), value);
+        assert_eq!(serde_json::from_value::<Path<false>>(value).unwrap(), path);
+        assert_eq!(serde_json::from_value::<Path<false>>(json!(".a[1][-1]")).unwrap(), path);
+        let error = serde_json::from_value::<Path<false>>(json!(".a[")).unwrap_err();
+        assert_eq!(error.to_string(), r#"invalid path ".a[" at position 3"#);
+    }
+
+    #[test]
+    fn compact() {
+        let mutation = batch!(a, replace!(-1, json!({"b": 1})), truncate!(c, 2));
+        let bytes = rmp_serde::to_vec(&Versioned(&mutation)).unwrap();
+        let value = rmpv::decode::read_value(&mut &bytes[..]).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"[1, [["a"], 9, [[[-1], 0, {"b": 1}], [["c"], 2, 2]]]]"#,
+        );
+        let Versioned(decoded) = rmp_serde::from_slice::<Versioned<Mutation<Value>>>(&bytes).unwrap();
+        assert_eq!(decoded, mutation);
+    }
+
+    #[test]
+    fn flush_into() {
+        let mut data = vec![0..1, 1..2];
+        let mut ob = data.__observe();
+        ob.push(2..4);
+
+        let mut buffer = vec![];
+        ob.flush_into(&mut serde_json::Serializer::new(&mut buffer)).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&buffer).unwrap(),
+            json!({"path": [], "kind": "append", "value": [{"start": 2, "end": 4}]}),
+        );
+
+        let mut buffer = vec![];
+        ob.flush_into(&mut serde_json::Serializer::new(&mut buffer)).unwrap();
+        assert_eq!(buffer, b"null");
+    }
+}